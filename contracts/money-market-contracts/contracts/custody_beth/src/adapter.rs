@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{
+    to_binary, Addr, CosmosMsg, Deps, QueryRequest, StdResult, Uint128, WasmMsg, WasmQuery,
+};
+
+use crate::external::handle::{RewardContractExecuteMsg, RewardContractQueryMsg};
+
+use moneymarket_custody_base::adapter::RewardAdapter;
+use terra_cosmwasm::TerraMsgWrapper;
+
+//BETHAccruedRewardsResponse the struct that shows the result of accrued_rewards query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct BETHAccruedRewardsResponse {
+    pub rewards: Uint128,
+}
+
+/// Claims the rewards of the BETH collateral from the BETH reward contract
+pub struct BETHRewardAdapter;
+
+impl RewardAdapter for BETHRewardAdapter {
+    fn accrued_rewards(deps: Deps, reward_contract: &Addr, custody: &Addr) -> StdResult<Uint128> {
+        let rewards: BETHAccruedRewardsResponse =
+            deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: reward_contract.to_string(),
+                msg: to_binary(&RewardContractQueryMsg::AccruedRewards {
+                    address: custody.to_string(),
+                })?,
+            }))?;
+
+        Ok(rewards.rewards)
+    }
+
+    fn claim_rewards_msg(reward_contract: &Addr) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: reward_contract.to_string(),
+            funds: vec![],
+            msg: to_binary(&RewardContractExecuteMsg::ClaimRewards { recipient: None })?,
+        }))
+    }
+}
@@ -1,26 +1,23 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
-    StdResult,
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult,
 };
 
-use crate::collateral::{
-    deposit_collateral, liquidate_collateral, lock_collateral, query_borrower, query_borrowers,
-    unlock_collateral, withdraw_collateral,
-};
-use crate::distribution::{distribute_hook, distribute_rewards, swap_to_stable_denom};
-use crate::error::ContractError;
-use crate::state::{read_config, store_config, Config};
+use crate::adapter::BETHRewardAdapter;
 
-use cw20::Cw20ReceiveMsg;
 use moneymarket::common::optional_addr_validate;
-use moneymarket::custody::{ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use moneymarket::custody::{ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use moneymarket::tokens::AssetInfoRaw;
+use moneymarket_custody_base::collateral::{query_borrower, query_borrowers};
+use moneymarket_custody_base::contract::{
+    self as custody, migrate_total_deposits, query_pause_info,
+};
+use moneymarket_custody_base::distribution::query_reward_routing;
+use moneymarket_custody_base::error::ContractError;
+use moneymarket_custody_base::state::{read_config, store_config, Config};
 use terra_cosmwasm::TerraMsgWrapper;
 
-pub const CLAIM_REWARDS_OPERATION: u64 = 1u64;
-pub const SWAP_TO_STABLE_OPERATION: u64 = 2u64;
-
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -31,9 +28,11 @@ pub fn instantiate(
     let config = Config {
         owner: deps.api.addr_canonicalize(&msg.owner)?,
         overseer_contract: deps.api.addr_canonicalize(&msg.overseer_contract)?,
-        collateral_token: deps.api.addr_canonicalize(&msg.collateral_token)?,
+        collateral_token: AssetInfoRaw::Token {
+            contract_addr: deps.api.addr_canonicalize(&msg.collateral_token)?,
+        },
         market_contract: deps.api.addr_canonicalize(&msg.market_contract)?,
-        reward_contract: deps.api.addr_canonicalize(&msg.reward_contract)?,
+        reward_contract: Some(deps.api.addr_canonicalize(&msg.reward_contract)?),
         liquidation_contract: deps.api.addr_canonicalize(&msg.liquidation_contract)?,
         stable_denom: msg.stable_denom,
         basset_info: msg.basset_info,
@@ -51,40 +50,7 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
-    match msg {
-        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
-        ExecuteMsg::UpdateConfig {
-            owner,
-            liquidation_contract,
-        } => {
-            let api = deps.api;
-            update_config(
-                deps,
-                info,
-                optional_addr_validate(api, owner)?,
-                optional_addr_validate(api, liquidation_contract)?,
-            )
-        }
-        ExecuteMsg::LockCollateral { borrower, amount } => {
-            let borrower_addr = deps.api.addr_validate(&borrower)?;
-            lock_collateral(deps, info, borrower_addr, amount)
-        }
-        ExecuteMsg::UnlockCollateral { borrower, amount } => {
-            let borrower_addr = deps.api.addr_validate(&borrower)?;
-            unlock_collateral(deps, info, borrower_addr, amount)
-        }
-        ExecuteMsg::DistributeRewards {} => distribute_rewards(deps, env, info),
-        ExecuteMsg::WithdrawCollateral { amount } => withdraw_collateral(deps, info, amount),
-        ExecuteMsg::LiquidateCollateral {
-            liquidator,
-            borrower,
-            amount,
-        } => {
-            let liquidator_addr = deps.api.addr_validate(&liquidator)?;
-            let borrower_addr = deps.api.addr_validate(&borrower)?;
-            liquidate_collateral(deps, info, liquidator_addr, borrower_addr, amount)
-        }
-    }
+    custody::execute::<BETHRewardAdapter>(deps, env, info, msg)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -93,59 +59,7 @@ pub fn reply(
     env: Env,
     msg: Reply,
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
-    match msg.id {
-        // ClaimRewards callback
-        CLAIM_REWARDS_OPERATION => swap_to_stable_denom(deps, env),
-        // Swap to stable callback
-        SWAP_TO_STABLE_OPERATION => distribute_hook(deps, env),
-        _ => Err(ContractError::InvalidReplyId {}),
-    }
-}
-
-pub fn receive_cw20(
-    deps: DepsMut,
-    info: MessageInfo,
-    cw20_msg: Cw20ReceiveMsg,
-) -> Result<Response<TerraMsgWrapper>, ContractError> {
-    let contract_addr = info.sender;
-
-    match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::DepositCollateral {}) => {
-            // only asset contract can execute this message
-            let config: Config = read_config(deps.storage)?;
-            if deps.api.addr_canonicalize(contract_addr.as_str())? != config.collateral_token {
-                return Err(ContractError::Unauthorized {});
-            }
-
-            let cw20_sender_addr = deps.api.addr_validate(&cw20_msg.sender)?;
-            deposit_collateral(deps, cw20_sender_addr, cw20_msg.amount.into())
-        }
-        _ => Err(ContractError::MissingDepositCollateralHook {}),
-    }
-}
-
-pub fn update_config(
-    deps: DepsMut,
-    info: MessageInfo,
-    owner: Option<Addr>,
-    liquidation_contract: Option<Addr>,
-) -> Result<Response<TerraMsgWrapper>, ContractError> {
-    let mut config: Config = read_config(deps.storage)?;
-
-    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
-
-    if let Some(owner) = owner {
-        config.owner = deps.api.addr_canonicalize(owner.as_str())?;
-    }
-
-    if let Some(liquidation_contract) = liquidation_contract {
-        config.liquidation_contract = deps.api.addr_canonicalize(liquidation_contract.as_str())?;
-    }
-
-    store_config(deps.storage, &config)?;
-    Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
+    custody::reply(deps, env, msg)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -161,23 +75,25 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             optional_addr_validate(deps.api, start_after)?,
             limit,
         )?),
+        QueryMsg::PauseInfo {} => to_binary(&query_pause_info(deps)?),
+        QueryMsg::RewardRouting {} => to_binary(&query_reward_routing(deps)?),
     }
 }
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config: Config = read_config(deps.storage)?;
+    let reward_contract = config
+        .reward_contract
+        .ok_or_else(|| StdError::generic_err("Reward contract is not set"))?;
     Ok(ConfigResponse {
         owner: deps.api.addr_humanize(&config.owner)?.to_string(),
-        collateral_token: deps
-            .api
-            .addr_humanize(&config.collateral_token)?
-            .to_string(),
+        collateral_token: config.collateral_token.to_normal(deps.api)?.to_string(),
         overseer_contract: deps
             .api
             .addr_humanize(&config.overseer_contract)?
             .to_string(),
         market_contract: deps.api.addr_humanize(&config.market_contract)?.to_string(),
-        reward_contract: deps.api.addr_humanize(&config.reward_contract)?.to_string(),
+        reward_contract: deps.api.addr_humanize(&reward_contract)?.to_string(),
         liquidation_contract: deps
             .api
             .addr_humanize(&config.liquidation_contract)?
@@ -186,3 +102,8 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         basset_info: config.basset_info,
     })
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    migrate_total_deposits(deps, env)
+}
@@ -1,23 +1,28 @@
-use cosmwasm_bignumber::Uint256;
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Api, Attribute, BankMsg, Coin, ContractResult, CosmosMsg,
-    Decimal, Reply, Response, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+    attr, from_binary, to_binary, to_vec, Api, Attribute, BankMsg, CanonicalAddr, Coin,
+    ContractResult, CosmosMsg, Decimal, Reply, Response, StdError, Storage, SubMsg,
+    SubMsgExecutionResponse, Uint128, WasmMsg,
 };
 
-use crate::contract::{
-    execute, instantiate, query, reply, CLAIM_REWARDS_OPERATION, SWAP_TO_STABLE_OPERATION,
-};
-use crate::error::ContractError;
+use crate::adapter::BETHAccruedRewardsResponse;
+use crate::contract::{execute, instantiate, migrate, query, reply};
 use crate::external::handle::RewardContractExecuteMsg;
-use crate::state::{read_borrower_info, BETHAccruedRewardsResponse};
 use crate::testing::mock_querier::mock_dependencies;
 
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_storage::to_length_prefixed;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use moneymarket::custody::{
-    BAssetInfo, BorrowerResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
+    BAssetInfo, BorrowerResponse, BorrowersResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg,
+    InstantiateMsg, MigrateMsg, QueryMsg, RewardRoutingResponse,
 };
 use moneymarket::liquidation::Cw20HookMsg as LiquidationCw20HookMsg;
+use moneymarket::pause::{PauseInfoResponse, PAUSE_ALL, PAUSE_DEPOSIT, PAUSE_UNLOCK};
+use moneymarket_custody_base::contract::{CLAIM_REWARDS_OPERATION, SWAP_TO_STABLE_OPERATION};
+use moneymarket_custody_base::error::ContractError;
+use moneymarket_custody_base::state::{read_borrower_info, store_borrower_info, BorrowerInfo};
+use serde::Serialize;
 use terra_cosmwasm::create_swap_msg;
 
 #[test]
@@ -179,6 +184,7 @@ fn deposit_collateral() {
             borrower: "addr0000".to_string(),
             balance: Uint256::from(100u128),
             spendable: Uint256::from(100u128),
+            pending_rewards: Uint256::zero(),
         }
     );
 
@@ -208,6 +214,7 @@ fn deposit_collateral() {
             borrower: "addr0000".to_string(),
             balance: Uint256::from(200u128),
             spendable: Uint256::from(200u128),
+            pending_rewards: Uint256::zero(),
         }
     );
 }
@@ -302,6 +309,7 @@ fn withdraw_collateral() {
             borrower: "addr0000".to_string(),
             balance: Uint256::from(50u64),
             spendable: Uint256::from(50u64),
+            pending_rewards: Uint256::zero(),
         }
     );
 
@@ -324,6 +332,7 @@ fn withdraw_collateral() {
             borrower: "addr0000".to_string(),
             balance: Uint256::from(10u128),
             spendable: Uint256::from(10u128),
+            pending_rewards: Uint256::zero(),
         }
     );
 
@@ -345,6 +354,7 @@ fn withdraw_collateral() {
             borrower: "addr0000".to_string(),
             balance: Uint256::zero(),
             spendable: Uint256::zero(),
+            pending_rewards: Uint256::zero(),
         }
     );
 }
@@ -466,6 +476,7 @@ fn lock_collateral() {
             borrower: "addr0000".to_string(),
             balance: Uint256::from(50u64),
             spendable: Uint256::from(0u64),
+            pending_rewards: Uint256::zero(),
         }
     );
 
@@ -544,6 +555,7 @@ fn lock_collateral() {
             borrower: "addr0000".to_string(),
             balance: Uint256::from(20u64),
             spendable: Uint256::from(0u64),
+            pending_rewards: Uint256::zero(),
         }
     );
 }
@@ -658,6 +670,8 @@ fn distribute_hook() {
         vec![
             attr("action", "distribute_rewards"),
             attr("buffer_rewards", "1000000"),
+            attr("borrower_rewards", "0"),
+            attr("collector_rewards", "0"),
         ]
     );
 
@@ -710,6 +724,8 @@ fn distribution_hook_zero_rewards() {
         vec![
             attr("action", "distribute_rewards"),
             attr("buffer_rewards", "0"),
+            attr("borrower_rewards", "0"),
+            attr("collector_rewards", "0"),
         ]
     );
 
@@ -843,6 +859,8 @@ fn liquidate_collateral() {
         liquidator: "addr0001".to_string(),
         borrower: "addr0000".to_string(),
         amount: Uint256::from(100u64),
+        liquidation_contract: None,
+        market_contract: None,
     };
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
@@ -861,8 +879,10 @@ fn liquidate_collateral() {
         liquidator: "liquidator".to_string(),
         borrower: "addr0000".to_string(),
         amount: Uint256::from(10u64),
+        liquidation_contract: None,
+        market_contract: None,
     };
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     assert_eq!(
         res.attributes,
         vec![
@@ -885,6 +905,36 @@ fn liquidate_collateral() {
                     liquidator: "liquidator".to_string(),
                     fee_address: Some("overseer".to_string()),
                     repay_address: Some("market".to_string()),
+                    borrower: Some("addr0000".to_string()),
+                })
+                .unwrap()
+            })
+            .unwrap(),
+        }))]
+    );
+
+    // loans of another stable denom are repaid through its own market
+    let msg = ExecuteMsg::LiquidateCollateral {
+        liquidator: "liquidator".to_string(),
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(10u64),
+        liquidation_contract: Some("liquidation_krw".to_string()),
+        market_contract: Some("market_krw".to_string()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "beth".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "liquidation_krw".to_string(),
+                amount: Uint128::from(10u128),
+                msg: to_binary(&LiquidationCw20HookMsg::ExecuteBid {
+                    liquidator: "liquidator".to_string(),
+                    fee_address: Some("overseer".to_string()),
+                    repay_address: Some("market_krw".to_string()),
+                    borrower: Some("addr0000".to_string()),
                 })
                 .unwrap()
             })
@@ -966,3 +1016,531 @@ fn proper_distribute_rewards_with_no_rewards() {
         ),]
     );
 }
+
+#[test]
+fn pause_operations() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_token: "beth".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        reward_contract: "reward".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "beth".to_string(),
+            symbol: "beth".to_string(),
+            decimals: 6,
+        },
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositCollateral {}).unwrap(),
+    });
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("beth", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    let msg2 = ExecuteMsg::LockCollateral {
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(50u64),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg2).unwrap();
+
+    // Unauthorized err
+    let msg2 = ExecuteMsg::UpdateGuardian {
+        guardian: Some("guardian".to_string()),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg2.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg2).unwrap();
+
+    let msg2 = ExecuteMsg::UpdatePause { paused: PAUSE_ALL };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg2).unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::PauseInfo {}).unwrap();
+    let pause_info: PauseInfoResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        pause_info,
+        PauseInfoResponse {
+            guardian: Some("guardian".to_string()),
+            paused: PAUSE_ALL,
+        }
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("beth", &[]), msg);
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::WithdrawCollateral { amount: None };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::LiquidateCollateral {
+        liquidator: "liquidator".to_string(),
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(10u64),
+        liquidation_contract: None,
+        market_contract: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("overseer", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // guardian cannot unpause
+    let msg2 = ExecuteMsg::UpdatePause {
+        paused: PAUSE_DEPOSIT | PAUSE_UNLOCK,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("guardian", &[]),
+        msg2.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg2).unwrap();
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg).unwrap();
+}
+
+#[test]
+fn deposit_cap() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_token: "beth".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        reward_contract: "reward".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "beth".to_string(),
+            symbol: "beth".to_string(),
+            decimals: 6,
+        },
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Unauthorized err
+    let msg = ExecuteMsg::UpdateDepositCap {
+        deposit_cap: Some(Uint256::from(100u64)),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(60u128),
+        msg: to_binary(&Cw20HookMsg::DepositCollateral {}).unwrap(),
+    });
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("beth", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("beth", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::DepositCapExceeded(100u128)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // withdrawals free up room under the cap
+    let msg2 = ExecuteMsg::WithdrawCollateral {
+        amount: Some(Uint256::from(20u64)),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg2).unwrap();
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("beth", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    let msg2 = ExecuteMsg::UpdateDepositCap { deposit_cap: None };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg2).unwrap();
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("beth", &[]), msg).unwrap();
+}
+
+#[test]
+fn migrate_total_deposits() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_token: "beth".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        reward_contract: "reward".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "beth".to_string(),
+            symbol: "beth".to_string(),
+            decimals: 6,
+        },
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // collateral deposited before deposits were tracked
+    let borrower_raw = deps.api.addr_canonicalize("addr0000").unwrap();
+    store_borrower_info(
+        &mut deps.storage,
+        &borrower_raw,
+        &BorrowerInfo {
+            balance: Uint256::from(100u64),
+            spendable: Uint256::from(100u64),
+            reward_index: Decimal256::zero(),
+            pending_rewards: Uint256::zero(),
+        },
+    )
+    .unwrap();
+    deps.querier.with_token_balances(&[(
+        &"beth".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+
+    let msg = ExecuteMsg::WithdrawCollateral {
+        amount: Some(Uint256::from(100u64)),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. }))
+            if msg == "Total deposits underflow" => {}
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "migrate"), attr("total_deposits", "100")]
+    );
+
+    // existing deposits count towards the cap
+    let msg2 = ExecuteMsg::UpdateDepositCap {
+        deposit_cap: Some(Uint256::from(150u64)),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg2).unwrap();
+
+    let msg2 = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(60u128),
+        msg: to_binary(&Cw20HookMsg::DepositCollateral {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info("beth", &[]), msg2);
+    match res {
+        Err(ContractError::DepositCapExceeded(150u128)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+}
+
+#[test]
+fn reward_routing() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_token: "beth".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        reward_contract: "reward".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "beth".to_string(),
+            symbol: "beth".to_string(),
+            decimals: 6,
+        },
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // all rewards go to the overseer by default
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::RewardRouting {}).unwrap();
+    let routing_res: RewardRoutingResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        routing_res,
+        RewardRoutingResponse {
+            buffer_share: Decimal256::one(),
+            borrower_share: Decimal256::zero(),
+            collector_share: Decimal256::zero(),
+            collector_contract: None,
+        }
+    );
+
+    let msg = ExecuteMsg::UpdateRewardRouting {
+        buffer_share: Decimal256::percent(50),
+        borrower_share: Decimal256::percent(30),
+        collector_share: Decimal256::percent(20),
+        collector_contract: Some("collector".to_string()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // shares must sum to one
+    let msg = ExecuteMsg::UpdateRewardRouting {
+        buffer_share: Decimal256::percent(50),
+        borrower_share: Decimal256::percent(30),
+        collector_share: Decimal256::percent(30),
+        collector_contract: Some("collector".to_string()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+    match res {
+        Err(ContractError::InvalidRewardRouting {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // collector share needs a collector
+    let msg = ExecuteMsg::UpdateRewardRouting {
+        buffer_share: Decimal256::percent(50),
+        borrower_share: Decimal256::percent(30),
+        collector_share: Decimal256::percent(20),
+        collector_contract: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+    match res {
+        Err(ContractError::InvalidRewardRouting {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::UpdateRewardRouting {
+        buffer_share: Decimal256::percent(50),
+        borrower_share: Decimal256::percent(30),
+        collector_share: Decimal256::percent(20),
+        collector_contract: Some("collector".to_string()),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    for (borrower, amount) in [("addr0000", 100u128), ("addr0001", 300u128)] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: borrower.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::DepositCollateral {}).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("beth", &[]), msg).unwrap();
+    }
+
+    // Claimed rewards is 1000000uusd
+    deps.querier.set_other_balances(Uint128::new(1000000));
+    let reply_msg = Reply {
+        id: 2,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_rewards"),
+            attr("buffer_rewards", "500000"),
+            attr("borrower_rewards", "300000"),
+            attr("collector_rewards", "200000"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "overseer".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(500000u128)
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(200000u128)
+                }],
+            })),
+        ]
+    );
+
+    // borrower rewards accrue pro rata to the collateral balance
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Borrower {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let borrower_res: BorrowerResponse = from_binary(&query_res).unwrap();
+    assert_eq!(borrower_res.pending_rewards, Uint256::from(75000u64));
+
+    let msg = ExecuteMsg::ClaimRewards {};
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(75000u128)
+            }],
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_rewards"),
+            attr("borrower", "addr0000"),
+            attr("claim_amount", "75000"),
+        ]
+    );
+
+    // withdrawn collateral no longer earns, unclaimed rewards are not redistributed
+    let msg = ExecuteMsg::WithdrawCollateral { amount: None };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps.querier.set_other_balances(Uint128::new(1225000));
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_rewards"),
+            attr("buffer_rewards", "500000"),
+            attr("borrower_rewards", "300000"),
+            attr("collector_rewards", "200000"),
+        ]
+    );
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Borrowers {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let borrowers_res: BorrowersResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        borrowers_res.borrowers,
+        vec![BorrowerResponse {
+            borrower: "addr0001".to_string(),
+            balance: Uint256::from(300u64),
+            spendable: Uint256::from(300u64),
+            pending_rewards: Uint256::from(525000u64),
+        }]
+    );
+}
+
+#[test]
+fn read_legacy_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    // configs stored before the custody logic was shared
+    // always hold a reward contract
+    #[derive(Serialize)]
+    struct LegacyConfig {
+        owner: CanonicalAddr,
+        collateral_token: CanonicalAddr,
+        overseer_contract: CanonicalAddr,
+        market_contract: CanonicalAddr,
+        reward_contract: CanonicalAddr,
+        liquidation_contract: CanonicalAddr,
+        stable_denom: String,
+        basset_info: BAssetInfo,
+    }
+
+    let api = deps.api;
+    let legacy_config = LegacyConfig {
+        owner: api.addr_canonicalize("owner").unwrap(),
+        collateral_token: api.addr_canonicalize("beth").unwrap(),
+        overseer_contract: api.addr_canonicalize("overseer").unwrap(),
+        market_contract: api.addr_canonicalize("market").unwrap(),
+        reward_contract: api.addr_canonicalize("reward").unwrap(),
+        liquidation_contract: api.addr_canonicalize("liquidation").unwrap(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "beth".to_string(),
+            symbol: "beth".to_string(),
+            decimals: 6,
+        },
+    };
+    deps.storage.set(
+        &to_length_prefixed(b"config"),
+        &to_vec(&legacy_config).unwrap(),
+    );
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!("owner".to_string(), config_res.owner);
+    assert_eq!("beth".to_string(), config_res.collateral_token);
+    assert_eq!("reward".to_string(), config_res.reward_contract);
+    assert_eq!("liquidation".to_string(), config_res.liquidation_contract);
+}
@@ -1,8 +1,5 @@
-pub mod collateral;
+pub mod adapter;
 pub mod contract;
-pub mod distribution;
-pub mod error;
-pub mod state;
 
 mod external;
 
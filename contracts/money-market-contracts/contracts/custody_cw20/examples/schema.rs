@@ -0,0 +1,27 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use moneymarket::custody::{
+    BorrowerResponse, BorrowersResponse, Cw20HookMsg, ExecuteMsg, QueryMsg, RewardRoutingResponse,
+};
+use moneymarket::custody_cw20::{ConfigResponse, InstantiateMsg};
+use moneymarket::pause::PauseInfoResponse;
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(Cw20HookMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(BorrowerResponse), &out_dir);
+    export_schema(&schema_for!(BorrowersResponse), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(PauseInfoResponse), &out_dir);
+    export_schema(&schema_for!(RewardRoutingResponse), &out_dir);
+}
@@ -0,0 +1,5 @@
+pub mod adapter;
+pub mod contract;
+
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,91 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, BalanceResponse, BankQuery, Coin, ContractResult,
+    OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use moneymarket::custody_cw20::{AccruedRewardsResponse, RewardAdapterQueryMsg};
+use terra_cosmwasm::TerraQueryWrapper;
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<TerraQueryWrapper>,
+    accrued_rewards: AccruedRewardsResponse,
+    reward_balance: Uint128,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<TerraQueryWrapper> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: _,
+                msg,
+            }) => match from_binary(msg).unwrap() {
+                RewardAdapterQueryMsg::AccruedRewards { address: _ } => {
+                    SystemResult::Ok(ContractResult::from(to_binary(&AccruedRewardsResponse {
+                        rewards: self.accrued_rewards.rewards,
+                    })))
+                }
+            },
+            QueryRequest::Bank(BankQuery::Balance { address, denom })
+                if address == "reward" && denom == "uusd" =>
+            {
+                let bank_res = BalanceResponse {
+                    amount: Coin {
+                        amount: self.reward_balance,
+                        denom: denom.to_string(),
+                    },
+                };
+                SystemResult::Ok(ContractResult::from(to_binary(&bank_res)))
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<TerraQueryWrapper>) -> Self {
+        WasmMockQuerier {
+            base,
+            accrued_rewards: AccruedRewardsResponse::default(),
+            reward_balance: Uint128::zero(),
+        }
+    }
+
+    pub fn set_accrued_rewards(&mut self, new_state: AccruedRewardsResponse) {
+        self.accrued_rewards = new_state
+    }
+
+    pub fn set_reward_balance(&mut self, balance: Uint128) {
+        self.reward_balance = balance
+    }
+}
@@ -0,0 +1,35 @@
+use cosmwasm_std::{
+    to_binary, Addr, CosmosMsg, Deps, QueryRequest, StdResult, Uint128, WasmMsg, WasmQuery,
+};
+
+use moneymarket::custody_cw20::{
+    AccruedRewardsResponse, RewardAdapterExecuteMsg, RewardAdapterQueryMsg,
+};
+use moneymarket_custody_base::adapter::RewardAdapter;
+use terra_cosmwasm::TerraMsgWrapper;
+
+/// Claims the collateral rewards from the `reward_adapter` given at
+/// instantiation, through the `moneymarket::custody_cw20` adapter interface
+pub struct NativeRewardAdapter;
+
+impl RewardAdapter for NativeRewardAdapter {
+    fn accrued_rewards(deps: Deps, reward_contract: &Addr, custody: &Addr) -> StdResult<Uint128> {
+        let rewards: AccruedRewardsResponse =
+            deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: reward_contract.to_string(),
+                msg: to_binary(&RewardAdapterQueryMsg::AccruedRewards {
+                    address: custody.to_string(),
+                })?,
+            }))?;
+
+        Ok(rewards.rewards)
+    }
+
+    fn claim_rewards_msg(reward_contract: &Addr) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: reward_contract.to_string(),
+            funds: vec![],
+            msg: to_binary(&RewardAdapterExecuteMsg::ClaimRewards { recipient: None })?,
+        }))
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult,
+};
+
+use crate::adapter::NativeRewardAdapter;
+
+use moneymarket::common::optional_addr_validate;
+use moneymarket::custody::{ExecuteMsg, QueryMsg};
+use moneymarket::custody_native::{ConfigResponse, InstantiateMsg};
+use moneymarket::tokens::{AssetInfo, AssetInfoRaw};
+use moneymarket_custody_base::collateral::{query_borrower, query_borrowers};
+use moneymarket_custody_base::contract::{self as custody, query_pause_info};
+use moneymarket_custody_base::distribution::query_reward_routing;
+use moneymarket_custody_base::error::ContractError;
+use moneymarket_custody_base::state::{read_config, store_config, Config};
+use terra_cosmwasm::TerraMsgWrapper;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    // rewards are swapped to the stable denom,
+    // so the collateral must be kept apart from them
+    if msg.collateral_denom == msg.stable_denom {
+        return Err(StdError::generic_err(
+            "Collateral denom cannot be the stable denom",
+        ));
+    }
+
+    let config = Config {
+        owner: deps.api.addr_canonicalize(&msg.owner)?,
+        overseer_contract: deps.api.addr_canonicalize(&msg.overseer_contract)?,
+        collateral_token: AssetInfoRaw::NativeToken {
+            denom: msg.collateral_denom,
+        },
+        market_contract: deps.api.addr_canonicalize(&msg.market_contract)?,
+        liquidation_contract: deps.api.addr_canonicalize(&msg.liquidation_contract)?,
+        stable_denom: msg.stable_denom,
+        basset_info: msg.basset_info,
+        reward_contract: match msg.reward_adapter {
+            Some(reward_adapter) => Some(deps.api.addr_canonicalize(&reward_adapter)?),
+            None => None,
+        },
+    };
+
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    custody::execute::<NativeRewardAdapter>(deps, env, info, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    custody::reply(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Borrower { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_binary(&query_borrower(deps, addr)?)
+        }
+        QueryMsg::Borrowers { start_after, limit } => to_binary(&query_borrowers(
+            deps,
+            optional_addr_validate(deps.api, start_after)?,
+            limit,
+        )?),
+        QueryMsg::PauseInfo {} => to_binary(&query_pause_info(deps)?),
+        QueryMsg::RewardRouting {} => to_binary(&query_reward_routing(deps)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let collateral_denom = match config.collateral_token.to_normal(deps.api)? {
+        AssetInfo::NativeToken { denom } => denom,
+        AssetInfo::Token { .. } => {
+            return Err(StdError::generic_err("Collateral is not a native coin"))
+        }
+    };
+
+    Ok(ConfigResponse {
+        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        collateral_denom,
+        overseer_contract: deps
+            .api
+            .addr_humanize(&config.overseer_contract)?
+            .to_string(),
+        market_contract: deps.api.addr_humanize(&config.market_contract)?.to_string(),
+        liquidation_contract: deps
+            .api
+            .addr_humanize(&config.liquidation_contract)?
+            .to_string(),
+        stable_denom: config.stable_denom,
+        basset_info: config.basset_info,
+        reward_adapter: match config.reward_contract {
+            Some(reward_adapter) => Some(deps.api.addr_humanize(&reward_adapter)?.to_string()),
+            None => None,
+        },
+    })
+}
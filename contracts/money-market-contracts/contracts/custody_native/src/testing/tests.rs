@@ -0,0 +1,610 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, coins, from_binary, to_binary, Attribute, BankMsg, Coin, ContractResult, CosmosMsg,
+    Reply, StdError, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+};
+
+use crate::contract::{execute, instantiate, query, reply};
+use crate::testing::mock_querier::mock_dependencies;
+
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cw20::Cw20ReceiveMsg;
+use moneymarket::custody::{BAssetInfo, BorrowerResponse, Cw20HookMsg, ExecuteMsg, QueryMsg};
+use moneymarket::custody_cw20::{AccruedRewardsResponse, RewardAdapterExecuteMsg};
+use moneymarket::custody_native::{ConfigResponse, InstantiateMsg};
+use moneymarket::liquidation_queue::ExecuteMsg as LiquidationQueueExecuteMsg;
+use moneymarket_custody_base::contract::{CLAIM_REWARDS_OPERATION, SWAP_TO_STABLE_OPERATION};
+use moneymarket_custody_base::error::ContractError;
+use terra_cosmwasm::create_swap_msg;
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_denom: "uluna".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "token".to_string(),
+            symbol: "token".to_string(),
+            decimals: 6,
+        },
+        reward_adapter: Some("reward".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+
+    // the stable denom cannot be the collateral
+    let mut invalid_msg = msg.clone();
+    invalid_msg.collateral_denom = "uusd".to_string();
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), invalid_msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "Collateral denom cannot be the stable denom")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!("owner".to_string(), config_res.owner);
+    assert_eq!("uluna".to_string(), config_res.collateral_denom);
+    assert_eq!("overseer".to_string(), config_res.overseer_contract);
+    assert_eq!("market".to_string(), config_res.market_contract);
+    assert_eq!(Some("reward".to_string()), config_res.reward_adapter);
+    assert_eq!("liquidation".to_string(), config_res.liquidation_contract);
+    assert_eq!("uusd".to_string(), config_res.stable_denom);
+}
+
+#[test]
+fn deposit_collateral() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_denom: "uluna".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "token".to_string(),
+            symbol: "token".to_string(),
+            decimals: 6,
+        },
+        reward_adapter: Some("reward".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::DepositCollateral {};
+
+    // failed; no collateral denom sent
+    let info = mock_info("addr0000", &coins(100u128, "uusd"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(ContractError::ZeroDeposit(denom)) => assert_eq!(denom, "uluna"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // failed; CW20 tokens are not collateral of this custody
+    let msg2 = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositCollateral {}).unwrap(),
+    });
+    let res2 = execute(deps.as_mut(), mock_env(), mock_info("token", &[]), msg2);
+    match res2 {
+        Err(ContractError::UnsupportedDeposit {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("addr0000", &coins(100u128, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "100"),
+        ]
+    );
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Borrower {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+
+    let borrower_res: BorrowerResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        borrower_res,
+        BorrowerResponse {
+            borrower: "addr0000".to_string(),
+            balance: Uint256::from(100u128),
+            spendable: Uint256::from(100u128),
+            pending_rewards: Uint256::zero(),
+        }
+    );
+
+    // Deposit more
+    let info = mock_info("addr0000", &coins(100u128, "uluna"));
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "100"),
+        ]
+    );
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Borrower {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let borrower_res: BorrowerResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        borrower_res,
+        BorrowerResponse {
+            borrower: "addr0000".to_string(),
+            balance: Uint256::from(200u128),
+            spendable: Uint256::from(200u128),
+            pending_rewards: Uint256::zero(),
+        }
+    );
+}
+
+#[test]
+fn deposit_collateral_for() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_denom: "uluna".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "token".to_string(),
+            symbol: "token".to_string(),
+            decimals: 6,
+        },
+        reward_adapter: Some("reward".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the liquidation contract returns the collateral of a cancelled auction
+    let msg = ExecuteMsg::DepositCollateralFor {
+        borrower: "addr0000".to_string(),
+    };
+    let info = mock_info("liquidation", &coins(100u128, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "100"),
+        ]
+    );
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Borrower {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let borrower_res: BorrowerResponse = from_binary(&query_res).unwrap();
+    assert_eq!(borrower_res.balance, Uint256::from(100u128));
+    assert_eq!(borrower_res.spendable, Uint256::from(100u128));
+}
+
+#[test]
+fn withdraw_collateral() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_denom: "uluna".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "token".to_string(),
+            symbol: "token".to_string(),
+            decimals: 6,
+        },
+        reward_adapter: Some("reward".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::DepositCollateral {};
+
+    let info = mock_info("addr0000", &coins(100u128, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "100"),
+        ]
+    );
+
+    let msg = ExecuteMsg::WithdrawCollateral {
+        amount: Some(Uint256::from(110u64)),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::WithdrawAmountExceedsSpendable(100)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::WithdrawCollateral {
+        amount: Some(Uint256::from(50u64)),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "50"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: coins(50u128, "uluna"),
+        }))]
+    );
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Borrower {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let borrower_res: BorrowerResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        borrower_res,
+        BorrowerResponse {
+            borrower: "addr0000".to_string(),
+            balance: Uint256::from(50u64),
+            spendable: Uint256::from(50u64),
+            pending_rewards: Uint256::zero(),
+        }
+    );
+
+    let msg = ExecuteMsg::WithdrawCollateral {
+        amount: Some(Uint256::from(40u128)),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Borrower {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let borrower_res: BorrowerResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        borrower_res,
+        BorrowerResponse {
+            borrower: "addr0000".to_string(),
+            balance: Uint256::from(10u128),
+            spendable: Uint256::from(10u128),
+            pending_rewards: Uint256::zero(),
+        }
+    );
+
+    //withdraw with "None" amount
+    let msg = ExecuteMsg::WithdrawCollateral { amount: None };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Borrower {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let borrower_res: BorrowerResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        borrower_res,
+        BorrowerResponse {
+            borrower: "addr0000".to_string(),
+            balance: Uint256::zero(),
+            spendable: Uint256::zero(),
+            pending_rewards: Uint256::zero(),
+        }
+    );
+}
+
+#[test]
+fn distribute_rewards() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1000000u128),
+    }]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_denom: "uluna".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "token".to_string(),
+            symbol: "token".to_string(),
+            decimals: 6,
+        },
+        reward_adapter: Some("reward".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::DistributeRewards {};
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    deps.querier.set_reward_balance(Uint128::new(10000000));
+    deps.querier.set_accrued_rewards(AccruedRewardsResponse {
+        rewards: Uint128::new(10000000),
+    });
+
+    let msg = ExecuteMsg::DistributeRewards {};
+    let info = mock_info("overseer", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // Do not print logs at this step
+    let empty_vector: Vec<Attribute> = Vec::new();
+    assert_eq!(res.attributes, empty_vector);
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward".to_string(),
+                funds: vec![],
+                msg: to_binary(&RewardAdapterExecuteMsg::ClaimRewards { recipient: None }).unwrap(),
+            }),
+            CLAIM_REWARDS_OPERATION
+        )]
+    );
+}
+
+#[test]
+fn swap_to_stable_denom() {
+    let mut deps = mock_dependencies(&[
+        Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        },
+        Coin {
+            denom: "ukrw".to_string(),
+            amount: Uint128::from(20000000000u128),
+        },
+        Coin {
+            denom: "usdr".to_string(),
+            amount: Uint128::from(2000000u128),
+        },
+        // collateral is not swapped
+        Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::from(5000000u128),
+        },
+    ]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_denom: "uluna".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "token".to_string(),
+            symbol: "token".to_string(),
+            decimals: 6,
+        },
+        reward_adapter: Some("reward".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // mimic callback from distribute_rewards to execute swap_to_stable_denom
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(create_swap_msg(
+                Coin {
+                    denom: "ukrw".to_string(),
+                    amount: Uint128::from(20000000000u128),
+                },
+                "uusd".to_string(),
+            )),
+            SubMsg::reply_on_success(
+                create_swap_msg(
+                    Coin {
+                        denom: "usdr".to_string(),
+                        amount: Uint128::from(2000000u128),
+                    },
+                    "uusd".to_string(),
+                ),
+                SWAP_TO_STABLE_OPERATION
+            ),
+        ]
+    );
+}
+
+#[test]
+fn liquidate_collateral() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        collateral_denom: "uluna".to_string(),
+        overseer_contract: "overseer".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "token".to_string(),
+            symbol: "token".to_string(),
+            decimals: 6,
+        },
+        reward_adapter: Some("reward".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::DepositCollateral {};
+
+    let info = mock_info("addr0000", &coins(100u128, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "100"),
+        ]
+    );
+
+    let msg = ExecuteMsg::LockCollateral {
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(50u64),
+    };
+    let info = mock_info("overseer", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "lock_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "50"),
+        ]
+    );
+
+    let msg = ExecuteMsg::LiquidateCollateral {
+        liquidator: "addr0001".to_string(),
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(100u64),
+        liquidation_contract: None,
+        market_contract: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("overseer", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::LiquidationAmountExceedsLocked(50)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+    let msg = ExecuteMsg::LiquidateCollateral {
+        liquidator: "liquidator".to_string(),
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(10u64),
+        liquidation_contract: None,
+        market_contract: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "liquidate_collateral"),
+            attr("liquidator", "liquidator"),
+            attr("borrower", "addr0000"),
+            attr("amount", "10"),
+        ]
+    );
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "liquidation".to_string(),
+            funds: coins(10u128, "uluna"),
+            msg: to_binary(&LiquidationQueueExecuteMsg::ExecuteBid {
+                liquidator: "liquidator".to_string(),
+                fee_address: Some("overseer".to_string()),
+                repay_address: Some("market".to_string()),
+                borrower: Some("addr0000".to_string()),
+            })
+            .unwrap(),
+        }))]
+    );
+
+    // loans of another stable denom are repaid through its own market
+    let msg = ExecuteMsg::LiquidateCollateral {
+        liquidator: "liquidator".to_string(),
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(10u64),
+        liquidation_contract: Some("liquidation_krw".to_string()),
+        market_contract: Some("market_krw".to_string()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "liquidation_krw".to_string(),
+            funds: coins(10u128, "uluna"),
+            msg: to_binary(&LiquidationQueueExecuteMsg::ExecuteBid {
+                liquidator: "liquidator".to_string(),
+                fee_address: Some("overseer".to_string()),
+                repay_address: Some("market_krw".to_string()),
+                borrower: Some("addr0000".to_string()),
+            })
+            .unwrap(),
+        }))]
+    );
+}
@@ -1,14 +1,21 @@
 use crate::error::ContractError;
-use crate::state::{read_config, store_config, Config};
+use crate::state::{
+    read_config, read_rate_controller, read_rate_controller_state, remove_rate_controller,
+    store_config, store_rate_controller, store_rate_controller_state, Config, RateController,
+    RateControllerState,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
 use cosmwasm_bignumber::Decimal256;
 use cosmwasm_bignumber::Uint256;
-use cosmwasm_std::{to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    attr, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+};
 use moneymarket::common::optional_addr_validate;
 use moneymarket::interest_model::{
-    BorrowRateResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
+    BorrowRateResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, InterestKink, QueryMsg,
+    RateControllerConfig, RateControllerResponse,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -17,13 +24,16 @@ pub fn instantiate(
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
+    assert_kinks(&msg.kinks)?;
+
     store_config(
         deps.storage,
         &Config {
             owner: deps.api.addr_canonicalize(&msg.owner)?,
             base_rate: msg.base_rate,
             interest_multiplier: msg.interest_multiplier,
+            kinks: msg.kinks,
         },
     )?;
 
@@ -33,7 +43,7 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -42,6 +52,7 @@ pub fn execute(
             owner,
             base_rate,
             interest_multiplier,
+            kinks,
         } => {
             let api = deps.api;
             update_config(
@@ -50,8 +61,24 @@ pub fn execute(
                 optional_addr_validate(api, owner)?,
                 base_rate,
                 interest_multiplier,
+                kinks,
             )
         }
+        ExecuteMsg::UpdateRateController { rate_controller } => {
+            update_rate_controller(deps, env, info, rate_controller)
+        }
+        ExecuteMsg::ExecuteEpochOperations {
+            market_balance,
+            total_liabilities,
+            total_reserves,
+        } => execute_epoch_operations(
+            deps,
+            env,
+            info,
+            market_balance,
+            total_liabilities,
+            total_reserves,
+        ),
     }
 }
 
@@ -61,6 +88,7 @@ pub fn update_config(
     owner: Option<Addr>,
     base_rate: Option<Decimal256>,
     interest_multiplier: Option<Decimal256>,
+    kinks: Option<Vec<InterestKink>>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = read_config(deps.storage)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
@@ -79,14 +107,133 @@ pub fn update_config(
         config.interest_multiplier = interest_multiplier;
     }
 
+    if let Some(kinks) = kinks {
+        assert_kinks(&kinks)?;
+        config.kinks = kinks;
+    }
+
     store_config(deps.storage, &config)?;
     Ok(Response::default())
 }
 
+pub fn update_rate_controller(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rate_controller: Option<RateControllerConfig>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(rate_controller) = rate_controller {
+        if rate_controller.target_utilization.is_zero()
+            || rate_controller.target_utilization > Decimal256::one()
+            || rate_controller.min_interest_multiplier > rate_controller.max_interest_multiplier
+        {
+            return Err(ContractError::InvalidRateController {});
+        }
+
+        store_rate_controller(
+            deps.storage,
+            &RateController {
+                market_contract: deps
+                    .api
+                    .addr_canonicalize(&rate_controller.market_contract)?,
+                target_utilization: rate_controller.target_utilization,
+                proportional_gain: rate_controller.proportional_gain,
+                integral_gain: rate_controller.integral_gain,
+                min_interest_multiplier: rate_controller.min_interest_multiplier,
+                max_interest_multiplier: rate_controller.max_interest_multiplier,
+            },
+        )?;
+
+        // start without proportional kick
+        store_rate_controller_state(
+            deps.storage,
+            &RateControllerState {
+                last_utilization: rate_controller.target_utilization,
+                last_executed_height: env.block.height,
+            },
+        )?;
+    } else {
+        remove_rate_controller(deps.storage);
+    }
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_rate_controller")]))
+}
+
+pub fn execute_epoch_operations(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    market_balance: Uint256,
+    total_liabilities: Decimal256,
+    total_reserves: Decimal256,
+) -> Result<Response, ContractError> {
+    // static model; nothing to adjust
+    let rate_controller: RateController = match read_rate_controller(deps.storage)? {
+        Some(rate_controller) => rate_controller,
+        None => return Ok(Response::default()),
+    };
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != rate_controller.market_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut config: Config = read_config(deps.storage)?;
+    let state: RateControllerState = read_rate_controller_state(deps.storage)?;
+
+    let utilization_ratio =
+        compute_utilization_ratio(market_balance, total_liabilities, total_reserves);
+
+    // multiplier += proportional_gain * (utilization - last_utilization)
+    //     + integral_gain * (utilization - target_utilization)
+    let increase = rate_controller.proportional_gain * utilization_ratio
+        + rate_controller.integral_gain * utilization_ratio;
+    let decrease = rate_controller.proportional_gain * state.last_utilization
+        + rate_controller.integral_gain * rate_controller.target_utilization;
+
+    let interest_multiplier = config.interest_multiplier + increase;
+    let interest_multiplier = if interest_multiplier > decrease {
+        interest_multiplier - decrease
+    } else {
+        Decimal256::zero()
+    };
+
+    config.interest_multiplier = if interest_multiplier > rate_controller.max_interest_multiplier {
+        rate_controller.max_interest_multiplier
+    } else if interest_multiplier < rate_controller.min_interest_multiplier {
+        rate_controller.min_interest_multiplier
+    } else {
+        interest_multiplier
+    };
+
+    store_config(deps.storage, &config)?;
+    store_rate_controller_state(
+        deps.storage,
+        &RateControllerState {
+            last_utilization: utilization_ratio,
+            last_executed_height: env.block.height,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "execute_epoch_operations"),
+        attr("utilization_ratio", utilization_ratio.to_string()),
+        attr(
+            "interest_multiplier",
+            config.interest_multiplier.to_string(),
+        ),
+    ]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::RateController {} => to_binary(&query_rate_controller(deps)?),
         QueryMsg::BorrowRate {
             market_balance,
             total_liabilities,
@@ -106,11 +253,39 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         owner: deps.api.addr_humanize(&state.owner)?.to_string(),
         base_rate: state.base_rate,
         interest_multiplier: state.interest_multiplier,
+        kinks: state.kinks,
     };
 
     Ok(resp)
 }
 
+fn query_rate_controller(deps: Deps) -> StdResult<RateControllerResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let state: RateControllerState = read_rate_controller_state(deps.storage)?;
+    let rate_controller = if let Some(rate_controller) = read_rate_controller(deps.storage)? {
+        Some(RateControllerConfig {
+            market_contract: deps
+                .api
+                .addr_humanize(&rate_controller.market_contract)?
+                .to_string(),
+            target_utilization: rate_controller.target_utilization,
+            proportional_gain: rate_controller.proportional_gain,
+            integral_gain: rate_controller.integral_gain,
+            min_interest_multiplier: rate_controller.min_interest_multiplier,
+            max_interest_multiplier: rate_controller.max_interest_multiplier,
+        })
+    } else {
+        None
+    };
+
+    Ok(RateControllerResponse {
+        rate_controller,
+        interest_multiplier: config.interest_multiplier,
+        last_utilization: state.last_utilization,
+        last_executed_height: state.last_executed_height,
+    })
+}
+
 fn query_borrow_rate(
     deps: Deps,
     market_balance: Uint256,
@@ -118,18 +293,58 @@ fn query_borrow_rate(
     total_reserves: Decimal256,
 ) -> StdResult<BorrowRateResponse> {
     let config: Config = read_config(deps.storage)?;
+    let utilization_ratio =
+        compute_utilization_ratio(market_balance, total_liabilities, total_reserves);
 
+    Ok(BorrowRateResponse {
+        rate: compute_borrow_rate(&config, utilization_ratio),
+    })
+}
+
+fn compute_utilization_ratio(
+    market_balance: Uint256,
+    total_liabilities: Decimal256,
+    total_reserves: Decimal256,
+) -> Decimal256 {
     // ignore decimal parts
     let total_value_in_market =
         Decimal256::from_uint256(market_balance) + total_liabilities - total_reserves;
 
-    let utilization_ratio = if total_value_in_market.is_zero() {
+    if total_value_in_market.is_zero() {
         Decimal256::zero()
     } else {
         total_liabilities / total_value_in_market
-    };
+    }
+}
 
-    Ok(BorrowRateResponse {
-        rate: utilization_ratio * config.interest_multiplier + config.base_rate,
-    })
+/// Evaluates the piecewise-linear borrow rate curve; each kink
+/// replaces the slope for the utilization above it
+pub fn compute_borrow_rate(config: &Config, utilization_ratio: Decimal256) -> Decimal256 {
+    let mut rate = config.base_rate;
+    let mut segment_start = Decimal256::zero();
+    let mut interest_multiplier = config.interest_multiplier;
+    for kink in config.kinks.iter() {
+        if utilization_ratio <= kink.utilization {
+            break;
+        }
+
+        rate += (kink.utilization - segment_start) * interest_multiplier;
+        segment_start = kink.utilization;
+        interest_multiplier = kink.interest_multiplier;
+    }
+
+    rate + (utilization_ratio - segment_start) * interest_multiplier
+}
+
+fn assert_kinks(kinks: &[InterestKink]) -> Result<(), ContractError> {
+    let mut prev_utilization = Decimal256::zero();
+    for kink in kinks.iter() {
+        if kink.utilization <= prev_utilization || kink.utilization > Decimal256::one() {
+            return Err(ContractError::InvalidKinks {});
+        }
+
+        prev_utilization = kink.utilization;
+    }
+
+    Ok(())
 }
@@ -11,4 +11,10 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Invalid kinks; utilizations must be strictly increasing and within (0, 1]")]
+    InvalidKinks {},
+
+    #[error("Invalid rate controller; target utilization must be within (0, 1] and min multiplier cannot exceed max multiplier")]
+    InvalidRateController {},
 }
@@ -4,14 +4,20 @@ use serde::{Deserialize, Serialize};
 use cosmwasm_bignumber::Decimal256;
 use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
 use cosmwasm_storage::{singleton, singleton_read};
+use moneymarket::interest_model::InterestKink;
 
 static KEY_CONFIG: &[u8] = b"config";
+static KEY_RATE_CONTROLLER: &[u8] = b"rate_controller";
+static KEY_RATE_CONTROLLER_STATE: &[u8] = b"rate_controller_state";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: CanonicalAddr,
     pub base_rate: Decimal256,
     pub interest_multiplier: Decimal256,
+    /// Missing from configs stored before kinks were introduced
+    #[serde(default)]
+    pub kinks: Vec<InterestKink>,
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -21,3 +27,51 @@ pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()>
 pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     singleton_read(storage, KEY_CONFIG).load()
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateController {
+    pub market_contract: CanonicalAddr,
+    pub target_utilization: Decimal256,
+    pub proportional_gain: Decimal256,
+    pub integral_gain: Decimal256,
+    pub min_interest_multiplier: Decimal256,
+    pub max_interest_multiplier: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateControllerState {
+    pub last_utilization: Decimal256,
+    pub last_executed_height: u64,
+}
+
+pub fn store_rate_controller(
+    storage: &mut dyn Storage,
+    rate_controller: &RateController,
+) -> StdResult<()> {
+    singleton(storage, KEY_RATE_CONTROLLER).save(rate_controller)
+}
+
+pub fn remove_rate_controller(storage: &mut dyn Storage) {
+    singleton::<RateController>(storage, KEY_RATE_CONTROLLER).remove()
+}
+
+pub fn read_rate_controller(storage: &dyn Storage) -> StdResult<Option<RateController>> {
+    singleton_read(storage, KEY_RATE_CONTROLLER).may_load()
+}
+
+pub fn store_rate_controller_state(
+    storage: &mut dyn Storage,
+    state: &RateControllerState,
+) -> StdResult<()> {
+    singleton(storage, KEY_RATE_CONTROLLER_STATE).save(state)
+}
+
+pub fn read_rate_controller_state(storage: &dyn Storage) -> StdResult<RateControllerState> {
+    match singleton_read(storage, KEY_RATE_CONTROLLER_STATE).may_load()? {
+        Some(state) => Ok(state),
+        None => Ok(RateControllerState {
+            last_utilization: Decimal256::zero(),
+            last_executed_height: 0,
+        }),
+    }
+}
@@ -1,11 +1,15 @@
 use crate::contract::{execute, instantiate, query};
 use crate::error::ContractError;
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::from_binary;
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{attr, from_binary, Api, CanonicalAddr};
+use cosmwasm_storage::singleton;
 use moneymarket::interest_model::{
-    BorrowRateResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
+    BorrowRateResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, InterestKink, QueryMsg,
+    RateControllerConfig, RateControllerResponse,
 };
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 #[test]
 fn proper_initialization() {
@@ -15,6 +19,7 @@ fn proper_initialization() {
         owner: "owner0000".to_string(),
         base_rate: Decimal256::percent(10),
         interest_multiplier: Decimal256::percent(10),
+        kinks: vec![],
     };
 
     let info = mock_info("addr0000", &[]);
@@ -59,6 +64,7 @@ fn update_config() {
         owner: "owner0000".to_string(),
         base_rate: Decimal256::percent(10),
         interest_multiplier: Decimal256::percent(10),
+        kinks: vec![],
     };
 
     let info = mock_info("addr0000", &[]);
@@ -70,6 +76,7 @@ fn update_config() {
         owner: Some("owner0001".to_string()),
         base_rate: None,
         interest_multiplier: None,
+        kinks: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -88,6 +95,7 @@ fn update_config() {
         owner: None,
         base_rate: Some(Decimal256::percent(1)),
         interest_multiplier: Some(Decimal256::percent(1)),
+        kinks: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -96,3 +104,262 @@ fn update_config() {
         _ => panic!("Must return unauthorized error"),
     }
 }
+
+#[test]
+fn kinked_borrow_rate() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_rate: Decimal256::percent(2),
+        interest_multiplier: Decimal256::percent(10),
+        kinks: vec![InterestKink {
+            utilization: Decimal256::percent(80),
+            interest_multiplier: Decimal256::percent(300),
+        }],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // below the kink; utilization_ratio = 0.5
+    // borrow_rate = 0.02 + 0.5 * 0.1
+    let query_msg = QueryMsg::BorrowRate {
+        market_balance: Uint256::from(500000u128),
+        total_liabilities: Decimal256::from_uint256(500000u128),
+        total_reserves: Decimal256::zero(),
+    };
+    let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+    let value: BorrowRateResponse = from_binary(&res).unwrap();
+    assert_eq!("0.07", &value.rate.to_string());
+
+    // above the kink; utilization_ratio = 0.9
+    // borrow_rate = 0.02 + 0.8 * 0.1 + 0.1 * 3
+    let query_msg = QueryMsg::BorrowRate {
+        market_balance: Uint256::from(100000u128),
+        total_liabilities: Decimal256::from_uint256(900000u128),
+        total_reserves: Decimal256::zero(),
+    };
+    let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+    let value: BorrowRateResponse = from_binary(&res).unwrap();
+    assert_eq!("0.4", &value.rate.to_string());
+
+    // kinks must be sorted by utilization
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        base_rate: None,
+        interest_multiplier: None,
+        kinks: Some(vec![
+            InterestKink {
+                utilization: Decimal256::percent(90),
+                interest_multiplier: Decimal256::percent(300),
+            },
+            InterestKink {
+                utilization: Decimal256::percent(80),
+                interest_multiplier: Decimal256::percent(100),
+            },
+        ]),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::InvalidKinks {}) => (),
+        _ => panic!("Must return invalid kinks error"),
+    }
+
+    // kink utilization cannot exceed one
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        base_rate: None,
+        interest_multiplier: None,
+        kinks: Some(vec![InterestKink {
+            utilization: Decimal256::percent(101),
+            interest_multiplier: Decimal256::percent(300),
+        }]),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::InvalidKinks {}) => (),
+        _ => panic!("Must return invalid kinks error"),
+    }
+
+    // remove the kink
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        base_rate: None,
+        interest_multiplier: None,
+        kinks: Some(vec![]),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let value: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(value.kinks, vec![]);
+}
+
+#[test]
+fn rate_controller() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_rate: Decimal256::percent(2),
+        interest_multiplier: Decimal256::percent(20),
+        kinks: vec![],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // without controller, epoch operations are no-op
+    let epoch_msg = ExecuteMsg::ExecuteEpochOperations {
+        market_balance: Uint256::from(100000u128),
+        total_liabilities: Decimal256::from_uint256(900000u128),
+        total_reserves: Decimal256::zero(),
+    };
+    let info = mock_info("market0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, epoch_msg.clone()).unwrap();
+    assert_eq!(0, res.attributes.len());
+
+    let rate_controller = RateControllerConfig {
+        market_contract: "market0000".to_string(),
+        target_utilization: Decimal256::percent(80),
+        proportional_gain: Decimal256::percent(50),
+        integral_gain: Decimal256::percent(10),
+        min_interest_multiplier: Decimal256::percent(5),
+        max_interest_multiplier: Decimal256::percent(200),
+    };
+
+    // only owner can set the controller
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::UpdateRateController {
+        rate_controller: Some(rate_controller.clone()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    // bounds must be ordered
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::UpdateRateController {
+        rate_controller: Some(RateControllerConfig {
+            min_interest_multiplier: Decimal256::percent(300),
+            ..rate_controller.clone()
+        }),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::InvalidRateController {}) => (),
+        _ => panic!("Must return invalid rate controller error"),
+    }
+
+    let msg = ExecuteMsg::UpdateRateController {
+        rate_controller: Some(rate_controller.clone()),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // only market can trigger the adjustment
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        epoch_msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    // utilization_ratio = 0.9
+    // interest_multiplier = 0.2 + 0.5 * (0.9 - 0.8) + 0.1 * (0.9 - 0.8)
+    let mut env = mock_env();
+    env.block.height += 100;
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("market0000", &[]),
+        epoch_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "execute_epoch_operations"),
+            attr("utilization_ratio", "0.9"),
+            attr("interest_multiplier", "0.26"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::RateController {}).unwrap();
+    let value: RateControllerResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        value,
+        RateControllerResponse {
+            rate_controller: Some(rate_controller),
+            interest_multiplier: Decimal256::from_str("0.26").unwrap(),
+            last_utilization: Decimal256::percent(90),
+            last_executed_height: env.block.height,
+        }
+    );
+
+    // utilization_ratio = 0.1
+    // interest_multiplier = 0.26 + 0.5 * (0.1 - 0.9) + 0.1 * (0.1 - 0.8)
+    //  => capped by min_interest_multiplier
+    let epoch_msg = ExecuteMsg::ExecuteEpochOperations {
+        market_balance: Uint256::from(900000u128),
+        total_liabilities: Decimal256::from_uint256(100000u128),
+        total_reserves: Decimal256::zero(),
+    };
+    execute(deps.as_mut(), env, mock_info("market0000", &[]), epoch_msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let value: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(Decimal256::percent(5), value.interest_multiplier);
+
+    // disable the controller
+    let msg = ExecuteMsg::UpdateRateController {
+        rate_controller: None,
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::RateController {}).unwrap();
+    let value: RateControllerResponse = from_binary(&res).unwrap();
+    assert_eq!(None, value.rate_controller);
+}
+
+#[test]
+fn legacy_config() {
+    #[derive(Serialize, Deserialize)]
+    struct LegacyConfig {
+        owner: CanonicalAddr,
+        base_rate: Decimal256,
+        interest_multiplier: Decimal256,
+    }
+
+    let mut deps = mock_dependencies(&[]);
+    let owner = deps.api.addr_canonicalize("owner0000").unwrap();
+    singleton(&mut deps.storage, b"config")
+        .save(&LegacyConfig {
+            owner,
+            base_rate: Decimal256::percent(10),
+            interest_multiplier: Decimal256::percent(10),
+        })
+        .unwrap();
+
+    // configs stored before kinks were introduced keep a linear curve
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let value: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!("owner0000", value.owner.as_str());
+    assert!(value.kinks.is_empty());
+
+    let query_msg = QueryMsg::BorrowRate {
+        market_balance: Uint256::from(1000000u128),
+        total_liabilities: Decimal256::from_uint256(500000u128),
+        total_reserves: Decimal256::from_uint256(100000u128),
+    };
+    let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+    let value: BorrowRateResponse = from_binary(&res).unwrap();
+    assert_eq!("0.135714285714285714", &value.rate.to_string());
+}
@@ -5,7 +5,7 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use moneymarket::liquidation::{
     BidResponse, BidsResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
-    LiquidationAmountResponse, QueryMsg,
+    LiquidationAmountResponse, PendingCollateralsResponse, QueryMsg,
 };
 
 fn main() {
@@ -22,4 +22,5 @@ fn main() {
     export_schema(&schema_for!(BidResponse), &out_dir);
     export_schema(&schema_for!(BidsResponse), &out_dir);
     export_schema(&schema_for!(LiquidationAmountResponse), &out_dir);
+    export_schema(&schema_for!(PendingCollateralsResponse), &out_dir);
 }
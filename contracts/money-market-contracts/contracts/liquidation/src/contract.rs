@@ -14,7 +14,8 @@ use cosmwasm_std::{
 use cw20::Cw20ReceiveMsg;
 use moneymarket::common::optional_addr_validate;
 use moneymarket::liquidation::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, LiquidationAmountResponse, QueryMsg,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, LiquidationAmountResponse,
+    PendingCollateralsResponse, QueryMsg,
 };
 use moneymarket::querier::query_tax_rate;
 use moneymarket::tokens::TokensHuman;
@@ -110,6 +111,7 @@ pub fn receive_cw20(
             liquidator,
             repay_address,
             fee_address,
+            borrower: _,
         }) => {
             let collateral_token = contract_addr.to_string();
             let repay_address = repay_address.unwrap_or_else(|| cw20_msg.sender.clone());
@@ -237,6 +239,10 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 limit,
             )?)
         }
+        // liquidated collaterals are sold right away
+        QueryMsg::PendingCollaterals { .. } => to_binary(&PendingCollateralsResponse {
+            collaterals: vec![],
+        }),
     }
 }
 
@@ -12,6 +12,7 @@ use moneymarket::liquidation::{
     BidResponse, BidsResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
     LiquidationAmountResponse, QueryMsg,
 };
+use moneymarket::tokens::AssetInfo;
 
 #[test]
 fn proper_initialization() {
@@ -354,6 +355,7 @@ fn execute_bid() {
             liquidator: "addr0000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -373,6 +375,7 @@ fn execute_bid() {
             liquidator: "addr0000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -413,6 +416,7 @@ fn execute_bid() {
             liquidator: "addr0000".to_string(),
             fee_address: None,
             repay_address: None,
+            borrower: None,
         })
         .unwrap(),
     });
@@ -474,7 +478,12 @@ fn query_liquidation_amount() {
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(931095u64),
         borrow_limit: Uint256::from(900000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(1000000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(1000000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -483,14 +492,24 @@ fn query_liquidation_amount() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(1000000u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(1000000u64)
+            )],
         }
     );
 
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(100000u64),
         borrow_limit: Uint256::from(1000000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(1000000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(1000000u64),
+        )],
         collateral_prices: vec![Decimal256::one()],
     };
 
@@ -507,9 +526,24 @@ fn query_liquidation_amount() {
         borrow_amount: Uint256::from(1000000u64),
         borrow_limit: Uint256::from(99999u64),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(1000000u64)),
-            ("token0001".to_string(), Uint256::from(2000000u64)),
-            ("token0002".to_string(), Uint256::from(3000000u64)),
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(1000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0001".to_string(),
+                },
+                Uint256::from(2000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0002".to_string(),
+                },
+                Uint256::from(3000000u64),
+            ),
         ],
         collateral_prices: vec![
             Decimal256::percent(50),
@@ -526,9 +560,24 @@ fn query_liquidation_amount() {
         res,
         LiquidationAmountResponse {
             collaterals: vec![
-                ("token0000".to_string(), Uint256::from(358001u64)),
-                ("token0001".to_string(), Uint256::from(716002u64)),
-                ("token0002".to_string(), Uint256::from(1074004u64)),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
+                    Uint256::from(358001u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0001".to_string()
+                    },
+                    Uint256::from(716002u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0002".to_string()
+                    },
+                    Uint256::from(1074004u64)
+                ),
             ],
         }
     );
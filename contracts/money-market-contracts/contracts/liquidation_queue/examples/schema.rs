@@ -4,8 +4,10 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use moneymarket::liquidation_queue::{
-    BidPoolResponse, BidPoolsResponse, BidResponse, BidsResponse, CollateralInfoResponse,
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, LiquidationAmountResponse, QueryMsg,
+    AuctionResponse, AuctionsResponse, BidDepthResponse, BidPoolResponse, BidPoolsResponse,
+    BidResponse, BidsResponse, CollateralInfoResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg,
+    InstantiateMsg, LiquidationAmountResponse, LiquidationHistoryResponse,
+    LiquidationStatsResponse, PendingCollateralsResponse, QueryMsg,
 };
 
 fn main() {
@@ -25,4 +27,10 @@ fn main() {
     export_schema(&schema_for!(BidPoolResponse), &out_dir);
     export_schema(&schema_for!(BidPoolsResponse), &out_dir);
     export_schema(&schema_for!(CollateralInfoResponse), &out_dir);
+    export_schema(&schema_for!(AuctionResponse), &out_dir);
+    export_schema(&schema_for!(AuctionsResponse), &out_dir);
+    export_schema(&schema_for!(PendingCollateralsResponse), &out_dir);
+    export_schema(&schema_for!(BidDepthResponse), &out_dir);
+    export_schema(&schema_for!(LiquidationHistoryResponse), &out_dir);
+    export_schema(&schema_for!(LiquidationStatsResponse), &out_dir);
 }
@@ -1,8 +1,10 @@
 use crate::state::Bid;
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{Env, StdError, StdResult};
+use moneymarket::liquidation_queue::LiquidationMode;
 
 const MAX_SLOT_CAP: u8 = 30u8;
+const MAX_CONDITIONAL_BIDS_PER_SLOT: usize = 20usize;
 
 pub fn assert_activate_status(
     bid: &Bid,
@@ -10,6 +12,9 @@ pub fn assert_activate_status(
     available_bids: Uint256,
     bid_threshold: Uint256,
 ) -> StdResult<()> {
+    if matches!(bid.expires_at, Some(t) if t <= env.block.time.seconds()) {
+        return Err(StdError::generic_err("Bid has expired"));
+    }
     match bid.wait_end {
         Some(wait_end) => {
             if available_bids < bid_threshold {
@@ -27,6 +32,37 @@ pub fn assert_activate_status(
     Ok(())
 }
 
+pub fn assert_expires_at(expires_at: Option<u64>, env: &Env) -> StdResult<()> {
+    if let Some(expires_at) = expires_at {
+        if expires_at <= env.block.time.seconds() {
+            return Err(StdError::generic_err("Bid expiry must be in the future"));
+        }
+    }
+    Ok(())
+}
+
+/// Conditional bids are checked on every liquidation of their pool,
+/// so a premium slot only holds a limited number of them, each of at
+/// least the minimum amount
+pub fn assert_conditional_bids(
+    conditional_bids: usize,
+    amount: Uint256,
+    min_conditional_bid_amount: Uint256,
+) -> StdResult<()> {
+    if amount < min_conditional_bid_amount {
+        return Err(StdError::generic_err(format!(
+            "Conditional bids must be at least {}",
+            min_conditional_bid_amount
+        )));
+    }
+    if conditional_bids >= MAX_CONDITIONAL_BIDS_PER_SLOT {
+        return Err(StdError::generic_err(
+            "Too many conditional bids in this premium slot",
+        ));
+    }
+    Ok(())
+}
+
 pub fn assert_withdraw_amount(
     withdraw_amount: Option<Uint256>,
     withdrawable_amount: Uint256,
@@ -62,6 +98,22 @@ pub fn assert_fees(fees: Decimal256) -> StdResult<()> {
     Ok(())
 }
 
+pub fn assert_liquidation_mode(liquidation_mode: &LiquidationMode) -> StdResult<()> {
+    if let LiquidationMode::DutchAuction {
+        max_premium_rate,
+        duration,
+    } = liquidation_mode
+    {
+        if *max_premium_rate >= Decimal256::one() {
+            return Err(StdError::generic_err("Max premium rate exceeds limit"));
+        }
+        if *duration == 0 {
+            return Err(StdError::generic_err("Auction duration must be positive"));
+        }
+    }
+    Ok(())
+}
+
 pub fn assert_max_slot_premium(max_slot: u8, premium_rate_per_slot: Decimal256) -> StdResult<()> {
     let max_slot_premium =
         premium_rate_per_slot * Decimal256::from_uint256(Uint256::from(max_slot as u128));
@@ -0,0 +1,365 @@
+use crate::bid::{record_liquidation, transfer_collateral_msg};
+use crate::querier::{query_collateral_whitelist_info, query_loan_amount};
+use crate::state::{
+    pop_auction_idx, read_auction, read_borrower_auction, read_config, remove_auction,
+    store_auction, Auction, CollateralInfo, Config,
+};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, to_binary, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use moneymarket::custody::{Cw20HookMsg as CustodyCw20HookMsg, ExecuteMsg as CustodyExecuteMsg};
+use moneymarket::market::ExecuteMsg as MarketExecuteMsg;
+use moneymarket::oracle::{PriceJumpResponse, PriceResponse};
+use moneymarket::overseer::ExecuteMsg as OverseerExecuteMsg;
+use moneymarket::querier::{deduct_tax, query_price, query_price_jump, TimeConstraints};
+use moneymarket::tokens::AssetInfo;
+
+/// Liquidated collateral in dutch auction mode is put up for sale instead of
+/// being bought by the bid pools. The borrower is repaid when the auction is filled
+#[allow(clippy::too_many_arguments)]
+pub fn start_auction(
+    deps: DepsMut,
+    env: Env,
+    collateral_info: &CollateralInfo,
+    borrower: String,
+    liquidator: String,
+    repay_address: String,
+    fee_address: String,
+    amount: Uint256,
+    max_premium_rate: Decimal256,
+    duration: u64,
+) -> StdResult<Response> {
+    let borrower_raw = deps.api.addr_canonicalize(&borrower)?;
+
+    // a borrower can not be liquidated again until the open auction is filled
+    if read_borrower_auction(
+        deps.storage,
+        &collateral_info.collateral_token,
+        &borrower_raw,
+    )?
+    .is_some()
+    {
+        return Err(StdError::generic_err(
+            "Borrower already has an open auction for this collateral",
+        ));
+    }
+
+    let auction_idx = pop_auction_idx(deps.storage)?;
+    store_auction(
+        deps.storage,
+        &Auction {
+            idx: auction_idx,
+            collateral_token: collateral_info.collateral_token.clone(),
+            borrower: borrower_raw,
+            amount,
+            start_time: env.block.time.seconds(),
+            max_premium_rate,
+            duration,
+            liquidator: deps.api.addr_canonicalize(&liquidator)?,
+            repay_address: deps.api.addr_canonicalize(&repay_address)?,
+            fee_address: deps.api.addr_canonicalize(&fee_address)?,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "start_auction"),
+        attr("auction_idx", auction_idx),
+        attr(
+            "collateral_token",
+            collateral_info
+                .collateral_token
+                .to_normal(deps.api)?
+                .to_string(),
+        ),
+        attr("collateral_amount", amount),
+        attr("borrower", borrower),
+    ]))
+}
+
+/// Anyone can buy auctioned collateral at the current auction price.
+/// The payment repays the borrower's loan, after bid and liquidator fees
+pub fn fill_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_idx: Uint128,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let mut auction: Auction = read_auction(deps.storage, auction_idx)?;
+    let collateral_token = auction.collateral_token.to_normal(deps.api)?;
+
+    let amount: Uint256 = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+    if amount.is_zero() {
+        return Err(StdError::generic_err(format!(
+            "No {} assets have been provided",
+            config.stable_denom
+        )));
+    }
+
+    let oracle_contract = deps.api.addr_humanize(&config.oracle_contract)?;
+    let price_jump: PriceJumpResponse = query_price_jump(
+        deps.as_ref(),
+        oracle_contract.clone(),
+        collateral_token.to_string(),
+    )?;
+    if price_jump.active {
+        return Err(StdError::generic_err(format!(
+            "Liquidations are paused; price jump detected for {}",
+            collateral_token
+        )));
+    }
+
+    let price: PriceResponse = query_price(
+        deps.as_ref(),
+        oracle_contract,
+        collateral_token.to_string(),
+        config.stable_denom.clone(),
+        Some(TimeConstraints {
+            block_time: env.block.time.seconds(),
+            valid_timeframe: config.price_timeframe,
+        }),
+    )?;
+
+    if price.rate.is_zero() {
+        return Err(StdError::generic_err(format!(
+            "Invalid oracle price for {}",
+            collateral_token
+        )));
+    }
+
+    let premium_rate = auction_premium_rate(&auction, env.block.time.seconds());
+    let auction_price = price.rate * (Decimal256::one() - premium_rate);
+
+    let (collateral_amount, paid_amount) = if amount / auction_price >= auction.amount {
+        (auction.amount, auction.amount * auction_price)
+    } else {
+        (amount / auction_price, amount)
+    };
+    if collateral_amount.is_zero() {
+        return Err(StdError::generic_err(
+            "Fill amount is too small to buy any collateral",
+        ));
+    }
+
+    auction.amount = auction.amount - collateral_amount;
+    if auction.amount.is_zero() {
+        remove_auction(deps.storage, &auction);
+    } else {
+        store_auction(deps.storage, &auction)?;
+    }
+
+    let bid_fee = paid_amount * config.bid_fee;
+    let liquidator_fee = paid_amount * config.liquidator_fee;
+    let refund_amount = amount - paid_amount;
+
+    // the market refunds any repay above the loan to the sender, so the
+    // proceeds exceeding the loan are sent to the borrower instead
+    let repay_address = deps.api.addr_humanize(&auction.repay_address)?;
+    let borrower = deps.api.addr_humanize(&auction.borrower)?;
+    let loan_amount = query_loan_amount(
+        &deps.querier,
+        repay_address.to_string(),
+        borrower.to_string(),
+        env.block.height,
+    )?;
+    let proceeds = paid_amount - bid_fee - liquidator_fee;
+    let (repay_amount, surplus_amount) = if proceeds > loan_amount {
+        (loan_amount, proceeds - loan_amount)
+    } else {
+        (proceeds, Uint256::zero())
+    };
+
+    record_liquidation(
+        deps.storage,
+        &auction.collateral_token,
+        Some(auction.borrower.clone()),
+        collateral_amount,
+        repay_amount,
+        premium_rate,
+        bid_fee,
+        liquidator_fee,
+        env.block.time.seconds(),
+    )?;
+
+    let mut messages: Vec<CosmosMsg> = vec![transfer_collateral_msg(
+        deps.as_ref(),
+        &collateral_token,
+        info.sender.to_string(),
+        collateral_amount,
+    )?];
+
+    // the repay address of auctions started by custody contracts is the market
+    if !repay_amount.is_zero() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: repay_address.to_string(),
+            funds: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: repay_amount.into(),
+                },
+            )?],
+            msg: to_binary(&MarketExecuteMsg::RepayStableFor {
+                borrower: borrower.to_string(),
+            })?,
+        }));
+    }
+
+    if !surplus_amount.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: borrower.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: surplus_amount.into(),
+                },
+            )?],
+        }));
+    }
+
+    for (recipient, fee) in [
+        (&auction.fee_address, bid_fee),
+        (&auction.liquidator, liquidator_fee),
+    ] {
+        if !fee.is_zero() {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: deps.api.addr_humanize(recipient)?.to_string(),
+                amount: vec![deduct_tax(
+                    deps.as_ref(),
+                    Coin {
+                        denom: config.stable_denom.clone(),
+                        amount: fee.into(),
+                    },
+                )?],
+            }));
+        }
+    }
+
+    if !refund_amount.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: refund_amount.into(),
+                },
+            )?],
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "fill_auction"),
+        attr("auction_idx", auction_idx),
+        attr("collateral_token", collateral_token.to_string()),
+        attr("collateral_amount", collateral_amount),
+        attr("repay_amount", repay_amount),
+        attr("bid_fee", bid_fee),
+        attr("liquidator_fee", liquidator_fee),
+    ]))
+}
+
+/// Returns the collateral left in an auction to the custody, where the
+/// overseer locks it for the borrower again. Auctions expire once they
+/// stayed at the maximum discount for another `duration` without being filled
+pub fn cancel_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_idx: Uint128,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let auction: Auction = read_auction(deps.storage, auction_idx)?;
+
+    let expired = env.block.time.seconds() >= auction.start_time + 2 * auction.duration;
+    if !expired && deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("Auction has not expired"));
+    }
+
+    remove_auction(deps.storage, &auction);
+
+    let collateral_token = auction.collateral_token.to_normal(deps.api)?;
+    let borrower = deps.api.addr_humanize(&auction.borrower)?;
+    let overseer = deps.api.addr_humanize(&config.overseer)?;
+    let custody_contract = query_collateral_whitelist_info(
+        &deps.querier,
+        overseer.to_string(),
+        collateral_token.clone(),
+    )?
+    .custody_contract;
+
+    let (deposit_msg, deposit_amount) = match &collateral_token {
+        AssetInfo::Token { contract_addr } => (
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: custody_contract,
+                    amount: auction.amount.into(),
+                    msg: to_binary(&CustodyCw20HookMsg::DepositCollateralFor {
+                        borrower: borrower.to_string(),
+                    })?,
+                })?,
+            }),
+            auction.amount,
+        ),
+        AssetInfo::NativeToken { denom } => {
+            let coin = deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: denom.to_string(),
+                    amount: auction.amount.into(),
+                },
+            )?;
+            let deposit_amount = Uint256::from(coin.amount);
+            (
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: custody_contract,
+                    funds: vec![coin],
+                    msg: to_binary(&CustodyExecuteMsg::DepositCollateralFor {
+                        borrower: borrower.to_string(),
+                    })?,
+                }),
+                deposit_amount,
+            )
+        }
+    };
+
+    let restore_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: overseer.to_string(),
+        funds: vec![],
+        msg: to_binary(&OverseerExecuteMsg::RestoreCollateral {
+            borrower: borrower.to_string(),
+            collaterals: vec![(collateral_token.clone(), deposit_amount)],
+        })?,
+    });
+
+    Ok(Response::new()
+        .add_messages(vec![deposit_msg, restore_msg])
+        .add_attributes(vec![
+            attr("action", "cancel_auction"),
+            attr("auction_idx", auction_idx),
+            attr("collateral_token", collateral_token.to_string()),
+            attr("collateral_amount", auction.amount),
+            attr("borrower", borrower),
+        ]))
+}
+
+/// Discount to the oracle price, increasing linearly from zero
+/// to `max_premium_rate` over the auction duration
+pub(crate) fn auction_premium_rate(auction: &Auction, block_time: u64) -> Decimal256 {
+    let elapsed = block_time
+        .saturating_sub(auction.start_time)
+        .min(auction.duration);
+
+    auction.max_premium_rate * Decimal256::from_ratio(elapsed, auction.duration)
+}
@@ -1,19 +1,31 @@
-use crate::asserts::{assert_activate_status, assert_withdraw_amount};
+use crate::asserts::{
+    assert_activate_status, assert_conditional_bids, assert_expires_at, assert_withdraw_amount,
+};
+use crate::auction::start_auction;
+use crate::contract::COMPOUND_OPERATION;
 use crate::querier::query_collateral_whitelist_info;
 use crate::state::{
-    pop_bid_idx, read_bid, read_bid_pool, read_bids_by_user, read_collateral_info, read_config,
-    read_epoch_scale_sum, read_or_create_bid_pool, read_total_bids, remove_bid, store_bid,
-    store_bid_pool, store_epoch_scale_sum, store_total_bids, Bid, BidPool, CollateralInfo, Config,
+    count_conditional_bids, pop_bid_idx, pop_liquidation_idx, read_bid, read_bid_pool,
+    read_bids_by_user, read_collateral_info, read_conditional_bids, read_config,
+    read_epoch_scale_sum, read_liquidation_stats, read_or_create_bid_pool, read_pending_compounds,
+    read_total_bids, remove_bid, store_bid, store_bid_pool, store_epoch_scale_sum,
+    store_liquidation_event, store_liquidation_stats, store_pending_compounds, store_total_bids,
+    Bid, BidAutoClaim, BidPool, CollateralInfo, Config, LiquidationEvent, PendingCompound,
 };
+use crate::swap::swap_collateral_msg;
 use bigint::U256;
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
-    attr, to_binary, BankMsg, CanonicalAddr, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response,
-    StdError, StdResult, Storage, Uint128, WasmMsg,
+    attr, to_binary, BankMsg, CanonicalAddr, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
-use moneymarket::oracle::PriceResponse;
-use moneymarket::querier::{deduct_tax, query_price, TimeConstraints};
+use moneymarket::liquidation_queue::{AutoClaim, LiquidationMode};
+use moneymarket::oracle::{PriceJumpResponse, PriceResponse};
+use moneymarket::querier::{
+    deduct_tax, query_balance, query_price, query_price_jump, TimeConstraints,
+};
+use moneymarket::tokens::{AssetInfo, AssetInfoRaw};
 
 /// Stable asset is submitted to create a bid record. If available bids for the collateral is under
 /// the threshold, the bid is activated. Bids are not used for liquidations until activated
@@ -21,14 +33,17 @@ pub fn submit_bid(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    collateral_token: String,
+    collateral_token: AssetInfo,
     premium_slot: u8,
+    expires_at: Option<u64>,
+    min_collateral_price: Option<Decimal256>,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
-    let collateral_token_raw: CanonicalAddr = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw: AssetInfoRaw = collateral_token.to_raw(deps.api)?;
     let collateral_info: CollateralInfo =
         read_collateral_info(deps.storage, &collateral_token_raw)?;
     let bidder_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    assert_expires_at(expires_at, &env)?;
 
     let amount: Uint256 = info
         .funds
@@ -52,15 +67,57 @@ pub fn submit_bid(
         })??
         .into();
 
+    if expires_at.is_some() || min_collateral_price.is_some() {
+        assert_conditional_bids(
+            count_conditional_bids(deps.storage, &collateral_token_raw, premium_slot),
+            amount,
+            config.min_conditional_bid_amount,
+        )?;
+    }
+
+    let bid_idx = create_bid(
+        deps.storage,
+        &env,
+        &config,
+        &collateral_info,
+        bidder_raw,
+        premium_slot,
+        amount,
+        None,
+        expires_at,
+        min_collateral_price,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "submit_bid"),
+        attr("bid_idx", bid_idx),
+        attr("amount", amount),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_bid(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    collateral_info: &CollateralInfo,
+    bidder: CanonicalAddr,
+    premium_slot: u8,
+    amount: Uint256,
+    auto_claim: Option<BidAutoClaim>,
+    expires_at: Option<u64>,
+    min_collateral_price: Option<Decimal256>,
+) -> StdResult<Uint128> {
+    let collateral_token_raw = collateral_info.collateral_token.clone();
+
     // read or create bid_pool, make sure slot is valid
-    let mut bid_pool: BidPool =
-        read_or_create_bid_pool(deps.storage, &collateral_info, premium_slot)?;
+    let mut bid_pool: BidPool = read_or_create_bid_pool(storage, collateral_info, premium_slot)?;
 
     // create bid object
-    let bid_idx: Uint128 = pop_bid_idx(deps.storage)?;
+    let bid_idx: Uint128 = pop_bid_idx(storage)?;
     let mut bid = Bid {
         idx: bid_idx,
-        bidder: bidder_raw,
+        bidder,
         collateral_token: collateral_token_raw.clone(),
         product_snapshot: Decimal256::one(),
         amount,
@@ -70,32 +127,59 @@ pub fn submit_bid(
         wait_end: None,
         epoch_snapshot: Uint128::zero(),
         scale_snapshot: Uint128::zero(),
+        auto_claim,
+        expires_at,
+        min_collateral_price,
     };
 
     // if available bids is lower than bid_threshold, directly activate bid
-    let available_bids: Uint256 =
-        read_total_bids(deps.storage, &collateral_token_raw).unwrap_or_default();
+    let available_bids: Uint256 = read_total_bids(storage, &collateral_token_raw)?;
     if available_bids < collateral_info.bid_threshold {
         // update bid and bid pool, add new share and pool indexes to bid
         process_bid_activation(&mut bid, &mut bid_pool, amount);
 
         // store bid_pool
-        store_bid_pool(deps.storage, &collateral_token_raw, premium_slot, &bid_pool)?;
+        store_bid_pool(storage, &collateral_token_raw, premium_slot, &bid_pool)?;
 
         // increase total bid amount
-        store_total_bids(deps.storage, &collateral_token_raw, available_bids + amount)?;
+        store_total_bids(storage, &collateral_token_raw, available_bids + amount)?;
     } else {
         // calculate wait_end from current time
         bid.wait_end = Some(env.block.time.plus_seconds(config.waiting_period).seconds());
     };
 
     // save to storage
+    store_bid(storage, bid_idx, &bid)?;
+
+    Ok(bid_idx)
+}
+
+/// Bid owner can opt in to have the liquidated collateral sold for stable denom
+/// when claimed, either compounding it into a new bid or sending it to a recipient
+pub fn set_auto_claim(
+    deps: DepsMut,
+    info: MessageInfo,
+    bid_idx: Uint128,
+    auto_claim: Option<AutoClaim>,
+) -> StdResult<Response> {
+    let sender_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut bid: Bid = read_bid(deps.storage, bid_idx)?;
+    if bid.bidder != sender_raw {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    bid.auto_claim = match auto_claim {
+        Some(AutoClaim::Compound {}) => Some(BidAutoClaim::Compound),
+        Some(AutoClaim::Withdraw { recipient }) => Some(BidAutoClaim::Withdraw {
+            recipient: deps.api.addr_canonicalize(&recipient)?,
+        }),
+        None => None,
+    };
     store_bid(deps.storage, bid_idx, &bid)?;
 
     Ok(Response::new().add_attributes(vec![
-        attr("action", "submit_bid"),
+        attr("action", "set_auto_claim"),
         attr("bid_idx", bid_idx),
-        attr("amount", amount),
     ]))
 }
 
@@ -105,15 +189,14 @@ pub fn activate_bids(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    collateral_token: String,
+    collateral_token: AssetInfo,
     bids_idx: Option<Vec<Uint128>>,
 ) -> StdResult<Response> {
     let sender_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
-    let collateral_token_raw: CanonicalAddr = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw: AssetInfoRaw = collateral_token.to_raw(deps.api)?;
     let collateral_info: CollateralInfo =
         read_collateral_info(deps.storage, &collateral_token_raw)?;
-    let mut available_bids: Uint256 =
-        read_total_bids(deps.storage, &collateral_token_raw).unwrap_or_default();
+    let mut available_bids: Uint256 = read_total_bids(deps.storage, &collateral_token_raw)?;
 
     let bids: Vec<Bid> = if let Some(bids_idx) = &bids_idx {
         bids_idx
@@ -188,7 +271,7 @@ pub fn retract_bid(
     let config: Config = read_config(deps.storage)?;
     let sender_raw: CanonicalAddr = deps.api.addr_canonicalize(info.sender.as_str())?;
     let mut bid: Bid = read_bid(deps.storage, bid_idx)?;
-    let collateral_token_raw: CanonicalAddr = bid.collateral_token.clone();
+    let collateral_token_raw: AssetInfoRaw = bid.collateral_token.clone();
 
     if bid.bidder != sender_raw {
         return Err(StdError::generic_err("unauthorized"));
@@ -296,21 +379,21 @@ pub fn execute_liquidation(
     liquidator: String,
     repay_address: String,
     fee_address: String,
-    collateral_token: String,
+    borrower: Option<String>,
+    collateral_token: AssetInfo,
     amount: Uint256,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
-    let collateral_token_raw = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
     let collateral_info: CollateralInfo =
         read_collateral_info(deps.storage, &collateral_token_raw)?;
-    let available_bids: Uint256 = read_total_bids(deps.storage, &collateral_token_raw)?;
 
     // only collateral token custody can execute liquidations
     let overseer = deps.api.addr_humanize(&config.overseer)?;
     let custody_contract = query_collateral_whitelist_info(
         &deps.querier,
         overseer.to_string(),
-        collateral_token.to_string(),
+        collateral_token.clone(),
     )?
     .custody_contract;
     if sender != custody_contract {
@@ -320,6 +403,41 @@ pub fn execute_liquidation(
     }
 
     let oracle_contract = deps.api.addr_humanize(&config.oracle_contract)?;
+    let price_jump: PriceJumpResponse = query_price_jump(
+        deps.as_ref(),
+        oracle_contract.clone(),
+        collateral_token.to_string(),
+    )?;
+    if price_jump.active {
+        return Err(StdError::generic_err(format!(
+            "Liquidations are paused; price jump detected for {}",
+            collateral_token
+        )));
+    }
+
+    if let LiquidationMode::DutchAuction {
+        max_premium_rate,
+        duration,
+    } = collateral_info.liquidation_mode
+    {
+        let borrower = borrower.ok_or_else(|| {
+            StdError::generic_err("Dutch auction liquidations require a borrower")
+        })?;
+        return start_auction(
+            deps,
+            env,
+            &collateral_info,
+            borrower,
+            liquidator,
+            repay_address,
+            fee_address,
+            amount,
+            max_premium_rate,
+            duration,
+        );
+    }
+
+    let available_bids: Uint256 = read_total_bids(deps.storage, &collateral_token_raw)?;
     let price: PriceResponse = query_price(
         deps.as_ref(),
         oracle_contract,
@@ -333,6 +451,8 @@ pub fn execute_liquidation(
 
     let mut remaining_collateral_to_liquidate = amount;
     let mut repay_amount = Uint256::zero();
+    let mut refunds: Vec<(CanonicalAddr, Uint256)> = vec![];
+    let mut refunded_bids = Uint256::zero();
     let mut filled: bool = false;
     for slot in 0..collateral_info.max_slot + 1 {
         let mut bid_pool: BidPool = match read_bid_pool(deps.storage, &collateral_token_raw, slot) {
@@ -343,15 +463,40 @@ pub fn execute_liquidation(
             continue;
         };
 
-        let (pool_repay_amount, pool_liquidated_collateral) = execute_pool_liquidation(
+        // expired bids are refunded and bids with an unmet price floor
+        // sit out this liquidation
+        let (sitting_out_bids, pool_refunded_bids) = withdraw_conditional_bids(
             deps.storage,
             &mut bid_pool,
             &collateral_token_raw,
             slot,
-            remaining_collateral_to_liquidate,
             price.rate,
-            &mut filled,
+            env.block.time.seconds(),
+            &mut refunds,
         )?;
+        refunded_bids += pool_refunded_bids;
+
+        let (pool_repay_amount, pool_liquidated_collateral) = if bid_pool.total_bid_amount.is_zero()
+        {
+            (Uint256::zero(), Uint256::zero())
+        } else {
+            execute_pool_liquidation(
+                deps.storage,
+                &mut bid_pool,
+                &collateral_token_raw,
+                slot,
+                remaining_collateral_to_liquidate,
+                price.rate,
+                &mut filled,
+            )?
+        };
+
+        // bids that sat out rejoin the pool with fresh snapshots
+        for mut bid in sitting_out_bids.into_iter() {
+            let amount_to_activate = bid.amount;
+            process_bid_activation(&mut bid, &mut bid_pool, amount_to_activate);
+            store_bid(deps.storage, bid.idx, &bid)?;
+        }
 
         store_bid_pool(deps.storage, &collateral_token_raw, slot, &bid_pool)?;
 
@@ -375,13 +520,37 @@ pub fn execute_liquidation(
     store_total_bids(
         deps.storage,
         &collateral_token_raw,
-        available_bids - repay_amount,
+        available_bids - repay_amount - refunded_bids,
     )?;
 
+    // average discount of the consumed bids to the oracle price
+    let collateral_value = amount * price.rate;
+    let premium_rate = if collateral_value.is_zero() || repay_amount >= collateral_value {
+        Decimal256::zero()
+    } else {
+        Decimal256::one()
+            - Decimal256::from_uint256(repay_amount) / Decimal256::from_uint256(collateral_value)
+    };
+
     let bid_fee = repay_amount * config.bid_fee;
     let liquidator_fee = repay_amount * config.liquidator_fee;
     let repay_amount = repay_amount - bid_fee - liquidator_fee;
 
+    let borrower_raw = borrower
+        .map(|borrower| deps.api.addr_canonicalize(&borrower))
+        .transpose()?;
+    record_liquidation(
+        deps.storage,
+        &collateral_token_raw,
+        borrower_raw,
+        amount,
+        repay_amount,
+        premium_rate,
+        bid_fee,
+        liquidator_fee,
+        env.block.time.seconds(),
+    )?;
+
     let mut messages: Vec<CosmosMsg> = vec![CosmosMsg::Bank(BankMsg::Send {
         to_address: repay_address,
         amount: vec![deduct_tax(
@@ -418,28 +587,109 @@ pub fn execute_liquidation(
         }));
     }
 
+    for (bidder, refund_amount) in refunds.into_iter() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: deps.api.addr_humanize(&bidder)?.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: refund_amount.into(),
+                },
+            )?],
+        }));
+    }
+
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "execute_bid"),
         attr("stable_denom", config.stable_denom),
         attr("repay_amount", repay_amount),
         attr("bid_fee", bid_fee),
         attr("liquidator_fee", liquidator_fee),
-        attr("collateral_token", collateral_token),
+        attr("collateral_token", collateral_token.to_string()),
         attr("collateral_amount", amount),
     ]))
 }
 
+/// Sends liquidated collateral, a CW20 transfer or a bank send after tax
+pub(crate) fn transfer_collateral_msg(
+    deps: Deps,
+    collateral_token: &AssetInfo,
+    recipient: String,
+    amount: Uint256,
+) -> StdResult<CosmosMsg> {
+    match collateral_token {
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient,
+                amount: amount.into(),
+            })?,
+        })),
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient,
+            amount: vec![deduct_tax(
+                deps,
+                Coin {
+                    denom: denom.to_string(),
+                    amount: amount.into(),
+                },
+            )?],
+        })),
+    }
+}
+
+/// Appends a liquidation to the journal and adds it to the collateral totals
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn record_liquidation(
+    storage: &mut dyn Storage,
+    collateral_token: &AssetInfoRaw,
+    borrower: Option<CanonicalAddr>,
+    collateral_amount: Uint256,
+    repay_amount: Uint256,
+    premium_rate: Decimal256,
+    bid_fee: Uint256,
+    liquidator_fee: Uint256,
+    block_time: u64,
+) -> StdResult<()> {
+    let liquidation_idx = pop_liquidation_idx(storage)?;
+    store_liquidation_event(
+        storage,
+        &LiquidationEvent {
+            idx: liquidation_idx,
+            collateral_token: collateral_token.clone(),
+            borrower,
+            collateral_amount,
+            repay_amount,
+            premium_rate,
+            bid_fee,
+            liquidator_fee,
+            block_time,
+        },
+    )?;
+
+    let mut stats = read_liquidation_stats(storage, collateral_token)?;
+    stats.liquidation_count += 1;
+    stats.total_collateral_amount += collateral_amount;
+    stats.total_repay_amount += repay_amount;
+    stats.total_bid_fee += bid_fee;
+    stats.total_liquidator_fee += liquidator_fee;
+    store_liquidation_stats(storage, collateral_token, &stats)
+}
+
 /// Bid owner can claim their share of the liquidated collateral until the
 /// bid is consumed
 pub fn claim_liquidations(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    collateral_token: String,
+    collateral_token: AssetInfo,
     bids_idx: Option<Vec<Uint128>>,
 ) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
     let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-    let collateral_token_raw = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
 
     let bids: Vec<Bid> = if let Some(bids_idx) = bids_idx {
         bids_idx
@@ -451,8 +701,9 @@ pub fn claim_liquidations(
     };
 
     let mut claim_amount = Uint256::zero();
+    let mut auto_claims: Vec<(BidAutoClaim, PendingCompound, Uint256)> = vec![];
     for bid in bids.into_iter() {
-        if bid.bidder != sender_raw {
+        if bid.bidder != sender_raw && bid.auto_claim.is_none() {
             return Err(StdError::generic_err("unauthorized"));
         }
         if bid.collateral_token != collateral_token_raw {
@@ -478,9 +729,27 @@ pub fn claim_liquidations(
         bid_pool.residue_bid += residue_bid;
 
         // get claimable amount
-        claim_amount += bid.pending_liquidated_collateral
+        let bid_claim_amount = bid.pending_liquidated_collateral
             + liquidated_collateral
             + claim_col_residue(&mut bid_pool);
+        match bid.auto_claim.clone() {
+            Some(auto_claim) => {
+                if !bid_claim_amount.is_zero() {
+                    auto_claims.push((
+                        auto_claim,
+                        PendingCompound {
+                            bidder: bid.bidder.clone(),
+                            collateral_token: collateral_token_raw.clone(),
+                            premium_slot: bid.premium_slot,
+                            expires_at: bid.expires_at,
+                            min_collateral_price: bid.min_collateral_price,
+                        },
+                        bid_claim_amount,
+                    ));
+                }
+            }
+            None => claim_amount += bid_claim_amount,
+        }
 
         // store bid_pool to update residue
         store_bid_pool(
@@ -509,25 +778,261 @@ pub fn claim_liquidations(
         }
     }
 
-    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut messages: Vec<SubMsg> = vec![];
     if !claim_amount.is_zero() {
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: collateral_token.clone(),
-            funds: vec![],
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: info.sender.to_string(),
-                amount: claim_amount.into(),
-            })?,
-        }));
+        messages.push(SubMsg::new(transfer_collateral_msg(
+            deps.as_ref(),
+            &collateral_token,
+            info.sender.to_string(),
+            claim_amount,
+        )?));
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
+    let mut auto_claim_amount = Uint256::zero();
+    if !auto_claims.is_empty() {
+        let collateral_info: CollateralInfo =
+            read_collateral_info(deps.storage, &collateral_token_raw)?;
+        let swap_pair = match collateral_info.swap_pair {
+            Some(swap_pair) => deps.api.addr_humanize(&swap_pair)?.to_string(),
+            None => {
+                return Err(StdError::generic_err(
+                    "No swap pair configured for this collateral",
+                ))
+            }
+        };
+
+        let price: PriceResponse = query_price(
+            deps.as_ref(),
+            deps.api.addr_humanize(&config.oracle_contract)?,
+            collateral_token.to_string(),
+            config.stable_denom.clone(),
+            Some(TimeConstraints {
+                block_time: env.block.time.seconds(),
+                valid_timeframe: config.price_timeframe,
+            }),
+        )?;
+
+        let mut pending_compounds: Vec<PendingCompound> = vec![];
+        for (auto_claim, pending_compound, amount) in auto_claims.into_iter() {
+            auto_claim_amount += amount;
+            match auto_claim {
+                BidAutoClaim::Compound => {
+                    // proceeds return to this contract and are re-submitted on reply
+                    messages.push(SubMsg::reply_on_success(
+                        swap_collateral_msg(
+                            deps.as_ref(),
+                            &collateral_token,
+                            swap_pair.clone(),
+                            amount,
+                            price.rate,
+                            collateral_info.max_swap_spread,
+                            None,
+                        )?,
+                        COMPOUND_OPERATION,
+                    ));
+                    pending_compounds.push(pending_compound);
+                }
+                BidAutoClaim::Withdraw { recipient } => {
+                    messages.push(SubMsg::new(swap_collateral_msg(
+                        deps.as_ref(),
+                        &collateral_token,
+                        swap_pair.clone(),
+                        amount,
+                        price.rate,
+                        collateral_info.max_swap_spread,
+                        Some(deps.api.addr_humanize(&recipient)?.to_string()),
+                    )?));
+                }
+            }
+        }
+
+        if !pending_compounds.is_empty() {
+            let mut pending = read_pending_compounds(deps.storage)?;
+            if pending.bids.is_empty() {
+                pending.stable_balance =
+                    query_balance(deps.as_ref(), env.contract.address, config.stable_denom)?;
+            }
+            pending.bids.extend(pending_compounds);
+            store_pending_compounds(deps.storage, &pending)?;
+        }
+    }
+
+    let mut attributes = vec![
         attr("action", "claim_liquidations"),
-        attr("collateral_token", collateral_token),
+        attr("collateral_token", collateral_token.to_string()),
         attr("collateral_amount", claim_amount),
+    ];
+    if !auto_claim_amount.is_zero() {
+        attributes.push(attr("auto_claim_amount", auto_claim_amount));
+    }
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(attributes))
+}
+
+/// Submits the stable denom received from a compounding swap
+/// as a new bid in the premium slot of the claimed bid
+/// Executor: itself
+pub fn compound_hook(deps: DepsMut, env: Env) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let mut pending = read_pending_compounds(deps.storage)?;
+    if pending.bids.is_empty() {
+        return Err(StdError::generic_err("No pending compounds"));
+    }
+    let pending_compound = pending.bids.remove(0);
+
+    // swap proceeds = balance after swap - balance before swap
+    let stable_balance: Uint256 = query_balance(
+        deps.as_ref(),
+        env.contract.address.clone(),
+        config.stable_denom.clone(),
+    )?;
+    let amount = stable_balance - pending.stable_balance;
+    pending.stable_balance = stable_balance;
+    store_pending_compounds(deps.storage, &pending)?;
+
+    if amount.is_zero() {
+        return Ok(Response::new());
+    }
+
+    // failing here would revert the claim, so the compounded bid
+    // drops its conditions when they are not allowed in its slot
+    let (mut expires_at, mut min_collateral_price) = (
+        pending_compound.expires_at,
+        pending_compound.min_collateral_price,
+    );
+    if (expires_at.is_some() || min_collateral_price.is_some())
+        && assert_conditional_bids(
+            count_conditional_bids(
+                deps.storage,
+                &pending_compound.collateral_token,
+                pending_compound.premium_slot,
+            ),
+            amount,
+            config.min_conditional_bid_amount,
+        )
+        .is_err()
+    {
+        expires_at = None;
+        min_collateral_price = None;
+    }
+
+    let collateral_info: CollateralInfo =
+        read_collateral_info(deps.storage, &pending_compound.collateral_token)?;
+    let bid_idx = create_bid(
+        deps.storage,
+        &env,
+        &config,
+        &collateral_info,
+        pending_compound.bidder,
+        pending_compound.premium_slot,
+        amount,
+        Some(BidAutoClaim::Compound),
+        expires_at,
+        min_collateral_price,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "compound_bid"),
+        attr("bid_idx", bid_idx),
+        attr("amount", amount),
     ]))
 }
 
+/// Takes the conditional bids of a pool out of it before the pool is consumed.
+/// Expired bids are refunded, keeping any liquidated collateral claimable, while
+/// bids whose price floor is not met are returned to rejoin the pool afterwards.
+/// Returns the bids sitting out and the active bid amount refunded from the pool
+fn withdraw_conditional_bids(
+    storage: &mut dyn Storage,
+    bid_pool: &mut BidPool,
+    collateral_token: &AssetInfoRaw,
+    premium_slot: u8,
+    price: Decimal256,
+    block_time: u64,
+    refunds: &mut Vec<(CanonicalAddr, Uint256)>,
+) -> StdResult<(Vec<Bid>, Uint256)> {
+    let mut sitting_out_bids: Vec<Bid> = vec![];
+    let mut refunded_bids = Uint256::zero();
+    for mut bid in read_conditional_bids(storage, collateral_token, premium_slot)?.into_iter() {
+        let expired = matches!(bid.expires_at, Some(t) if t <= block_time);
+        if bid.wait_end.is_some() {
+            // waiting bids are not part of the pool
+            if expired {
+                refunds.push((bid.bidder.clone(), bid.amount));
+                remove_bid(storage, bid.idx)?;
+            }
+            continue;
+        }
+
+        let below_floor = matches!(bid.min_collateral_price, Some(p) if price <= p);
+        if !expired && !below_floor {
+            continue;
+        }
+
+        let (remaining_bid, residue_bid) = calculate_remaining_bid(&bid, bid_pool)?;
+        let (liquidated_collateral, residue_collateral) =
+            calculate_liquidated_collateral(storage, &bid)?;
+
+        bid.pending_liquidated_collateral += liquidated_collateral;
+        bid_pool.residue_collateral += residue_collateral;
+        bid_pool.residue_bid += residue_bid;
+        bid_pool.total_bid_amount = bid_pool.total_bid_amount - remaining_bid;
+
+        if expired {
+            refunded_bids += remaining_bid;
+            if !remaining_bid.is_zero() {
+                refunds.push((bid.bidder.clone(), remaining_bid));
+            }
+            if bid.pending_liquidated_collateral.is_zero() {
+                remove_bid(storage, bid.idx)?;
+            } else {
+                // keep the bid until its collateral is claimed
+                store_bid(
+                    storage,
+                    bid.idx,
+                    &Bid {
+                        amount: Uint256::zero(),
+                        product_snapshot: bid_pool.product_snapshot,
+                        sum_snapshot: bid_pool.sum_snapshot,
+                        scale_snapshot: bid_pool.current_scale,
+                        epoch_snapshot: bid_pool.current_epoch,
+                        ..bid
+                    },
+                )?;
+            }
+        } else {
+            bid.amount = remaining_bid;
+            sitting_out_bids.push(bid);
+        }
+    }
+
+    Ok((sitting_out_bids, refunded_bids))
+}
+
+/// Active bid amount of a pool that `withdraw_conditional_bids` would take out
+/// of a liquidation at the given price and time
+pub(crate) fn conditional_bids_excluded(
+    storage: &dyn Storage,
+    bid_pool: &BidPool,
+    collateral_token: &AssetInfoRaw,
+    premium_slot: u8,
+    price: Decimal256,
+    block_time: u64,
+) -> StdResult<Uint256> {
+    let mut excluded = Uint256::zero();
+    for bid in read_conditional_bids(storage, collateral_token, premium_slot)?.iter() {
+        let expired = matches!(bid.expires_at, Some(t) if t <= block_time);
+        let below_floor = matches!(bid.min_collateral_price, Some(p) if price <= p);
+        if bid.wait_end.is_none() && (expired || below_floor) {
+            excluded += calculate_remaining_bid(bid, bid_pool)?.0;
+        }
+    }
+
+    Ok(excluded)
+}
+
 fn process_bid_activation(bid: &mut Bid, bid_pool: &mut BidPool, amount: Uint256) {
     bid.product_snapshot = bid_pool.product_snapshot;
     bid.sum_snapshot = bid_pool.sum_snapshot;
@@ -546,20 +1051,20 @@ fn process_bid_activation(bid: &mut Bid, bid_pool: &mut BidPool, amount: Uint256
 fn execute_pool_liquidation(
     storage: &mut dyn Storage,
     bid_pool: &mut BidPool,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     premium_slot: u8,
     collateral_to_liquidate: Uint256,
     price: Decimal256,
     filled: &mut bool,
 ) -> StdResult<(Uint256, Uint256)> {
-    let premium_price = price * (Decimal256::one() - bid_pool.premium_rate);
-    let mut pool_collateral_to_liquidate = collateral_to_liquidate;
-    let mut pool_required_stable = pool_collateral_to_liquidate * premium_price;
-
-    if pool_required_stable > bid_pool.total_bid_amount {
-        pool_required_stable = bid_pool.total_bid_amount;
-        pool_collateral_to_liquidate = pool_required_stable / premium_price;
-    } else {
+    let (pool_required_stable, pool_collateral_to_liquidate, pool_filled) =
+        calculate_pool_liquidation(
+            bid_pool.total_bid_amount,
+            bid_pool.premium_rate,
+            collateral_to_liquidate,
+            price,
+        );
+    if pool_filled {
         *filled = true;
     }
 
@@ -615,6 +1120,24 @@ fn execute_pool_liquidation(
     Ok((pool_required_stable, pool_collateral_to_liquidate))
 }
 
+/// Returns the stable required from a pool and the collateral it buys,
+/// and whether the pool bids cover the whole collateral amount
+pub(crate) fn calculate_pool_liquidation(
+    pool_bid_amount: Uint256,
+    premium_rate: Decimal256,
+    collateral_to_liquidate: Uint256,
+    price: Decimal256,
+) -> (Uint256, Uint256, bool) {
+    let premium_price = price * (Decimal256::one() - premium_rate);
+    let required_stable = collateral_to_liquidate * premium_price;
+
+    if required_stable > pool_bid_amount {
+        (pool_bid_amount, pool_bid_amount / premium_price, false)
+    } else {
+        (required_stable, collateral_to_liquidate, true)
+    }
+}
+
 pub(crate) fn calculate_remaining_bid(
     bid: &Bid,
     bid_pool: &BidPool,
@@ -1,12 +1,20 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
-use crate::asserts::{assert_fees, assert_max_slot, assert_max_slot_premium};
-use crate::bid::{activate_bids, claim_liquidations, execute_liquidation, retract_bid, submit_bid};
+use crate::asserts::{
+    assert_fees, assert_liquidation_mode, assert_max_slot, assert_max_slot_premium,
+};
+use crate::auction::{cancel_auction, fill_auction};
+use crate::bid::{
+    activate_bids, claim_liquidations, compound_hook, execute_liquidation, retract_bid,
+    set_auto_claim, submit_bid,
+};
 use crate::querier::query_collateral_whitelist_info;
 use crate::query::{
-    query_bid, query_bid_pool, query_bid_pools, query_bids_by_user, query_collateral_info,
-    query_config, query_liquidation_amount,
+    query_auction, query_auctions_by_collateral, query_bid, query_bid_depth, query_bid_pool,
+    query_bid_pools, query_bids_by_user, query_collateral_info, query_config,
+    query_liquidation_amount, query_liquidation_history, query_liquidation_stats,
+    query_pending_collaterals,
 };
 use crate::state::{
     read_collateral_info, read_config, store_collateral_info, store_config, CollateralInfo, Config,
@@ -14,10 +22,16 @@ use crate::state::{
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
-    from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError,
+    StdResult,
 };
 use cw20::Cw20ReceiveMsg;
-use moneymarket::liquidation_queue::{Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use moneymarket::liquidation_queue::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, LiquidationMode, QueryMsg,
+};
+use moneymarket::tokens::AssetInfo;
+
+pub const COMPOUND_OPERATION: u64 = 1u64;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -41,6 +55,7 @@ pub fn instantiate(
             price_timeframe: msg.price_timeframe,
             waiting_period: msg.waiting_period,
             overseer: deps.api.addr_canonicalize(&msg.overseer)?,
+            min_conditional_bid_amount: Uint256::zero(),
         },
     )?;
 
@@ -61,6 +76,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             price_timeframe,
             waiting_period,
             overseer,
+            min_conditional_bid_amount,
         } => update_config(
             deps,
             info,
@@ -73,12 +89,14 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             price_timeframe,
             waiting_period,
             overseer,
+            min_conditional_bid_amount,
         ),
         ExecuteMsg::WhitelistCollateral {
             collateral_token,
             bid_threshold,
             max_slot,
             premium_rate_per_slot,
+            liquidation_mode,
         } => whitelist_collateral(
             deps,
             info,
@@ -86,25 +104,77 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             bid_threshold,
             max_slot,
             premium_rate_per_slot,
+            liquidation_mode,
         ),
         ExecuteMsg::UpdateCollateralInfo {
             collateral_token,
             bid_threshold,
             max_slot,
-        } => update_collateral_info(deps, info, collateral_token, bid_threshold, max_slot),
+            swap_pair,
+            max_swap_spread,
+            liquidation_mode,
+        } => update_collateral_info(
+            deps,
+            info,
+            collateral_token,
+            bid_threshold,
+            max_slot,
+            swap_pair,
+            max_swap_spread,
+            liquidation_mode,
+        ),
         ExecuteMsg::SubmitBid {
             collateral_token,
             premium_slot,
-        } => submit_bid(deps, env, info, collateral_token, premium_slot),
+            expires_at,
+            min_collateral_price,
+        } => submit_bid(
+            deps,
+            env,
+            info,
+            collateral_token,
+            premium_slot,
+            expires_at,
+            min_collateral_price,
+        ),
         ExecuteMsg::ActivateBids {
             collateral_token,
             bids_idx,
         } => activate_bids(deps, env, info, collateral_token, bids_idx),
+        ExecuteMsg::SetAutoClaim {
+            bid_idx,
+            auto_claim,
+        } => set_auto_claim(deps, info, bid_idx, auto_claim),
         ExecuteMsg::RetractBid { bid_idx, amount } => retract_bid(deps, env, info, bid_idx, amount),
         ExecuteMsg::ClaimLiquidations {
             collateral_token,
             bids_idx,
         } => claim_liquidations(deps, env, info, collateral_token, bids_idx),
+        ExecuteMsg::FillAuction { auction_idx } => fill_auction(deps, env, info, auction_idx),
+        ExecuteMsg::CancelAuction { auction_idx } => cancel_auction(deps, env, info, auction_idx),
+        ExecuteMsg::ExecuteBid {
+            liquidator,
+            fee_address,
+            repay_address,
+            borrower,
+        } => receive_native(
+            deps,
+            env,
+            info,
+            liquidator,
+            fee_address,
+            repay_address,
+            borrower,
+        ),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        // Compounding swap callback
+        COMPOUND_OPERATION => compound_hook(deps, env),
+        _ => Err(StdError::generic_err("Invalid reply ID")),
     }
 }
 
@@ -120,8 +190,11 @@ pub fn receive_cw20(
             liquidator,
             repay_address,
             fee_address,
+            borrower,
         } => {
-            let collateral_token = contract_addr.to_string();
+            let collateral_token = AssetInfo::Token {
+                contract_addr: contract_addr.to_string(),
+            };
             let repay_address = repay_address.unwrap_or_else(|| cw20_msg.sender.clone());
             let fee_address = fee_address.unwrap_or_else(|| cw20_msg.sender.clone());
 
@@ -132,6 +205,7 @@ pub fn receive_cw20(
                 liquidator,
                 repay_address,
                 fee_address,
+                borrower,
                 collateral_token,
                 cw20_msg.amount.into(),
             )
@@ -139,6 +213,45 @@ pub fn receive_cw20(
     }
 }
 
+/// Native collateral is liquidated like CW20 collateral,
+/// the coin sent with the message takes the place of the CW20 send
+pub fn receive_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    liquidator: String,
+    fee_address: Option<String>,
+    repay_address: Option<String>,
+    borrower: Option<String>,
+) -> StdResult<Response> {
+    if info.funds.len() != 1 {
+        return Err(StdError::generic_err(
+            "Exactly one native collateral coin must be sent",
+        ));
+    }
+
+    let coin = &info.funds[0];
+    let collateral_token = AssetInfo::NativeToken {
+        denom: coin.denom.clone(),
+    };
+    let amount = Uint256::from(coin.amount);
+    let sender = info.sender.to_string();
+    let repay_address = repay_address.unwrap_or_else(|| sender.clone());
+    let fee_address = fee_address.unwrap_or_else(|| sender.clone());
+
+    execute_liquidation(
+        deps,
+        env,
+        sender,
+        liquidator,
+        repay_address,
+        fee_address,
+        borrower,
+        collateral_token,
+        amount,
+    )
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
@@ -152,6 +265,7 @@ pub fn update_config(
     price_timeframe: Option<u64>,
     waiting_period: Option<u64>,
     overseer: Option<String>,
+    min_conditional_bid_amount: Option<Uint256>,
 ) -> StdResult<Response> {
     let mut config: Config = read_config(deps.storage)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
@@ -196,6 +310,10 @@ pub fn update_config(
         config.overseer = deps.api.addr_canonicalize(&overseer)?;
     }
 
+    if let Some(min_conditional_bid_amount) = min_conditional_bid_amount {
+        config.min_conditional_bid_amount = min_conditional_bid_amount;
+    }
+
     store_config(deps.storage, &config)?;
     Ok(Response::new())
 }
@@ -203,13 +321,14 @@ pub fn update_config(
 pub fn whitelist_collateral(
     deps: DepsMut,
     info: MessageInfo,
-    collateral_token: String,
+    collateral_token: AssetInfo,
     bid_threshold: Uint256,
     max_slot: u8,
     premium_rate_per_slot: Decimal256,
+    liquidation_mode: Option<LiquidationMode>,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
-    let collateral_token_raw = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
         return Err(StdError::generic_err("unauthorized"));
     }
@@ -230,6 +349,9 @@ pub fn whitelist_collateral(
     assert_max_slot(max_slot)?;
     assert_max_slot_premium(max_slot, premium_rate_per_slot)?;
 
+    let liquidation_mode = liquidation_mode.unwrap_or_default();
+    assert_liquidation_mode(&liquidation_mode)?;
+
     // save collateral info
     store_collateral_info(
         deps.storage,
@@ -239,21 +361,28 @@ pub fn whitelist_collateral(
             max_slot,
             bid_threshold,
             premium_rate_per_slot,
+            swap_pair: None,
+            max_swap_spread: None,
+            liquidation_mode,
         },
     )?;
 
     Ok(Response::new().add_attribute("action", "whitelist_collateral"))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_collateral_info(
     deps: DepsMut,
     info: MessageInfo,
-    collateral_token: String,
+    collateral_token: AssetInfo,
     bid_threshold: Option<Uint256>,
     max_slot: Option<u8>,
+    swap_pair: Option<String>,
+    max_swap_spread: Option<Decimal256>,
+    liquidation_mode: Option<LiquidationMode>,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
-    let collateral_token_raw = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
         return Err(StdError::generic_err("unauthorized"));
     }
@@ -273,6 +402,19 @@ pub fn update_collateral_info(
         collateral_info.max_slot = max_slot;
     }
 
+    if let Some(swap_pair) = swap_pair {
+        collateral_info.swap_pair = Some(deps.api.addr_canonicalize(&swap_pair)?);
+    }
+
+    if let Some(max_swap_spread) = max_swap_spread {
+        collateral_info.max_swap_spread = Some(max_swap_spread);
+    }
+
+    if let Some(liquidation_mode) = liquidation_mode {
+        assert_liquidation_mode(&liquidation_mode)?;
+        collateral_info.liquidation_mode = liquidation_mode;
+    }
+
     // save collateral info
     store_collateral_info(deps.storage, &collateral_token_raw, &collateral_info)?;
 
@@ -280,7 +422,7 @@ pub fn update_collateral_info(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::LiquidationAmount {
@@ -290,6 +432,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             collateral_prices,
         } => to_binary(&query_liquidation_amount(
             deps,
+            env,
             borrow_amount,
             borrow_limit,
             collaterals,
@@ -325,5 +468,42 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             start_after,
             limit,
         )?),
+        QueryMsg::Auction { auction_idx } => to_binary(&query_auction(deps, env, auction_idx)?),
+        QueryMsg::AuctionsByCollateral {
+            collateral_token,
+            start_after,
+            limit,
+        } => to_binary(&query_auctions_by_collateral(
+            deps,
+            env,
+            collateral_token,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::PendingCollaterals { borrower } => {
+            to_binary(&query_pending_collaterals(deps, borrower)?)
+        }
+        QueryMsg::LiquidationHistory {
+            collateral_token,
+            start_after,
+            limit,
+        } => to_binary(&query_liquidation_history(
+            deps,
+            collateral_token,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::LiquidationStats { collateral_token } => {
+            to_binary(&query_liquidation_stats(deps, collateral_token)?)
+        }
+        QueryMsg::BidDepth {
+            collateral_token,
+            collateral_amount,
+        } => to_binary(&query_bid_depth(
+            deps,
+            env,
+            collateral_token,
+            collateral_amount,
+        )?),
     }
 }
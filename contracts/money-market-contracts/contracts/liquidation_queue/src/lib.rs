@@ -1,9 +1,11 @@
 mod asserts;
+mod auction;
 mod bid;
 pub mod contract;
 mod querier;
 mod query;
 mod state;
+mod swap;
 
 #[cfg(test)]
 mod testing;
@@ -1,12 +1,15 @@
+use cosmwasm_bignumber::Uint256;
 use cosmwasm_std::{to_binary, QuerierWrapper, QueryRequest, StdResult, WasmQuery};
+use moneymarket::market::{BorrowerInfoResponse, QueryMsg as MarketQueryMsg};
 use moneymarket::overseer::{
     QueryMsg as OverseerQueryMsg, WhitelistResponse, WhitelistResponseElem,
 };
+use moneymarket::tokens::AssetInfo;
 
 pub fn query_collateral_whitelist_info(
     querier: &QuerierWrapper,
     overseer: String,
-    collateral_token: String,
+    collateral_token: AssetInfo,
 ) -> StdResult<WhitelistResponseElem> {
     let whitelist_res: WhitelistResponse =
         querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
@@ -20,3 +23,22 @@ pub fn query_collateral_whitelist_info(
 
     Ok(whitelist_res.elems[0].clone())
 }
+
+/// Query the current loan of the borrower, floating and fixed, from the market contract
+pub fn query_loan_amount(
+    querier: &QuerierWrapper,
+    market: String,
+    borrower: String,
+    block_height: u64,
+) -> StdResult<Uint256> {
+    let borrower_info: BorrowerInfoResponse =
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: market,
+            msg: to_binary(&MarketQueryMsg::BorrowerInfo {
+                borrower,
+                block_height: Some(block_height),
+            })?,
+        }))?;
+
+    Ok(borrower_info.loan_amount)
+}
@@ -1,17 +1,27 @@
-use crate::bid::{calculate_liquidated_collateral, calculate_remaining_bid};
+use crate::auction::auction_premium_rate;
+use crate::bid::{
+    calculate_liquidated_collateral, calculate_pool_liquidation, calculate_remaining_bid,
+    conditional_bids_excluded,
+};
 use crate::querier::query_collateral_whitelist_info;
 use crate::state::{
-    read_bid, read_bid_pool, read_bid_pools, read_bids_by_user, read_collateral_info, read_config,
-    read_total_bids, Bid, BidPool, CollateralInfo, Config,
+    read_auction, read_auctions_by_collateral, read_bid, read_bid_pool, read_bid_pools,
+    read_bids_by_user, read_borrower_auction, read_collateral_info, read_collateral_infos,
+    read_config, read_liquidation_events, read_liquidation_stats, read_total_bids, Auction, Bid,
+    BidAutoClaim, BidPool, CollateralInfo, Config, LiquidationEvent, LiquidationStats,
 };
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{CanonicalAddr, Deps, StdResult, Uint128};
+use cosmwasm_std::{Deps, Env, StdResult, Uint128};
 use moneymarket::liquidation_queue::{
+    AuctionResponse, AuctionsResponse, AutoClaim, BidDepthResponse, BidDepthSlotResponse,
     BidPoolResponse, BidPoolsResponse, BidResponse, BidsResponse, CollateralInfoResponse,
-    ConfigResponse, LiquidationAmountResponse,
+    ConfigResponse, LiquidationAmountResponse, LiquidationEventResponse,
+    LiquidationHistoryResponse, LiquidationMode, LiquidationSimulationResponse,
+    LiquidationStatsResponse, PendingCollateralsResponse,
 };
-use moneymarket::querier::query_tax_rate_and_cap;
-use moneymarket::tokens::TokensHuman;
+use moneymarket::oracle::PriceResponse;
+use moneymarket::querier::{query_price, query_tax_rate_and_cap};
+use moneymarket::tokens::{AssetInfo, AssetInfoRaw, TokensHuman};
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = read_config(deps.storage)?;
@@ -26,6 +36,7 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         price_timeframe: config.price_timeframe,
         waiting_period: config.waiting_period,
         overseer: deps.api.addr_humanize(&config.overseer)?.to_string(),
+        min_conditional_bid_amount: config.min_conditional_bid_amount,
     };
 
     Ok(resp)
@@ -39,6 +50,7 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 /// available bids at different premium rates
 pub fn query_liquidation_amount(
     deps: Deps,
+    env: Env,
     borrow_amount: Uint256,
     borrow_limit: Uint256,
     collaterals: TokensHuman,
@@ -78,11 +90,11 @@ pub fn query_liquidation_amount(
         * (Decimal256::one() - config.liquidator_fee)
         * (Decimal256::one() - tax_rate);
 
-    let mut result: Vec<(String, Uint256)> = vec![];
+    let mut result: TokensHuman = vec![];
     for (i, collateral) in collaterals.iter().enumerate() {
         let (price, weight, max_ltv) = (collateral_prices[i], collateral_weights[i], max_ltvs[i]);
 
-        let collateral_token_raw = deps.api.addr_canonicalize(&collateral.0)?;
+        let collateral_token_raw = collateral.0.to_raw(deps.api)?;
         let collateral_info = read_collateral_info(deps.storage, &collateral_token_raw)?;
 
         // calculate borrow amount and limit portion
@@ -91,6 +103,27 @@ pub fn query_liquidation_amount(
         let collateral_borrow_amount = borrow_amount * position_portion;
         let collateral_borrow_limit = borrow_limit * position_portion;
 
+        // auctions have no bid depth; liquidate assuming the collateral
+        // is sold at the maximum auction discount
+        if let LiquidationMode::DutchAuction {
+            max_premium_rate, ..
+        } = collateral_info.liquidation_mode
+        {
+            let discount_deductor = (Decimal256::one() - max_premium_rate) * base_fee_deductor;
+            let liquidation_amount = if discount_deductor > safe_ratio * max_ltv {
+                let nominator =
+                    collateral_borrow_amount - safe_ratio * collateral_borrow_limit + tax_cap_adj;
+                let denominator = price * (discount_deductor - (safe_ratio * max_ltv));
+
+                (nominator / denominator) + Uint256::one() // round up
+            } else {
+                collateral.1
+            };
+
+            result.push((collateral.0.clone(), liquidation_amount.min(collateral.1)));
+            continue;
+        }
+
         // iterate bid pools until safe ratio condition is met (intersection f(x) and g(x))
         let mut x = Uint256::zero();
         let mut g_x = Uint256::zero();
@@ -98,7 +131,19 @@ pub fn query_liquidation_amount(
         for slot in 0..collateral_info.max_slot + 1 {
             let (slot_available_bids, premium_rate) =
                 match read_bid_pool(deps.storage, &collateral_token_raw, slot) {
-                    Ok(bid_pool) => (bid_pool.total_bid_amount, bid_pool.premium_rate),
+                    Ok(bid_pool) => (
+                        // conditional bids that would not be consumed at this price
+                        bid_pool.total_bid_amount
+                            - conditional_bids_excluded(
+                                deps.storage,
+                                &bid_pool,
+                                &collateral_token_raw,
+                                slot,
+                                price,
+                                env.block.time.seconds(),
+                            )?,
+                        bid_pool.premium_rate,
+                    ),
                     Err(_) => continue,
                 };
             if slot_available_bids.is_zero() {
@@ -128,10 +173,7 @@ pub fn query_liquidation_amount(
 
                 let liquidation_amount = (nominator / denominator) + Uint256::one(); // round up
 
-                result.push((
-                    collateral.0.to_string(),
-                    liquidation_amount.min(collateral.1),
-                ));
+                result.push((collateral.0.clone(), liquidation_amount.min(collateral.1)));
                 intersected = true;
                 break;
             }
@@ -140,7 +182,7 @@ pub fn query_liquidation_amount(
         //      1. Not enough bids. Should try to liquidate as much as possible
         //      2. Not enouugh collateral. Also liquidate all collateral
         if !intersected {
-            result.push((collateral.0.to_string(), x)); // liquidate all collateral possible
+            result.push((collateral.0.clone(), x)); // liquidate all collateral possible
         }
     }
 
@@ -164,17 +206,24 @@ fn compute_collateral_weights(
     let mut max_ltvs: Vec<Decimal256> = vec![];
 
     for (collateral, price) in collaterals.iter().zip(collateral_prices.iter()) {
+        let collateral_token_raw = collateral.0.to_raw(deps.api)?;
+        let collateral_value = collateral.1 * *price;
+
+        // auctions are not bounded by the available bids
         let collateral_available_bids =
-            read_total_bids(deps.storage, &deps.api.addr_canonicalize(&collateral.0)?)
-                .unwrap_or_default();
+            match read_collateral_info(deps.storage, &collateral_token_raw)?.liquidation_mode {
+                LiquidationMode::DutchAuction { .. } => collateral_value,
+                LiquidationMode::BidPools {} => {
+                    read_total_bids(deps.storage, &collateral_token_raw)?
+                }
+            };
         let max_ltv = query_collateral_whitelist_info(
             &deps.querier,
             overseer.to_string(),
-            collateral.0.to_string(),
+            collateral.0.clone(),
         )?
         .max_ltv;
 
-        let collateral_value = collateral.1 * *price;
         let weigth = collateral_value.min(collateral_available_bids) / max_ltv;
 
         total_weight += weigth;
@@ -212,7 +261,7 @@ pub fn query_bid(deps: Deps, bid_idx: Uint128) -> StdResult<BidResponse> {
 
     Ok(BidResponse {
         idx: bid.idx,
-        collateral_token: deps.api.addr_humanize(&bid.collateral_token)?.to_string(),
+        collateral_token: bid.collateral_token.to_normal(deps.api)?,
         bidder: deps.api.addr_humanize(&bid.bidder)?.to_string(),
         amount: bid_amount,
         premium_slot: bid.premium_slot,
@@ -222,17 +271,20 @@ pub fn query_bid(deps: Deps, bid_idx: Uint128) -> StdResult<BidResponse> {
         wait_end: bid.wait_end,
         epoch_snapshot: bid.epoch_snapshot,
         scale_snapshot: bid.scale_snapshot,
+        auto_claim: auto_claim_response(deps, &bid.auto_claim)?,
+        expires_at: bid.expires_at,
+        min_collateral_price: bid.min_collateral_price,
     })
 }
 
 pub fn query_bids_by_user(
     deps: Deps,
-    collateral_token: String,
+    collateral_token: AssetInfo,
     bidder: String,
     start_after: Option<Uint128>,
     limit: Option<u8>,
 ) -> StdResult<BidsResponse> {
-    let collateral_token_raw = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
     let bidder_raw = deps.api.addr_canonicalize(&bidder)?;
 
     let bids: Vec<BidResponse> = read_bids_by_user(
@@ -262,7 +314,7 @@ pub fn query_bids_by_user(
         };
         let res = BidResponse {
             idx: bid.idx,
-            collateral_token: deps.api.addr_humanize(&bid.collateral_token)?.to_string(),
+            collateral_token: bid.collateral_token.to_normal(deps.api)?,
             bidder: deps.api.addr_humanize(&bid.bidder)?.to_string(),
             amount: bid_amount,
             premium_slot: bid.premium_slot,
@@ -272,6 +324,9 @@ pub fn query_bids_by_user(
             wait_end: bid.wait_end,
             epoch_snapshot: bid.epoch_snapshot,
             scale_snapshot: bid.scale_snapshot,
+            auto_claim: auto_claim_response(deps, &bid.auto_claim)?,
+            expires_at: bid.expires_at,
+            min_collateral_price: bid.min_collateral_price,
         };
         Ok(res)
     })
@@ -282,10 +337,10 @@ pub fn query_bids_by_user(
 
 pub fn query_bid_pool(
     deps: Deps,
-    collateral_token: String,
+    collateral_token: AssetInfo,
     bid_slot: u8,
 ) -> StdResult<BidPoolResponse> {
-    let collateral_token_raw: CanonicalAddr = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw: AssetInfoRaw = collateral_token.to_raw(deps.api)?;
     let bid_pool: BidPool = read_bid_pool(deps.storage, &collateral_token_raw, bid_slot)?;
 
     Ok(BidPoolResponse {
@@ -300,11 +355,11 @@ pub fn query_bid_pool(
 
 pub fn query_bid_pools(
     deps: Deps,
-    collateral_token: String,
+    collateral_token: AssetInfo,
     start_after: Option<u8>,
     limit: Option<u8>,
 ) -> StdResult<BidPoolsResponse> {
-    let collateral_token_raw = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
 
     let bid_pools: Vec<BidPoolResponse> =
         read_bid_pools(deps.storage, &collateral_token_raw, start_after, limit)?
@@ -324,16 +379,246 @@ pub fn query_bid_pools(
 
 pub fn query_collateral_info(
     deps: Deps,
-    collateral_token: String,
+    collateral_token: AssetInfo,
 ) -> StdResult<CollateralInfoResponse> {
-    let collateral_token_raw = deps.api.addr_canonicalize(&collateral_token)?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
     let collateral_info: CollateralInfo =
         read_collateral_info(deps.storage, &collateral_token_raw)?;
 
     Ok(CollateralInfoResponse {
-        collateral_token: deps.api.addr_humanize(&collateral_token_raw)?.to_string(),
+        collateral_token: collateral_token_raw.to_normal(deps.api)?,
         bid_threshold: collateral_info.bid_threshold,
         max_slot: collateral_info.max_slot,
         premium_rate_per_slot: collateral_info.premium_rate_per_slot,
+        swap_pair: collateral_info
+            .swap_pair
+            .map(|swap_pair| deps.api.addr_humanize(&swap_pair))
+            .transpose()?
+            .map(|swap_pair| swap_pair.to_string()),
+        max_swap_spread: collateral_info.max_swap_spread,
+        liquidation_mode: collateral_info.liquidation_mode,
+    })
+}
+
+pub fn query_auction(deps: Deps, env: Env, auction_idx: Uint128) -> StdResult<AuctionResponse> {
+    let auction: Auction = read_auction(deps.storage, auction_idx)?;
+    auction_response(deps, &env, &auction)
+}
+
+pub fn query_auctions_by_collateral(
+    deps: Deps,
+    env: Env,
+    collateral_token: AssetInfo,
+    start_after: Option<Uint128>,
+    limit: Option<u8>,
+) -> StdResult<AuctionsResponse> {
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
+
+    let auctions: Vec<AuctionResponse> =
+        read_auctions_by_collateral(deps.storage, &collateral_token_raw, start_after, limit)?
+            .iter()
+            .map(|auction| auction_response(deps, &env, auction))
+            .collect::<StdResult<Vec<AuctionResponse>>>()?;
+
+    Ok(AuctionsResponse { auctions })
+}
+
+pub fn query_pending_collaterals(
+    deps: Deps,
+    borrower: String,
+) -> StdResult<PendingCollateralsResponse> {
+    let borrower_raw = deps.api.addr_canonicalize(&borrower)?;
+
+    let mut collaterals: TokensHuman = vec![];
+    for collateral_info in read_collateral_infos(deps.storage)? {
+        if let Some(auction_idx) = read_borrower_auction(
+            deps.storage,
+            &collateral_info.collateral_token,
+            &borrower_raw,
+        )? {
+            let auction: Auction = read_auction(deps.storage, auction_idx)?;
+            collaterals.push((
+                auction.collateral_token.to_normal(deps.api)?,
+                auction.amount,
+            ));
+        }
+    }
+
+    Ok(PendingCollateralsResponse { collaterals })
+}
+
+pub fn query_liquidation_history(
+    deps: Deps,
+    collateral_token: Option<AssetInfo>,
+    start_after: Option<Uint128>,
+    limit: Option<u8>,
+) -> StdResult<LiquidationHistoryResponse> {
+    let collateral_token_raw = collateral_token
+        .map(|collateral_token| collateral_token.to_raw(deps.api))
+        .transpose()?;
+
+    let liquidations: Vec<LiquidationEventResponse> = read_liquidation_events(
+        deps.storage,
+        collateral_token_raw.as_ref(),
+        start_after,
+        limit,
+    )?
+    .iter()
+    .map(|liquidation: &LiquidationEvent| {
+        Ok(LiquidationEventResponse {
+            idx: liquidation.idx,
+            collateral_token: liquidation.collateral_token.to_normal(deps.api)?,
+            borrower: liquidation
+                .borrower
+                .as_ref()
+                .map(|borrower| deps.api.addr_humanize(borrower))
+                .transpose()?
+                .map(|borrower| borrower.to_string()),
+            collateral_amount: liquidation.collateral_amount,
+            repay_amount: liquidation.repay_amount,
+            premium_rate: liquidation.premium_rate,
+            bid_fee: liquidation.bid_fee,
+            liquidator_fee: liquidation.liquidator_fee,
+            block_time: liquidation.block_time,
+        })
+    })
+    .collect::<StdResult<Vec<LiquidationEventResponse>>>()?;
+
+    Ok(LiquidationHistoryResponse { liquidations })
+}
+
+pub fn query_liquidation_stats(
+    deps: Deps,
+    collateral_token: AssetInfo,
+) -> StdResult<LiquidationStatsResponse> {
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
+    let stats: LiquidationStats = read_liquidation_stats(deps.storage, &collateral_token_raw)?;
+
+    Ok(LiquidationStatsResponse {
+        collateral_token,
+        liquidation_count: stats.liquidation_count,
+        total_collateral_amount: stats.total_collateral_amount,
+        total_repay_amount: stats.total_repay_amount,
+        total_bid_fee: stats.total_bid_fee,
+        total_liquidator_fee: stats.total_liquidator_fee,
+    })
+}
+
+/// Walks the bid pools from the lowest premium slot, as `execute_liquidation` does,
+/// without consuming them
+pub fn query_bid_depth(
+    deps: Deps,
+    env: Env,
+    collateral_token: AssetInfo,
+    collateral_amount: Option<Uint256>,
+) -> StdResult<BidDepthResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
+    let collateral_info: CollateralInfo =
+        read_collateral_info(deps.storage, &collateral_token_raw)?;
+
+    let price: PriceResponse = query_price(
+        deps,
+        deps.api.addr_humanize(&config.oracle_contract)?,
+        collateral_token.to_string(),
+        config.stable_denom,
+        None,
+    )?;
+
+    let mut slots: Vec<BidDepthSlotResponse> = vec![];
+    let mut cumulative_bid_amount = Uint256::zero();
+    let mut remaining_collateral = collateral_amount.unwrap_or_default();
+    let mut liquidated_collateral = Uint256::zero();
+    let mut repay_amount = Uint256::zero();
+    for slot in 0..collateral_info.max_slot + 1 {
+        let bid_pool: BidPool = match read_bid_pool(deps.storage, &collateral_token_raw, slot) {
+            Ok(bid_pool) => bid_pool,
+            Err(_) => continue,
+        };
+        let bid_amount = bid_pool.total_bid_amount
+            - conditional_bids_excluded(
+                deps.storage,
+                &bid_pool,
+                &collateral_token_raw,
+                slot,
+                price.rate,
+                env.block.time.seconds(),
+            )?;
+        if bid_amount.is_zero() {
+            continue;
+        }
+
+        cumulative_bid_amount += bid_amount;
+        slots.push(BidDepthSlotResponse {
+            premium_slot: slot,
+            premium_rate: bid_pool.premium_rate,
+            bid_amount,
+            cumulative_bid_amount,
+        });
+
+        if !remaining_collateral.is_zero() {
+            let (pool_repay_amount, pool_liquidated_collateral, filled) =
+                calculate_pool_liquidation(
+                    bid_amount,
+                    bid_pool.premium_rate,
+                    remaining_collateral,
+                    price.rate,
+                );
+            repay_amount += pool_repay_amount;
+            liquidated_collateral += pool_liquidated_collateral;
+            remaining_collateral = if filled {
+                Uint256::zero()
+            } else {
+                remaining_collateral - pool_liquidated_collateral
+            };
+        }
+    }
+
+    let simulation = collateral_amount.map(|_| {
+        let collateral_value = liquidated_collateral * price.rate;
+        let premium_rate = if collateral_value.is_zero() || repay_amount >= collateral_value {
+            Decimal256::zero()
+        } else {
+            Decimal256::one()
+                - Decimal256::from_uint256(repay_amount)
+                    / Decimal256::from_uint256(collateral_value)
+        };
+
+        LiquidationSimulationResponse {
+            collateral_amount: liquidated_collateral,
+            repay_amount,
+            premium_rate,
+        }
+    });
+
+    Ok(BidDepthResponse {
+        collateral_token,
+        collateral_price: price.rate,
+        slots,
+        simulation,
+    })
+}
+
+fn auction_response(deps: Deps, env: &Env, auction: &Auction) -> StdResult<AuctionResponse> {
+    Ok(AuctionResponse {
+        idx: auction.idx,
+        collateral_token: auction.collateral_token.to_normal(deps.api)?,
+        borrower: deps.api.addr_humanize(&auction.borrower)?.to_string(),
+        amount: auction.amount,
+        start_time: auction.start_time,
+        premium_rate: auction_premium_rate(auction, env.block.time.seconds()),
+    })
+}
+
+fn auto_claim_response(
+    deps: Deps,
+    auto_claim: &Option<BidAutoClaim>,
+) -> StdResult<Option<AutoClaim>> {
+    Ok(match auto_claim {
+        Some(BidAutoClaim::Compound) => Some(AutoClaim::Compound {}),
+        Some(BidAutoClaim::Withdraw { recipient }) => Some(AutoClaim::Withdraw {
+            recipient: deps.api.addr_humanize(recipient)?.to_string(),
+        }),
+        None => None,
     })
 }
@@ -1,19 +1,31 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{CanonicalAddr, Order, StdError, StdResult, Storage, Uint128};
 use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+use moneymarket::liquidation_queue::LiquidationMode;
+use moneymarket::tokens::AssetInfoRaw;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
 
 static KEY_CONFIG: &[u8] = b"config";
 static KEY_BID_IDX: &[u8] = b"bid_idx";
+static KEY_PENDING_COMPOUNDS: &[u8] = b"pending_compounds";
+static KEY_AUCTION_IDX: &[u8] = b"auction_idx";
+static KEY_LIQUIDATION_IDX: &[u8] = b"liquidation_idx";
 
 static PREFIX_BID: &[u8] = b"bid";
 static PREFIX_BID_BY_USER: &[u8] = b"bid_by_user";
+static PREFIX_CONDITIONAL_BID_BY_SLOT: &[u8] = b"cond_bid_by_slot";
 static PREFIX_BID_POOL_BY_COLLATERAL: &[u8] = b"bid_pool_by_col";
 static PREFIX_TOTAL_BIDS_BY_COLLATERAL: &[u8] = b"total_bids_by_col";
 static PREFIX_COLLATERAL_INFO: &[u8] = b"col_info";
 static PREFIX_EPOCH_SCALE_SUM: &[u8] = b"epoch_scale_sum";
+static PREFIX_AUCTION: &[u8] = b"auction";
+static PREFIX_AUCTION_BY_COLLATERAL: &[u8] = b"auction_by_col";
+static PREFIX_AUCTION_BY_BORROWER: &[u8] = b"auction_by_borrower";
+static PREFIX_LIQUIDATION: &[u8] = b"liquidation";
+static PREFIX_LIQUIDATION_BY_COLLATERAL: &[u8] = b"liquidation_by_col";
+static PREFIX_LIQUIDATION_STATS: &[u8] = b"liquidation_stats";
 
 const MAX_LIMIT: u8 = 31;
 const DEFAULT_LIMIT: u8 = 10;
@@ -30,6 +42,10 @@ pub struct Config {
     pub price_timeframe: u64,
     pub waiting_period: u64,
     pub overseer: CanonicalAddr,
+    /// Minimum amount of a bid with an expiry or a price floor;
+    /// zero for configs stored before it was introduced
+    #[serde(default)]
+    pub min_conditional_bid_amount: Uint256,
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -47,30 +63,62 @@ pub fn pop_bid_idx(storage: &mut dyn Storage) -> StdResult<Uint128> {
     Ok(last_idx)
 }
 
+/// Compounding swaps waiting for their reply, in execution order.
+/// `stable_balance` is the contract balance before the next swap settles
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PendingCompounds {
+    pub stable_balance: Uint256,
+    pub bids: Vec<PendingCompound>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingCompound {
+    pub bidder: CanonicalAddr,
+    pub collateral_token: AssetInfoRaw,
+    pub premium_slot: u8,
+    pub expires_at: Option<u64>,
+    pub min_collateral_price: Option<Decimal256>,
+}
+
+pub fn store_pending_compounds(
+    storage: &mut dyn Storage,
+    pending_compounds: &PendingCompounds,
+) -> StdResult<()> {
+    singleton(storage, KEY_PENDING_COMPOUNDS).save(pending_compounds)
+}
+
+pub fn read_pending_compounds(storage: &dyn Storage) -> StdResult<PendingCompounds> {
+    Ok(singleton_read(storage, KEY_PENDING_COMPOUNDS)
+        .may_load()?
+        .unwrap_or_default())
+}
+
 pub fn store_total_bids(
     storage: &mut dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     total_bids: Uint256,
 ) -> StdResult<()> {
     let mut total_bids_bucket: Bucket<Uint256> =
         Bucket::new(storage, PREFIX_TOTAL_BIDS_BY_COLLATERAL);
-    total_bids_bucket.save(collateral_token.as_slice(), &total_bids)?;
+    total_bids_bucket.save(collateral_token.as_bytes(), &total_bids)?;
 
     Ok(())
 }
 
 pub fn read_total_bids(
     storage: &dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
 ) -> StdResult<Uint256> {
     let total_bids_bucket: ReadonlyBucket<Uint256> =
         ReadonlyBucket::new(storage, PREFIX_TOTAL_BIDS_BY_COLLATERAL);
-    total_bids_bucket.load(collateral_token.as_slice())
+    Ok(total_bids_bucket
+        .may_load(collateral_token.as_bytes())?
+        .unwrap_or_default())
 }
 
 pub fn store_epoch_scale_sum(
     storage: &mut dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     premium_slot: u8,
     epoch: Uint128,
     scale: Uint128,
@@ -80,7 +128,7 @@ pub fn store_epoch_scale_sum(
         storage,
         &[
             PREFIX_EPOCH_SCALE_SUM,
-            collateral_token.as_slice(),
+            collateral_token.as_bytes(),
             &premium_slot.to_be_bytes(),
             &epoch.u128().to_be_bytes(),
         ],
@@ -90,7 +138,7 @@ pub fn store_epoch_scale_sum(
 
 pub fn read_epoch_scale_sum(
     storage: &dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     premium_slot: u8,
     epoch: Uint128,
     scale: Uint128,
@@ -99,7 +147,7 @@ pub fn read_epoch_scale_sum(
         storage,
         &[
             PREFIX_EPOCH_SCALE_SUM,
-            collateral_token.as_slice(),
+            collateral_token.as_bytes(),
             &premium_slot.to_be_bytes(),
             &epoch.u128().to_be_bytes(),
         ],
@@ -109,33 +157,49 @@ pub fn read_epoch_scale_sum(
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CollateralInfo {
-    pub collateral_token: CanonicalAddr,
+    pub collateral_token: AssetInfoRaw,
     pub bid_threshold: Uint256,
     pub max_slot: u8,
     pub premium_rate_per_slot: Decimal256,
+    pub swap_pair: Option<CanonicalAddr>,
+    pub max_swap_spread: Option<Decimal256>,
+    #[serde(default)]
+    pub liquidation_mode: LiquidationMode,
 }
 
 pub fn store_collateral_info(
     storage: &mut dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     collateral_info: &CollateralInfo,
 ) -> StdResult<()> {
     let mut collateral_info_bucket: Bucket<CollateralInfo> =
         Bucket::new(storage, PREFIX_COLLATERAL_INFO);
-    collateral_info_bucket.save(collateral_token.as_slice(), collateral_info)?;
+    collateral_info_bucket.save(collateral_token.as_bytes(), collateral_info)?;
 
     Ok(())
 }
 
 pub fn read_collateral_info(
     storage: &dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
 ) -> StdResult<CollateralInfo> {
     let collateral_info_bucket: ReadonlyBucket<CollateralInfo> =
         ReadonlyBucket::new(storage, PREFIX_COLLATERAL_INFO);
     collateral_info_bucket
-        .load(collateral_token.as_slice())
-        .map_err(|_| StdError::generic_err("Collateral is not whitelisted"))
+        .may_load(collateral_token.as_bytes())?
+        .ok_or_else(|| StdError::generic_err("Collateral is not whitelisted"))
+}
+
+pub fn read_collateral_infos(storage: &dyn Storage) -> StdResult<Vec<CollateralInfo>> {
+    let collateral_info_bucket: ReadonlyBucket<CollateralInfo> =
+        ReadonlyBucket::new(storage, PREFIX_COLLATERAL_INFO);
+    collateral_info_bucket
+        .range(None, None, Order::Ascending)
+        .map(|elem| {
+            let (_, v) = elem?;
+            Ok(v)
+        })
+        .collect()
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -152,29 +216,29 @@ pub struct BidPool {
 
 pub fn store_bid_pool(
     storage: &mut dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     premium_slot: u8,
     bid_pool: &BidPool,
 ) -> StdResult<()> {
     let mut bid_pool_bucket: Bucket<BidPool> = Bucket::multilevel(
         storage,
-        &[PREFIX_BID_POOL_BY_COLLATERAL, collateral_token.as_slice()],
+        &[PREFIX_BID_POOL_BY_COLLATERAL, collateral_token.as_bytes()],
     );
     bid_pool_bucket.save(&premium_slot.to_be_bytes(), bid_pool)
 }
 
 pub fn read_bid_pool(
     storage: &dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     premium_slot: u8,
 ) -> StdResult<BidPool> {
     let bid_pool_bucket: ReadonlyBucket<BidPool> = ReadonlyBucket::multilevel(
         storage,
-        &[PREFIX_BID_POOL_BY_COLLATERAL, collateral_token.as_slice()],
+        &[PREFIX_BID_POOL_BY_COLLATERAL, collateral_token.as_bytes()],
     );
     bid_pool_bucket
-        .load(&premium_slot.to_be_bytes())
-        .map_err(|_| StdError::generic_err("Bid pool not found"))
+        .may_load(&premium_slot.to_be_bytes())?
+        .ok_or_else(|| StdError::generic_err("Bid pool not found"))
 }
 
 pub fn read_or_create_bid_pool(
@@ -186,12 +250,12 @@ pub fn read_or_create_bid_pool(
         storage,
         &[
             PREFIX_BID_POOL_BY_COLLATERAL,
-            collateral_info.collateral_token.as_slice(),
+            collateral_info.collateral_token.as_bytes(),
         ],
     );
-    match bid_pool_bucket.load(&premium_slot.to_be_bytes()) {
-        Ok(bid_pool) => Ok(bid_pool),
-        Err(_) => {
+    match bid_pool_bucket.may_load(&premium_slot.to_be_bytes())? {
+        Some(bid_pool) => Ok(bid_pool),
+        None => {
             if (0..collateral_info.max_slot + 1).contains(&premium_slot) {
                 let bid_pool = BidPool {
                     product_snapshot: Decimal256::one(),
@@ -220,13 +284,13 @@ pub fn read_or_create_bid_pool(
 
 pub fn read_bid_pools(
     storage: &dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     start_after: Option<u8>,
     limit: Option<u8>,
 ) -> StdResult<Vec<BidPool>> {
     let bid_pool_bucket: ReadonlyBucket<BidPool> = ReadonlyBucket::multilevel(
         storage,
-        &[PREFIX_BID_POOL_BY_COLLATERAL, collateral_token.as_slice()],
+        &[PREFIX_BID_POOL_BY_COLLATERAL, collateral_token.as_bytes()],
     );
 
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
@@ -245,7 +309,7 @@ pub fn read_bid_pools(
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Bid {
     pub idx: Uint128,
-    pub collateral_token: CanonicalAddr,
+    pub collateral_token: AssetInfoRaw,
     pub premium_slot: u8,
     pub bidder: CanonicalAddr,
     pub amount: Uint256,
@@ -255,6 +319,21 @@ pub struct Bid {
     pub wait_end: Option<u64>,
     pub epoch_snapshot: Uint128,
     pub scale_snapshot: Uint128,
+    pub auto_claim: Option<BidAutoClaim>,
+    pub expires_at: Option<u64>,
+    pub min_collateral_price: Option<Decimal256>,
+}
+
+impl Bid {
+    pub fn is_conditional(&self) -> bool {
+        self.expires_at.is_some() || self.min_collateral_price.is_some()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum BidAutoClaim {
+    Compound,
+    Withdraw { recipient: CanonicalAddr },
 }
 
 pub fn store_bid(storage: &mut dyn Storage, bid_idx: Uint128, bid: &Bid) -> StdResult<()> {
@@ -265,12 +344,28 @@ pub fn store_bid(storage: &mut dyn Storage, bid_idx: Uint128, bid: &Bid) -> StdR
         storage,
         &[
             PREFIX_BID_BY_USER,
-            bid.collateral_token.as_slice(),
+            bid.collateral_token.as_bytes(),
             bid.bidder.as_slice(),
         ],
     );
     bid_indexer_by_user.save(&bid_idx.u128().to_be_bytes(), &true)?;
 
+    // conditional bids are checked on every liquidation of their pool
+    // until they are consumed or refunded
+    let mut conditional_bid_indexer: Bucket<bool> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_CONDITIONAL_BID_BY_SLOT,
+            bid.collateral_token.as_bytes(),
+            &bid.premium_slot.to_be_bytes(),
+        ],
+    );
+    if bid.is_conditional() && !bid.amount.is_zero() {
+        conditional_bid_indexer.save(&bid_idx.u128().to_be_bytes(), &true)?;
+    } else {
+        conditional_bid_indexer.remove(&bid_idx.u128().to_be_bytes());
+    }
+
     Ok(())
 }
 
@@ -284,25 +379,267 @@ pub fn remove_bid(storage: &mut dyn Storage, bid_idx: Uint128) -> StdResult<()>
         storage,
         &[
             PREFIX_BID_BY_USER,
-            bid.collateral_token.as_slice(),
+            bid.collateral_token.as_bytes(),
             bid.bidder.as_slice(),
         ],
     );
     bid_indexer_by_user.remove(&bid_idx.u128().to_be_bytes());
 
+    let mut conditional_bid_indexer: Bucket<bool> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_CONDITIONAL_BID_BY_SLOT,
+            bid.collateral_token.as_bytes(),
+            &bid.premium_slot.to_be_bytes(),
+        ],
+    );
+    conditional_bid_indexer.remove(&bid_idx.u128().to_be_bytes());
+
     Ok(())
 }
 
 pub fn read_bid(storage: &dyn Storage, bid_idx: Uint128) -> StdResult<Bid> {
     let bid_bucket: ReadonlyBucket<Bid> = ReadonlyBucket::new(storage, PREFIX_BID);
     bid_bucket
-        .load(&bid_idx.u128().to_be_bytes())
-        .map_err(|_| StdError::generic_err("No bids with the specified information exist"))
+        .may_load(&bid_idx.u128().to_be_bytes())?
+        .ok_or_else(|| StdError::generic_err("No bids with the specified information exist"))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Auction {
+    pub idx: Uint128,
+    pub collateral_token: AssetInfoRaw,
+    pub borrower: CanonicalAddr,
+    pub amount: Uint256,
+    pub start_time: u64,
+    pub max_premium_rate: Decimal256,
+    pub duration: u64,
+    pub liquidator: CanonicalAddr,
+    pub repay_address: CanonicalAddr,
+    pub fee_address: CanonicalAddr,
+}
+
+pub fn pop_auction_idx(storage: &mut dyn Storage) -> StdResult<Uint128> {
+    let mut idx_store = singleton(storage, KEY_AUCTION_IDX);
+    let last_idx: Uint128 = idx_store.load().unwrap_or_else(|_| Uint128::from(1u128));
+    idx_store.save(&(last_idx + Uint128::from(1u128)))?;
+    Ok(last_idx)
+}
+
+pub fn store_auction(storage: &mut dyn Storage, auction: &Auction) -> StdResult<()> {
+    let idx_key = auction.idx.u128().to_be_bytes();
+    let mut auction_bucket: Bucket<Auction> = Bucket::new(storage, PREFIX_AUCTION);
+    auction_bucket.save(&idx_key, auction)?;
+
+    let mut auction_indexer: Bucket<bool> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_AUCTION_BY_COLLATERAL,
+            auction.collateral_token.as_bytes(),
+        ],
+    );
+    auction_indexer.save(&idx_key, &true)?;
+
+    let mut borrower_indexer: Bucket<Uint128> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_AUCTION_BY_BORROWER,
+            auction.collateral_token.as_bytes(),
+        ],
+    );
+    borrower_indexer.save(auction.borrower.as_slice(), &auction.idx)?;
+
+    Ok(())
+}
+
+pub fn remove_auction(storage: &mut dyn Storage, auction: &Auction) {
+    let idx_key = auction.idx.u128().to_be_bytes();
+    let mut auction_bucket: Bucket<Auction> = Bucket::new(storage, PREFIX_AUCTION);
+    auction_bucket.remove(&idx_key);
+
+    // remove indexers
+    let mut auction_indexer: Bucket<bool> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_AUCTION_BY_COLLATERAL,
+            auction.collateral_token.as_bytes(),
+        ],
+    );
+    auction_indexer.remove(&idx_key);
+
+    let mut borrower_indexer: Bucket<Uint128> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_AUCTION_BY_BORROWER,
+            auction.collateral_token.as_bytes(),
+        ],
+    );
+    borrower_indexer.remove(auction.borrower.as_slice());
+}
+
+pub fn read_auction(storage: &dyn Storage, auction_idx: Uint128) -> StdResult<Auction> {
+    let auction_bucket: ReadonlyBucket<Auction> = ReadonlyBucket::new(storage, PREFIX_AUCTION);
+    auction_bucket
+        .may_load(&auction_idx.u128().to_be_bytes())?
+        .ok_or_else(|| StdError::generic_err("No auctions with the specified information exist"))
+}
+
+pub fn read_borrower_auction(
+    storage: &dyn Storage,
+    collateral_token: &AssetInfoRaw,
+    borrower: &CanonicalAddr,
+) -> StdResult<Option<Uint128>> {
+    let borrower_indexer: ReadonlyBucket<Uint128> = ReadonlyBucket::multilevel(
+        storage,
+        &[PREFIX_AUCTION_BY_BORROWER, collateral_token.as_bytes()],
+    );
+    borrower_indexer.may_load(borrower.as_slice())
+}
+
+pub fn read_auctions_by_collateral(
+    storage: &dyn Storage,
+    collateral_token: &AssetInfoRaw,
+    start_after: Option<Uint128>,
+    limit: Option<u8>,
+) -> StdResult<Vec<Auction>> {
+    let auction_indexer: ReadonlyBucket<bool> = ReadonlyBucket::multilevel(
+        storage,
+        &[PREFIX_AUCTION_BY_COLLATERAL, collateral_token.as_bytes()],
+    );
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start_idx(start_after);
+
+    auction_indexer
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|elem| {
+            let (k, _) = elem?;
+            read_auction(storage, Uint128::from(bytes_to_u128(&k)?))
+        })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationEvent {
+    pub idx: Uint128,
+    pub collateral_token: AssetInfoRaw,
+    pub borrower: Option<CanonicalAddr>,
+    pub collateral_amount: Uint256,
+    pub repay_amount: Uint256,
+    pub premium_rate: Decimal256,
+    pub bid_fee: Uint256,
+    pub liquidator_fee: Uint256,
+    pub block_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LiquidationStats {
+    pub liquidation_count: u64,
+    pub total_collateral_amount: Uint256,
+    pub total_repay_amount: Uint256,
+    pub total_bid_fee: Uint256,
+    pub total_liquidator_fee: Uint256,
+}
+
+pub fn pop_liquidation_idx(storage: &mut dyn Storage) -> StdResult<Uint128> {
+    let mut idx_store = singleton(storage, KEY_LIQUIDATION_IDX);
+    let last_idx: Uint128 = idx_store.load().unwrap_or_else(|_| Uint128::from(1u128));
+    idx_store.save(&(last_idx + Uint128::from(1u128)))?;
+    Ok(last_idx)
+}
+
+pub fn store_liquidation_event(
+    storage: &mut dyn Storage,
+    liquidation: &LiquidationEvent,
+) -> StdResult<()> {
+    let idx_key = liquidation.idx.u128().to_be_bytes();
+    let mut liquidation_bucket: Bucket<LiquidationEvent> = Bucket::new(storage, PREFIX_LIQUIDATION);
+    liquidation_bucket.save(&idx_key, liquidation)?;
+
+    let mut liquidation_indexer: Bucket<bool> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_LIQUIDATION_BY_COLLATERAL,
+            liquidation.collateral_token.as_bytes(),
+        ],
+    );
+    liquidation_indexer.save(&idx_key, &true)
+}
+
+pub fn read_liquidation_event(
+    storage: &dyn Storage,
+    liquidation_idx: Uint128,
+) -> StdResult<LiquidationEvent> {
+    let liquidation_bucket: ReadonlyBucket<LiquidationEvent> =
+        ReadonlyBucket::new(storage, PREFIX_LIQUIDATION);
+    liquidation_bucket.load(&liquidation_idx.u128().to_be_bytes())
+}
+
+pub fn read_liquidation_events(
+    storage: &dyn Storage,
+    collateral_token: Option<&AssetInfoRaw>,
+    start_after: Option<Uint128>,
+    limit: Option<u8>,
+) -> StdResult<Vec<LiquidationEvent>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start_idx(start_after);
+
+    if let Some(collateral_token) = collateral_token {
+        let liquidation_indexer: ReadonlyBucket<bool> = ReadonlyBucket::multilevel(
+            storage,
+            &[
+                PREFIX_LIQUIDATION_BY_COLLATERAL,
+                collateral_token.as_bytes(),
+            ],
+        );
+
+        liquidation_indexer
+            .range(start.as_deref(), None, Order::Ascending)
+            .take(limit)
+            .map(|elem| {
+                let (k, _) = elem?;
+                read_liquidation_event(storage, Uint128::from(bytes_to_u128(&k)?))
+            })
+            .collect()
+    } else {
+        let liquidation_bucket: ReadonlyBucket<LiquidationEvent> =
+            ReadonlyBucket::new(storage, PREFIX_LIQUIDATION);
+
+        liquidation_bucket
+            .range(start.as_deref(), None, Order::Ascending)
+            .take(limit)
+            .map(|elem| {
+                let (_, v) = elem?;
+                Ok(v)
+            })
+            .collect()
+    }
+}
+
+pub fn store_liquidation_stats(
+    storage: &mut dyn Storage,
+    collateral_token: &AssetInfoRaw,
+    stats: &LiquidationStats,
+) -> StdResult<()> {
+    let mut stats_bucket: Bucket<LiquidationStats> = Bucket::new(storage, PREFIX_LIQUIDATION_STATS);
+    stats_bucket.save(collateral_token.as_bytes(), stats)
+}
+
+pub fn read_liquidation_stats(
+    storage: &dyn Storage,
+    collateral_token: &AssetInfoRaw,
+) -> StdResult<LiquidationStats> {
+    let stats_bucket: ReadonlyBucket<LiquidationStats> =
+        ReadonlyBucket::new(storage, PREFIX_LIQUIDATION_STATS);
+    Ok(stats_bucket
+        .may_load(collateral_token.as_bytes())?
+        .unwrap_or_default())
 }
 
 pub fn read_bids_by_user(
     storage: &dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     bidder: &CanonicalAddr,
     start_after: Option<Uint128>,
     limit: Option<u8>,
@@ -311,7 +648,7 @@ pub fn read_bids_by_user(
         storage,
         &[
             PREFIX_BID_BY_USER,
-            collateral_token.as_slice(),
+            collateral_token.as_bytes(),
             bidder.as_slice(),
         ],
     );
@@ -329,6 +666,48 @@ pub fn read_bids_by_user(
         .collect()
 }
 
+pub fn read_conditional_bids(
+    storage: &dyn Storage,
+    collateral_token: &AssetInfoRaw,
+    premium_slot: u8,
+) -> StdResult<Vec<Bid>> {
+    let conditional_bid_index: ReadonlyBucket<bool> = ReadonlyBucket::multilevel(
+        storage,
+        &[
+            PREFIX_CONDITIONAL_BID_BY_SLOT,
+            collateral_token.as_bytes(),
+            &premium_slot.to_be_bytes(),
+        ],
+    );
+
+    conditional_bid_index
+        .range(None, None, Order::Ascending)
+        .map(|elem| {
+            let (k, _) = elem?;
+            read_bid(storage, Uint128::from(bytes_to_u128(&k)?))
+        })
+        .collect()
+}
+
+pub fn count_conditional_bids(
+    storage: &dyn Storage,
+    collateral_token: &AssetInfoRaw,
+    premium_slot: u8,
+) -> usize {
+    let conditional_bid_index: ReadonlyBucket<bool> = ReadonlyBucket::multilevel(
+        storage,
+        &[
+            PREFIX_CONDITIONAL_BID_BY_SLOT,
+            collateral_token.as_bytes(),
+            &premium_slot.to_be_bytes(),
+        ],
+    );
+
+    conditional_bid_index
+        .range(None, None, Order::Ascending)
+        .count()
+}
+
 fn bytes_to_u128(data: &[u8]) -> StdResult<u128> {
     match data[0..16].try_into() {
         Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
@@ -0,0 +1,90 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{to_binary, Coin, CosmosMsg, Deps, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use moneymarket::querier::deduct_tax;
+use moneymarket::tokens::AssetInfo;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairCw20HookMsg {
+    /// Sell the sent tokens for the other asset of the pair
+    Swap {
+        belief_price: Option<Decimal256>,
+        max_spread: Option<Decimal256>,
+        to: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairAsset {
+    pub info: AssetInfo,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairExecuteMsg {
+    /// Sell the sent native coins for the other asset of the pair
+    Swap {
+        offer_asset: PairAsset,
+        belief_price: Option<Decimal256>,
+        max_spread: Option<Decimal256>,
+        to: Option<String>,
+    },
+}
+
+/// Sends `amount` of collateral to the swap pair, selling it for stable denom.
+/// `price` is the oracle price of the collateral in stable denom
+pub fn swap_collateral_msg(
+    deps: Deps,
+    collateral_token: &AssetInfo,
+    swap_pair: String,
+    amount: Uint256,
+    price: Decimal256,
+    max_spread: Option<Decimal256>,
+    to: Option<String>,
+) -> StdResult<CosmosMsg> {
+    // belief price is quoted as offer asset per ask asset
+    let belief_price = Some(Decimal256::one() / price);
+
+    match collateral_token {
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: swap_pair,
+                amount: amount.into(),
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    belief_price,
+                    max_spread,
+                    to,
+                })?,
+            })?,
+        })),
+        AssetInfo::NativeToken { denom } => {
+            let offer = deduct_tax(
+                deps,
+                Coin {
+                    denom: denom.to_string(),
+                    amount: amount.into(),
+                },
+            )?;
+
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: swap_pair,
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset: PairAsset {
+                        info: collateral_token.clone(),
+                        amount: offer.amount,
+                    },
+                    belief_price,
+                    max_spread,
+                    to,
+                })?,
+                funds: vec![offer],
+            }))
+        }
+    }
+}
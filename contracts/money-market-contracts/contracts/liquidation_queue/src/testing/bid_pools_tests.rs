@@ -8,6 +8,7 @@ use cw20::Cw20ReceiveMsg;
 use moneymarket::liquidation_queue::{
     BidPoolResponse, BidResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
 };
+use moneymarket::tokens::AssetInfo;
 
 #[test]
 fn one_bidder_distribution() {
@@ -45,18 +46,25 @@ fn one_bidder_distribution() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // ALICE BIDS 100 UST
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -70,7 +78,9 @@ fn one_bidder_distribution() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let mut env = mock_env();
@@ -87,6 +97,7 @@ fn one_bidder_distribution() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -94,7 +105,9 @@ fn one_bidder_distribution() {
 
     // ALICE CAN CLAIM 2 COL
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -160,18 +173,25 @@ fn two_bidder_distribution() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // ALICE BIDS 100 UST
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -185,7 +205,9 @@ fn two_bidder_distribution() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -202,6 +224,7 @@ fn two_bidder_distribution() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -209,8 +232,12 @@ fn two_bidder_distribution() {
 
     // BOB BIDS 60 UST
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "bob0000",
@@ -222,7 +249,9 @@ fn two_bidder_distribution() {
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let info = mock_info("bob0000", &[]);
@@ -250,6 +279,7 @@ fn two_bidder_distribution() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -259,7 +289,9 @@ fn two_bidder_distribution() {
     //      SPENT: 40 UST + 60 UST
     //      CLAIM: 4col + 3col
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -286,7 +318,9 @@ fn two_bidder_distribution() {
     //      SPENT: 60 UST (remaining 20)
     //      CLAIM: 3col
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("bob0000", &[]);
@@ -346,18 +380,25 @@ fn two_bidder_distribution_big_numbers() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // ALICE BIDS 10,000 UST
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -371,7 +412,9 @@ fn two_bidder_distribution_big_numbers() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -388,6 +431,7 @@ fn two_bidder_distribution_big_numbers() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -395,8 +439,12 @@ fn two_bidder_distribution_big_numbers() {
 
     // BOB BIDS 6,000 UST
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "bob0000",
@@ -408,7 +456,9 @@ fn two_bidder_distribution_big_numbers() {
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let info = mock_info("bob0000", &[]);
@@ -436,6 +486,7 @@ fn two_bidder_distribution_big_numbers() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -445,7 +496,9 @@ fn two_bidder_distribution_big_numbers() {
     //      SPENT: 4000 UST + 6000 UST
     //      CLAIM: 400col + 300col
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -472,7 +525,9 @@ fn two_bidder_distribution_big_numbers() {
     //      SPENT: 6000 UST (remaining 2000)
     //      CLAIM: 300col
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("bob0000", &[]);
@@ -532,18 +587,25 @@ fn one_user_two_bid_slots() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // ALICE BIDS 100 UST at 5%
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -558,8 +620,12 @@ fn one_user_two_bid_slots() {
 
     // ALICE BIDS 100 UST at 10%
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -571,7 +637,9 @@ fn one_user_two_bid_slots() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128), Uint128::from(2u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -588,6 +656,7 @@ fn one_user_two_bid_slots() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -595,7 +664,9 @@ fn one_user_two_bid_slots() {
 
     // ALICE CAN CLAIM 5 COL
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("alice0000", &[]);
@@ -618,6 +689,7 @@ fn one_user_two_bid_slots() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -625,7 +697,9 @@ fn one_user_two_bid_slots() {
 
     // ALICE CAN CLAIM FROM ALL BIDS
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("alice0000", &[]);
@@ -698,18 +772,25 @@ fn partial_withdraw_after_execution() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // ALICE BIDS 1000 UST
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -723,7 +804,9 @@ fn partial_withdraw_after_execution() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -740,6 +823,7 @@ fn partial_withdraw_after_execution() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -755,8 +839,12 @@ fn partial_withdraw_after_execution() {
 
     // BOB BIDS 250 UST
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "bob0000",
@@ -768,7 +856,9 @@ fn partial_withdraw_after_execution() {
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let info = mock_info("bob0000", &[]);
@@ -784,6 +874,7 @@ fn partial_withdraw_after_execution() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -794,7 +885,9 @@ fn partial_withdraw_after_execution() {
     //      WITHDRAWABLE: 150UST
     //      CLAIM: 10col + 2col
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -830,7 +923,9 @@ fn partial_withdraw_after_execution() {
     //      WITHDRAWABLE: 150UST
     //      CLAIM: 2col
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("bob0000", &[]);
@@ -899,18 +994,25 @@ fn completely_empty_pool() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     // ALICE BIDS 1000 UST
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -930,6 +1032,7 @@ fn completely_empty_pool() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -937,8 +1040,12 @@ fn completely_empty_pool() {
 
     // BOB BIDS 2000 UST
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "bob0000",
@@ -968,7 +1075,9 @@ fn completely_empty_pool() {
             deps.as_ref(),
             mock_env(),
             QueryMsg::BidPool {
-                collateral_token: "col0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "col0000".to_string(),
+                },
                 bid_slot: 0u8,
             },
         )
@@ -997,6 +1106,7 @@ fn completely_empty_pool() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1004,7 +1114,9 @@ fn completely_empty_pool() {
 
     // alice can only claim the initial 20 col
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("alice0000", &[]);
@@ -1026,7 +1138,9 @@ fn completely_empty_pool() {
 
     // bob can claim the later 20 col
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("bob0000", &[]);
@@ -1077,10 +1191,13 @@ fn product_truncated_to_zero() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -1089,8 +1206,12 @@ fn product_truncated_to_zero() {
     for _ in 0..8 {
         // ALICE BIDS 1000000000 uUST
         let msg = ExecuteMsg::SubmitBid {
-            collateral_token: "col0000".to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: "col0000".to_string(),
+            },
             premium_slot: 0u8,
+            expires_at: None,
+            min_collateral_price: None,
         };
         let info = mock_info(
             "alice0000",
@@ -1110,6 +1231,7 @@ fn product_truncated_to_zero() {
                 liquidator: "liquidator00000".to_string(),
                 fee_address: Some("fee0000".to_string()),
                 repay_address: Some("repay0000".to_string()),
+                borrower: None,
             })
             .unwrap(),
         });
@@ -1118,7 +1240,9 @@ fn product_truncated_to_zero() {
 
     // alice can claim everything
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("alice0000", &[]);
@@ -1137,7 +1261,9 @@ fn product_truncated_to_zero() {
             deps.as_ref(),
             mock_env(),
             QueryMsg::BidPool {
-                collateral_token: "col0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "col0000".to_string(),
+                },
                 bid_slot: 0u8,
             },
         )
@@ -1200,18 +1326,25 @@ fn two_bidder_reward_distribution_common_slot() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     // ALICE BIDS 100 UST IN THE 5% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -1225,7 +1358,9 @@ fn two_bidder_reward_distribution_common_slot() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -1235,8 +1370,12 @@ fn two_bidder_reward_distribution_common_slot() {
 
     // BOB BIDS 100 UST IN THE SAME POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "bob0000",
@@ -1250,7 +1389,9 @@ fn two_bidder_reward_distribution_common_slot() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let info = mock_info("bob0000", &[]);
@@ -1269,6 +1410,7 @@ fn two_bidder_reward_distribution_common_slot() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1279,7 +1421,9 @@ fn two_bidder_reward_distribution_common_slot() {
     //      CLAIM: 5 col
     //      WITHDRAW: 100 - 47.5 = 52.5 ust
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("alice0000", &[]);
@@ -1311,7 +1455,9 @@ fn two_bidder_reward_distribution_common_slot() {
     //      CLAIM: 5 col
     //      WITHDRAW: 100 - 47.5 = 52.5 UST
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("bob0000", &[]);
@@ -1376,10 +1522,13 @@ fn two_bidder_distribution_multiple_common_slots() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -1387,8 +1536,12 @@ fn two_bidder_distribution_multiple_common_slots() {
 
     // Alice BIDS 100 UST to 5% pool
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
 
     let info = mock_info(
@@ -1403,7 +1556,9 @@ fn two_bidder_distribution_multiple_common_slots() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let mut env = mock_env();
@@ -1413,8 +1568,12 @@ fn two_bidder_distribution_multiple_common_slots() {
 
     // BOB BIDS 100 UST TO THE 5% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
 
     let info = mock_info(
@@ -1428,7 +1587,9 @@ fn two_bidder_distribution_multiple_common_slots() {
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let info = mock_info("bob0000", &[]);
@@ -1438,8 +1599,12 @@ fn two_bidder_distribution_multiple_common_slots() {
 
     // ALICE BIDS 200 UST TO THE 10% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
 
     let info = mock_info(
@@ -1454,7 +1619,9 @@ fn two_bidder_distribution_multiple_common_slots() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(3u128)]),
     };
 
@@ -1465,8 +1632,12 @@ fn two_bidder_distribution_multiple_common_slots() {
 
     // Bob Bids 200 UST to 10% pool
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
 
     let info = mock_info(
@@ -1481,7 +1652,9 @@ fn two_bidder_distribution_multiple_common_slots() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(4u128)]),
     };
 
@@ -1501,6 +1674,7 @@ fn two_bidder_distribution_multiple_common_slots() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1516,6 +1690,7 @@ fn two_bidder_distribution_multiple_common_slots() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1527,7 +1702,9 @@ fn two_bidder_distribution_multiple_common_slots() {
 
     // ALICE LIQUIDATION CLAIM
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
 
@@ -1543,7 +1720,9 @@ fn two_bidder_distribution_multiple_common_slots() {
     );
 
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(3u128)]),
     };
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -1558,7 +1737,9 @@ fn two_bidder_distribution_multiple_common_slots() {
 
     // BOB LIQUIDATION CLAIM
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let info = mock_info("bob0000", &[]);
@@ -1572,7 +1753,9 @@ fn two_bidder_distribution_multiple_common_slots() {
         ]
     );
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(4u128)]),
     };
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -1692,18 +1875,25 @@ fn two_bidder_unequal_deposit_reward_distribution() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     // ALICE BIDS 150 UST IN THE 2% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 2u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -1717,7 +1907,9 @@ fn two_bidder_unequal_deposit_reward_distribution() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -1727,8 +1919,12 @@ fn two_bidder_unequal_deposit_reward_distribution() {
 
     // BOB BIDS 200 UST IN THE SAME POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 2u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "bob0000",
@@ -1742,7 +1938,9 @@ fn two_bidder_unequal_deposit_reward_distribution() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let info = mock_info("bob0000", &[]);
@@ -1761,6 +1959,7 @@ fn two_bidder_unequal_deposit_reward_distribution() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1781,7 +1980,9 @@ fn two_bidder_unequal_deposit_reward_distribution() {
 
     // ALICE
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("alice0000", &[]);
@@ -1810,7 +2011,9 @@ fn two_bidder_unequal_deposit_reward_distribution() {
 
     // BOB
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("bob0000", &[]);
@@ -1875,10 +2078,13 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -1886,8 +2092,12 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
 
     // ALICE BIDS 50 UST TO 10% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
 
     let info = mock_info(
@@ -1902,7 +2112,9 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -1912,8 +2124,12 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
 
     // BOB BIDS 100 UST TO 10% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
 
     let info = mock_info(
@@ -1927,7 +2143,9 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let mut env = mock_env();
@@ -1937,8 +2155,12 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
 
     // JOHN BIDS 100 UST TO 10% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
 
     let info = mock_info(
@@ -1953,7 +2175,9 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(3u128)]),
     };
 
@@ -1976,6 +2200,7 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1987,8 +2212,12 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
 
     // BOB BIDS 250 UST TO 10% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
 
     let info = mock_info(
@@ -2002,7 +2231,9 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(4u128)]),
     };
 
@@ -2013,8 +2244,12 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
 
     // JOHN BIDS 250 UST TO 10% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
 
     let info = mock_info(
@@ -2029,7 +2264,9 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(5u128)]),
     };
 
@@ -2049,6 +2286,7 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2060,7 +2298,9 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
 
     // ALICE LIQUIDATION CLAIM
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
 
@@ -2130,18 +2370,25 @@ fn not_enough_bid_for_collateral() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     // ALICE BIDS 100 UST IN THE 6% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 6u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -2155,7 +2402,9 @@ fn not_enough_bid_for_collateral() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let info = mock_info("alice0000", &[]);
@@ -2165,8 +2414,12 @@ fn not_enough_bid_for_collateral() {
 
     // BOB BIDS 100 UST IN THE 6% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 6u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "bob0000",
@@ -2178,7 +2431,9 @@ fn not_enough_bid_for_collateral() {
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let info = mock_info("bob0000", &[]);
@@ -2198,6 +2453,7 @@ fn not_enough_bid_for_collateral() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2247,18 +2503,25 @@ fn two_bidder_reward_distribution_common_slot_large_numbers() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // ALICE BIDS 1 TRILLION UST IN THE 5% POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "alice0000",
@@ -2272,7 +2535,9 @@ fn two_bidder_reward_distribution_common_slot_large_numbers() {
     execute(deps.as_mut(), env, info.clone(), msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let mut env = mock_env();
@@ -2281,8 +2546,12 @@ fn two_bidder_reward_distribution_common_slot_large_numbers() {
 
     // BOB BIDS 1 TRILLION UST IN THE SAME POOL
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "bob0000",
@@ -2296,7 +2565,9 @@ fn two_bidder_reward_distribution_common_slot_large_numbers() {
     execute(deps.as_mut(), env, info.clone(), msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(2u128)]),
     };
     let mut env = mock_env();
@@ -2314,6 +2585,7 @@ fn two_bidder_reward_distribution_common_slot_large_numbers() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2324,7 +2596,9 @@ fn two_bidder_reward_distribution_common_slot_large_numbers() {
     //      CLAIM: 500 million col
     //      WITHDRAW: 1.05 trillion / 2 = 0.525 billion = 525 million
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("alice0000", &[]);
@@ -2356,7 +2630,9 @@ fn two_bidder_reward_distribution_common_slot_large_numbers() {
     //      CLAIM: 500 million col
     //      WITHDRAW: 1.05 trillion / 2 = 0.525 billion = 525 million
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("bob0000", &[]);
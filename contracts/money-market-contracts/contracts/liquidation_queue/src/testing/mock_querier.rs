@@ -2,7 +2,7 @@ use moneymarket::overseer::{WhitelistResponse, WhitelistResponseElem};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_bignumber::Decimal256;
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
@@ -10,7 +10,9 @@ use cosmwasm_std::{
 };
 use std::collections::HashMap;
 
-use moneymarket::oracle::PriceResponse;
+use moneymarket::market::BorrowerInfoResponse;
+use moneymarket::oracle::{PriceJumpResponse, PriceResponse};
+use moneymarket::tokens::AssetInfo;
 use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -18,11 +20,18 @@ use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrap
 pub enum QueryMsg {
     /// Query oracle price to oracle contract
     Price { base: String, quote: String },
+    /// Query price jump flag to oracle contract
+    PriceJump { asset: String },
     Whitelist {
-        collateral_token: Option<String>,
-        start_after: Option<String>,
+        collateral_token: Option<AssetInfo>,
+        start_after: Option<AssetInfo>,
         limit: Option<u32>,
     },
+    /// Query borrower loan to market contract
+    BorrowerInfo {
+        borrower: String,
+        block_height: Option<u64>,
+    },
 }
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
@@ -45,7 +54,9 @@ pub struct WasmMockQuerier {
     base: MockQuerier<TerraQueryWrapper>,
     tax_querier: TaxQuerier,
     oracle_price_querier: OraclePriceQuerier,
+    price_jump_querier: PriceJumpQuerier,
     collateral_querier: CollateralQuerier,
+    loan_querier: LoanQuerier,
 }
 
 #[derive(Clone, Default)]
@@ -71,6 +82,21 @@ pub(crate) fn collaterals_to_map(
     collateral_map
 }
 
+#[derive(Clone, Default)]
+pub struct LoanQuerier {
+    loans: HashMap<String, Uint256>,
+}
+
+impl LoanQuerier {
+    pub fn new(loans: &[(&String, &Uint256)]) -> Self {
+        let mut loan_map: HashMap<String, Uint256> = HashMap::new();
+        for (borrower, loan_amount) in loans.iter() {
+            loan_map.insert((*borrower).clone(), **loan_amount);
+        }
+        LoanQuerier { loans: loan_map }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct TaxQuerier {
     rate: Decimal,
@@ -95,6 +121,20 @@ pub(crate) fn caps_to_map(caps: &[(&String, &Uint128)]) -> HashMap<String, Uint1
     owner_map
 }
 
+#[derive(Clone, Default)]
+pub struct PriceJumpQuerier {
+    // assets with an active price jump
+    price_jumps: Vec<String>,
+}
+
+impl PriceJumpQuerier {
+    pub fn new(price_jumps: &[&String]) -> Self {
+        PriceJumpQuerier {
+            price_jumps: price_jumps.iter().map(|asset| asset.to_string()).collect(),
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct OraclePriceQuerier {
     // this lets us iterate over all pairs that match the first string
@@ -170,6 +210,16 @@ impl WasmMockQuerier {
                 contract_addr: _,
                 msg,
             }) => match from_binary(msg).unwrap() {
+                QueryMsg::PriceJump { asset } => {
+                    let active = self.price_jump_querier.price_jumps.contains(&asset);
+                    SystemResult::Ok(ContractResult::from(to_binary(&PriceJumpResponse {
+                        asset,
+                        max_price_jump: None,
+                        cooldown: None,
+                        active,
+                        detected_time: None,
+                    })))
+                }
                 QueryMsg::Price { base, quote } => {
                     match self.oracle_price_querier.oracle_price.get(&(base, quote)) {
                         Some(v) => {
@@ -177,6 +227,8 @@ impl WasmMockQuerier {
                                 rate: v.0,
                                 last_updated_base: v.1,
                                 last_updated_quote: v.2,
+                                feeders_base: vec![],
+                                feeders_quote: vec![],
                             })))
                         }
                         None => SystemResult::Err(SystemError::InvalidRequest {
@@ -190,10 +242,11 @@ impl WasmMockQuerier {
                     start_after: _,
                     limit: _,
                 } => {
+                    let collateral_token = collateral_token.unwrap();
                     match self
                         .collateral_querier
                         .collaterals
-                        .get(&collateral_token.unwrap())
+                        .get(&collateral_token.to_string())
                     {
                         Some(v) => {
                             SystemResult::Ok(ContractResult::from(to_binary(&WhitelistResponse {
@@ -201,8 +254,11 @@ impl WasmMockQuerier {
                                     name: "name".to_string(),
                                     symbol: "symbol".to_string(),
                                     max_ltv: *v,
+                                    liquidation_threshold: *v,
                                     custody_contract: "custody0000".to_string(),
-                                    collateral_token: "token0000".to_string(),
+                                    collateral_token,
+                                    paused: 0,
+                                    deposit_cap: None,
                                 }],
                             })))
                         }
@@ -212,6 +268,28 @@ impl WasmMockQuerier {
                         }),
                     }
                 }
+                QueryMsg::BorrowerInfo {
+                    borrower,
+                    block_height: _,
+                } => {
+                    let loan_amount = self
+                        .loan_querier
+                        .loans
+                        .get(&borrower)
+                        .copied()
+                        .unwrap_or_else(Uint256::zero);
+                    SystemResult::Ok(ContractResult::from(to_binary(&BorrowerInfoResponse {
+                        borrower,
+                        interest_index: Decimal256::one(),
+                        reward_index: Decimal256::zero(),
+                        loan_amount,
+                        pending_rewards: Decimal256::zero(),
+                        floating_loan_amount: loan_amount,
+                        fixed_loan_amount: Uint256::zero(),
+                        fixed_rate: Decimal256::zero(),
+                        fixed_interest_index: Decimal256::one(),
+                    })))
+                }
             },
             _ => self.base.handle_query(request),
         }
@@ -224,7 +302,9 @@ impl WasmMockQuerier {
             base,
             tax_querier: TaxQuerier::default(),
             oracle_price_querier: OraclePriceQuerier::default(),
+            price_jump_querier: PriceJumpQuerier::default(),
             collateral_querier: CollateralQuerier::default(),
+            loan_querier: LoanQuerier::default(),
         }
     }
 
@@ -241,7 +321,23 @@ impl WasmMockQuerier {
         self.oracle_price_querier = OraclePriceQuerier::new(oracle_price);
     }
 
+    pub fn with_price_jump(&mut self, price_jumps: &[&String]) {
+        self.price_jump_querier = PriceJumpQuerier::new(price_jumps);
+    }
+
+    pub fn update_balance<U: Into<String>>(
+        &mut self,
+        addr: U,
+        balance: Vec<Coin>,
+    ) -> Option<Vec<Coin>> {
+        self.base.update_balance(addr, balance)
+    }
+
     pub fn with_collateral_max_ltv(&mut self, collaterals: &[(&String, &Decimal256)]) {
         self.collateral_querier = CollateralQuerier::new(collaterals);
     }
+
+    pub fn with_loan_amount(&mut self, loans: &[(&String, &Uint256)]) {
+        self.loan_querier = LoanQuerier::new(loans);
+    }
 }
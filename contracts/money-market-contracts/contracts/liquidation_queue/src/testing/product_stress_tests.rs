@@ -11,6 +11,7 @@ use cw20::Cw20ReceiveMsg;
 use moneymarket::liquidation_queue::{
     BidsResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
 };
+use moneymarket::tokens::AssetInfo;
 
 use super::mock_querier::WasmMockQuerier;
 
@@ -121,10 +122,13 @@ fn instantiate_and_whitelist(deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMo
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "col0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "col0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000000000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -153,8 +157,12 @@ fn simulate_bids_with_2_liq_amounts(
     for i in 0..iterations {
         // ALICE BIDS
         let msg = ExecuteMsg::SubmitBid {
-            collateral_token: "col0000".to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: "col0000".to_string(),
+            },
             premium_slot: 0u8,
+            expires_at: None,
+            min_collateral_price: None,
         };
         let info = mock_info(
             "alice0000",
@@ -175,6 +183,7 @@ fn simulate_bids_with_2_liq_amounts(
                     liquidator: "liquidator00000".to_string(),
                     fee_address: Some("fee0000".to_string()),
                     repay_address: Some("repay0000".to_string()),
+                    borrower: None,
                 })
                 .unwrap(),
             });
@@ -191,6 +200,7 @@ fn simulate_bids_with_2_liq_amounts(
                     liquidator: "liquidator00000".to_string(),
                     fee_address: Some("fee0000".to_string()),
                     repay_address: Some("repay0000".to_string()),
+                    borrower: None,
                 })
                 .unwrap(),
             });
@@ -209,7 +219,9 @@ fn simulate_bids_with_2_liq_amounts(
                 deps.as_ref(),
                 mock_env(),
                 QueryMsg::BidsByUser {
-                    collateral_token: "col0000".to_string(),
+                    collateral_token: AssetInfo::Token {
+                        contract_addr: "col0000".to_string(),
+                    },
                     bidder: "alice0000".to_string(),
                     limit: Some(30u8),
                     start_after: Some(Uint128::from(queried_bids)),
@@ -7,6 +7,7 @@ use cw20::Cw20ReceiveMsg;
 use moneymarket::liquidation_queue::{
     Cw20HookMsg, ExecuteMsg, InstantiateMsg, LiquidationAmountResponse, QueryMsg,
 };
+use moneymarket::tokens::AssetInfo;
 
 #[test]
 fn partial_one_collateral_one_slot_high_ltv() {
@@ -35,17 +36,24 @@ fn partial_one_collateral_one_slot_high_ltv() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -59,7 +67,12 @@ fn partial_one_collateral_one_slot_high_ltv() {
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(19000u64),
         borrow_limit: Uint256::from(18000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(20000u64))], // value 20000 (LTV 90%), limit = 18,000
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(20000u64),
+        )], // value 20000 (LTV 90%), limit = 18,000
         collateral_prices: vec![Decimal256::percent(100)],
     };
 
@@ -68,7 +81,12 @@ fn partial_one_collateral_one_slot_high_ltv() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(16433u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(16433u64)
+            )],
         }
     );
 
@@ -97,6 +115,7 @@ fn partial_one_collateral_one_slot_high_ltv() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -141,17 +160,24 @@ fn partial_one_collateral_one_slot() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -165,7 +191,12 @@ fn partial_one_collateral_one_slot() {
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(1200u64),
         borrow_limit: Uint256::from(1000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(20000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(20000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -174,7 +205,12 @@ fn partial_one_collateral_one_slot() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(7291u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(7291u64)
+            )],
         }
     );
 
@@ -203,6 +239,7 @@ fn partial_one_collateral_one_slot() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -247,17 +284,24 @@ fn partial_one_collateral_one_slot_with_fees() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -271,7 +315,12 @@ fn partial_one_collateral_one_slot_with_fees() {
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(1200u64),
         borrow_limit: Uint256::from(1000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(20000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(20000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -280,7 +329,12 @@ fn partial_one_collateral_one_slot_with_fees() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(7551u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(7551u64)
+            )],
         }
     );
 
@@ -309,6 +363,7 @@ fn partial_one_collateral_one_slot_with_fees() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -362,17 +417,24 @@ fn partial_one_collateral_one_slot_with_fees_all() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -386,7 +448,12 @@ fn partial_one_collateral_one_slot_with_fees_all() {
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(1200u64),
         borrow_limit: Uint256::from(1000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(20000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(20000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -395,7 +462,12 @@ fn partial_one_collateral_one_slot_with_fees_all() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(7686u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(7686u64)
+            )],
         }
     );
 
@@ -424,6 +496,7 @@ fn partial_one_collateral_one_slot_with_fees_all() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -484,17 +557,24 @@ fn partial_one_collateral_two_slots() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -505,15 +585,24 @@ fn partial_one_collateral_two_slots() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(10300u64),
         borrow_limit: Uint256::from(10000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(200000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(200000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -522,7 +611,12 @@ fn partial_one_collateral_two_slots() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(42861u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(42861u64)
+            )],
         }
     );
 
@@ -553,6 +647,7 @@ fn partial_one_collateral_two_slots() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -597,17 +692,24 @@ fn partial_one_collateral_two_slots_with_fees() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -618,15 +720,24 @@ fn partial_one_collateral_two_slots_with_fees() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(10300u64),
         borrow_limit: Uint256::from(10000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(200000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(200000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -635,7 +746,12 @@ fn partial_one_collateral_two_slots_with_fees() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(44453u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(44453u64)
+            )],
         }
     );
 
@@ -666,6 +782,7 @@ fn partial_one_collateral_two_slots_with_fees() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -719,17 +836,24 @@ fn non_partial_liquidation() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -743,7 +867,12 @@ fn non_partial_liquidation() {
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(1200u64),
         borrow_limit: Uint256::from(1000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(20000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(20000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -752,7 +881,12 @@ fn non_partial_liquidation() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(12643u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(12643u64)
+            )],
         }
     );
 
@@ -774,6 +908,7 @@ fn non_partial_liquidation() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -818,17 +953,24 @@ fn non_partial_liquidation_two_slots() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -839,15 +981,24 @@ fn non_partial_liquidation_two_slots() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(1200u64),
         borrow_limit: Uint256::from(1000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(20000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(20000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -856,7 +1007,12 @@ fn non_partial_liquidation_two_slots() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(12756u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(12756u64)
+            )],
         }
     );
 
@@ -878,6 +1034,7 @@ fn non_partial_liquidation_two_slots() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -922,17 +1079,24 @@ fn non_partial_liquidation_with_fees() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -946,7 +1110,12 @@ fn non_partial_liquidation_with_fees() {
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(1200u64),
         borrow_limit: Uint256::from(1000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(20000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(20000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -955,7 +1124,12 @@ fn non_partial_liquidation_with_fees() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(12899u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(12899u64)
+            )],
         }
     );
 
@@ -977,6 +1151,7 @@ fn non_partial_liquidation_with_fees() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1030,17 +1205,24 @@ fn non_partial_liquidation_two_slots_with_fees() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -1051,15 +1233,24 @@ fn non_partial_liquidation_two_slots_with_fees() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(1200u64),
         borrow_limit: Uint256::from(1000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(20000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(20000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -1068,7 +1259,12 @@ fn non_partial_liquidation_two_slots_with_fees() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(13015u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(13015u64)
+            )],
         }
     );
 
@@ -1090,6 +1286,7 @@ fn non_partial_liquidation_two_slots_with_fees() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1143,17 +1340,24 @@ fn non_partial_liquidation_two_slots_with_fees_big_nums() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000000000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -1164,15 +1368,24 @@ fn non_partial_liquidation_two_slots_with_fees_big_nums() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(1300000000u64),
         borrow_limit: Uint256::from(1000000000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(20000000000u64))], // value = 2,000,000,000
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(20000000000u64),
+        )], // value = 2,000,000,000
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -1181,7 +1394,12 @@ fn non_partial_liquidation_two_slots_with_fees_big_nums() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(13833067518u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(13833067518u64)
+            )],
         }
     );
 
@@ -1203,6 +1421,7 @@ fn non_partial_liquidation_two_slots_with_fees_big_nums() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1258,24 +1477,34 @@ fn partial_two_collaterals_ltv_diff() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -1286,8 +1515,12 @@ fn partial_two_collaterals_ltv_diff() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         premium_slot: 0u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1295,8 +1528,18 @@ fn partial_two_collaterals_ltv_diff() {
         borrow_amount: Uint256::from(1200000000u64),
         borrow_limit: Uint256::from(1000000000u64),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(1000000000u64)), // value = 1000000000 (LTV 99%) limit = 990..
-            ("token0001".to_string(), Uint256::from(1000000000u64)), // value = 1000000000 (LTV 1%) limit = 10..
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(1000000000u64),
+            ), // value = 1000000000 (LTV 99%) limit = 990..
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0001".to_string(),
+                },
+                Uint256::from(1000000000u64),
+            ), // value = 1000000000 (LTV 1%) limit = 10..
         ],
         collateral_prices: vec![Decimal256::percent(100), Decimal256::percent(100)],
     };
@@ -1307,8 +1550,18 @@ fn partial_two_collaterals_ltv_diff() {
         res,
         LiquidationAmountResponse {
             collaterals: vec![
-                ("token0000".to_string(), Uint256::from(19230775u64)),
-                ("token0001".to_string(), Uint256::from(399193550u64))
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
+                    Uint256::from(19230775u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0001".to_string()
+                    },
+                    Uint256::from(399193550u64)
+                )
             ],
         }
     );
@@ -1352,6 +1605,7 @@ fn partial_two_collaterals_ltv_diff() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1376,6 +1630,7 @@ fn partial_two_collaterals_ltv_diff() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1423,24 +1678,34 @@ fn partial_two_collaterals_multi_slots_per_col() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -1451,18 +1716,30 @@ fn partial_two_collaterals_multi_slots_per_col() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 11u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         premium_slot: 3u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1470,8 +1747,18 @@ fn partial_two_collaterals_multi_slots_per_col() {
         borrow_amount: Uint256::from(1700u64),
         borrow_limit: Uint256::from(1450u64),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(20000u64)), // value = 2000 (LTV = 50%) limit = 1000
-            ("token0001".to_string(), Uint256::from(30000u64)), // value = 1500 (LTV = 30%) limit = 450
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(20000u64),
+            ), // value = 2000 (LTV = 50%) limit = 1000
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0001".to_string(),
+                },
+                Uint256::from(30000u64),
+            ), // value = 1500 (LTV = 30%) limit = 450
         ],
         collateral_prices: vec![Decimal256::percent(10), Decimal256::percent(5)],
     };
@@ -1482,8 +1769,18 @@ fn partial_two_collaterals_multi_slots_per_col() {
         res,
         LiquidationAmountResponse {
             collaterals: vec![
-                ("token0000".to_string(), Uint256::from(3796u64)),
-                ("token0001".to_string(), Uint256::from(9637u64))
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
+                    Uint256::from(3796u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0001".to_string()
+                    },
+                    Uint256::from(9637u64)
+                )
             ],
         }
     );
@@ -1527,6 +1824,7 @@ fn partial_two_collaterals_multi_slots_per_col() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1551,6 +1849,7 @@ fn partial_two_collaterals_multi_slots_per_col() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1598,24 +1897,34 @@ fn partial_two_collaterals_one_slot_diff_ltv() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -1626,8 +1935,12 @@ fn partial_two_collaterals_one_slot_diff_ltv() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1635,8 +1948,18 @@ fn partial_two_collaterals_one_slot_diff_ltv() {
         borrow_amount: Uint256::from(1600u64),
         borrow_limit: Uint256::from(1450u64),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(20000u64)), // value = 2000 LTV = 50% (limit = 1000)
-            ("token0001".to_string(), Uint256::from(30000u64)), // value = 1500 LTV = 30% (limit = 450)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(20000u64),
+            ), // value = 2000 LTV = 50% (limit = 1000)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0001".to_string(),
+                },
+                Uint256::from(30000u64),
+            ), // value = 1500 LTV = 30% (limit = 450)
         ],
         collateral_prices: vec![Decimal256::percent(10), Decimal256::percent(5)],
     };
@@ -1647,8 +1970,18 @@ fn partial_two_collaterals_one_slot_diff_ltv() {
         res,
         LiquidationAmountResponse {
             collaterals: vec![
-                ("token0000".to_string(), Uint256::from(3037u64)),
-                ("token0001".to_string(), Uint256::from(7775u64))
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
+                    Uint256::from(3037u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0001".to_string()
+                    },
+                    Uint256::from(7775u64)
+                )
             ],
         }
     );
@@ -1692,6 +2025,7 @@ fn partial_two_collaterals_one_slot_diff_ltv() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1716,6 +2050,7 @@ fn partial_two_collaterals_one_slot_diff_ltv() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1764,31 +2099,44 @@ fn partial_three_collaterals_one_slot_diff_ltv() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0002".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0002".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(1000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -1799,13 +2147,21 @@ fn partial_three_collaterals_one_slot_diff_ltv() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0002".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0002".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1813,9 +2169,24 @@ fn partial_three_collaterals_one_slot_diff_ltv() {
         borrow_amount: Uint256::from(4500u64),
         borrow_limit: Uint256::from(4030u64),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(20000u64)), // value = 2000 LTV = 50% (limit = 1000)
-            ("token0001".to_string(), Uint256::from(30000u64)), // value = 1500 LTV = 70% (limit = 1,050)
-            ("token0002".to_string(), Uint256::from(6000u64)), // value = 6600 LTV = 30% (limit = 1,980)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(20000u64),
+            ), // value = 2000 LTV = 50% (limit = 1000)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0001".to_string(),
+                },
+                Uint256::from(30000u64),
+            ), // value = 1500 LTV = 70% (limit = 1,050)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0002".to_string(),
+                },
+                Uint256::from(6000u64),
+            ), // value = 6600 LTV = 30% (limit = 1,980)
         ],
         collateral_prices: vec![
             Decimal256::percent(10),
@@ -1830,9 +2201,24 @@ fn partial_three_collaterals_one_slot_diff_ltv() {
         res,
         LiquidationAmountResponse {
             collaterals: vec![
-                ("token0000".to_string(), Uint256::from(3328u64)),
-                ("token0001".to_string(), Uint256::from(5824u64)),
-                ("token0002".to_string(), Uint256::from(1210u64)),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
+                    Uint256::from(3328u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0001".to_string()
+                    },
+                    Uint256::from(5824u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0002".to_string()
+                    },
+                    Uint256::from(1210u64)
+                ),
             ],
         }
     );
@@ -1886,6 +2272,7 @@ fn partial_three_collaterals_one_slot_diff_ltv() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1910,6 +2297,7 @@ fn partial_three_collaterals_one_slot_diff_ltv() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1934,6 +2322,7 @@ fn partial_three_collaterals_one_slot_diff_ltv() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -1982,31 +2371,44 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(100000000000000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(100000000000000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0002".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0002".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(100000000000000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -2017,13 +2419,21 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0002".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0002".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -2036,9 +2446,24 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts() {
         borrow_amount: Uint256::from(1200000000000u64),
         borrow_limit: Uint256::from(1007980000000u128),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(200000000000u64)), // value = 2,000,000,000,000 LTV = 50% (limit = 1,000,000,000,000)
-            ("token0001".to_string(), Uint256::from(3000000000u64)), // value = 15,000,000,000 LTV = 40% (limit = 6,000,000,000)
-            ("token0002".to_string(), Uint256::from(60000000u64)), // value = 6,600,000,000 LTV = 30% (limit = 1,980,000,000)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(200000000000u64),
+            ), // value = 2,000,000,000,000 LTV = 50% (limit = 1,000,000,000,000)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0001".to_string(),
+                },
+                Uint256::from(3000000000u64),
+            ), // value = 15,000,000,000 LTV = 40% (limit = 6,000,000,000)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0002".to_string(),
+                },
+                Uint256::from(60000000u64),
+            ), // value = 6,600,000,000 LTV = 30% (limit = 1,980,000,000)
         ],
         collateral_prices: vec![
             Decimal256::percent(1000),
@@ -2053,9 +2478,24 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts() {
         res,
         LiquidationAmountResponse {
             collaterals: vec![
-                ("token0000".to_string(), Uint256::from(69498951644u64)),
-                ("token0001".to_string(), Uint256::from(2471406687u64)),
-                ("token0002".to_string(), Uint256::from(50965898u64)),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
+                    Uint256::from(69498951644u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0001".to_string()
+                    },
+                    Uint256::from(2471406687u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0002".to_string()
+                    },
+                    Uint256::from(50965898u64)
+                ),
             ],
         }
     );
@@ -2110,6 +2550,7 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2134,6 +2575,7 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2158,6 +2600,7 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2206,31 +2649,44 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts_2() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(100000000000000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(100000000000000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0002".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0002".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(100000000000000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -2241,13 +2697,21 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts_2() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0002".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0002".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -2260,9 +2724,24 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts_2() {
         borrow_amount: Uint256::from(9100000000u64),
         borrow_limit: Uint256::from(8980000000u128),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(200000000u64)), // value = 2,000,000,000 LTV = 50% (limit = 1,000,000,000)
-            ("token0001".to_string(), Uint256::from(3000000000u64)), // value = 15,000,000,000 LTV = 40% (limit = 6,000,000,000)
-            ("token0002".to_string(), Uint256::from(60000000u64)), // value = 6,600,000,000 LTV = 30% (limit = 1,980,000,000)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(200000000u64),
+            ), // value = 2,000,000,000 LTV = 50% (limit = 1,000,000,000)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0001".to_string(),
+                },
+                Uint256::from(3000000000u64),
+            ), // value = 15,000,000,000 LTV = 40% (limit = 6,000,000,000)
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0002".to_string(),
+                },
+                Uint256::from(60000000u64),
+            ), // value = 6,600,000,000 LTV = 30% (limit = 1,980,000,000)
         ],
         collateral_prices: vec![
             Decimal256::percent(1000),
@@ -2277,9 +2756,24 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts_2() {
         res,
         LiquidationAmountResponse {
             collaterals: vec![
-                ("token0000".to_string(), Uint256::from(21944167u64)),
-                ("token0001".to_string(), Uint256::from(390171057u64)),
-                ("token0002".to_string(), Uint256::from(8046195u64)),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
+                    Uint256::from(21944167u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0001".to_string()
+                    },
+                    Uint256::from(390171057u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0002".to_string()
+                    },
+                    Uint256::from(8046195u64)
+                ),
             ],
         }
     );
@@ -2334,6 +2828,7 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts_2() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2358,6 +2853,7 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts_2() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2382,6 +2878,7 @@ fn partial_three_collaterals_one_slot_diff_ltv_big_amounts_2() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2429,24 +2926,34 @@ fn not_enough_bids_for_one_of_two_col() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -2464,8 +2971,12 @@ fn not_enough_bids_for_one_of_two_col() {
         }],
     );
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -2473,8 +2984,18 @@ fn not_enough_bids_for_one_of_two_col() {
         borrow_amount: Uint256::from(2800000u64),
         borrow_limit: Uint256::from(2450000u64),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(20000000u64)), // value = 2,000,000 (LTV 50%) limit = 2,000,000
-            ("token0001".to_string(), Uint256::from(30000000u64)), // value = 1,500,000 (LTV 30%) limit = 450,000
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(20000000u64),
+            ), // value = 2,000,000 (LTV 50%) limit = 2,000,000
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0001".to_string(),
+                },
+                Uint256::from(30000000u64),
+            ), // value = 1,500,000 (LTV 30%) limit = 450,000
         ],
         collateral_prices: vec![Decimal256::percent(10), Decimal256::percent(5)],
     };
@@ -2485,8 +3006,18 @@ fn not_enough_bids_for_one_of_two_col() {
         res,
         LiquidationAmountResponse {
             collaterals: vec![
-                ("token0000".to_string(), Uint256::from(11862304u64)),
-                ("token0001".to_string(), Uint256::from(6541171u64))
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
+                    Uint256::from(11862304u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0001".to_string()
+                    },
+                    Uint256::from(6541171u64)
+                )
             ],
         }
     );
@@ -2530,6 +3061,7 @@ fn not_enough_bids_for_one_of_two_col() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2563,6 +3095,7 @@ fn not_enough_bids_for_one_of_two_col() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2619,18 +3152,25 @@ fn integration_test_simul() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(100000000000000000000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     for slot in 1..30 {
         let msg = ExecuteMsg::SubmitBid {
-            collateral_token: "token0000".to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
             premium_slot: slot as u8,
+            expires_at: None,
+            min_collateral_price: None,
         };
         let info = mock_info(
             "addr0000",
@@ -2643,8 +3183,12 @@ fn integration_test_simul() {
     }
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 30u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -2659,7 +3203,12 @@ fn integration_test_simul() {
         borrow_amount: Uint256::from(6000000000u64),
         borrow_limit: Uint256::from(5400000000u64),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(10000000000u64)), // value = 9,000,000,000 (LTV 60%) limit = 5,400,000,000
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(10000000000u64),
+            ), // value = 9,000,000,000 (LTV 60%) limit = 5,400,000,000
         ],
         collateral_prices: vec![Decimal256::percent(90)],
     };
@@ -2669,7 +3218,12 @@ fn integration_test_simul() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(8489891541u64)),],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(8489891541u64)
+            ),],
         }
     );
 
@@ -2697,6 +3251,7 @@ fn integration_test_simul() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -2,11 +2,15 @@ use crate::contract::{execute, instantiate, query};
 use crate::testing::mock_querier::mock_dependencies;
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{from_binary, Coin, Decimal, Uint128};
+use cosmwasm_std::{from_binary, Api, CanonicalAddr, Coin, Decimal, StdError, Uint128};
+use cosmwasm_storage::Bucket;
 use moneymarket::liquidation_queue::{
-    BidPoolResponse, BidPoolsResponse, BidResponse, BidsResponse, CollateralInfoResponse,
-    ExecuteMsg, InstantiateMsg, LiquidationAmountResponse, QueryMsg,
+    BidDepthResponse, BidDepthSlotResponse, BidPoolResponse, BidPoolsResponse, BidResponse,
+    BidsResponse, CollateralInfoResponse, ExecuteMsg, InstantiateMsg, LiquidationAmountResponse,
+    LiquidationMode, LiquidationSimulationResponse, QueryMsg,
 };
+use moneymarket::tokens::AssetInfo;
+use serde::{Deserialize, Serialize};
 
 #[test]
 fn query_liquidation_amount() {
@@ -38,31 +42,44 @@ fn query_liquidation_amount() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0002".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0002".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -73,13 +90,21 @@ fn query_liquidation_amount() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0001".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0001".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0002".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0002".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -88,7 +113,12 @@ fn query_liquidation_amount() {
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(931095u64),
         borrow_limit: Uint256::from(900000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(1000000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(1000000u64),
+        )],
         collateral_prices: vec![Decimal256::percent(10)],
     };
 
@@ -97,14 +127,24 @@ fn query_liquidation_amount() {
     assert_eq!(
         res,
         LiquidationAmountResponse {
-            collaterals: vec![("token0000".to_string(), Uint256::from(1000000u64))],
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
+                Uint256::from(1000000u64)
+            )],
         }
     );
 
     let msg = QueryMsg::LiquidationAmount {
         borrow_amount: Uint256::from(100000u64),
         borrow_limit: Uint256::from(1000000u64),
-        collaterals: vec![("token0000".to_string(), Uint256::from(1000000u64))],
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            Uint256::from(1000000u64),
+        )],
         collateral_prices: vec![Decimal256::one()],
     };
 
@@ -121,9 +161,24 @@ fn query_liquidation_amount() {
         borrow_amount: Uint256::from(1000000u64),
         borrow_limit: Uint256::from(99999u64),
         collaterals: vec![
-            ("token0000".to_string(), Uint256::from(1000000u64)),
-            ("token0001".to_string(), Uint256::from(2000000u64)),
-            ("token0002".to_string(), Uint256::from(3000000u64)),
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                Uint256::from(1000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0001".to_string(),
+                },
+                Uint256::from(2000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "token0002".to_string(),
+                },
+                Uint256::from(3000000u64),
+            ),
         ],
         collateral_prices: vec![
             Decimal256::percent(50),
@@ -140,9 +195,24 @@ fn query_liquidation_amount() {
         res,
         LiquidationAmountResponse {
             collaterals: vec![
-                ("token0000".to_string(), Uint256::from(358003u64)),
-                ("token0001".to_string(), Uint256::from(716005u64)),
-                ("token0002".to_string(), Uint256::from(1074007u64)),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
+                    Uint256::from(358003u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0001".to_string()
+                    },
+                    Uint256::from(716005u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: "token0002".to_string()
+                    },
+                    Uint256::from(1074007u64)
+                ),
             ],
         }
     );
@@ -175,17 +245,24 @@ fn query_bids() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -196,13 +273,21 @@ fn query_bids() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -221,7 +306,9 @@ fn query_bids() {
         bid_response,
         BidResponse {
             idx: Uint128::from(1u128),
-            collateral_token: "token0000".to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: "token0000".to_string()
+            },
             bidder: "addr0000".to_string(),
             amount: Uint256::from(1000u128),
             premium_slot: 5u8,
@@ -231,6 +318,9 @@ fn query_bids() {
             sum_snapshot: Decimal256::zero(),
             epoch_snapshot: Uint128::zero(),
             scale_snapshot: Uint128::zero(),
+            auto_claim: None,
+            expires_at: None,
+            min_collateral_price: None,
         }
     );
 
@@ -239,7 +329,9 @@ fn query_bids() {
             deps.as_ref(),
             mock_env(),
             QueryMsg::BidsByUser {
-                collateral_token: "token0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
                 bidder: "addr0000".to_string(),
                 start_after: None,
                 limit: None,
@@ -254,7 +346,9 @@ fn query_bids() {
             bids: vec![
                 BidResponse {
                     idx: Uint128::from(1u128),
-                    collateral_token: "token0000".to_string(),
+                    collateral_token: AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
                     bidder: "addr0000".to_string(),
                     amount: Uint256::from(1000u128),
                     premium_slot: 5u8,
@@ -264,10 +358,15 @@ fn query_bids() {
                     sum_snapshot: Decimal256::zero(),
                     epoch_snapshot: Uint128::zero(),
                     scale_snapshot: Uint128::zero(),
+                    auto_claim: None,
+                    expires_at: None,
+                    min_collateral_price: None,
                 },
                 BidResponse {
                     idx: Uint128::from(2u128),
-                    collateral_token: "token0000".to_string(),
+                    collateral_token: AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
                     bidder: "addr0000".to_string(),
                     amount: Uint256::from(1000u128),
                     premium_slot: 5u8,
@@ -277,10 +376,15 @@ fn query_bids() {
                     sum_snapshot: Decimal256::zero(),
                     epoch_snapshot: Uint128::zero(),
                     scale_snapshot: Uint128::zero(),
+                    auto_claim: None,
+                    expires_at: None,
+                    min_collateral_price: None,
                 },
                 BidResponse {
                     idx: Uint128::from(3u128),
-                    collateral_token: "token0000".to_string(),
+                    collateral_token: AssetInfo::Token {
+                        contract_addr: "token0000".to_string()
+                    },
                     bidder: "addr0000".to_string(),
                     amount: Uint256::from(1000u128),
                     premium_slot: 10u8,
@@ -290,6 +394,9 @@ fn query_bids() {
                     sum_snapshot: Decimal256::zero(),
                     epoch_snapshot: Uint128::zero(),
                     scale_snapshot: Uint128::zero(),
+                    auto_claim: None,
+                    expires_at: None,
+                    min_collateral_price: None,
                 }
             ]
         }
@@ -300,7 +407,9 @@ fn query_bids() {
             deps.as_ref(),
             mock_env(),
             QueryMsg::BidsByUser {
-                collateral_token: "token0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
                 bidder: "addr0000".to_string(),
                 start_after: Some(Uint128::from(1u128)),
                 limit: Some(1u8),
@@ -314,7 +423,9 @@ fn query_bids() {
         BidsResponse {
             bids: vec![BidResponse {
                 idx: Uint128::from(2u128),
-                collateral_token: "token0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string()
+                },
                 bidder: "addr0000".to_string(),
                 amount: Uint256::from(1000u128),
                 premium_slot: 5u8,
@@ -324,6 +435,9 @@ fn query_bids() {
                 sum_snapshot: Decimal256::zero(),
                 epoch_snapshot: Uint128::zero(),
                 scale_snapshot: Uint128::zero(),
+                auto_claim: None,
+                expires_at: None,
+                min_collateral_price: None,
             }]
         }
     );
@@ -356,17 +470,24 @@ fn query_bid_pools() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128), // to get instant activation
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 5u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -377,13 +498,21 @@ fn query_bid_pools() {
     );
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 6u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         premium_slot: 10u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -392,7 +521,9 @@ fn query_bid_pools() {
             deps.as_ref(),
             mock_env(),
             QueryMsg::BidPool {
-                collateral_token: "token0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
                 bid_slot: 5u8,
             },
         )
@@ -416,7 +547,9 @@ fn query_bid_pools() {
             deps.as_ref(),
             mock_env(),
             QueryMsg::BidPoolsByCollateral {
-                collateral_token: "token0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
                 start_after: None,
                 limit: None,
             },
@@ -461,7 +594,9 @@ fn query_bid_pools() {
             deps.as_ref(),
             mock_env(),
             QueryMsg::BidPoolsByCollateral {
-                collateral_token: "token0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
                 start_after: Some(1u8),
                 limit: Some(1u8),
             },
@@ -511,10 +646,13 @@ fn query_collateral_info() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::from(10000u128),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -524,7 +662,9 @@ fn query_collateral_info() {
             deps.as_ref(),
             mock_env(),
             QueryMsg::CollateralInfo {
-                collateral_token: "token0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
             },
         )
         .unwrap(),
@@ -533,10 +673,237 @@ fn query_collateral_info() {
     assert_eq!(
         collateral_info_response,
         CollateralInfoResponse {
-            collateral_token: "token0000".to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: "token0000".to_string()
+            },
             max_slot: 30u8,
             bid_threshold: Uint256::from(10000u128),
             premium_rate_per_slot: Decimal256::percent(1),
+            swap_pair: None,
+            max_swap_spread: None,
+            liquidation_mode: LiquidationMode::BidPools {},
         }
     );
 }
+
+#[test]
+fn query_legacy_collateral_info() {
+    // collateral infos stored before native collaterals were supported
+    #[derive(Serialize, Deserialize)]
+    struct LegacyCollateralInfo {
+        collateral_token: CanonicalAddr,
+        bid_threshold: Uint256,
+        max_slot: u8,
+        premium_rate_per_slot: Decimal256,
+        swap_pair: Option<CanonicalAddr>,
+        max_swap_spread: Option<Decimal256>,
+    }
+
+    let mut deps = mock_dependencies(&[]);
+    let token_raw = deps.api.addr_canonicalize("token0000").unwrap();
+    Bucket::new(&mut deps.storage, b"col_info")
+        .save(
+            token_raw.as_slice(),
+            &LegacyCollateralInfo {
+                collateral_token: token_raw.clone(),
+                bid_threshold: Uint256::from(10000u128),
+                max_slot: 30u8,
+                premium_rate_per_slot: Decimal256::percent(1),
+                swap_pair: None,
+                max_swap_spread: None,
+            },
+        )
+        .unwrap();
+
+    let collateral_info_response: CollateralInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::CollateralInfo {
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        collateral_info_response.collateral_token,
+        AssetInfo::Token {
+            contract_addr: "token0000".to_string()
+        }
+    );
+
+    // undecodable entries are not reported as missing
+    let token_raw = deps.api.addr_canonicalize("token0001").unwrap();
+    Bucket::new(&mut deps.storage, b"col_info")
+        .save(token_raw.as_slice(), &"broken".to_string())
+        .unwrap();
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::CollateralInfo {
+            collateral_token: AssetInfo::Token {
+                contract_addr: "token0001".to_string(),
+            },
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) if msg == "Collateral is not whitelisted" => {
+            panic!("DO NOT ENTER HERE")
+        }
+        Err(_) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn query_bid_depth() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier
+        .with_collateral_max_ltv(&[(&"token0000".to_string(), &Decimal256::percent(90))]);
+    deps.querier.with_oracle_price(&[(
+        &("token0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::one(),
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds(),
+        ),
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(10),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100000000u64),
+        price_timeframe: 60u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::from(100000000u64),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for premium_slot in [0u8, 10u8] {
+        let msg = ExecuteMsg::SubmitBid {
+            collateral_token: AssetInfo::Token {
+                contract_addr: "token0000".to_string(),
+            },
+            premium_slot,
+            expires_at: None,
+            min_collateral_price: None,
+        };
+        let info = mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        );
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    // slot 0 buys 1,000,000 at 1.0 and slot 10 buys 1,000,000 at 0.9
+    let depth_response: BidDepthResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BidDepth {
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                collateral_amount: Some(Uint256::from(2000000u64)),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        depth_response,
+        BidDepthResponse {
+            collateral_token: AssetInfo::Token {
+                contract_addr: "token0000".to_string()
+            },
+            collateral_price: Decimal256::one(),
+            slots: vec![
+                BidDepthSlotResponse {
+                    premium_slot: 0u8,
+                    premium_rate: Decimal256::zero(),
+                    bid_amount: Uint256::from(1000000u64),
+                    cumulative_bid_amount: Uint256::from(1000000u64),
+                },
+                BidDepthSlotResponse {
+                    premium_slot: 10u8,
+                    premium_rate: Decimal256::percent(10),
+                    bid_amount: Uint256::from(1000000u64),
+                    cumulative_bid_amount: Uint256::from(2000000u64),
+                },
+            ],
+            simulation: Some(LiquidationSimulationResponse {
+                collateral_amount: Uint256::from(2000000u64),
+                repay_amount: Uint256::from(1900000u64),
+                premium_rate: Decimal256::percent(5),
+            }),
+        }
+    );
+
+    // not enough bids, slot 10 buys 1,111,111 with its 1,000,000
+    let depth_response: BidDepthResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BidDepth {
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                collateral_amount: Some(Uint256::from(3000000u64)),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let simulation = depth_response.simulation.unwrap();
+    assert_eq!(simulation.collateral_amount, Uint256::from(2111111u64));
+    assert_eq!(simulation.repay_amount, Uint256::from(2000000u64));
+
+    // simulation does not consume the pools
+    let depth_response: BidDepthResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BidDepth {
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
+                collateral_amount: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        depth_response.slots[1].cumulative_bid_amount,
+        Uint256::from(2000000u64)
+    );
+    assert_eq!(depth_response.simulation, None);
+}
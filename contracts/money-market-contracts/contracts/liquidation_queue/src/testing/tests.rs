@@ -1,16 +1,24 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, query, reply, COMPOUND_OPERATION};
+use crate::swap::PairCw20HookMsg;
 use crate::testing::mock_querier::mock_dependencies;
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, StdError, SubMsg, Uint128,
+    attr, from_binary, to_binary, BankMsg, Coin, ContractResult, CosmosMsg, Decimal, Reply,
+    StdError, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
 };
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use moneymarket::custody::Cw20HookMsg as CustodyCw20HookMsg;
 use moneymarket::liquidation_queue::{
-    BidResponse, CollateralInfoResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
-    QueryMsg,
+    AuctionResponse, AuctionsResponse, AutoClaim, BidPoolResponse, BidResponse,
+    CollateralInfoResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
+    LiquidationAmountResponse, LiquidationEventResponse, LiquidationHistoryResponse,
+    LiquidationMode, LiquidationStatsResponse, PendingCollateralsResponse, QueryMsg,
 };
+use moneymarket::market::ExecuteMsg as MarketExecuteMsg;
+use moneymarket::overseer::ExecuteMsg as OverseerExecuteMsg;
+use moneymarket::tokens::AssetInfo;
 
 #[test]
 fn proper_initialization() {
@@ -51,6 +59,7 @@ fn proper_initialization() {
             price_timeframe: 60u64,
             waiting_period: 60u64,
             overseer: "overseer0000".to_string(),
+            min_conditional_bid_amount: Uint256::zero(),
         }
     );
 }
@@ -87,6 +96,7 @@ fn update_config() {
         price_timeframe: None,
         waiting_period: None,
         overseer: None,
+        min_conditional_bid_amount: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -108,6 +118,7 @@ fn update_config() {
             price_timeframe: 60u64,
             waiting_period: 60u64,
             overseer: "overseer0000".to_string(),
+            min_conditional_bid_amount: Uint256::zero(),
         }
     );
 
@@ -123,6 +134,7 @@ fn update_config() {
         price_timeframe: Some(120u64),
         waiting_period: Some(100u64),
         overseer: Some("overseer0001".to_string()),
+        min_conditional_bid_amount: Some(Uint256::from(1000u64)),
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -144,6 +156,7 @@ fn update_config() {
             price_timeframe: 120u64,
             waiting_period: 100u64,
             overseer: "overseer0001".to_string(),
+            min_conditional_bid_amount: Uint256::from(1000u64),
         }
     );
 
@@ -159,6 +172,7 @@ fn update_config() {
         price_timeframe: Some(100u64),
         waiting_period: Some(100u64),
         overseer: Some("overseer0001".to_string()),
+        min_conditional_bid_amount: None,
     };
 
     let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
@@ -188,17 +202,24 @@ fn submit_bid() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info("addr0000", &[]);
     let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
@@ -265,7 +286,9 @@ fn submit_bid() {
         bid_response,
         BidResponse {
             idx: Uint128::from(1u128),
-            collateral_token: "asset0000".to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: "asset0000".to_string()
+            },
             bidder: "addr0000".to_string(),
             amount: Uint256::from(1000000u128),
             premium_slot: 1u8,
@@ -275,6 +298,9 @@ fn submit_bid() {
             wait_end: Some(wait_end.seconds()),
             epoch_snapshot: Uint128::zero(),
             scale_snapshot: Uint128::zero(),
+            auto_claim: None,
+            expires_at: None,
+            min_collateral_price: None,
         }
     );
 }
@@ -302,17 +328,24 @@ fn activate_bid() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -326,7 +359,9 @@ fn activate_bid() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u64)]),
     };
     let info = mock_info("addr0001", &[]);
@@ -346,7 +381,9 @@ fn activate_bid() {
 
     // graceful return when idx is not specified
     let msg2 = ExecuteMsg::ActivateBids {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         bids_idx: None,
     };
     let res = execute(deps.as_mut(), env, info, msg2).unwrap();
@@ -380,7 +417,9 @@ fn activate_bid() {
         bid_response,
         BidResponse {
             idx: Uint128::from(1u128),
-            collateral_token: "asset0000".to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: "asset0000".to_string()
+            },
             bidder: "addr0000".to_string(),
             amount: Uint256::from(1000000u128),
             premium_slot: 1u8,
@@ -390,6 +429,9 @@ fn activate_bid() {
             wait_end: None,
             epoch_snapshot: Uint128::zero(),
             scale_snapshot: Uint128::zero(),
+            auto_claim: None,
+            expires_at: None,
+            min_collateral_price: None,
         }
     );
 }
@@ -417,17 +459,24 @@ fn retract_bid() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -441,7 +490,9 @@ fn retract_bid() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u64)]),
     };
     let mut env = mock_env();
@@ -490,17 +541,24 @@ fn retract_unactive_bid() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -529,7 +587,9 @@ fn retract_unactive_bid() {
     );
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("addr0000", &[]);
@@ -579,17 +639,24 @@ fn execute_bid() {
     let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -603,7 +670,9 @@ fn execute_bid() {
     execute(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let mut env = mock_env();
@@ -622,6 +691,7 @@ fn execute_bid() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -642,6 +712,7 @@ fn execute_bid() {
             liquidator: "liquidator0000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -681,6 +752,7 @@ fn execute_bid() {
             liquidator: "liquidator0000".to_string(),
             fee_address: None,
             repay_address: None,
+            borrower: None,
         })
         .unwrap(),
     });
@@ -719,6 +791,7 @@ fn execute_bid() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
@@ -729,6 +802,274 @@ fn execute_bid() {
     );
 }
 
+#[test]
+fn execute_bid_price_jump() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(90))]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(10),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(1),
+        liquidation_threshold: Uint256::from(100000000u64),
+        price_timeframe: 100000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    deps.querier.with_oracle_price(&[(
+        &("asset0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::percent(50),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::zero(),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::SubmitBid {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let env = mock_env();
+    let wait_end = env.block.time.plus_seconds(60u64);
+    execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::ActivateBids {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        bids_idx: Some(vec![Uint128::from(1u128)]),
+    };
+    let mut env = mock_env();
+    env.block.time = wait_end;
+    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "custody0000".to_string(), // only custody contract can execute
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteBid {
+            liquidator: "liquidator0000".to_string(),
+            fee_address: Some("fee0000".to_string()),
+            repay_address: Some("repay0000".to_string()),
+            borrower: None,
+        })
+        .unwrap(),
+    });
+
+    // liquidations are paused while the oracle flags a price jump
+    let info = mock_info("asset0000", &[]);
+    let env = mock_env();
+    deps.querier.with_price_jump(&[&"asset0000".to_string()]);
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Liquidations are paused; price jump detected for asset0000")
+    );
+
+    // resumed once the jump is cleared
+    deps.querier.with_price_jump(&[]);
+    execute(deps.as_mut(), env, info, msg).unwrap();
+}
+
+#[test]
+fn execute_bid_native_collateral() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier
+        .with_collateral_max_ltv(&[(&"uluna".to_string(), &Decimal256::percent(90))]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(10),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(1),
+        liquidation_threshold: Uint256::from(100000000u64),
+        price_timeframe: 100000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    deps.querier.with_oracle_price(&[(
+        &("uluna".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::percent(50),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let collateral_token = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: collateral_token.clone(),
+        max_slot: 30u8,
+        bid_threshold: Uint256::zero(),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::SubmitBid {
+        collateral_token: collateral_token.clone(),
+        premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let env = mock_env();
+    let wait_end = env.block.time.plus_seconds(60u64);
+    execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::ActivateBids {
+        collateral_token: collateral_token.clone(),
+        bids_idx: Some(vec![Uint128::from(1u128)]),
+    };
+    let mut env = mock_env();
+    env.block.time = wait_end;
+    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::ExecuteBid {
+        liquidator: "liquidator0000".to_string(),
+        fee_address: Some("fee0000".to_string()),
+        repay_address: Some("repay0000".to_string()),
+        borrower: None,
+    };
+
+    // the collateral must be sent as a single coin
+    let env = mock_env();
+    let info = mock_info("custody0000", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Exactly one native collateral coin must be sent")
+    );
+
+    // unauthorized attempt
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Unauthorized: only custody contract can execute liquidations",)
+    );
+
+    // required_stable 495,000
+    // bid_fee         4,950
+    // liquidator_fee  4,950
+    // repay_amount    485,100
+    let info = mock_info(
+        "custody0000",
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "repay0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(480297u128), // 485100 / (1 + tax_rate)
+                }]
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "fee0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(4900u128), // 4950 / (1 + tax_rate)
+                }]
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "liquidator0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(4900u128), // 4950 / (1 + tax_rate)
+                }]
+            })),
+        ]
+    );
+
+    // liquidated collateral is claimed as a bank send
+    let msg = ExecuteMsg::ClaimLiquidations {
+        collateral_token,
+        bids_idx: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::from(1000000u128),
+            }]
+        }))]
+    );
+}
+
 #[test]
 fn claim_liquidations() {
     let mut deps = mock_dependencies(&[]);
@@ -765,17 +1106,24 @@ fn claim_liquidations() {
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         max_slot: 30u8,
         bid_threshold: Uint256::zero(),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::SubmitBid {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
     };
     let info = mock_info(
         "addr0000",
@@ -789,7 +1137,9 @@ fn claim_liquidations() {
     execute(deps.as_mut(), env, info.clone(), msg).unwrap();
 
     let msg = ExecuteMsg::ActivateBids {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         bids_idx: Some(vec![Uint128::from(1u128)]),
     };
     let mut env = mock_env();
@@ -807,13 +1157,16 @@ fn claim_liquidations() {
             liquidator: "liquidator00000".to_string(),
             fee_address: Some("fee0000".to_string()),
             repay_address: Some("repay0000".to_string()),
+            borrower: None,
         })
         .unwrap(),
     });
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::ClaimLiquidations {
-        collateral_token: "asset0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         bids_idx: None,
     };
     let info = mock_info("addr0000", &[]);
@@ -829,11 +1182,14 @@ fn claim_liquidations() {
 }
 
 #[test]
-fn update_collateral_info() {
+fn auto_claim_liquidations() {
     let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
     deps.querier
-        .with_collateral_max_ltv(&[(&"token0000".to_string(), &Decimal256::percent(90))]);
-
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(90))]);
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         oracle_contract: "oracle0000".to_string(),
@@ -842,28 +1198,1656 @@ fn update_collateral_info() {
         bid_fee: Decimal256::percent(1),
         liquidator_fee: Decimal256::percent(0),
         liquidation_threshold: Uint256::from(100000000u64),
-        price_timeframe: 60u64,
+        price_timeframe: 1000000u64,
         waiting_period: 60u64,
         overseer: "overseer0000".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let env = mock_env();
+    deps.querier.with_oracle_price(&[(
+        &("asset0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::percent(50),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
     let msg = ExecuteMsg::WhitelistCollateral {
-        collateral_token: "token0000".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
         max_slot: 30u8,
-        bid_threshold: Uint256::from(10000u128),
+        bid_threshold: Uint256::from(100000000u128),
         premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    let msg = ExecuteMsg::UpdateCollateralInfo {
-        collateral_token: "token0000".to_string(),
-        bid_threshold: Some(Uint256::from(20000u128)),
-        max_slot: Some(20u8),
-    };
+    for bidder in ["addr0000", "addr0001"] {
+        let msg = ExecuteMsg::SubmitBid {
+            collateral_token: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            premium_slot: 1u8,
+            expires_at: None,
+            min_collateral_price: None,
+        };
+        let info = mock_info(
+            bidder,
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        );
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    // required_stable 990,000, each bid receives 1,000,000 collateral
+    let info = mock_info("asset0000", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "custody0000".to_string(),
+        amount: Uint128::from(2000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteBid {
+            liquidator: "liquidator00000".to_string(),
+            fee_address: Some("fee0000".to_string()),
+            repay_address: Some("repay0000".to_string()),
+            borrower: None,
+        })
+        .unwrap(),
+    });
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bids without auto-claim can only be claimed by the bidder
+    let msg = ExecuteMsg::ClaimLiquidations {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        bids_idx: Some(vec![Uint128::from(1u128), Uint128::from(2u128)]),
+    };
+    let keeper_info = mock_info("keeper0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), keeper_info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+
+    let set_msg = ExecuteMsg::SetAutoClaim {
+        bid_idx: Uint128::from(1u128),
+        auto_claim: Some(AutoClaim::Compound {}),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        set_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("unauthorized"));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        set_msg,
+    )
+    .unwrap();
+
+    let set_msg = ExecuteMsg::SetAutoClaim {
+        bid_idx: Uint128::from(2u128),
+        auto_claim: Some(AutoClaim::Withdraw {
+            recipient: "treasury0000".to_string(),
+        }),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        set_msg,
+    )
+    .unwrap();
+
+    let msg_update = ExecuteMsg::UpdateCollateralInfo {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        bid_threshold: None,
+        max_slot: None,
+        swap_pair: Some("pair0000".to_string()),
+        max_swap_spread: Some(Decimal256::percent(1)),
+        liquidation_mode: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner0000", &[]),
+        msg_update,
+    )
+    .unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), keeper_info, msg).unwrap();
+    let swap_msg = |to: Option<String>| {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair0000".to_string(),
+                amount: Uint128::from(1000000u128),
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    belief_price: Some(Decimal256::from_uint256(2u64)),
+                    max_spread: Some(Decimal256::percent(1)),
+                    to,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+        })
+    };
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::reply_on_success(swap_msg(None), COMPOUND_OPERATION),
+            SubMsg::new(swap_msg(Some("treasury0000".to_string()))),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_liquidations"),
+            attr("collateral_token", "asset0000"),
+            attr("collateral_amount", "0"),
+            attr("auto_claim_amount", "2000000"),
+        ]
+    );
+
+    // pair returns 990,000 uusd for the compounding swap
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(990000u128),
+        }],
+    );
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: COMPOUND_OPERATION,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound_bid"),
+            attr("bid_idx", "3"),
+            attr("amount", "990000"),
+        ]
+    );
+
+    let bid_response: BidResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bid {
+                bid_idx: Uint128::from(3u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bid_response.bidder, "addr0000".to_string());
+    assert_eq!(bid_response.premium_slot, 1u8);
+    assert_eq!(bid_response.amount, Uint256::from(990000u128));
+    assert_eq!(bid_response.auto_claim, Some(AutoClaim::Compound {}));
+}
+
+#[test]
+fn compound_into_full_slot() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_tax(Decimal::zero(), &[(&"uusd".to_string(), &Uint128::zero())]);
+    deps.querier
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(90))]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(10),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100000000u64),
+        price_timeframe: 1000000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    deps.querier.with_oracle_price(&[(
+        &("asset0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::percent(50),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::from(100000000u128),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateCollateralInfo {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        bid_threshold: None,
+        max_slot: None,
+        swap_pair: Some("pair0000".to_string()),
+        max_swap_spread: Some(Decimal256::percent(1)),
+        liquidation_mode: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    let submit_bid_msg = ExecuteMsg::SubmitBid {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: Some(Decimal256::percent(10)),
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    execute(deps.as_mut(), mock_env(), info, submit_bid_msg.clone()).unwrap();
+
+    let msg = ExecuteMsg::SetAutoClaim {
+        bid_idx: Uint128::from(1u128),
+        auto_claim: Some(AutoClaim::Compound {}),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // required_stable 990,000, the bid receives 2,000,000 collateral
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "custody0000".to_string(),
+        amount: Uint128::from(2000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteBid {
+            liquidator: "liquidator00000".to_string(),
+            fee_address: Some("fee0000".to_string()),
+            repay_address: Some("repay0000".to_string()),
+            borrower: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("asset0000", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::ClaimLiquidations {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        bids_idx: Some(vec![Uint128::from(1u128)]),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("keeper0000", &[]), msg).unwrap();
+
+    // other bidders fill the conditional bids of the slot before the swap returns
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+    let err = loop {
+        if let Err(err) = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            submit_bid_msg.clone(),
+        ) {
+            break err;
+        }
+    };
+    assert_eq!(
+        err,
+        StdError::generic_err("Too many conditional bids in this premium slot")
+    );
+
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(990000u128),
+        }],
+    );
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: COMPOUND_OPERATION,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    let bid_idx = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "bid_idx")
+        .unwrap()
+        .value
+        .parse::<u128>()
+        .unwrap();
+
+    // the compounded bid is placed without its price floor
+    let bid_response: BidResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bid {
+                bid_idx: Uint128::from(bid_idx),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bid_response.bidder, "addr0000".to_string());
+    assert_eq!(bid_response.amount, Uint256::from(990000u128));
+    assert_eq!(bid_response.min_collateral_price, None);
+    assert_eq!(bid_response.auto_claim, Some(AutoClaim::Compound {}));
+}
+
+#[test]
+fn dutch_auction() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_tax(Decimal::zero(), &[(&"uusd".to_string(), &Uint128::zero())]);
+    deps.querier
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(50))]);
+    deps.querier
+        .with_loan_amount(&[(&"borrower0000".to_string(), &Uint256::from(2000000u64))]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(80),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100u64),
+        price_timeframe: 1000000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    deps.querier.with_oracle_price(&[(
+        &("asset0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::from_ratio(2u64, 1u64),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::zero(),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: Some(LiquidationMode::DutchAuction {
+            max_premium_rate: Decimal256::one(),
+            duration: 1000u64,
+        }),
+    };
+    let info = mock_info("owner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, StdError::generic_err("Max premium rate exceeds limit"));
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::zero(),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: Some(LiquidationMode::DutchAuction {
+            max_premium_rate: Decimal256::percent(20),
+            duration: 1000u64,
+        }),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // collateral_value 2,000,000, borrow_limit 1,000,000
+    // liquidated at the max discount, the remaining position has a 0.8 safe ratio
+    let liquidation_amount: LiquidationAmountResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LiquidationAmount {
+                borrow_amount: Uint256::from(1200000u64),
+                borrow_limit: Uint256::from(1000000u64),
+                collaterals: vec![(
+                    AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    Uint256::from(1000000u64),
+                )],
+                collateral_prices: vec![Decimal256::from_ratio(2u64, 1u64)],
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        liquidation_amount.collaterals,
+        vec![(
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string()
+            },
+            Uint256::from(510206u64)
+        )]
+    );
+
+    let execute_bid_msg = |borrower: Option<String>| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "custody0000".to_string(),
+            amount: Uint128::from(1000000u128),
+            msg: to_binary(&Cw20HookMsg::ExecuteBid {
+                liquidator: "liquidator0000".to_string(),
+                fee_address: Some("fee0000".to_string()),
+                repay_address: Some("market0000".to_string()),
+                borrower,
+            })
+            .unwrap(),
+        })
+    };
+    let info = mock_info("asset0000", &[]);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        execute_bid_msg(None),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Dutch auction liquidations require a borrower")
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        execute_bid_msg(Some("borrower0000".to_string())),
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "start_auction"),
+            attr("auction_idx", "1"),
+            attr("collateral_token", "asset0000"),
+            attr("collateral_amount", "1000000"),
+            attr("borrower", "borrower0000"),
+        ]
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        execute_bid_msg(Some("borrower0000".to_string())),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Borrower already has an open auction for this collateral")
+    );
+
+    // halfway through the auction, price = 2 * (1 - 0.1) = 1.8
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(500u64);
+    let auction: AuctionResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Auction {
+                auction_idx: Uint128::from(1u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(auction.premium_rate, Decimal256::percent(10));
+
+    let msg = ExecuteMsg::FillAuction {
+        auction_idx: Uint128::from(1u128),
+    };
+    let info = mock_info(
+        "filler0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(900000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), env, info, msg.clone()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "filler0000".to_string(),
+                    amount: Uint128::from(500000u128),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "market0000".to_string(),
+                funds: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(891000u128),
+                }],
+                msg: to_binary(&MarketExecuteMsg::RepayStableFor {
+                    borrower: "borrower0000".to_string(),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "fee0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(9000u128),
+                }],
+            })),
+        ]
+    );
+
+    // auction reached the max discount, price = 2 * (1 - 0.2) = 1.6
+    // remaining 500,000 collateral costs 800,000
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(2000u64);
+    let info = mock_info(
+        "filler0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fill_auction"),
+            attr("auction_idx", "1"),
+            attr("collateral_token", "asset0000"),
+            attr("collateral_amount", "500000"),
+            attr("repay_amount", "792000"),
+            attr("bid_fee", "8000"),
+            attr("liquidator_fee", "0"),
+        ]
+    );
+    assert_eq!(
+        res.messages[3],
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "filler0000".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(200000u128),
+            }],
+        }))
+    );
+
+    let auctions: AuctionsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::AuctionsByCollateral {
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(auctions.auctions.is_empty());
+
+    let stats: LiquidationStatsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LiquidationStats {
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stats.liquidation_count, 2u64);
+    assert_eq!(stats.total_collateral_amount, Uint256::from(1000000u64));
+    assert_eq!(stats.total_repay_amount, Uint256::from(1683000u64));
+    assert_eq!(stats.total_bid_fee, Uint256::from(17000u64));
+}
+
+#[test]
+fn fill_auction_above_loan() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_tax(Decimal::zero(), &[(&"uusd".to_string(), &Uint128::zero())]);
+    deps.querier
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(50))]);
+    deps.querier
+        .with_loan_amount(&[(&"borrower0000".to_string(), &Uint256::from(500000u64))]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(80),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100u64),
+        price_timeframe: 1000000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    deps.querier.with_oracle_price(&[(
+        &("asset0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::from_ratio(2u64, 1u64),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::zero(),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: Some(LiquidationMode::DutchAuction {
+            max_premium_rate: Decimal256::percent(20),
+            duration: 1000u64,
+        }),
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "custody0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteBid {
+            liquidator: "liquidator0000".to_string(),
+            fee_address: Some("fee0000".to_string()),
+            repay_address: Some("market0000".to_string()),
+            borrower: Some("borrower0000".to_string()),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // price = 1.8, 900,000 buys 500,000 collateral and leaves 891,000 after fees
+    // only the 500,000 loan is repaid, the rest goes to the borrower
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(500u64);
+    let msg = ExecuteMsg::FillAuction {
+        auction_idx: Uint128::from(1u128),
+    };
+    let info = mock_info(
+        "filler0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(900000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "filler0000".to_string(),
+                    amount: Uint128::from(500000u128),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "market0000".to_string(),
+                funds: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(500000u128),
+                }],
+                msg: to_binary(&MarketExecuteMsg::RepayStableFor {
+                    borrower: "borrower0000".to_string(),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "borrower0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(391000u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "fee0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(9000u128),
+                }],
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fill_auction"),
+            attr("auction_idx", "1"),
+            attr("collateral_token", "asset0000"),
+            attr("collateral_amount", "500000"),
+            attr("repay_amount", "500000"),
+            attr("bid_fee", "9000"),
+            attr("liquidator_fee", "0"),
+        ]
+    );
+
+    let stats: LiquidationStatsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LiquidationStats {
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stats.total_repay_amount, Uint256::from(500000u64));
+}
+
+#[test]
+fn cancel_auction() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_tax(Decimal::zero(), &[(&"uusd".to_string(), &Uint128::zero())]);
+    deps.querier
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(50))]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(80),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100u64),
+        price_timeframe: 1000000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    deps.querier.with_oracle_price(&[(
+        &("asset0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::zero(),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::zero(),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: Some(LiquidationMode::DutchAuction {
+            max_premium_rate: Decimal256::percent(20),
+            duration: 1000u64,
+        }),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    let execute_bid_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "custody0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteBid {
+            liquidator: "liquidator0000".to_string(),
+            fee_address: Some("fee0000".to_string()),
+            repay_address: Some("market0000".to_string()),
+            borrower: Some("borrower0000".to_string()),
+        })
+        .unwrap(),
+    });
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset0000", &[]),
+        execute_bid_msg.clone(),
+    )
+    .unwrap();
+
+    let pending: PendingCollateralsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PendingCollaterals {
+                borrower: "borrower0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        pending.collaterals,
+        vec![(
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string()
+            },
+            Uint256::from(1000000u64)
+        )]
+    );
+
+    // auctions are not filled at a zero oracle price
+    let msg = ExecuteMsg::FillAuction {
+        auction_idx: Uint128::from(1u128),
+    };
+    let info = mock_info(
+        "filler0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Invalid oracle price for asset0000")
+    );
+
+    // the auction expires once it stayed at the max discount for another duration
+    let msg = ExecuteMsg::CancelAuction {
+        auction_idx: Uint128::from(1u128),
+    };
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(1999u64);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("Auction has not expired"));
+
+    env.block.time = env.block.time.plus_seconds(1u64);
+    // the collateral goes back to the custody, locked again by the overseer
+    let res = execute(deps.as_mut(), env, mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "custody0000".to_string(),
+                    amount: Uint128::from(1000000u128),
+                    msg: to_binary(&CustodyCw20HookMsg::DepositCollateralFor {
+                        borrower: "borrower0000".to_string(),
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "overseer0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&OverseerExecuteMsg::RestoreCollateral {
+                    borrower: "borrower0000".to_string(),
+                    collaterals: vec![(
+                        AssetInfo::Token {
+                            contract_addr: "asset0000".to_string(),
+                        },
+                        Uint256::from(1000000u64),
+                    )],
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "cancel_auction"),
+            attr("auction_idx", "1"),
+            attr("collateral_token", "asset0000"),
+            attr("collateral_amount", "1000000"),
+            attr("borrower", "borrower0000"),
+        ]
+    );
+
+    let pending: PendingCollateralsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PendingCollaterals {
+                borrower: "borrower0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(pending.collaterals.is_empty());
+
+    // the borrower can be liquidated again, and the owner cancels right away
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("asset0000", &[]),
+        execute_bid_msg,
+    )
+    .unwrap();
+    let msg = ExecuteMsg::CancelAuction {
+        auction_idx: Uint128::from(2u128),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+}
+
+#[test]
+fn conditional_bids() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(90))]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(10),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100000000u64),
+        price_timeframe: 1000000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    deps.querier.with_oracle_price(&[(
+        &("asset0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::percent(50),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // bids are activated on submission
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::from(100000000u64),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env();
+    let msg = ExecuteMsg::SubmitBid {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        premium_slot: 1u8,
+        expires_at: Some(env.block.time.seconds()),
+        min_collateral_price: None,
+    };
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "Bid expiry must be in the future")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // bid 1: only consumed above 0.6 uusd
+    // bid 2: expires in 100 seconds
+    // bid 3: unconditional
+    for (bidder, expires_at, min_collateral_price) in [
+        ("addr0000", None, Some(Decimal256::percent(60))),
+        ("addr0001", Some(env.block.time.seconds() + 100u64), None),
+        ("addr0002", None, None),
+    ] {
+        let msg = ExecuteMsg::SubmitBid {
+            collateral_token: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            premium_slot: 1u8,
+            expires_at,
+            min_collateral_price,
+        };
+        let info = mock_info(
+            bidder,
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        );
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+
+    // price 0.5 is below the floor of bid 1 and bid 2 has expired,
+    // only bid 3 is consumed
+    // required_stable 495,000
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(200u64);
+    let info = mock_info("asset0000", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "custody0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteBid {
+            liquidator: "liquidator00000".to_string(),
+            fee_address: Some("fee0000".to_string()),
+            repay_address: Some("repay0000".to_string()),
+            borrower: None,
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "repay0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(485198u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "fee0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(4900u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0001".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(990099u128), // refund
+                }],
+            })),
+        ]
+    );
+
+    let bid_response: BidResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Bid {
+                bid_idx: Uint128::from(1u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bid_response.amount, Uint256::from(1000000u64));
+    assert_eq!(bid_response.pending_liquidated_collateral, Uint256::zero());
+    assert_eq!(
+        bid_response.min_collateral_price,
+        Some(Decimal256::percent(60))
+    );
+
+    // expired bid without liquidated collateral is removed
+    query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Bid {
+            bid_idx: Uint128::from(2u128),
+        },
+    )
+    .unwrap_err();
+
+    let bid_response: BidResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Bid {
+                bid_idx: Uint128::from(3u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bid_response.amount, Uint256::from(505000u64));
+    assert_eq!(
+        bid_response.pending_liquidated_collateral,
+        Uint256::from(1000000u64)
+    );
+
+    let bid_pool_response: BidPoolResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BidPool {
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                bid_slot: 1u8,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bid_pool_response.total_bid_amount,
+        Uint256::from(1505000u64)
+    );
+
+    // price 0.7 is above the floor, bid 1 is consumed again
+    // required_stable 693,000
+    deps.querier.with_oracle_price(&[(
+        &("asset0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::percent(70),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+    let info = mock_info("asset0000", &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "custody0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteBid {
+            liquidator: "liquidator00000".to_string(),
+            fee_address: Some("fee0000".to_string()),
+            repay_address: Some("repay0000".to_string()),
+            borrower: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let bid_response: BidResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Bid {
+                bid_idx: Uint128::from(1u128),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bid_response.amount, Uint256::from(539534u64));
+    assert_eq!(
+        bid_response.pending_liquidated_collateral,
+        Uint256::from(664451u64)
+    );
+}
+
+#[test]
+fn conditional_bid_cap() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(90))]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(10),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100000000u64),
+        price_timeframe: 1000000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::from(100000000u64),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    let submit_bid_msg =
+        |premium_slot: u8, min_collateral_price: Option<Decimal256>| ExecuteMsg::SubmitBid {
+            collateral_token: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            premium_slot,
+            expires_at: None,
+            min_collateral_price,
+        };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+    for _ in 0..20 {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            submit_bid_msg(1u8, Some(Decimal256::percent(60))),
+        )
+        .unwrap();
+    }
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        submit_bid_msg(1u8, Some(Decimal256::percent(60))),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Too many conditional bids in this premium slot")
+    );
+
+    // unconditional bids and other slots are not capped
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        submit_bid_msg(1u8, None),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        submit_bid_msg(2u8, Some(Decimal256::percent(60))),
+    )
+    .unwrap();
+}
+
+#[test]
+fn conditional_bid_min_amount() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(90))]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(10),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100000000u64),
+        price_timeframe: 1000000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::from(100000000u64),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        oracle_contract: None,
+        safe_ratio: None,
+        bid_fee: None,
+        liquidator_fee: None,
+        liquidation_threshold: None,
+        price_timeframe: None,
+        waiting_period: None,
+        overseer: None,
+        min_conditional_bid_amount: Some(Uint256::from(1000u64)),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    let submit_bid_msg = |min_collateral_price: Option<Decimal256>| ExecuteMsg::SubmitBid {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price,
+    };
+    let dust_info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(999u128),
+        }],
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        dust_info.clone(),
+        submit_bid_msg(Some(Decimal256::percent(60))),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Conditional bids must be at least 1000")
+    );
+
+    // unconditional bids have no minimum
+    execute(deps.as_mut(), mock_env(), dust_info, submit_bid_msg(None)).unwrap();
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        submit_bid_msg(Some(Decimal256::percent(60))),
+    )
+    .unwrap();
+}
+
+#[test]
+fn liquidation_history() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier
+        .with_collateral_max_ltv(&[(&"asset0000".to_string(), &Decimal256::percent(90))]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(10),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100000000u64),
+        price_timeframe: 1000000u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    deps.querier.with_oracle_price(&[(
+        &("asset0000".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::percent(50),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::from(100000000u64),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::SubmitBid {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        premium_slot: 1u8,
+        expires_at: None,
+        min_collateral_price: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // required_stable 495,000 and 247,500
+    for (amount, borrower) in [
+        (1000000u128, Some("borrower0000".to_string())),
+        (500000u128, None),
+    ] {
+        let info = mock_info("asset0000", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "custody0000".to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::ExecuteBid {
+                liquidator: "liquidator00000".to_string(),
+                fee_address: Some("fee0000".to_string()),
+                repay_address: Some("repay0000".to_string()),
+                borrower,
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    let env = mock_env();
+    let history: LiquidationHistoryResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::LiquidationHistory {
+                collateral_token: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        history.liquidations,
+        vec![
+            LiquidationEventResponse {
+                idx: Uint128::from(1u128),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string()
+                },
+                borrower: Some("borrower0000".to_string()),
+                collateral_amount: Uint256::from(1000000u64),
+                repay_amount: Uint256::from(490050u64),
+                premium_rate: Decimal256::percent(1),
+                bid_fee: Uint256::from(4950u64),
+                liquidator_fee: Uint256::zero(),
+                block_time: env.block.time.seconds(),
+            },
+            LiquidationEventResponse {
+                idx: Uint128::from(2u128),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string()
+                },
+                borrower: None,
+                collateral_amount: Uint256::from(500000u64),
+                repay_amount: Uint256::from(245025u64),
+                premium_rate: Decimal256::percent(1),
+                bid_fee: Uint256::from(2475u64),
+                liquidator_fee: Uint256::zero(),
+                block_time: env.block.time.seconds(),
+            },
+        ]
+    );
+
+    let history: LiquidationHistoryResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::LiquidationHistory {
+                collateral_token: Some(AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                }),
+                start_after: Some(Uint128::from(1u128)),
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(history.liquidations.len(), 1);
+    assert_eq!(history.liquidations[0].idx, Uint128::from(2u128));
+
+    let stats: LiquidationStatsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::LiquidationStats {
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        stats,
+        LiquidationStatsResponse {
+            collateral_token: AssetInfo::Token {
+                contract_addr: "asset0000".to_string()
+            },
+            liquidation_count: 2u64,
+            total_collateral_amount: Uint256::from(1500000u64),
+            total_repay_amount: Uint256::from(735075u64),
+            total_bid_fee: Uint256::from(7425u64),
+            total_liquidator_fee: Uint256::zero(),
+        }
+    );
+}
+
+#[test]
+fn update_collateral_info() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_collateral_max_ltv(&[(&"token0000".to_string(), &Decimal256::percent(90))]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        oracle_contract: "oracle0000".to_string(),
+        stable_denom: "uusd".to_string(),
+        safe_ratio: Decimal256::percent(10),
+        bid_fee: Decimal256::percent(1),
+        liquidator_fee: Decimal256::percent(0),
+        liquidation_threshold: Uint256::from(100000000u64),
+        price_timeframe: 60u64,
+        waiting_period: 60u64,
+        overseer: "overseer0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::WhitelistCollateral {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
+        max_slot: 30u8,
+        bid_threshold: Uint256::from(10000u128),
+        premium_rate_per_slot: Decimal256::percent(1),
+        liquidation_mode: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateCollateralInfo {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "token0000".to_string(),
+        },
+        bid_threshold: Some(Uint256::from(20000u128)),
+        max_slot: Some(20u8),
+        swap_pair: Some("pair0000".to_string()),
+        max_swap_spread: Some(Decimal256::percent(1)),
+        liquidation_mode: None,
+    };
 
     // unauthorized attempt
     let info = mock_info("addr0000", &[]);
@@ -880,7 +2864,9 @@ fn update_collateral_info() {
             deps.as_ref(),
             mock_env(),
             QueryMsg::CollateralInfo {
-                collateral_token: "token0000".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "token0000".to_string(),
+                },
             },
         )
         .unwrap(),
@@ -889,10 +2875,15 @@ fn update_collateral_info() {
     assert_eq!(
         collateral_info_response,
         CollateralInfoResponse {
-            collateral_token: "token0000".to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: "token0000".to_string()
+            },
             max_slot: 20u8,                          // updated max_slot
             bid_threshold: Uint256::from(20000u128), // updated bid threshold
             premium_rate_per_slot: Decimal256::percent(1),
+            swap_pair: Some("pair0000".to_string()),
+            max_swap_spread: Some(Decimal256::percent(1)),
+            liquidation_mode: LiquidationMode::BidPools {},
         }
     );
 }
@@ -4,9 +4,10 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use moneymarket::market::{
-    BorrowerInfoResponse, BorrowerInfosResponse, ConfigResponse, Cw20HookMsg, EpochStateResponse,
-    ExecuteMsg, InstantiateMsg, QueryMsg,
+    BadDebtResponse, BorrowAllowanceResponse, BorrowerInfoResponse, BorrowerInfosResponse,
+    ConfigResponse, Cw20HookMsg, EpochStateResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
 };
+use moneymarket::pause::PauseInfoResponse;
 use moneymarket_market::state::State;
 
 fn main() {
@@ -24,4 +25,7 @@ fn main() {
     export_schema(&schema_for!(EpochStateResponse), &out_dir);
     export_schema(&schema_for!(BorrowerInfoResponse), &out_dir);
     export_schema(&schema_for!(BorrowerInfosResponse), &out_dir);
+    export_schema(&schema_for!(PauseInfoResponse), &out_dir);
+    export_schema(&schema_for!(BadDebtResponse), &out_dir);
+    export_schema(&schema_for!(BorrowAllowanceResponse), &out_dir);
 }
@@ -0,0 +1,143 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, Addr, CanonicalAddr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage,
+};
+
+use crate::error::ContractError;
+use crate::state::{
+    read_borrow_allowance, read_config, remove_borrow_allowance, store_borrow_allowance,
+    BorrowAllowance, Config,
+};
+
+use moneymarket::market::BorrowAllowanceResponse;
+
+/// Grant `spender` an allowance to borrow against the collaterals of the sender
+pub fn increase_borrow_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: Addr,
+    amount: Uint256,
+    expires_at: Option<u64>,
+) -> Result<Response, ContractError> {
+    let block_time = env.block.time.seconds();
+    if matches!(expires_at, Some(expires_at) if expires_at <= block_time) {
+        return Err(ContractError::InvalidAllowanceExpiry {});
+    }
+
+    let borrower_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let spender_raw = deps.api.addr_canonicalize(spender.as_str())?;
+    let mut allowance = read_borrow_allowance(deps.storage, &borrower_raw, &spender_raw)?;
+
+    // an expired allowance starts over
+    if allowance.is_expired(block_time) {
+        allowance.amount = Uint256::zero();
+    }
+    allowance.amount += amount;
+    allowance.expires_at = expires_at;
+    store_borrow_allowance(deps.storage, &borrower_raw, &spender_raw, &allowance)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "increase_borrow_allowance"),
+        attr("borrower", info.sender),
+        attr("spender", spender),
+        attr("amount", allowance.amount),
+    ]))
+}
+
+pub fn revoke_borrow_allowance(
+    deps: DepsMut,
+    info: MessageInfo,
+    spender: Addr,
+) -> Result<Response, ContractError> {
+    remove_borrow_allowance(
+        deps.storage,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        &deps.api.addr_canonicalize(spender.as_str())?,
+    );
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "revoke_borrow_allowance"),
+        attr("borrower", info.sender),
+        attr("spender", spender),
+    ]))
+}
+
+/// Overseer spends the allowance of `spender` for collaterals unlocked on behalf of `borrower`
+pub fn spend_unlock_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    borrower: Addr,
+    spender: Addr,
+    amount: Uint256,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.overseer_contract != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    spend_borrow_allowance(
+        deps.storage,
+        &deps.api.addr_canonicalize(borrower.as_str())?,
+        &deps.api.addr_canonicalize(spender.as_str())?,
+        amount,
+        env.block.time.seconds(),
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "spend_borrow_allowance"),
+        attr("borrower", borrower),
+        attr("spender", spender),
+        attr("amount", amount),
+    ]))
+}
+
+/// Deducts a borrow on behalf of the borrower from the allowance of the spender
+pub(crate) fn spend_borrow_allowance(
+    storage: &mut dyn Storage,
+    borrower: &CanonicalAddr,
+    spender: &CanonicalAddr,
+    amount: Uint256,
+    block_time: u64,
+) -> Result<(), ContractError> {
+    let mut allowance = read_borrow_allowance(storage, borrower, spender)?;
+    if allowance.is_expired(block_time) {
+        allowance.amount = Uint256::zero();
+    }
+
+    if amount > allowance.amount {
+        return Err(ContractError::BorrowAllowanceExceeded(
+            allowance.amount.into(),
+        ));
+    }
+
+    allowance.amount = allowance.amount - amount;
+    store_borrow_allowance(storage, borrower, spender, &allowance)?;
+
+    Ok(())
+}
+
+pub fn query_borrow_allowance(
+    deps: Deps,
+    env: Env,
+    borrower: Addr,
+    spender: Addr,
+) -> StdResult<BorrowAllowanceResponse> {
+    let allowance: BorrowAllowance = read_borrow_allowance(
+        deps.storage,
+        &deps.api.addr_canonicalize(borrower.as_str())?,
+        &deps.api.addr_canonicalize(spender.as_str())?,
+    )?;
+
+    Ok(BorrowAllowanceResponse {
+        borrower: borrower.to_string(),
+        spender: spender.to_string(),
+        amount: if allowance.is_expired(env.block.time.seconds()) {
+            Uint256::zero()
+        } else {
+            allowance.amount
+        },
+        expires_at: allowance.expires_at,
+    })
+}
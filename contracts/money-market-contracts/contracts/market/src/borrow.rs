@@ -5,36 +5,60 @@ use cosmwasm_std::{
     StdResult, WasmMsg,
 };
 use moneymarket::interest_model::BorrowRateResponse;
-use moneymarket::market::{BorrowerInfoResponse, BorrowerInfosResponse};
+use moneymarket::market::{BadDebtResponse, BorrowerInfoResponse, BorrowerInfosResponse};
 use moneymarket::overseer::BorrowLimitResponse;
+use moneymarket::pause::PAUSE_BORROW;
 use moneymarket::querier::{deduct_tax, query_balance, query_supply};
 
+use crate::allowance::spend_borrow_allowance;
 use crate::deposit::compute_exchange_rate_raw;
 use crate::error::ContractError;
+use crate::flash_loan::{assert_no_flash_loan, query_market_balance, record_flash_loan_repay};
 use crate::querier::{query_borrow_limit, query_borrow_rate, query_target_deposit_rate};
 use crate::state::{
-    read_borrower_info, read_borrower_infos, read_config, read_state, store_borrower_info,
-    store_state, BorrowerInfo, Config, State,
+    read_bad_debt, read_borrower_info, read_borrower_infos, read_config, read_pause_info,
+    read_state, store_bad_debt, store_borrower_info, store_state, BadDebt, BorrowerInfo, Config,
+    State,
 };
 
+/// Borrow at the floating rate, or at a fixed rate when `fixed_rate` is set.
+/// Borrows on behalf of `borrower` spend the allowance of the sender
+#[allow(clippy::too_many_arguments)]
 pub fn borrow_stable(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     borrow_amount: Uint256,
     to: Option<Addr>,
+    borrower: Option<Addr>,
+    fixed_rate: bool,
+    max_fixed_rate: Option<Decimal256>,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
+    if read_pause_info(deps.storage)?.paused & PAUSE_BORROW != 0 {
+        return Err(ContractError::Paused {});
+    }
+
+    assert_no_flash_loan(deps.storage)?;
 
     let mut state: State = read_state(deps.storage)?;
 
-    let borrower = info.sender;
+    let borrower = borrower.unwrap_or_else(|| info.sender.clone());
     let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
+    if borrower != info.sender {
+        spend_borrow_allowance(
+            deps.storage,
+            &borrower_raw,
+            &deps.api.addr_canonicalize(info.sender.as_str())?,
+            borrow_amount,
+            env.block.time.seconds(),
+        )?;
+    }
     let mut liability: BorrowerInfo = read_borrower_info(deps.storage, &borrower_raw);
 
     // Compute interest
     compute_interest(deps.as_ref(), &config, &mut state, env.block.height, None)?;
-    compute_borrower_interest(&state, &mut liability);
+    compute_borrower_interest(&mut state, &mut liability);
 
     // Compute ANC reward
     compute_reward(&mut state, env.block.height);
@@ -46,9 +70,12 @@ pub fn borrow_stable(
         overseer,
         borrower.clone(),
         Some(env.block.time.seconds()),
+        config.stable_denom.clone(),
     )?;
 
-    if borrow_limit_res.borrow_limit < borrow_amount + liability.loan_amount {
+    if borrow_limit_res.borrow_limit
+        < borrow_amount + liability.loan_amount + liability.fixed_loan_amount
+    {
         return Err(ContractError::BorrowExceedsLimit(
             borrow_limit_res.borrow_limit.into(),
         ));
@@ -62,15 +89,46 @@ pub fn borrow_stable(
 
     // Assert borrow amount
     assert_max_borrow_factor(&config, &state, current_balance, borrow_amount)?;
+    assert_borrow_cap(&config, &state, borrow_amount)?;
+
+    let mut attributes = vec![
+        attr("action", "borrow_stable"),
+        attr("borrower", borrower.clone()),
+        attr("borrow_amount", borrow_amount),
+    ];
+    if borrower != info.sender {
+        attributes.push(attr("spender", info.sender.clone()));
+    }
+
+    if fixed_rate {
+        let rate = query_fixed_rate(deps.as_ref(), &config, &state, current_balance)?;
+        if let Some(max_fixed_rate) = max_fixed_rate {
+            if rate > max_fixed_rate {
+                return Err(ContractError::FixedRateTooHigh(rate.to_string()));
+            }
+        }
+
+        // an existing fixed-rate loan is blended with the new one
+        remove_fixed_liability(&mut state, &liability);
+        liability.fixed_rate = (liability.fixed_rate
+            * Decimal256::from_uint256(liability.fixed_loan_amount)
+            + rate * Decimal256::from_uint256(borrow_amount))
+            / Decimal256::from_uint256(liability.fixed_loan_amount + borrow_amount);
+        liability.fixed_loan_amount += borrow_amount;
+        add_fixed_liability(&mut state, &liability);
+
+        attributes.push(attr("fixed_rate", liability.fixed_rate.to_string()));
+    } else {
+        liability.loan_amount += borrow_amount;
+    }
 
-    liability.loan_amount += borrow_amount;
     state.total_liabilities += Decimal256::from_uint256(borrow_amount);
     store_state(deps.storage, &state)?;
     store_borrower_info(deps.storage, &borrower_raw, &liability)?;
 
     Ok(Response::new()
         .add_message(CosmosMsg::Bank(BankMsg::Send {
-            to_address: to.unwrap_or_else(|| borrower.clone()).to_string(),
+            to_address: to.unwrap_or(info.sender).to_string(),
             amount: vec![deduct_tax(
                 deps.as_ref(),
                 Coin {
@@ -79,11 +137,7 @@ pub fn borrow_stable(
                 },
             )?],
         }))
-        .add_attributes(vec![
-            attr("action", "borrow_stable"),
-            attr("borrower", borrower),
-            attr("borrow_amount", borrow_amount),
-        ]))
+        .add_attributes(attributes))
 }
 
 pub fn repay_stable_from_liquidation(
@@ -104,6 +158,15 @@ pub fn repay_stable_from_liquidation(
         config.stable_denom.to_string(),
     )?;
 
+    // collateral put up for auction is repaid once the auction is filled
+    if cur_balance == prev_balance {
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "repay_stable"),
+            attr("borrower", borrower),
+            attr("repay_amount", Uint256::zero()),
+        ]));
+    }
+
     // override env
     let mut info = info;
 
@@ -117,6 +180,17 @@ pub fn repay_stable_from_liquidation(
 }
 
 pub fn repay_stable(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let borrower = info.sender.clone();
+    repay_stable_for(deps, env, info, borrower)
+}
+
+/// Repay the loan of `borrower`; excess is returned to the sender
+pub fn repay_stable_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    borrower: Addr,
+) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
 
     // Check stable denom deposit
@@ -134,7 +208,6 @@ pub fn repay_stable(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respon
 
     let mut state: State = read_state(deps.storage)?;
 
-    let borrower = info.sender;
     let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
     let mut liability: BorrowerInfo = read_borrower_info(deps.storage, &borrower_raw);
 
@@ -146,21 +219,26 @@ pub fn repay_stable(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respon
         env.block.height,
         Some(amount),
     )?;
-    compute_borrower_interest(&state, &mut liability);
+    compute_borrower_interest(&mut state, &mut liability);
 
     // Compute ANC reward
     compute_reward(&mut state, env.block.height);
     compute_borrower_reward(&state, &mut liability);
 
-    let repay_amount: Uint256;
-    let mut messages: Vec<CosmosMsg> = vec![];
-    if liability.loan_amount < amount {
-        repay_amount = liability.loan_amount;
-        liability.loan_amount = Uint256::zero();
+    // The floating-rate loan is repaid first
+    let floating_repay_amount = std::cmp::min(liability.loan_amount, amount);
+    liability.loan_amount = liability.loan_amount - floating_repay_amount;
 
+    let fixed_repay_amount =
+        std::cmp::min(liability.fixed_loan_amount, amount - floating_repay_amount);
+    repay_fixed_loan(&mut state, &mut liability, fixed_repay_amount);
+
+    let repay_amount = floating_repay_amount + fixed_repay_amount;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if repay_amount < amount {
         // Payback left repay amount to sender
         messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: borrower.to_string(),
+            to_address: info.sender.to_string(),
             amount: vec![deduct_tax(
                 deps.as_ref(),
                 Coin {
@@ -169,15 +247,13 @@ pub fn repay_stable(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respon
                 },
             )?],
         }));
-    } else {
-        repay_amount = amount;
-        liability.loan_amount = liability.loan_amount - repay_amount;
     }
 
     state.total_liabilities = state.total_liabilities - Decimal256::from_uint256(repay_amount);
 
     store_borrower_info(deps.storage, &borrower_raw, &liability)?;
     store_state(deps.storage, &state)?;
+    record_flash_loan_repay(deps.storage, repay_amount)?;
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "repay_stable"),
@@ -186,6 +262,171 @@ pub fn repay_stable(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respon
     ]))
 }
 
+/// Overseer writes off the remaining loan of a borrower whose collaterals
+/// are exhausted, sending interest buffer funds to cover the loss
+pub fn write_off_bad_debt(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    borrower: Addr,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.overseer_contract != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    assert_no_flash_loan(deps.storage)?;
+
+    let buffer_amount: Uint256 = info
+        .funds
+        .iter()
+        .find(|c| c.denom == config.stable_denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+
+    let mut state: State = read_state(deps.storage)?;
+    let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
+    let mut liability: BorrowerInfo = read_borrower_info(deps.storage, &borrower_raw);
+
+    // Compute interest
+    compute_interest(
+        deps.as_ref(),
+        &config,
+        &mut state,
+        env.block.height,
+        Some(buffer_amount),
+    )?;
+    compute_borrower_interest(&mut state, &mut liability);
+
+    // Compute ANC reward
+    compute_reward(&mut state, env.block.height);
+    compute_borrower_reward(&state, &mut liability);
+
+    let bad_debt = liability.loan_amount + liability.fixed_loan_amount;
+    if bad_debt.is_zero() {
+        return Err(ContractError::NoBadDebt {});
+    }
+
+    liability.loan_amount = Uint256::zero();
+    let fixed_loan_amount = liability.fixed_loan_amount;
+    repay_fixed_loan(&mut state, &mut liability, fixed_loan_amount);
+    state.total_liabilities = state.total_liabilities - Decimal256::from_uint256(bad_debt);
+
+    // reserves cover the loss first, then the interest buffer
+    let reserve_cover = std::cmp::min(state.total_reserves * Uint256::one(), bad_debt);
+    state.total_reserves = state.total_reserves - Decimal256::from_uint256(reserve_cover);
+
+    let buffer_cover = std::cmp::min(buffer_amount, bad_debt - reserve_cover);
+    let socialized = bad_debt - reserve_cover - buffer_cover;
+
+    let mut bad_debt_info: BadDebt = read_bad_debt(deps.storage)?;
+    bad_debt_info.total_bad_debt += bad_debt;
+    bad_debt_info.covered_by_reserves += reserve_cover;
+    bad_debt_info.covered_by_buffer += buffer_cover;
+    bad_debt_info.socialized += socialized;
+    bad_debt_info.write_off_count += 1;
+
+    store_bad_debt(deps.storage, &bad_debt_info)?;
+    store_borrower_info(deps.storage, &borrower_raw, &liability)?;
+    store_state(deps.storage, &state)?;
+
+    // Payback unused buffer funds to overseer
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if buffer_amount > buffer_cover {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom,
+                    amount: (buffer_amount - buffer_cover).into(),
+                },
+            )?],
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "write_off_bad_debt"),
+        attr("borrower", borrower),
+        attr("bad_debt", bad_debt),
+        attr("reserve_cover", reserve_cover),
+        attr("buffer_cover", buffer_cover),
+        attr("socialized", socialized),
+    ]))
+}
+
+/// Reset the rate of a fixed-rate loan to the offered fixed rate once it
+/// deviates from it by more than the rebalance threshold. Anyone can lower
+/// the rate, only the borrower and the owner can raise it
+pub fn rebalance_fixed_rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    borrower: Addr,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+
+    // the offered rate is priced from the market balance
+    assert_no_flash_loan(deps.storage)?;
+
+    let mut state: State = read_state(deps.storage)?;
+
+    let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
+    let mut liability: BorrowerInfo = read_borrower_info(deps.storage, &borrower_raw);
+
+    // Compute interest
+    compute_interest(deps.as_ref(), &config, &mut state, env.block.height, None)?;
+    compute_borrower_interest(&mut state, &mut liability);
+
+    // Compute ANC reward
+    compute_reward(&mut state, env.block.height);
+    compute_borrower_reward(&state, &mut liability);
+
+    if liability.fixed_loan_amount.is_zero() {
+        return Err(ContractError::NoFixedRateLoan {});
+    }
+
+    let current_balance = query_balance(
+        deps.as_ref(),
+        env.contract.address,
+        config.stable_denom.to_string(),
+    )?;
+    let fixed_rate = query_fixed_rate(deps.as_ref(), &config, &state, current_balance)?;
+
+    let deviation = if fixed_rate > liability.fixed_rate {
+        fixed_rate - liability.fixed_rate
+    } else {
+        liability.fixed_rate - fixed_rate
+    };
+    if config.fixed_rate_rebalance_threshold.is_zero()
+        || deviation <= config.fixed_rate_rebalance_threshold
+    {
+        return Err(ContractError::FixedRateNotRebalanceable {});
+    }
+
+    if fixed_rate > liability.fixed_rate
+        && info.sender != borrower
+        && deps.api.addr_canonicalize(info.sender.as_str())? != config.owner_addr
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let prev_rate = liability.fixed_rate;
+    remove_fixed_liability(&mut state, &liability);
+    liability.fixed_rate = fixed_rate;
+    add_fixed_liability(&mut state, &liability);
+
+    store_state(deps.storage, &state)?;
+    store_borrower_info(deps.storage, &borrower_raw, &liability)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "rebalance_fixed_rate"),
+        attr("borrower", borrower),
+        attr("prev_rate", prev_rate.to_string()),
+        attr("fixed_rate", fixed_rate.to_string()),
+    ]))
+}
+
 pub fn claim_rewards(
     deps: DepsMut,
     env: Env,
@@ -201,7 +442,7 @@ pub fn claim_rewards(
 
     // Compute interest
     compute_interest(deps.as_ref(), &config, &mut state, env.block.height, None)?;
-    compute_borrower_interest(&state, &mut liability);
+    compute_borrower_interest(&mut state, &mut liability);
 
     // Compute ANC reward
     compute_reward(&mut state, env.block.height);
@@ -253,11 +494,7 @@ pub fn compute_interest(
     }
 
     let aterra_supply = query_supply(deps, deps.api.addr_humanize(&config.aterra_contract)?)?;
-    let balance: Uint256 = query_balance(
-        deps,
-        deps.api.addr_humanize(&config.contract_addr)?,
-        config.stable_denom.to_string(),
-    )? - deposit_amount.unwrap_or_else(Uint256::zero);
+    let balance: Uint256 = query_market_balance(deps, config, deposit_amount)?;
 
     let borrow_rate_res: BorrowRateResponse = query_borrow_rate(
         deps,
@@ -301,11 +538,21 @@ pub fn compute_interest_raw(
 
     let passed_blocks = Decimal256::from_uint256(block_height - state.last_interest_updated);
 
+    // fixed-rate loans accrue their rates on their amounts
+    // as of their last update
     let interest_factor = passed_blocks * borrow_rate;
-    let interest_accrued = state.total_liabilities * interest_factor;
+    let fixed_interest_accrued = state.fixed_interest_per_block * passed_blocks;
+    let floating_liabilities = if state.total_liabilities > state.total_fixed_liabilities {
+        state.total_liabilities - state.total_fixed_liabilities
+    } else {
+        Decimal256::zero()
+    };
+    let interest_accrued = floating_liabilities * interest_factor + fixed_interest_accrued;
 
     state.global_interest_index =
         state.global_interest_index * (Decimal256::one() + interest_factor);
+    state.fixed_interest_index += passed_blocks;
+    state.total_fixed_liabilities += fixed_interest_accrued;
     state.total_liabilities += interest_accrued;
 
     let mut exchange_rate = compute_exchange_rate_raw(state, aterra_supply, balance);
@@ -331,10 +578,84 @@ pub fn compute_interest_raw(
 }
 
 /// Compute new interest and apply to liability
-pub(crate) fn compute_borrower_interest(state: &State, liability: &mut BorrowerInfo) {
+pub(crate) fn compute_borrower_interest(state: &mut State, liability: &mut BorrowerInfo) {
     liability.loan_amount =
         liability.loan_amount * state.global_interest_index / liability.interest_index;
     liability.interest_index = state.global_interest_index;
+
+    // the fixed-rate loan shares the fixed interest index
+    if !liability.fixed_loan_amount.is_zero() {
+        let prev_loan_amount = Decimal256::from_uint256(liability.fixed_loan_amount);
+        let loan_amount = prev_loan_amount
+            + prev_loan_amount
+                * liability.fixed_rate
+                * (state.fixed_interest_index - liability.fixed_interest_index);
+        liability.fixed_loan_amount = loan_amount * Uint256::one();
+
+        // the accrued interest is already part of total_fixed_liabilities,
+        // except for the fraction truncated from the loan
+        let truncated = loan_amount - Decimal256::from_uint256(liability.fixed_loan_amount);
+        state.total_fixed_liabilities = sub_or_zero(state.total_fixed_liabilities, truncated);
+        state.total_liabilities = sub_or_zero(state.total_liabilities, truncated);
+        state.fixed_interest_per_block += liability.fixed_rate
+            * (Decimal256::from_uint256(liability.fixed_loan_amount) - prev_loan_amount);
+    }
+    liability.fixed_interest_index = state.fixed_interest_index;
+}
+
+/// Fixed rate offered to new loans; the current floating
+/// rate plus the fixed rate premium
+pub(crate) fn query_fixed_rate(
+    deps: Deps,
+    config: &Config,
+    state: &State,
+    balance: Uint256,
+) -> StdResult<Decimal256> {
+    let borrow_rate_res: BorrowRateResponse = query_borrow_rate(
+        deps,
+        deps.api.addr_humanize(&config.interest_model)?,
+        balance,
+        state.total_liabilities,
+        state.total_reserves,
+    )?;
+
+    Ok(borrow_rate_res.rate + config.fixed_rate_premium)
+}
+
+fn add_fixed_liability(state: &mut State, liability: &BorrowerInfo) {
+    let amount = Decimal256::from_uint256(liability.fixed_loan_amount);
+    state.total_fixed_liabilities += amount;
+    state.fixed_interest_per_block += amount * liability.fixed_rate;
+}
+
+fn remove_fixed_liability(state: &mut State, liability: &BorrowerInfo) {
+    let amount = Decimal256::from_uint256(liability.fixed_loan_amount);
+    state.total_fixed_liabilities = sub_or_zero(state.total_fixed_liabilities, amount);
+    state.fixed_interest_per_block = sub_or_zero(
+        state.fixed_interest_per_block,
+        amount * liability.fixed_rate,
+    );
+}
+
+fn repay_fixed_loan(state: &mut State, liability: &mut BorrowerInfo, amount: Uint256) {
+    if amount.is_zero() {
+        return;
+    }
+
+    remove_fixed_liability(state, liability);
+    liability.fixed_loan_amount = liability.fixed_loan_amount - amount;
+    if liability.fixed_loan_amount.is_zero() {
+        liability.fixed_rate = Decimal256::zero();
+    }
+    add_fixed_liability(state, liability);
+}
+
+fn sub_or_zero(a: Decimal256, b: Decimal256) -> Decimal256 {
+    if a > b {
+        a - b
+    } else {
+        Decimal256::zero()
+    }
 }
 
 /// Compute distributed reward and update global index
@@ -356,9 +677,10 @@ pub fn compute_reward(state: &mut State, block_height: u64) {
 
 /// Compute reward amount a borrower received
 pub(crate) fn compute_borrower_reward(state: &State, liability: &mut BorrowerInfo) {
-    liability.pending_rewards += Decimal256::from_uint256(liability.loan_amount)
-        / state.global_interest_index
-        * (state.global_reward_index - liability.reward_index);
+    liability.pending_rewards +=
+        Decimal256::from_uint256(liability.loan_amount + liability.fixed_loan_amount)
+            / state.global_interest_index
+            * (state.global_reward_index - liability.reward_index);
     liability.reward_index = state.global_reward_index;
 }
 
@@ -383,7 +705,7 @@ pub fn query_borrower_info(
     let mut state: State = read_state(deps.storage)?;
 
     compute_interest(deps, &config, &mut state, block_height, None)?;
-    compute_borrower_interest(&state, &mut borrower_info);
+    compute_borrower_interest(&mut state, &mut borrower_info);
 
     compute_reward(&mut state, block_height);
     compute_borrower_reward(&state, &mut borrower_info);
@@ -392,8 +714,12 @@ pub fn query_borrower_info(
         borrower: borrower.to_string(),
         interest_index: borrower_info.interest_index,
         reward_index: borrower_info.reward_index,
-        loan_amount: borrower_info.loan_amount,
+        loan_amount: borrower_info.loan_amount + borrower_info.fixed_loan_amount,
         pending_rewards: borrower_info.pending_rewards,
+        floating_loan_amount: borrower_info.loan_amount,
+        fixed_loan_amount: borrower_info.fixed_loan_amount,
+        fixed_rate: borrower_info.fixed_rate,
+        fixed_interest_index: borrower_info.fixed_interest_index,
     })
 }
 
@@ -412,6 +738,17 @@ pub fn query_borrower_infos(
     Ok(BorrowerInfosResponse { borrower_infos })
 }
 
+pub fn query_bad_debt(deps: Deps) -> StdResult<BadDebtResponse> {
+    let bad_debt: BadDebt = read_bad_debt(deps.storage)?;
+    Ok(BadDebtResponse {
+        total_bad_debt: bad_debt.total_bad_debt,
+        covered_by_reserves: bad_debt.covered_by_reserves,
+        covered_by_buffer: bad_debt.covered_by_buffer,
+        socialized: bad_debt.socialized,
+        write_off_count: bad_debt.write_off_count,
+    })
+}
+
 fn assert_max_borrow_factor(
     config: &Config,
     state: &State,
@@ -440,3 +777,19 @@ fn assert_max_borrow_factor(
 
     Ok(())
 }
+
+fn assert_borrow_cap(
+    config: &Config,
+    state: &State,
+    borrow_amount: Uint256,
+) -> Result<(), ContractError> {
+    if let Some(borrow_cap) = config.borrow_cap {
+        if state.total_liabilities + Decimal256::from_uint256(borrow_amount)
+            > Decimal256::from_uint256(borrow_cap)
+        {
+            return Err(ContractError::BorrowExceedsCap(borrow_cap.into()));
+        }
+    }
+
+    Ok(())
+}
@@ -1,15 +1,27 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
+use crate::allowance::{
+    increase_borrow_allowance, query_borrow_allowance, revoke_borrow_allowance,
+    spend_unlock_allowance,
+};
 use crate::borrow::{
     borrow_stable, claim_rewards, compute_interest, compute_interest_raw, compute_reward,
-    query_borrower_info, query_borrower_infos, repay_stable, repay_stable_from_liquidation,
+    query_bad_debt, query_borrower_info, query_borrower_infos, rebalance_fixed_rate, repay_stable,
+    repay_stable_for, repay_stable_from_liquidation, write_off_bad_debt,
 };
 use crate::deposit::{compute_exchange_rate_raw, deposit_stable, redeem_stable};
 use crate::error::ContractError;
+use crate::flash_loan::{
+    assert_no_flash_loan, flash_loan, flash_loan_callback, query_market_balance,
+    FLASH_LOAN_REPLY_ID,
+};
 use crate::querier::{query_anc_emission_rate, query_borrow_rate, query_target_deposit_rate};
 use crate::response::MsgInstantiateContractResponse;
-use crate::state::{read_config, read_state, store_config, store_state, Config, State};
+use crate::state::{
+    read_config, read_pause_info, read_state, store_config, store_pause_info, store_state, Config,
+    PauseInfo, State,
+};
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
@@ -19,11 +31,12 @@ use cosmwasm_std::{
 use cw20::{Cw20Coin, Cw20ReceiveMsg, MinterResponse};
 
 use moneymarket::common::optional_addr_validate;
-use moneymarket::interest_model::BorrowRateResponse;
+use moneymarket::interest_model::{BorrowRateResponse, ExecuteMsg as InterestExecuteMsg};
 use moneymarket::market::{
     ConfigResponse, Cw20HookMsg, EpochStateResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
     StateResponse,
 };
+use moneymarket::pause::{is_guardian_pause, is_valid_pause, PauseInfoResponse};
 use moneymarket::querier::{deduct_tax, query_balance, query_supply};
 use protobuf::Message;
 use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
@@ -64,6 +77,10 @@ pub fn instantiate(
             distributor_contract: CanonicalAddr::from(vec![]),
             stable_denom: msg.stable_denom.clone(),
             max_borrow_factor: msg.max_borrow_factor,
+            borrow_cap: None,
+            flash_loan_fee: Decimal256::zero(),
+            fixed_rate_premium: Decimal256::zero(),
+            fixed_rate_rebalance_threshold: Decimal256::zero(),
         },
     )?;
 
@@ -79,6 +96,9 @@ pub fn instantiate(
             anc_emission_rate: msg.anc_emission_rate,
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     )?;
 
@@ -142,6 +162,10 @@ pub fn execute(
             interest_model,
             distribution_model,
             max_borrow_factor,
+            borrow_cap,
+            flash_loan_fee,
+            fixed_rate_premium,
+            fixed_rate_rebalance_threshold,
         } => {
             let api = deps.api;
             update_config(
@@ -152,8 +176,17 @@ pub fn execute(
                 optional_addr_validate(api, interest_model)?,
                 optional_addr_validate(api, distribution_model)?,
                 max_borrow_factor,
+                borrow_cap,
+                flash_loan_fee,
+                fixed_rate_premium,
+                fixed_rate_rebalance_threshold,
             )
         }
+        ExecuteMsg::UpdateGuardian { guardian } => {
+            let api = deps.api;
+            update_guardian(deps, info, optional_addr_validate(api, guardian)?)
+        }
+        ExecuteMsg::UpdatePause { paused } => update_pause(deps, info, paused),
         ExecuteMsg::ExecuteEpochOperations {
             deposit_rate,
             target_deposit_rate,
@@ -169,7 +202,29 @@ pub fn execute(
             distributed_interest,
         ),
         ExecuteMsg::DepositStable {} => deposit_stable(deps, env, info),
-        ExecuteMsg::BorrowStable { borrow_amount, to } => {
+        ExecuteMsg::BorrowStable {
+            borrow_amount,
+            to,
+            borrower,
+        } => {
+            let api = deps.api;
+            borrow_stable(
+                deps,
+                env,
+                info,
+                borrow_amount,
+                optional_addr_validate(api, to)?,
+                optional_addr_validate(api, borrower)?,
+                false,
+                None,
+            )
+        }
+        ExecuteMsg::BorrowStableFixed {
+            borrow_amount,
+            max_rate,
+            to,
+            borrower,
+        } => {
             let api = deps.api;
             borrow_stable(
                 deps,
@@ -177,9 +232,50 @@ pub fn execute(
                 info,
                 borrow_amount,
                 optional_addr_validate(api, to)?,
+                optional_addr_validate(api, borrower)?,
+                true,
+                max_rate,
             )
         }
+        ExecuteMsg::IncreaseBorrowAllowance {
+            spender,
+            amount,
+            expires_at,
+        } => {
+            let api = deps.api;
+            increase_borrow_allowance(
+                deps,
+                env,
+                info,
+                api.addr_validate(&spender)?,
+                amount,
+                expires_at,
+            )
+        }
+        ExecuteMsg::SpendBorrowAllowance {
+            borrower,
+            spender,
+            amount,
+        } => {
+            let api = deps.api;
+            spend_unlock_allowance(
+                deps,
+                env,
+                info,
+                api.addr_validate(&borrower)?,
+                api.addr_validate(&spender)?,
+                amount,
+            )
+        }
+        ExecuteMsg::RevokeBorrowAllowance { spender } => {
+            let api = deps.api;
+            revoke_borrow_allowance(deps, info, api.addr_validate(&spender)?)
+        }
         ExecuteMsg::RepayStable {} => repay_stable(deps, env, info),
+        ExecuteMsg::RepayStableFor { borrower } => {
+            let api = deps.api;
+            repay_stable_for(deps, env, info, api.addr_validate(&borrower)?)
+        }
         ExecuteMsg::RepayStableFromLiquidation {
             borrower,
             prev_balance,
@@ -193,15 +289,38 @@ pub fn execute(
                 prev_balance,
             )
         }
+        ExecuteMsg::WriteOffBadDebt { borrower } => {
+            let api = deps.api;
+            write_off_bad_debt(deps, env, info, api.addr_validate(&borrower)?)
+        }
+        ExecuteMsg::RebalanceFixedRate { borrower } => {
+            let api = deps.api;
+            rebalance_fixed_rate(deps, env, info, api.addr_validate(&borrower)?)
+        }
         ExecuteMsg::ClaimRewards { to } => {
             let api = deps.api;
             claim_rewards(deps, env, info, optional_addr_validate(api, to)?)
         }
+        ExecuteMsg::FlashLoan {
+            amount,
+            callback_contract,
+            msg,
+        } => {
+            let api = deps.api;
+            flash_loan(
+                deps,
+                env,
+                info,
+                amount,
+                api.addr_validate(&callback_contract)?,
+                msg,
+            )
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg.id {
         1 => {
             // get new token's contract address
@@ -218,6 +337,7 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 
             register_aterra(deps, token_addr)
         }
+        FLASH_LOAN_REPLY_ID => flash_loan_callback(deps, env),
         _ => Err(ContractError::InvalidReplyId {}),
     }
 }
@@ -284,6 +404,7 @@ pub fn register_contracts(
     Ok(Response::default())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
     env: Env,
@@ -292,6 +413,10 @@ pub fn update_config(
     interest_model: Option<Addr>,
     distribution_model: Option<Addr>,
     max_borrow_factor: Option<Decimal256>,
+    borrow_cap: Option<Uint256>,
+    flash_loan_fee: Option<Decimal256>,
+    fixed_rate_premium: Option<Decimal256>,
+    fixed_rate_rebalance_threshold: Option<Decimal256>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = read_config(deps.storage)?;
 
@@ -322,10 +447,82 @@ pub fn update_config(
         config.max_borrow_factor = max_borrow_factor;
     }
 
+    if let Some(borrow_cap) = borrow_cap {
+        config.borrow_cap = if borrow_cap.is_zero() {
+            None
+        } else {
+            Some(borrow_cap)
+        };
+    }
+
+    if let Some(flash_loan_fee) = flash_loan_fee {
+        if flash_loan_fee >= Decimal256::one() {
+            return Err(ContractError::InvalidFlashLoanFee {});
+        }
+
+        config.flash_loan_fee = flash_loan_fee;
+    }
+
+    if let Some(fixed_rate_premium) = fixed_rate_premium {
+        config.fixed_rate_premium = fixed_rate_premium;
+    }
+
+    if let Some(fixed_rate_rebalance_threshold) = fixed_rate_rebalance_threshold {
+        config.fixed_rate_rebalance_threshold = fixed_rate_rebalance_threshold;
+    }
+
     store_config(deps.storage, &config)?;
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
 }
 
+pub fn update_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    pause_info.guardian = match guardian {
+        Some(guardian) => Some(deps.api.addr_canonicalize(guardian.as_str())?),
+        None => None,
+    };
+
+    store_pause_info(deps.storage, &pause_info)?;
+    Ok(Response::new().add_attributes(vec![attr("action", "update_guardian")]))
+}
+
+pub fn update_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: u8,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let mut pause_info: PauseInfo = read_pause_info(deps.storage)?;
+
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let is_guardian = pause_info.guardian.as_ref() == Some(&sender_raw);
+    if sender_raw != config.owner_addr
+        && !(is_guardian && is_guardian_pause(pause_info.paused, paused))
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !is_valid_pause(paused) {
+        return Err(ContractError::InvalidPause {});
+    }
+
+    pause_info.paused = paused;
+    store_pause_info(deps.storage, &pause_info)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_pause"),
+        attr("paused", paused.to_string()),
+    ]))
+}
+
 pub fn execute_epoch_operations(
     deps: DepsMut,
     env: Env,
@@ -340,6 +537,8 @@ pub fn execute_epoch_operations(
         return Err(ContractError::Unauthorized {});
     }
 
+    assert_no_flash_loan(deps.storage)?;
+
     let mut state: State = read_state(deps.storage)?;
 
     // Compute interest and reward before updating anc_emission_rate
@@ -376,14 +575,26 @@ pub fn execute_epoch_operations(
 
     compute_reward(&mut state, env.block.height);
 
+    // Let the interest model adapt its rate to the
+    // utilization observed at the end of the epoch
+    let mut messages: Vec<CosmosMsg> = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.interest_model)?.to_string(),
+        funds: vec![],
+        msg: to_binary(&InterestExecuteMsg::ExecuteEpochOperations {
+            market_balance: balance + distributed_interest,
+            total_liabilities: state.total_liabilities,
+            total_reserves: state.total_reserves,
+        })?,
+    })];
+
     // Compute total_reserves to fund collector contract
     // Update total_reserves and send it to collector contract
     // only when there is enough balance
     let total_reserves = state.total_reserves * Uint256::one();
-    let messages: Vec<CosmosMsg> = if !total_reserves.is_zero() && balance > total_reserves {
+    if !total_reserves.is_zero() && balance > total_reserves {
         state.total_reserves = state.total_reserves - Decimal256::from_uint256(total_reserves);
 
-        vec![CosmosMsg::Bank(BankMsg::Send {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
             to_address: deps
                 .api
                 .addr_humanize(&config.collector_contract)?
@@ -395,10 +606,8 @@ pub fn execute_epoch_operations(
                     amount: total_reserves.into(),
                 },
             )?],
-        })]
-    } else {
-        vec![]
-    };
+        }));
+    }
 
     // Query updated anc_emission_rate
     state.anc_emission_rate = query_anc_emission_rate(
@@ -447,6 +656,14 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             optional_addr_validate(deps.api, start_after)?,
             limit,
         )?),
+        QueryMsg::PauseInfo {} => to_binary(&query_pause_info(deps)?),
+        QueryMsg::BadDebt {} => to_binary(&query_bad_debt(deps)?),
+        QueryMsg::BorrowAllowance { borrower, spender } => to_binary(&query_borrow_allowance(
+            deps,
+            env,
+            deps.api.addr_validate(&borrower)?,
+            deps.api.addr_validate(&spender)?,
+        )?),
     }
 }
 
@@ -474,6 +691,9 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
             .to_string(),
         stable_denom: config.stable_denom,
         max_borrow_factor: config.max_borrow_factor,
+        flash_loan_fee: config.flash_loan_fee,
+        fixed_rate_premium: config.fixed_rate_premium,
+        fixed_rate_rebalance_threshold: config.fixed_rate_rebalance_threshold,
     })
 }
 
@@ -516,6 +736,10 @@ pub fn query_state(deps: Deps, env: Env, block_height: Option<u64>) -> StdResult
         anc_emission_rate: state.anc_emission_rate,
         prev_aterra_supply: state.prev_aterra_supply,
         prev_exchange_rate: state.prev_exchange_rate,
+        borrow_cap: config.borrow_cap,
+        total_fixed_liabilities: state.total_fixed_liabilities,
+        fixed_interest_per_block: state.fixed_interest_per_block,
+        fixed_interest_index: state.fixed_interest_index,
     })
 }
 
@@ -529,11 +753,7 @@ pub fn query_epoch_state(
 
     let distributed_interest = distributed_interest.unwrap_or_else(Uint256::zero);
     let aterra_supply = query_supply(deps, deps.api.addr_humanize(&config.aterra_contract)?)?;
-    let balance = query_balance(
-        deps,
-        deps.api.addr_humanize(&config.contract_addr)?,
-        config.stable_denom.to_string(),
-    )? - distributed_interest;
+    let balance = query_market_balance(deps, &config, Some(distributed_interest))?;
 
     if let Some(block_height) = block_height {
         if block_height < state.last_interest_updated {
@@ -574,3 +794,14 @@ pub fn query_epoch_state(
         aterra_supply,
     })
 }
+
+pub fn query_pause_info(deps: Deps) -> StdResult<PauseInfoResponse> {
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    Ok(PauseInfoResponse {
+        guardian: match pause_info.guardian {
+            Some(guardian) => Some(deps.api.addr_humanize(&guardian)?.to_string()),
+            None => None,
+        },
+        paused: pause_info.paused,
+    })
+}
@@ -6,9 +6,11 @@ use cosmwasm_std::{
 
 use crate::borrow::{compute_interest, compute_reward};
 use crate::error::ContractError;
-use crate::state::{read_config, read_state, store_state, Config, State};
+use crate::flash_loan::{assert_no_flash_loan, query_market_balance};
+use crate::state::{read_config, read_pause_info, read_state, store_state, Config, State};
 
 use cw20::Cw20ExecuteMsg;
+use moneymarket::pause::PAUSE_DEPOSIT;
 use moneymarket::querier::{deduct_tax, query_balance, query_supply};
 
 pub fn deposit_stable(
@@ -17,6 +19,11 @@ pub fn deposit_stable(
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
+    if read_pause_info(deps.storage)?.paused & PAUSE_DEPOSIT != 0 {
+        return Err(ContractError::Paused {});
+    }
+
+    assert_no_flash_loan(deps.storage)?;
 
     // Check base denom deposit
     let deposit_amount: Uint256 = info
@@ -73,6 +80,7 @@ pub fn redeem_stable(
     burn_amount: Uint128,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
+    assert_no_flash_loan(deps.storage)?;
 
     // Update interest related state
     let mut state: State = read_state(deps.storage)?;
@@ -145,11 +153,7 @@ pub(crate) fn compute_exchange_rate(
     deposit_amount: Option<Uint256>,
 ) -> StdResult<Decimal256> {
     let aterra_supply = query_supply(deps, deps.api.addr_humanize(&config.aterra_contract)?)?;
-    let balance = query_balance(
-        deps,
-        deps.api.addr_humanize(&config.contract_addr)?,
-        config.stable_denom.to_string(),
-    )? - deposit_amount.unwrap_or_else(Uint256::zero);
+    let balance = query_market_balance(deps, config, deposit_amount)?;
 
     Ok(compute_exchange_rate_raw(state, aterra_supply, balance))
 }
@@ -12,12 +12,39 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Borrow amount too high; Total liabilities become greater than borrow cap: {0}")]
+    BorrowExceedsCap(u128),
+
     #[error("Borrow amount too high; Loan liability becomes greater than borrow limit: {0}")]
     BorrowExceedsLimit(u128),
 
+    #[error("Borrow amount exceeds the borrow allowance: {0}")]
+    BorrowAllowanceExceeded(u128),
+
+    #[error("Borrow allowance expiry must be in the future")]
+    InvalidAllowanceExpiry {},
+
+    #[error("Operation is not allowed while a flash loan is outstanding")]
+    FlashLoanInProgress {},
+
+    #[error("Flash loan not repaid; {0} must be returned")]
+    FlashLoanNotRepaid(u128),
+
+    #[error("Fixed rate has not deviated enough to be rebalanced")]
+    FixedRateNotRebalanceable {},
+
+    #[error("Offered fixed rate {0} exceeds the max rate")]
+    FixedRateTooHigh(String),
+
     #[error("Must deposit initial funds {0}{0}")]
     InitialFundsNotDeposited(u128, String),
 
+    #[error("Flash loan fee must be less than one")]
+    InvalidFlashLoanFee {},
+
+    #[error("Invalid pause bitmask")]
+    InvalidPause {},
+
     #[error("Invalid reply ID")]
     InvalidReplyId {},
 
@@ -30,9 +57,21 @@ pub enum ContractError {
     #[error("Not enough {0} available; borrow demand too high")]
     NoStableAvailable(String),
 
+    #[error("Borrower has no bad debt")]
+    NoBadDebt {},
+
+    #[error("Borrower has no fixed-rate loan")]
+    NoFixedRateLoan {},
+
+    #[error("Operation is paused")]
+    Paused {},
+
     #[error("Deposit amount must be greater than 0 {0}")]
     ZeroDeposit(String),
 
+    #[error("Flash loan amount must be greater than 0 {0}")]
+    ZeroFlashLoan(String),
+
     #[error("Repay amount must be greater than 0 {0}")]
     ZeroRepay(String),
 }
@@ -0,0 +1,158 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, Addr, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Storage, SubMsg, WasmMsg,
+};
+
+use crate::error::ContractError;
+use crate::state::{
+    read_config, read_flash_loan, read_pause_info, read_state, remove_flash_loan, store_flash_loan,
+    store_state, Config, FlashLoan, State,
+};
+
+use moneymarket::pause::PAUSE_BORROW;
+use moneymarket::querier::{deduct_tax, query_balance};
+
+pub const FLASH_LOAN_REPLY_ID: u64 = 2;
+
+/// Lend stable to the callback contract for the duration of its execution.
+/// Repayment is checked in the reply of the callback
+pub fn flash_loan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint256,
+    callback_contract: Addr,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if read_pause_info(deps.storage)?.paused & PAUSE_BORROW != 0 {
+        return Err(ContractError::Paused {});
+    }
+
+    assert_no_flash_loan(deps.storage)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroFlashLoan(config.stable_denom));
+    }
+
+    let prev_balance: Uint256 = query_balance(
+        deps.as_ref(),
+        env.contract.address,
+        config.stable_denom.to_string(),
+    )?;
+    if prev_balance < amount {
+        return Err(ContractError::NoStableAvailable(config.stable_denom));
+    }
+
+    let fee = amount * config.flash_loan_fee;
+    store_flash_loan(
+        deps.storage,
+        &FlashLoan {
+            prev_balance,
+            amount,
+            fee,
+            repaid: Uint256::zero(),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: callback_contract.to_string(),
+                funds: vec![deduct_tax(
+                    deps.as_ref(),
+                    Coin {
+                        denom: config.stable_denom,
+                        amount: amount.into(),
+                    },
+                )?],
+                msg,
+            }),
+            FLASH_LOAN_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            attr("action", "flash_loan"),
+            attr("borrower", info.sender),
+            attr("callback_contract", callback_contract),
+            attr("amount", amount),
+            attr("fee", fee),
+        ]))
+}
+
+/// The callback has returned; the loan and its fee must be back in the
+/// market balance. The fee accrues to the reserves
+pub fn flash_loan_callback(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let flash_loan = match read_flash_loan(deps.storage)? {
+        Some(flash_loan) => flash_loan,
+        None => return Err(ContractError::InvalidReplyId {}),
+    };
+    remove_flash_loan(deps.storage);
+
+    let balance: Uint256 = query_balance(
+        deps.as_ref(),
+        env.contract.address,
+        config.stable_denom.to_string(),
+    )?;
+    let required_balance = flash_loan.prev_balance + flash_loan.fee + flash_loan.repaid;
+    if balance < required_balance {
+        return Err(ContractError::FlashLoanNotRepaid(
+            (required_balance - balance).into(),
+        ));
+    }
+
+    let mut state: State = read_state(deps.storage)?;
+    state.total_reserves += Decimal256::from_uint256(flash_loan.fee);
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "flash_loan_repaid"),
+        attr("amount", flash_loan.amount),
+        attr("fee", flash_loan.fee),
+    ]))
+}
+
+/// Loans repaid while a flash loan is out raise the market balance
+/// without returning the flash loan
+pub fn record_flash_loan_repay(
+    storage: &mut dyn Storage,
+    repay_amount: Uint256,
+) -> Result<(), ContractError> {
+    if let Some(mut flash_loan) = read_flash_loan(storage)? {
+        flash_loan.repaid += repay_amount;
+        store_flash_loan(storage, &flash_loan)?;
+    }
+
+    Ok(())
+}
+
+/// Market balance with an outstanding flash loan counted as returned,
+/// excluding `deposit_amount` sent along with the current message.
+/// While the loan is out, funds sent along are recorded as repaid
+/// only after the interest is computed
+pub fn query_market_balance(
+    deps: Deps,
+    config: &Config,
+    deposit_amount: Option<Uint256>,
+) -> StdResult<Uint256> {
+    if let Some(flash_loan) = read_flash_loan(deps.storage)? {
+        return Ok(flash_loan.prev_balance + flash_loan.repaid);
+    }
+
+    Ok(query_balance(
+        deps,
+        deps.api.addr_humanize(&config.contract_addr)?,
+        config.stable_denom.to_string(),
+    )? - deposit_amount.unwrap_or_else(Uint256::zero))
+}
+
+/// Operations pricing aTerra from, or paying out of, the market
+/// balance are not allowed while the loaned amount is out
+pub fn assert_no_flash_loan(storage: &dyn Storage) -> Result<(), ContractError> {
+    if read_flash_loan(storage)?.is_some() {
+        return Err(ContractError::FlashLoanInProgress {});
+    }
+
+    Ok(())
+}
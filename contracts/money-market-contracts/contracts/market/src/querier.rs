@@ -30,6 +30,7 @@ pub fn query_borrow_limit(
     overseer_addr: Addr,
     borrower: Addr,
     block_time: Option<u64>,
+    stable_denom: String,
 ) -> StdResult<BorrowLimitResponse> {
     let borrow_limit: BorrowLimitResponse =
         deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
@@ -37,6 +38,7 @@ pub fn query_borrow_limit(
             msg: to_binary(&OverseerQueryMsg::BorrowLimit {
                 borrower: borrower.to_string(),
                 block_time,
+                stable_denom: Some(stable_denom),
             })?,
         }))?;
 
@@ -3,14 +3,18 @@ use serde::{Deserialize, Serialize};
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{CanonicalAddr, Deps, Order, StdResult, Storage};
-use cosmwasm_storage::{bucket, bucket_read, ReadonlyBucket, ReadonlySingleton, Singleton};
+use cosmwasm_storage::{bucket, bucket_read, Bucket, ReadonlyBucket, ReadonlySingleton, Singleton};
 
 use moneymarket::market::BorrowerInfoResponse;
 
 pub const KEY_CONFIG: &[u8] = b"config";
 pub const KEY_STATE: &[u8] = b"state";
+pub const KEY_PAUSE_INFO: &[u8] = b"pause_info";
+pub const KEY_BAD_DEBT: &[u8] = b"bad_debt";
+pub const KEY_FLASH_LOAN: &[u8] = b"flash_loan";
 
 const PREFIX_LIABILITY: &[u8] = b"liability";
+const PREFIX_BORROW_ALLOWANCE: &[u8] = b"borrow_allowance";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -24,6 +28,18 @@ pub struct Config {
     pub distributor_contract: CanonicalAddr,
     pub stable_denom: String,
     pub max_borrow_factor: Decimal256,
+    pub borrow_cap: Option<Uint256>,
+    /// Missing from configs stored before flash loans were introduced
+    #[serde(default)]
+    pub flash_loan_fee: Decimal256,
+    /// Premium over the floating borrow rate charged on fixed-rate loans;
+    /// zero for configs stored before fixed-rate loans were introduced
+    #[serde(default)]
+    pub fixed_rate_premium: Decimal256,
+    /// Deviation from the offered fixed rate above which a fixed-rate loan
+    /// can be rebalanced; zero disables rebalancing
+    #[serde(default)]
+    pub fixed_rate_rebalance_threshold: Decimal256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -37,14 +53,34 @@ pub struct State {
     pub anc_emission_rate: Decimal256,
     pub prev_aterra_supply: Uint256,
     pub prev_exchange_rate: Decimal256,
+    /// Part of `total_liabilities` borrowed at fixed rates
+    #[serde(default)]
+    pub total_fixed_liabilities: Decimal256,
+    /// Interest accrued per block by fixed-rate loans; the sum of their
+    /// amounts as of their last update times their rates
+    #[serde(default)]
+    pub fixed_interest_per_block: Decimal256,
+    /// Blocks of interest accrued by fixed-rate loans. Each loan accrues
+    /// its rate times the growth of this index since its last update
+    #[serde(default)]
+    pub fixed_interest_index: Decimal256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BorrowerInfo {
     pub interest_index: Decimal256,
     pub reward_index: Decimal256,
+    /// Floating-rate loan
     pub loan_amount: Uint256,
     pub pending_rewards: Decimal256,
+    #[serde(default)]
+    pub fixed_loan_amount: Uint256,
+    /// Per block rate locked by the fixed-rate loan
+    #[serde(default)]
+    pub fixed_rate: Decimal256,
+    /// `State::fixed_interest_index` as of the last update of the loan
+    #[serde(default)]
+    pub fixed_interest_index: Decimal256,
 }
 
 pub fn store_config(storage: &mut dyn Storage, data: &Config) -> StdResult<()> {
@@ -63,6 +99,107 @@ pub fn read_state(storage: &dyn Storage) -> StdResult<State> {
     ReadonlySingleton::new(storage, KEY_STATE).load()
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseInfo {
+    pub guardian: Option<CanonicalAddr>,
+    pub paused: u8,
+}
+
+pub fn store_pause_info(storage: &mut dyn Storage, data: &PauseInfo) -> StdResult<()> {
+    Singleton::new(storage, KEY_PAUSE_INFO).save(data)
+}
+
+pub fn read_pause_info(storage: &dyn Storage) -> StdResult<PauseInfo> {
+    Ok(ReadonlySingleton::new(storage, KEY_PAUSE_INFO)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct BadDebt {
+    pub total_bad_debt: Uint256,
+    pub covered_by_reserves: Uint256,
+    pub covered_by_buffer: Uint256,
+    pub socialized: Uint256,
+    pub write_off_count: u64,
+}
+
+pub fn store_bad_debt(storage: &mut dyn Storage, data: &BadDebt) -> StdResult<()> {
+    Singleton::new(storage, KEY_BAD_DEBT).save(data)
+}
+
+pub fn read_bad_debt(storage: &dyn Storage) -> StdResult<BadDebt> {
+    Ok(ReadonlySingleton::new(storage, KEY_BAD_DEBT)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+/// Flash loan awaiting repayment in the reply of its callback
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FlashLoan {
+    pub prev_balance: Uint256,
+    pub amount: Uint256,
+    pub fee: Uint256,
+    /// Stable repaid to loans while the flash loan is out
+    pub repaid: Uint256,
+}
+
+pub fn store_flash_loan(storage: &mut dyn Storage, data: &FlashLoan) -> StdResult<()> {
+    Singleton::new(storage, KEY_FLASH_LOAN).save(data)
+}
+
+pub fn read_flash_loan(storage: &dyn Storage) -> StdResult<Option<FlashLoan>> {
+    ReadonlySingleton::new(storage, KEY_FLASH_LOAN).may_load()
+}
+
+pub fn remove_flash_loan(storage: &mut dyn Storage) {
+    Singleton::<FlashLoan>::new(storage, KEY_FLASH_LOAN).remove()
+}
+
+/// Amount a spender may borrow against the collaterals of a borrower
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct BorrowAllowance {
+    pub amount: Uint256,
+    pub expires_at: Option<u64>,
+}
+
+impl BorrowAllowance {
+    pub fn is_expired(&self, block_time: u64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= block_time)
+    }
+}
+
+pub fn store_borrow_allowance(
+    storage: &mut dyn Storage,
+    borrower: &CanonicalAddr,
+    spender: &CanonicalAddr,
+    allowance: &BorrowAllowance,
+) -> StdResult<()> {
+    Bucket::multilevel(storage, &[PREFIX_BORROW_ALLOWANCE, borrower.as_slice()])
+        .save(spender.as_slice(), allowance)
+}
+
+pub fn read_borrow_allowance(
+    storage: &dyn Storage,
+    borrower: &CanonicalAddr,
+    spender: &CanonicalAddr,
+) -> StdResult<BorrowAllowance> {
+    Ok(
+        ReadonlyBucket::multilevel(storage, &[PREFIX_BORROW_ALLOWANCE, borrower.as_slice()])
+            .may_load(spender.as_slice())?
+            .unwrap_or_default(),
+    )
+}
+
+pub fn remove_borrow_allowance(
+    storage: &mut dyn Storage,
+    borrower: &CanonicalAddr,
+    spender: &CanonicalAddr,
+) {
+    Bucket::<BorrowAllowance>::multilevel(storage, &[PREFIX_BORROW_ALLOWANCE, borrower.as_slice()])
+        .remove(spender.as_slice())
+}
+
 pub fn store_borrower_info(
     storage: &mut dyn Storage,
     borrower: &CanonicalAddr,
@@ -79,6 +216,9 @@ pub fn read_borrower_info(storage: &dyn Storage, borrower: &CanonicalAddr) -> Bo
             reward_index: Decimal256::zero(),
             loan_amount: Uint256::zero(),
             pending_rewards: Decimal256::zero(),
+            fixed_loan_amount: Uint256::zero(),
+            fixed_rate: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     }
 }
@@ -107,8 +247,12 @@ pub fn read_borrower_infos(
                 borrower,
                 interest_index: v.interest_index,
                 reward_index: v.reward_index,
-                loan_amount: v.loan_amount,
+                loan_amount: v.loan_amount + v.fixed_loan_amount,
                 pending_rewards: v.pending_rewards,
+                floating_loan_amount: v.loan_amount,
+                fixed_loan_amount: v.fixed_loan_amount,
+                fixed_rate: v.fixed_rate,
+                fixed_interest_index: v.fixed_interest_index,
             })
         })
         .collect()
@@ -8,7 +8,7 @@ use cosmwasm_std::{Api, Coin, Uint128};
 #[test]
 fn proper_compute_borrower_interest() {
     let env = mock_env();
-    let mock_state = State {
+    let mut mock_state = State {
         total_liabilities: Decimal256::from_uint256(1000000u128),
         total_reserves: Decimal256::from_uint256(0u128),
         last_interest_updated: env.block.height,
@@ -18,23 +18,32 @@ fn proper_compute_borrower_interest() {
         anc_emission_rate: Decimal256::one(),
         prev_aterra_supply: Uint256::zero(),
         prev_exchange_rate: Decimal256::one(),
+        total_fixed_liabilities: Decimal256::zero(),
+        fixed_interest_per_block: Decimal256::zero(),
+        fixed_interest_index: Decimal256::zero(),
     };
     let mut liability1 = BorrowerInfo {
         interest_index: Decimal256::one(),
         reward_index: Decimal256::zero(),
         loan_amount: Uint256::zero(),
         pending_rewards: Decimal256::zero(),
+        fixed_loan_amount: Uint256::zero(),
+        fixed_rate: Decimal256::zero(),
+        fixed_interest_index: Decimal256::zero(),
     };
-    compute_borrower_interest(&mock_state, &mut liability1);
+    compute_borrower_interest(&mut mock_state, &mut liability1);
     let liability2 = BorrowerInfo {
         interest_index: Decimal256::one(),
         reward_index: Decimal256::zero(),
         loan_amount: Uint256::zero(),
         pending_rewards: Decimal256::zero(),
+        fixed_loan_amount: Uint256::zero(),
+        fixed_rate: Decimal256::zero(),
+        fixed_interest_index: Decimal256::zero(),
     };
     assert_eq!(liability1, liability2);
 
-    let mock_state2 = State {
+    let mut mock_state2 = State {
         total_liabilities: Decimal256::from_uint256(300000u128),
         total_reserves: Decimal256::from_uint256(1000u128),
         last_interest_updated: env.block.height,
@@ -44,19 +53,28 @@ fn proper_compute_borrower_interest() {
         anc_emission_rate: Decimal256::zero(),
         prev_aterra_supply: Uint256::zero(),
         prev_exchange_rate: Decimal256::one(),
+        total_fixed_liabilities: Decimal256::zero(),
+        fixed_interest_per_block: Decimal256::zero(),
+        fixed_interest_index: Decimal256::zero(),
     };
     let mut liability3 = BorrowerInfo {
         interest_index: Decimal256::from_uint256(4u128),
         reward_index: Decimal256::zero(),
         loan_amount: Uint256::from(80u128),
         pending_rewards: Decimal256::zero(),
+        fixed_loan_amount: Uint256::zero(),
+        fixed_rate: Decimal256::zero(),
+        fixed_interest_index: Decimal256::zero(),
     };
-    compute_borrower_interest(&mock_state2, &mut liability3);
+    compute_borrower_interest(&mut mock_state2, &mut liability3);
     let liability4 = BorrowerInfo {
         interest_index: Decimal256::from_uint256(2u128),
         reward_index: Decimal256::zero(),
         loan_amount: Uint256::from(40u128),
         pending_rewards: Decimal256::zero(),
+        fixed_loan_amount: Uint256::zero(),
+        fixed_rate: Decimal256::zero(),
+        fixed_interest_index: Decimal256::zero(),
     };
     assert_eq!(liability3, liability4);
 }
@@ -86,6 +104,10 @@ fn proper_compute_interest() {
         overseer_contract: deps.api.addr_canonicalize("overseer").unwrap(),
         stable_denom: "uusd".to_string(),
         max_borrow_factor: Decimal256::one(),
+        borrow_cap: None,
+        flash_loan_fee: Decimal256::zero(),
+        fixed_rate_premium: Decimal256::zero(),
+        fixed_rate_rebalance_threshold: Decimal256::zero(),
     };
 
     deps.querier
@@ -101,6 +123,9 @@ fn proper_compute_interest() {
         anc_emission_rate: Decimal256::one(),
         prev_aterra_supply: Uint256::zero(),
         prev_exchange_rate: Decimal256::one(),
+        total_fixed_liabilities: Decimal256::zero(),
+        fixed_interest_per_block: Decimal256::zero(),
+        fixed_interest_index: Decimal256::zero(),
     };
     store_state(&mut deps.storage, &mock_state).unwrap();
 
@@ -126,6 +151,9 @@ fn proper_compute_interest() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         }
     );
 
@@ -151,6 +179,9 @@ fn proper_compute_interest() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::from(2000000u64),
             prev_exchange_rate: Decimal256::from_ratio(19995, 10000),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::from_uint256(100u64),
         }
     );
 
@@ -165,6 +196,9 @@ fn proper_compute_interest() {
         anc_emission_rate: Decimal256::one(),
         prev_aterra_supply: Uint256::from(2000000u128),
         prev_exchange_rate: Decimal256::one(),
+        total_fixed_liabilities: Decimal256::zero(),
+        fixed_interest_per_block: Decimal256::zero(),
+        fixed_interest_index: Decimal256::zero(),
     };
     store_state(&mut deps.storage, &mock_state).unwrap();
 
@@ -200,6 +234,9 @@ fn proper_compute_interest() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::from(2000000u64),
             prev_exchange_rate: Decimal256::from_uint256(2u64),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         }
     );
 }
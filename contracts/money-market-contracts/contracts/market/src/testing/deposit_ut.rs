@@ -24,6 +24,10 @@ fn proper_compute_exchange_rate() {
         overseer_contract: deps.api.addr_canonicalize("overseer").unwrap(),
         stable_denom: "uusd".to_string(),
         max_borrow_factor: Decimal256::one(),
+        borrow_cap: None,
+        flash_loan_fee: Decimal256::zero(),
+        fixed_rate_premium: Decimal256::zero(),
+        fixed_rate_rebalance_threshold: Decimal256::zero(),
     };
     deps.querier.with_token_balances(&[(
         &"AT-uusd".to_string(),
@@ -40,6 +44,9 @@ fn proper_compute_exchange_rate() {
         anc_emission_rate: Decimal256::one(),
         prev_aterra_supply: Uint256::zero(),
         prev_exchange_rate: Decimal256::one(),
+        total_fixed_liabilities: Decimal256::zero(),
+        fixed_interest_per_block: Decimal256::zero(),
+        fixed_interest_index: Decimal256::zero(),
     };
     let mock_deposit_amount = Some(Uint256::from(1000000u128));
 
@@ -267,6 +267,7 @@ impl WasmMockQuerier {
                             stable_denom: "uusd".to_string(),
                             epoch_period: 100u64,
                             price_timeframe: 100u64,
+                            close_factor: Decimal256::one(),
                         })))
                     }
                     QueryMsg::TokenInfo {} => {
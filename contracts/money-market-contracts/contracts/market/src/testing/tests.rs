@@ -12,10 +12,12 @@ use cosmwasm_std::{
     SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
 };
 use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
+use moneymarket::interest_model::ExecuteMsg as InterestExecuteMsg;
 use moneymarket::market::{
-    BorrowerInfoResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
-    StateResponse,
+    BadDebtResponse, BorrowAllowanceResponse, BorrowerInfoResponse, ConfigResponse, Cw20HookMsg,
+    ExecuteMsg, InstantiateMsg, QueryMsg, StateResponse,
 };
+use moneymarket::pause::{PauseInfoResponse, PAUSE_BORROW, PAUSE_DEPOSIT};
 use moneymarket::querier::deduct_tax;
 use protobuf::Message;
 use std::str::FromStr;
@@ -196,6 +198,10 @@ fn update_config() {
         interest_model: None,
         distribution_model: None,
         max_borrow_factor: None,
+        borrow_cap: None,
+        flash_loan_fee: None,
+        fixed_rate_premium: None,
+        fixed_rate_rebalance_threshold: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -213,6 +219,10 @@ fn update_config() {
         interest_model: Some("interest2".to_string()),
         distribution_model: Some("distribution2".to_string()),
         max_borrow_factor: Some(Decimal256::percent(100)),
+        borrow_cap: None,
+        flash_loan_fee: Some(Decimal256::permille(9)),
+        fixed_rate_premium: None,
+        fixed_rate_rebalance_threshold: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -225,6 +235,7 @@ fn update_config() {
     assert_eq!("interest2".to_string(), config_res.interest_model);
     assert_eq!("distribution2".to_string(), config_res.distribution_model);
     assert_eq!(Decimal256::percent(100), config_res.max_borrow_factor);
+    assert_eq!(Decimal256::permille(9), config_res.flash_loan_fee);
 
     // Unauthorized err
     let info = mock_info("owner", &[]);
@@ -233,6 +244,10 @@ fn update_config() {
         interest_model: None,
         distribution_model: None,
         max_borrow_factor: None,
+        borrow_cap: None,
+        flash_loan_fee: None,
+        fixed_rate_premium: None,
+        fixed_rate_rebalance_threshold: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -521,6 +536,9 @@ fn deposit_stable() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::from(1000000u64),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         }
     );
 
@@ -560,6 +578,9 @@ fn deposit_stable() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::from_ratio(1u64, 2u64),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     )
     .unwrap();
@@ -610,6 +631,9 @@ fn deposit_stable() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::from_ratio(1u64, 2u64),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     )
     .unwrap();
@@ -637,6 +661,9 @@ fn deposit_stable() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::from(INITIAL_DEPOSIT_AMOUNT + 1818181),
             prev_exchange_rate: Decimal256::from_ratio(55u64, 100u64),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         }
     );
 }
@@ -774,6 +801,9 @@ fn redeem_stable() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::from(2000000u64),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     )
     .unwrap();
@@ -900,6 +930,9 @@ fn borrow_stable() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     )
     .unwrap();
@@ -907,6 +940,7 @@ fn borrow_stable() {
     let msg = ExecuteMsg::BorrowStable {
         borrow_amount: Uint256::from(500000u64),
         to: None,
+        borrower: None,
     };
 
     env.block.height += 100;
@@ -965,6 +999,9 @@ fn borrow_stable() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::from_uint256(100u64),
         }
     );
 
@@ -991,6 +1028,9 @@ fn borrow_stable() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::from_uint256(101u64),
         }
     );
 
@@ -1013,6 +1053,10 @@ fn borrow_stable() {
             reward_index: Decimal256::from_str("0.0001").unwrap(),
             loan_amount: Uint256::from(500000u64),
             pending_rewards: Decimal256::zero(),
+            floating_loan_amount: Uint256::from(500000u64),
+            fixed_loan_amount: Uint256::zero(),
+            fixed_rate: Decimal256::zero(),
+            fixed_interest_index: Decimal256::from_uint256(100u64),
         }
     );
 
@@ -1035,6 +1079,10 @@ fn borrow_stable() {
             reward_index: Decimal256::from_str("0.0001").unwrap(),
             loan_amount: Uint256::from(500000u64),
             pending_rewards: Decimal256::zero(),
+            floating_loan_amount: Uint256::from(500000u64),
+            fixed_loan_amount: Uint256::zero(),
+            fixed_rate: Decimal256::zero(),
+            fixed_interest_index: Decimal256::from_uint256(100u64),
         }
     );
 
@@ -1059,6 +1107,10 @@ fn borrow_stable() {
             reward_index: Decimal256::from_str("0.00018").unwrap(),
             loan_amount: Uint256::from(1000000u64),
             pending_rewards: Decimal256::from_uint256(20u64),
+            floating_loan_amount: Uint256::from(1000000u64),
+            fixed_loan_amount: Uint256::zero(),
+            fixed_rate: Decimal256::zero(),
+            fixed_interest_index: Decimal256::from_uint256(200u64),
         }
     );
 
@@ -1066,6 +1118,7 @@ fn borrow_stable() {
     let msg = ExecuteMsg::BorrowStable {
         borrow_amount: Uint256::from(500001u64),
         to: None,
+        borrower: None,
     };
     let res = execute(deps.as_mut(), env, info, msg);
     match res {
@@ -1144,6 +1197,9 @@ fn assert_max_borrow_factor() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     )
     .unwrap();
@@ -1151,6 +1207,7 @@ fn assert_max_borrow_factor() {
     let msg = ExecuteMsg::BorrowStable {
         borrow_amount: Uint256::from(10000u64),
         to: None,
+        borrower: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -1175,6 +1232,7 @@ fn assert_max_borrow_factor() {
     let msg = ExecuteMsg::BorrowStable {
         borrow_amount: Uint256::from(1u64),
         to: None,
+        borrower: None,
     };
     let res = execute(deps.as_mut(), mock_env(), info, msg);
     let _uusd_string = "uusd";
@@ -1185,7 +1243,7 @@ fn assert_max_borrow_factor() {
 }
 
 #[test]
-fn repay_stable() {
+fn borrow_cap() {
     let mut deps = mock_dependencies(&[Coin {
         denom: "uusd".to_string(),
         amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
@@ -1210,8 +1268,6 @@ fn repay_stable() {
             amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
         }],
     );
-
-    // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // Register anchor token contract
@@ -1234,141 +1290,90 @@ fn repay_stable() {
         collector_contract: "collector".to_string(),
         distributor_contract: "distributor".to_string(),
     };
-    let mut env = mock_env();
-    let mut info = mock_info("addr0000", &[]);
-    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
     deps.querier
         .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
     deps.querier
         .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
 
-    store_state(
-        deps.as_mut().storage,
-        &State {
-            total_liabilities: Decimal256::from_uint256(1000000u128),
-            total_reserves: Decimal256::zero(),
-            last_interest_updated: env.block.height,
-            last_reward_updated: env.block.height,
-            global_interest_index: Decimal256::one(),
-            global_reward_index: Decimal256::zero(),
-            anc_emission_rate: Decimal256::one(),
-            prev_aterra_supply: Uint256::zero(),
-            prev_exchange_rate: Decimal256::one(),
-        },
+    let msg = ExecuteMsg::UpdateConfig {
+        owner_addr: None,
+        interest_model: None,
+        distribution_model: None,
+        max_borrow_factor: None,
+        borrow_cap: Some(Uint256::from(15000u64)),
+        flash_loan_fee: None,
+        fixed_rate_premium: None,
+        fixed_rate_rebalance_threshold: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::State { block_height: None },
     )
     .unwrap();
+    let state: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(state.borrow_cap, Some(Uint256::from(15000u64)));
 
     let msg = ExecuteMsg::BorrowStable {
-        borrow_amount: Uint256::from(500000u64),
+        borrow_amount: Uint256::from(10000u64),
         to: None,
+        borrower: None,
     };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
 
-    env.block.height += 100;
-    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-
-    let msg = ExecuteMsg::RepayStable {};
-    info.funds = vec![Coin {
-        denom: "ukrw".to_string(),
-        amount: Uint128::from(100000u128),
-    }];
-
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
-    let _uusd_string = "uusd";
-    match res {
-        Err(ContractError::ZeroRepay(_uusd_string)) => (),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
-    info.funds = vec![Coin {
-        denom: "uusd".to_string(),
-        amount: Uint128::zero(),
-    }];
-
-    let res2 = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
-    match res2 {
-        Err(ContractError::ZeroRepay(_uusd_string)) => (),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
+    // subtract borrow amount
     deps.querier.update_balance(
-        MOCK_CONTRACT_ADDR.to_string(),
+        MOCK_CONTRACT_ADDR,
         vec![Coin {
             denom: "uusd".to_string(),
-            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 100000u128),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT - 10000u128),
         }],
     );
 
-    info.funds = vec![Coin {
-        denom: "uusd".to_string(),
-        amount: Uint128::from(100000u128),
-    }];
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
-    assert_eq!(
-        res.attributes,
-        vec![
-            attr("action", "repay_stable"),
-            attr("borrower", "addr0000"),
-            attr("repay_amount", "100000"),
-        ]
-    );
-
-    //Loan amount and Total liability have decreased according to the repayment
-    let res_loan = read_borrower_infos(deps.as_ref(), None, None)
-        .unwrap()
-        .get(0)
-        .unwrap()
-        .loan_amount;
-    assert_eq!(res_loan, Uint256::from(400000u128));
-    assert_eq!(
-        read_state(deps.as_ref().storage).unwrap().total_liabilities,
-        Decimal256::from_uint256(2400000u128)
-    );
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone());
+    match res {
+        Err(ContractError::BorrowExceedsCap(15000u128)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 
-    info.funds = vec![Coin {
-        denom: "uusd".to_string(),
-        amount: Uint128::from(500000u128),
-    }];
-    let res = execute(deps.as_mut(), env, info, msg).unwrap();
-    assert_eq!(
-        res.attributes,
-        vec![
-            attr("action", "repay_stable"),
-            attr("borrower", "addr0000"),
-            attr("repay_amount", "400000"),
-        ]
-    );
+    // zero cap removes the limit
+    let update_msg = ExecuteMsg::UpdateConfig {
+        owner_addr: None,
+        interest_model: None,
+        distribution_model: None,
+        max_borrow_factor: None,
+        borrow_cap: Some(Uint256::zero()),
+        flash_loan_fee: None,
+        fixed_rate_premium: None,
+        fixed_rate_rebalance_threshold: None,
+    };
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_msg,
+    )
+    .unwrap();
 
-    //Loan amount and Total liability have decreased according to the repayment
-    let res_loan = read_borrower_infos(deps.as_ref(), None, None)
-        .unwrap()
-        .get(0)
-        .unwrap()
-        .loan_amount;
-    assert_eq!(res_loan, Uint256::zero());
-    assert_eq!(
-        read_state(deps.as_ref().storage).unwrap().total_liabilities,
-        Decimal256::from_uint256(2000000u128)
-    );
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::State { block_height: None },
+    )
+    .unwrap();
+    let state: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(state.borrow_cap, None);
 
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-            to_address: "addr0000".to_string(),
-            amount: vec![deduct_tax(
-                deps.as_ref(),
-                Coin {
-                    denom: "uusd".to_string(),
-                    amount: Uint128::from(100000u128),
-                }
-            )
-            .unwrap()]
-        }))]
-    );
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 }
 
 #[test]
-fn repay_stable_from_liquidation() {
+fn borrow_stable_fixed() {
     let mut deps = mock_dependencies(&[Coin {
         denom: "uusd".to_string(),
         amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
@@ -1393,8 +1398,6 @@ fn repay_stable_from_liquidation() {
             amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
         }],
     );
-
-    // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // Register anchor token contract
@@ -1417,7 +1420,7 @@ fn repay_stable_from_liquidation() {
         collector_contract: "collector".to_string(),
         distributor_contract: "distributor".to_string(),
     };
-    let mut env = mock_env();
+    let env = mock_env();
     let info = mock_info("addr0000", &[]);
     let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
@@ -1426,6 +1429,18 @@ fn repay_stable_from_liquidation() {
     deps.querier
         .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
 
+    let msg = ExecuteMsg::UpdateConfig {
+        owner_addr: None,
+        interest_model: None,
+        distribution_model: None,
+        max_borrow_factor: None,
+        borrow_cap: None,
+        flash_loan_fee: None,
+        fixed_rate_premium: Some(Decimal256::permille(5)),
+        fixed_rate_rebalance_threshold: Some(Decimal256::permille(2)),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+
     store_state(
         deps.as_mut().storage,
         &State {
@@ -1438,108 +1453,199 @@ fn repay_stable_from_liquidation() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     )
     .unwrap();
 
-    let msg = ExecuteMsg::BorrowStable {
-        borrow_amount: Uint256::from(500000u64),
+    // offered fixed rate is 1% + 0.5%
+    let msg = ExecuteMsg::BorrowStableFixed {
+        borrow_amount: Uint256::from(200000u64),
+        max_rate: Some(Decimal256::percent(1)),
         to: None,
+        borrower: None,
     };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    match res {
+        Err(ContractError::FixedRateTooHigh(_)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 
-    env.block.height += 100;
+    let msg = ExecuteMsg::BorrowStableFixed {
+        borrow_amount: Uint256::from(200000u64),
+        max_rate: None,
+        to: None,
+        borrower: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "borrow_stable"),
+            attr("borrower", "addr0000"),
+            attr("borrow_amount", "200000"),
+            attr("fixed_rate", "0.015"),
+        ]
+    );
+
+    let msg = ExecuteMsg::BorrowStable {
+        borrow_amount: Uint256::from(100000u64),
+        to: None,
+        borrower: None,
+    };
     let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-    // update balance to make repay
-    deps.querier.update_balance(
-        MOCK_CONTRACT_ADDR.to_string(),
-        vec![
-            Coin {
-                denom: "ukrw".to_string(),
-                amount: Uint128::from(100000u128),
-            },
-            Coin {
-                denom: "uusd".to_string(),
-                amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
-            },
-        ],
+    let state = read_state(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        state.total_liabilities,
+        Decimal256::from_uint256(1300000u64)
+    );
+    assert_eq!(
+        state.total_fixed_liabilities,
+        Decimal256::from_uint256(200000u64)
+    );
+    assert_eq!(
+        state.fixed_interest_per_block,
+        Decimal256::from_uint256(3000u64)
     );
 
-    let msg = ExecuteMsg::RepayStableFromLiquidation {
+    // after 100 blocks, the floating loan doubles and
+    // the fixed-rate loan grows by 100 blocks of 1.5%
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BorrowerInfo {
+            borrower: "addr0000".to_string(),
+            block_height: Some(env.block.height + 100),
+        },
+    )
+    .unwrap();
+    let borrower_info: BorrowerInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(borrower_info.loan_amount, Uint256::from(700000u64));
+    assert_eq!(borrower_info.floating_loan_amount, Uint256::from(200000u64));
+    assert_eq!(borrower_info.fixed_loan_amount, Uint256::from(500000u64));
+    assert_eq!(borrower_info.fixed_rate, Decimal256::permille(15));
+    assert_eq!(
+        borrower_info.fixed_interest_index,
+        Decimal256::from_uint256(100u64)
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::State {
+            block_height: Some(env.block.height + 100),
+        },
+    )
+    .unwrap();
+    let state: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        state.total_liabilities,
+        Decimal256::from_uint256(2700000u64)
+    );
+    assert_eq!(
+        state.total_fixed_liabilities,
+        Decimal256::from_uint256(500000u64)
+    );
+    assert_eq!(
+        state.fixed_interest_per_block,
+        Decimal256::from_uint256(3000u64)
+    );
+    assert_eq!(state.fixed_interest_index, Decimal256::from_uint256(100u64));
+
+    // the offered rate has not moved
+    let msg = ExecuteMsg::RebalanceFixedRate {
         borrower: "addr0000".to_string(),
-        prev_balance: Uint256::from(INITIAL_DEPOSIT_AMOUNT),
     };
-
-    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
     match res {
-        Err(ContractError::Unauthorized {}) => (),
+        Err(ContractError::FixedRateNotRebalanceable {}) => (),
         _ => panic!("DO NOT ENTER HERE"),
     }
 
-    let info = mock_info("overseer", &[]);
-
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
-    let _uusd_string = "uusd";
+    // a third party can not raise the rate
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(2))]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
     match res {
-        Err(ContractError::ZeroRepay(__uusd_string)) => (),
+        Err(ContractError::Unauthorized {}) => (),
         _ => panic!("DO NOT ENTER HERE"),
     }
 
-    // update balance to make repay
-    deps.querier.update_balance(
-        MOCK_CONTRACT_ADDR.to_string(),
-        vec![Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 100000u128),
-        }],
-    );
-
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
     assert_eq!(
         res.attributes,
         vec![
-            attr("action", "repay_stable"),
+            attr("action", "rebalance_fixed_rate"),
             attr("borrower", "addr0000"),
-            attr("repay_amount", "100000"),
+            attr("prev_rate", "0.015"),
+            attr("fixed_rate", "0.025"),
         ]
     );
 
-    deps.querier.update_balance(
-        MOCK_CONTRACT_ADDR.to_string(),
-        vec![Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 500000u128),
-        }],
+    let state = read_state(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        state.fixed_interest_per_block,
+        Decimal256::from_uint256(5000u64)
     );
 
-    let res = execute(deps.as_mut(), env, info, msg).unwrap();
-    assert_eq!(
-        res.attributes,
-        vec![
-            attr("action", "repay_stable"),
-            attr("borrower", "addr0000"),
-            attr("repay_amount", "400000"),
-        ]
+    // the floating-rate loan is repaid first
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(250000u128),
+        }],
     );
+    let _res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::RepayStable {}).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BorrowerInfo {
+            borrower: "addr0000".to_string(),
+            block_height: None,
+        },
+    )
+    .unwrap();
+    let borrower_info: BorrowerInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(borrower_info.floating_loan_amount, Uint256::zero());
+    assert_eq!(borrower_info.fixed_loan_amount, Uint256::from(50000u64));
 
+    let state = read_state(deps.as_ref().storage).unwrap();
     assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-            to_address: "addr0000".to_string(),
-            amount: vec![deduct_tax(
-                deps.as_ref(),
-                Coin {
-                    denom: "uusd".to_string(),
-                    amount: Uint128::from(100000u128),
-                }
-            )
-            .unwrap()]
-        }))]
+        state.total_liabilities,
+        Decimal256::from_uint256(1050000u64)
+    );
+    assert_eq!(
+        state.total_fixed_liabilities,
+        Decimal256::from_uint256(50000u64)
     );
+
+    let msg = ExecuteMsg::RebalanceFixedRate {
+        borrower: "addr0001".to_string(),
+    };
+    let res = execute(deps.as_mut(), env, mock_info("addr0001", &[]), msg);
+    match res {
+        Err(ContractError::NoFixedRateLoan {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 }
 
 #[test]
-fn claim_rewards() {
+fn rebalance_fixed_rate_down() {
     let mut deps = mock_dependencies(&[Coin {
         denom: "uusd".to_string(),
         amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
@@ -1564,8 +1670,6 @@ fn claim_rewards() {
             amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
         }],
     );
-
-    // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // Register anchor token contract
@@ -1588,8 +1692,8 @@ fn claim_rewards() {
         collector_contract: "collector".to_string(),
         distributor_contract: "distributor".to_string(),
     };
+    let env = mock_env();
     let info = mock_info("addr0000", &[]);
-    let mut env = mock_env();
     let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
     deps.querier
@@ -1597,6 +1701,18 @@ fn claim_rewards() {
     deps.querier
         .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
 
+    let msg = ExecuteMsg::UpdateConfig {
+        owner_addr: None,
+        interest_model: None,
+        distribution_model: None,
+        max_borrow_factor: None,
+        borrow_cap: None,
+        flash_loan_fee: None,
+        fixed_rate_premium: Some(Decimal256::permille(5)),
+        fixed_rate_rebalance_threshold: Some(Decimal256::permille(2)),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+
     store_state(
         deps.as_mut().storage,
         &State {
@@ -1609,68 +1725,48 @@ fn claim_rewards() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     )
     .unwrap();
 
-    // zero loan claim, will return empty messages
-    let msg = ExecuteMsg::ClaimRewards { to: None };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-    assert_eq!(res.messages.len(), 0);
-
-    let msg = ExecuteMsg::BorrowStable {
-        borrow_amount: Uint256::from(500000u64),
+    // offered fixed rate is 1% + 0.5%
+    let msg = ExecuteMsg::BorrowStableFixed {
+        borrow_amount: Uint256::from(200000u64),
+        max_rate: None,
         to: None,
+        borrower: None,
     };
-    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-    // zero block passed
-    let msg = ExecuteMsg::ClaimRewards {
-        to: Some("addr0001".to_string()),
+    // anyone can lower the rate to the offered rate
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::permille(5))]);
+    let msg = ExecuteMsg::RebalanceFixedRate {
+        borrower: "addr0000".to_string(),
     };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
-    assert_eq!(res.messages.len(), 0);
-
-    // 100 blocks passed
-    env.block.height += 100;
-    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let res = execute(deps.as_mut(), env, mock_info("addr0001", &[]), msg).unwrap();
     assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "distributor".to_string(),
-            funds: vec![],
-            msg: to_binary(&FaucetExecuteMsg::Spend {
-                recipient: "addr0001".to_string(),
-                amount: Uint128::from(33u128),
-            })
-            .unwrap(),
-        }))]
+        res.attributes,
+        vec![
+            attr("action", "rebalance_fixed_rate"),
+            attr("borrower", "addr0000"),
+            attr("prev_rate", "0.015"),
+            attr("fixed_rate", "0.01"),
+        ]
     );
 
-    let res: BorrowerInfoResponse = from_binary(
-        &query(
-            deps.as_ref(),
-            mock_env(),
-            QueryMsg::BorrowerInfo {
-                borrower: "addr0000".to_string(),
-                block_height: None,
-            },
-        )
-        .unwrap(),
-    )
-    .unwrap();
-    assert_eq!(
-        res.pending_rewards,
-        Decimal256::from_str("0.333333333333").unwrap()
-    );
+    let state = read_state(deps.as_ref().storage).unwrap();
     assert_eq!(
-        res.reward_index,
-        Decimal256::from_str("0.000066666666666666").unwrap()
+        state.fixed_interest_per_block,
+        Decimal256::from_uint256(2000u64)
     );
 }
 
 #[test]
-fn execute_epoch_operations() {
+fn rebalance_fixed_rate_during_flash_loan() {
     let mut deps = mock_dependencies(&[Coin {
         denom: "uusd".to_string(),
         amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
@@ -1695,8 +1791,6 @@ fn execute_epoch_operations() {
             amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
         }],
     );
-
-    // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // Register anchor token contract
@@ -1719,8 +1813,8 @@ fn execute_epoch_operations() {
         collector_contract: "collector".to_string(),
         distributor_contract: "distributor".to_string(),
     };
-    let mut env = mock_env();
-    let mut info = mock_info("addr0000", &[]);
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
     let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
     deps.querier
@@ -1728,11 +1822,23 @@ fn execute_epoch_operations() {
     deps.querier
         .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
 
+    let msg = ExecuteMsg::UpdateConfig {
+        owner_addr: None,
+        interest_model: None,
+        distribution_model: None,
+        max_borrow_factor: None,
+        borrow_cap: None,
+        flash_loan_fee: None,
+        fixed_rate_premium: Some(Decimal256::permille(5)),
+        fixed_rate_rebalance_threshold: Some(Decimal256::permille(2)),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+
     store_state(
         deps.as_mut().storage,
         &State {
             total_liabilities: Decimal256::from_uint256(1000000u128),
-            total_reserves: Decimal256::from_uint256(3000u128),
+            total_reserves: Decimal256::zero(),
             last_interest_updated: env.block.height,
             last_reward_updated: env.block.height,
             global_interest_index: Decimal256::one(),
@@ -1740,201 +1846,1985 @@ fn execute_epoch_operations() {
             anc_emission_rate: Decimal256::one(),
             prev_aterra_supply: Uint256::zero(),
             prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
         },
     )
     .unwrap();
 
-    env.block.height += 100;
+    // offered fixed rate is 1% + 0.5%
+    let msg = ExecuteMsg::BorrowStableFixed {
+        borrow_amount: Uint256::from(200000u64),
+        max_rate: None,
+        to: None,
+        borrower: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-    // reserve == 3000
-    let msg = ExecuteMsg::ExecuteEpochOperations {
-        deposit_rate: Decimal256::one(),
-        target_deposit_rate: Decimal256::one(),
-        threshold_deposit_rate: Decimal256::one(),
-        distributed_interest: Uint256::zero(),
+    // the loaned amount would raise the offered rate
+    let msg = ExecuteMsg::FlashLoan {
+        amount: Uint256::from(500000u64),
+        callback_contract: "bot".to_string(),
+        msg: to_binary(&"rebalance").unwrap(),
     };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("bot", &[]), msg).unwrap();
 
-    // only overseer can execute this
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(2))]);
+    let msg = ExecuteMsg::RebalanceFixedRate {
+        borrower: "addr0000".to_string(),
+    };
+    let res = execute(deps.as_mut(), env, mock_info("owner", &[]), msg);
     match res {
-        Err(ContractError::Unauthorized {}) => (),
+        Err(ContractError::FlashLoanInProgress {}) => (),
         _ => panic!("DO NOT ENTER HERE"),
     }
+}
 
-    info.sender = Addr::unchecked("overseer");
-    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-            to_address: "collector".to_string(),
-            amount: vec![Coin {
-                denom: "uusd".to_string(),
-                amount: Uint128::from(2970u128), // 1% tax
-            }],
-        }))]
-    );
-
-    let state = read_state(deps.as_ref().storage).unwrap();
-    assert_eq!(
-        state,
-        State {
-            total_liabilities: Decimal256::from_uint256(2000000u128),
-            total_reserves: Decimal256::zero(),
-            last_interest_updated: env.block.height,
-            last_reward_updated: env.block.height,
-            global_interest_index: Decimal256::from_uint256(2u64),
-            global_reward_index: Decimal256::from_str("0.0001").unwrap(),
-            anc_emission_rate: Decimal256::from_uint256(5u64),
-            prev_aterra_supply: Uint256::zero(),
-            prev_exchange_rate: Decimal256::one(),
-        }
-    );
+#[test]
+fn fixed_liabilities_match_loans() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
 
-    // When there is not enough balance to cover reserve
-    // no message will be sent and reserve will be left as same
-    deps.querier.update_balance(
-        MOCK_CONTRACT_ADDR.to_string(),
-        vec![Coin {
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
             denom: "uusd".to_string(),
-            amount: Uint128::from(2999u128),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
         }],
     );
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Register anchor token contract
+    let mut token_inst_res = MsgInstantiateContractResponse::new();
+    token_inst_res.set_contract_address("AT-uusd".to_string());
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(token_inst_res.write_to_bytes().unwrap().into()),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
 
+    // Register overseer contract
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
     let mut env = mock_env();
-    let info = mock_info("overseer", &[]);
-    store_state(
-        deps.as_mut().storage,
-        &State {
-            total_liabilities: Decimal256::from_uint256(1000000u128),
-            total_reserves: Decimal256::from_uint256(3000u128),
-            last_interest_updated: env.block.height,
-            last_reward_updated: env.block.height,
-            global_interest_index: Decimal256::one(),
-            global_reward_index: Decimal256::zero(),
-            anc_emission_rate: Decimal256::one(),
-            prev_aterra_supply: Uint256::zero(),
-            prev_exchange_rate: Decimal256::one(),
-        },
-    )
-    .unwrap();
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
 
-    env.block.height += 100;
+    deps.querier.with_borrow_rate(&[(
+        &"interest".to_string(),
+        &Decimal256::from_str("0.000123").unwrap(),
+    )]);
+    deps.querier.with_borrow_limit(&[
+        (&"addr0000".to_string(), &Uint256::from(1000000u64)),
+        (&"addr0001".to_string(), &Uint256::from(1000000u64)),
+    ]);
 
-    // reserve == 3000
-    let msg = ExecuteMsg::ExecuteEpochOperations {
-        deposit_rate: Decimal256::one(),
-        target_deposit_rate: Decimal256::one(),
-        threshold_deposit_rate: Decimal256::one(),
-        distributed_interest: Uint256::zero(),
+    let msg = ExecuteMsg::UpdateConfig {
+        owner_addr: None,
+        interest_model: None,
+        distribution_model: None,
+        max_borrow_factor: None,
+        borrow_cap: None,
+        flash_loan_fee: None,
+        fixed_rate_premium: Some(Decimal256::from_str("0.0005").unwrap()),
+        fixed_rate_rebalance_threshold: Some(Decimal256::permille(2)),
     };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
 
-    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-    assert_eq!(res.messages.len(), 0);
+    let msg = ExecuteMsg::BorrowStableFixed {
+        borrow_amount: Uint256::from(200000u64),
+        max_rate: None,
+        to: None,
+        borrower: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
 
-    let state = read_state(deps.as_ref().storage).unwrap();
-    assert_eq!(
-        state,
-        State {
-            total_liabilities: Decimal256::from_uint256(2000000u128),
-            total_reserves: Decimal256::from_uint256(3000u128),
-            last_interest_updated: env.block.height,
-            last_reward_updated: env.block.height,
-            global_interest_index: Decimal256::from_uint256(2u64),
-            global_reward_index: Decimal256::from_str("0.0001").unwrap(),
-            anc_emission_rate: Decimal256::from_uint256(5u64),
-            prev_aterra_supply: Uint256::zero(),
-            prev_exchange_rate: Decimal256::one(),
-        }
-    );
-}
+    env.block.height += 37;
+    let msg = ExecuteMsg::BorrowStableFixed {
+        borrow_amount: Uint256::from(300001u64),
+        max_rate: None,
+        to: None,
+        borrower: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0001", &[]), msg).unwrap();
+
+    // the new loan is blended with the accrued one
+    env.block.height += 13;
+    deps.querier.with_borrow_rate(&[(
+        &"interest".to_string(),
+        &Decimal256::from_str("0.000456").unwrap(),
+    )]);
+    let msg = ExecuteMsg::BorrowStableFixed {
+        borrow_amount: Uint256::from(100003u64),
+        max_rate: None,
+        to: None,
+        borrower: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    env.block.height += 41;
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(70007u128),
+        }],
+    );
+    let _res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::RepayStable {}).unwrap();
+
+    env.block.height += 29;
+    deps.querier.with_borrow_rate(&[(
+        &"interest".to_string(),
+        &Decimal256::from_str("0.003").unwrap(),
+    )]);
+    let msg = ExecuteMsg::RebalanceFixedRate {
+        borrower: "addr0001".to_string(),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+
+    // update both loans at the same block
+    env.block.height += 53;
+    for borrower in ["addr0000", "addr0001"] {
+        let info = mock_info(
+            borrower,
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1u128),
+            }],
+        );
+        let _res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::RepayStable {}).unwrap();
+    }
+
+    let state = read_state(deps.as_ref().storage).unwrap();
+    let borrower_infos = read_borrower_infos(deps.as_ref(), None, None).unwrap();
+    assert_eq!(borrower_infos.len(), 2);
+
+    let mut fixed_loan_amount = Decimal256::zero();
+    let mut fixed_interest_per_block = Decimal256::zero();
+    for borrower_info in borrower_infos.iter() {
+        let amount = Decimal256::from_uint256(borrower_info.fixed_loan_amount);
+        fixed_loan_amount += amount;
+        fixed_interest_per_block += amount * borrower_info.fixed_rate;
+    }
+    assert!(fixed_loan_amount > Decimal256::from_uint256(530000u64));
+    assert_eq!(state.total_fixed_liabilities, fixed_loan_amount);
+    assert_eq!(state.fixed_interest_per_block, fixed_interest_per_block);
+}
+
+#[test]
+fn repay_stable() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        }],
+    );
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Register anchor token contract
+    let mut token_inst_res = MsgInstantiateContractResponse::new();
+    token_inst_res.set_contract_address("AT-uusd".to_string());
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(token_inst_res.write_to_bytes().unwrap().into()),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // Register overseer contract
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
+    let mut env = mock_env();
+    let mut info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
+    deps.querier
+        .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
+
+    store_state(
+        deps.as_mut().storage,
+        &State {
+            total_liabilities: Decimal256::from_uint256(1000000u128),
+            total_reserves: Decimal256::zero(),
+            last_interest_updated: env.block.height,
+            last_reward_updated: env.block.height,
+            global_interest_index: Decimal256::one(),
+            global_reward_index: Decimal256::zero(),
+            anc_emission_rate: Decimal256::one(),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
+        },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::BorrowStable {
+        borrow_amount: Uint256::from(500000u64),
+        to: None,
+        borrower: None,
+    };
+
+    env.block.height += 100;
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::RepayStable {};
+    info.funds = vec![Coin {
+        denom: "ukrw".to_string(),
+        amount: Uint128::from(100000u128),
+    }];
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    let _uusd_string = "uusd";
+    match res {
+        Err(ContractError::ZeroRepay(_uusd_string)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    info.funds = vec![Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::zero(),
+    }];
+
+    let res2 = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    match res2 {
+        Err(ContractError::ZeroRepay(_uusd_string)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 100000u128),
+        }],
+    );
+
+    info.funds = vec![Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(100000u128),
+    }];
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "repay_stable"),
+            attr("borrower", "addr0000"),
+            attr("repay_amount", "100000"),
+        ]
+    );
+
+    //Loan amount and Total liability have decreased according to the repayment
+    let res_loan = read_borrower_infos(deps.as_ref(), None, None)
+        .unwrap()
+        .get(0)
+        .unwrap()
+        .loan_amount;
+    assert_eq!(res_loan, Uint256::from(400000u128));
+    assert_eq!(
+        read_state(deps.as_ref().storage).unwrap().total_liabilities,
+        Decimal256::from_uint256(2400000u128)
+    );
+
+    // repay on behalf of the borrower, excess is returned to the sender
+    let msg = ExecuteMsg::RepayStableFor {
+        borrower: "addr0000".to_string(),
+    };
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(500000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "repay_stable"),
+            attr("borrower", "addr0000"),
+            attr("repay_amount", "400000"),
+        ]
+    );
+
+    //Loan amount and Total liability have decreased according to the repayment
+    let res_loan = read_borrower_infos(deps.as_ref(), None, None)
+        .unwrap()
+        .get(0)
+        .unwrap()
+        .loan_amount;
+    assert_eq!(res_loan, Uint256::zero());
+    assert_eq!(
+        read_state(deps.as_ref().storage).unwrap().total_liabilities,
+        Decimal256::from_uint256(2000000u128)
+    );
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0001".to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(100000u128),
+                }
+            )
+            .unwrap()]
+        }))]
+    );
+}
+
+#[test]
+fn repay_stable_from_liquidation() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        }],
+    );
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Register anchor token contract
+    let mut token_inst_res = MsgInstantiateContractResponse::new();
+    token_inst_res.set_contract_address("AT-uusd".to_string());
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(token_inst_res.write_to_bytes().unwrap().into()),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // Register overseer contract
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
+    let mut env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
+    deps.querier
+        .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
+
+    store_state(
+        deps.as_mut().storage,
+        &State {
+            total_liabilities: Decimal256::from_uint256(1000000u128),
+            total_reserves: Decimal256::zero(),
+            last_interest_updated: env.block.height,
+            last_reward_updated: env.block.height,
+            global_interest_index: Decimal256::one(),
+            global_reward_index: Decimal256::zero(),
+            anc_emission_rate: Decimal256::one(),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
+        },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::BorrowStable {
+        borrow_amount: Uint256::from(500000u64),
+        to: None,
+        borrower: None,
+    };
+
+    env.block.height += 100;
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // update balance to make repay
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR.to_string(),
+        vec![
+            Coin {
+                denom: "ukrw".to_string(),
+                amount: Uint128::from(100000u128),
+            },
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+            },
+        ],
+    );
+
+    let msg = ExecuteMsg::RepayStableFromLiquidation {
+        borrower: "addr0000".to_string(),
+        prev_balance: Uint256::from(INITIAL_DEPOSIT_AMOUNT),
+    };
+
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("overseer", &[]);
+
+    // nothing to repay when the collateral is put up for auction
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "repay_stable"),
+            attr("borrower", "addr0000"),
+            attr("repay_amount", "0"),
+        ]
+    );
+
+    // update balance to make repay
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 100000u128),
+        }],
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "repay_stable"),
+            attr("borrower", "addr0000"),
+            attr("repay_amount", "100000"),
+        ]
+    );
+
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 500000u128),
+        }],
+    );
+
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "repay_stable"),
+            attr("borrower", "addr0000"),
+            attr("repay_amount", "400000"),
+        ]
+    );
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(100000u128),
+                }
+            )
+            .unwrap()]
+        }))]
+    );
+}
+
+#[test]
+fn write_off_bad_debt() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        }],
+    );
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Register anchor token contract
+    let mut token_inst_res = MsgInstantiateContractResponse::new();
+    token_inst_res.set_contract_address("AT-uusd".to_string());
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(token_inst_res.write_to_bytes().unwrap().into()),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // Register overseer contract
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
+    deps.querier
+        .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
+
+    store_state(
+        deps.as_mut().storage,
+        &State {
+            total_liabilities: Decimal256::from_uint256(1000000u128),
+            total_reserves: Decimal256::from_uint256(200000u128),
+            last_interest_updated: env.block.height,
+            last_reward_updated: env.block.height,
+            global_interest_index: Decimal256::one(),
+            global_reward_index: Decimal256::zero(),
+            anc_emission_rate: Decimal256::one(),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
+        },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::BorrowStable {
+        borrow_amount: Uint256::from(500000u64),
+        to: None,
+        borrower: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::WriteOffBadDebt {
+        borrower: "addr0000".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // reserves cover 200,000, the buffer 100,000 and
+    // the rest is socialized
+    let info = mock_info(
+        "overseer",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "write_off_bad_debt"),
+            attr("borrower", "addr0000"),
+            attr("bad_debt", "500000"),
+            attr("reserve_cover", "200000"),
+            attr("buffer_cover", "100000"),
+            attr("socialized", "200000"),
+        ]
+    );
+
+    let state = read_state(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        state.total_liabilities,
+        Decimal256::from_uint256(1000000u128)
+    );
+    assert_eq!(state.total_reserves, Decimal256::zero());
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BorrowerInfo {
+            borrower: "addr0000".to_string(),
+            block_height: None,
+        },
+    )
+    .unwrap();
+    let borrower_info: BorrowerInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(borrower_info.loan_amount, Uint256::zero());
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("overseer", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::NoBadDebt {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // unused buffer funds are sent back to the overseer
+    let info = mock_info("addr0001", &[]);
+    let msg = ExecuteMsg::BorrowStable {
+        borrow_amount: Uint256::from(300000u64),
+        to: None,
+        borrower: None,
+    };
+    deps.querier
+        .with_borrow_limit(&[(&"addr0001".to_string(), &Uint256::from(1000000u64))]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let info = mock_info(
+        "overseer",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(400000u128),
+        }],
+    );
+    let msg = ExecuteMsg::WriteOffBadDebt {
+        borrower: "addr0001".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "overseer".to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(100000u128),
+                }
+            )
+            .unwrap()]
+        }))]
+    );
+
+    let res = query(deps.as_ref(), env, QueryMsg::BadDebt {}).unwrap();
+    let bad_debt: BadDebtResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        bad_debt,
+        BadDebtResponse {
+            total_bad_debt: Uint256::from(800000u64),
+            covered_by_reserves: Uint256::from(200000u64),
+            covered_by_buffer: Uint256::from(400000u64),
+            socialized: Uint256::from(200000u64),
+            write_off_count: 2,
+        }
+    );
+}
+
+#[test]
+fn flash_loan() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        }],
+    );
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Register anchor token contract
+    let mut token_inst_res = MsgInstantiateContractResponse::new();
+    token_inst_res.set_contract_address("at-uusd".to_string());
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(token_inst_res.write_to_bytes().unwrap().into()),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // Register overseer contract
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner_addr: None,
+        interest_model: None,
+        distribution_model: None,
+        max_borrow_factor: None,
+        borrow_cap: None,
+        flash_loan_fee: Some(Decimal256::permille(9)),
+        fixed_rate_premium: None,
+        fixed_rate_rebalance_threshold: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let callback_msg = to_binary(&"liquidate").unwrap();
+    let msg = ExecuteMsg::FlashLoan {
+        amount: Uint256::from(2000000u64),
+        callback_contract: "bot".to_string(),
+        msg: callback_msg.clone(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0001", &[]), msg);
+    match res {
+        Err(ContractError::NoStableAvailable(_)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::FlashLoan {
+        amount: Uint256::from(500000u64),
+        callback_contract: "bot".to_string(),
+        msg: callback_msg.clone(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "bot".to_string(),
+                funds: vec![deduct_tax(
+                    deps.as_ref(),
+                    Coin {
+                        denom: "uusd".to_string(),
+                        amount: Uint128::from(500000u128),
+                    }
+                )
+                .unwrap()],
+                msg: callback_msg,
+            }),
+            2
+        )]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "flash_loan"),
+            attr("borrower", "addr0001"),
+            attr("callback_contract", "bot"),
+            attr("amount", "500000"),
+            attr("fee", "4500"),
+        ]
+    );
+
+    // aTerra can not be priced while the loan is out
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bot", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::FlashLoanInProgress {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "bot",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(500000u128),
+            }],
+        ),
+        ExecuteMsg::DepositStable {},
+    );
+    match res {
+        Err(ContractError::FlashLoanInProgress {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // the loan is returned without the fee
+    let reply_msg = Reply {
+        id: 2,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg.clone());
+    match res {
+        Err(ContractError::FlashLoanNotRepaid(4500)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 4500u128),
+        }],
+    );
+    let res = reply(deps.as_mut(), mock_env(), reply_msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "flash_loan_repaid"),
+            attr("amount", "500000"),
+            attr("fee", "4500"),
+        ]
+    );
+
+    let state = read_state(deps.as_ref().storage).unwrap();
+    assert_eq!(state.total_reserves, Decimal256::from_uint256(4500u64));
+
+    deps.querier
+        .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
+    let borrow_msg = ExecuteMsg::BorrowStable {
+        borrow_amount: Uint256::from(100000u64),
+        to: None,
+        borrower: None,
+    };
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        borrow_msg.clone(),
+    )
+    .unwrap();
+
+    // the loaned amount can not be borrowed
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        borrow_msg,
+    );
+    match res {
+        Err(ContractError::FlashLoanInProgress {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // interest accrued while the loan is out is computed
+    // on the balance before the loan
+    deps.querier.with_token_balances(&[(
+        &"at-uusd".to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        )],
+    )]);
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 4500u128 - 500000u128 + 100000u128),
+        }],
+    );
+    let mut env = mock_env();
+    env.block.height += 100;
+
+    // a repayment made with the loaned amount does not return the loan
+    let _res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(100000u128),
+            }],
+        ),
+        ExecuteMsg::RepayStable {},
+    )
+    .unwrap();
+
+    // (1004500 balance + 200000 liabilities - 4500 reserves) / 1000000 aterra
+    let state = read_state(deps.as_ref().storage).unwrap();
+    assert_eq!(state.last_interest_updated, mock_env().block.height + 100);
+    assert_eq!(
+        state.prev_exchange_rate,
+        Decimal256::from_ratio(1200000u64, 1000000u64)
+    );
+
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 104500u128),
+        }],
+    );
+    let res = reply(deps.as_mut(), mock_env(), reply_msg.clone());
+    match res {
+        Err(ContractError::FlashLoanNotRepaid(4500)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0001", &[]), msg).unwrap();
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT + 109000u128),
+        }],
+    );
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // the fee must stay below one
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            owner_addr: None,
+            interest_model: None,
+            distribution_model: None,
+            max_borrow_factor: None,
+            borrow_cap: None,
+            flash_loan_fee: Some(Decimal256::one()),
+            fixed_rate_premium: None,
+            fixed_rate_rebalance_threshold: None,
+        },
+    );
+    match res {
+        Err(ContractError::InvalidFlashLoanFee {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // deposits are allowed again
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "bot",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(500000u128),
+            }],
+        ),
+        ExecuteMsg::DepositStable {},
+    )
+    .unwrap();
+}
+
+#[test]
+fn claim_rewards() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        }],
+    );
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Register anchor token contract
+    let mut token_inst_res = MsgInstantiateContractResponse::new();
+    token_inst_res.set_contract_address("AT-uusd".to_string());
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(token_inst_res.write_to_bytes().unwrap().into()),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // Register overseer contract
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
+    deps.querier
+        .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
+
+    store_state(
+        deps.as_mut().storage,
+        &State {
+            total_liabilities: Decimal256::from_uint256(1000000u128),
+            total_reserves: Decimal256::zero(),
+            last_interest_updated: env.block.height,
+            last_reward_updated: env.block.height,
+            global_interest_index: Decimal256::one(),
+            global_reward_index: Decimal256::zero(),
+            anc_emission_rate: Decimal256::one(),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
+        },
+    )
+    .unwrap();
+
+    // zero loan claim, will return empty messages
+    let msg = ExecuteMsg::ClaimRewards { to: None };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let msg = ExecuteMsg::BorrowStable {
+        borrow_amount: Uint256::from(500000u64),
+        to: None,
+        borrower: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // zero block passed
+    let msg = ExecuteMsg::ClaimRewards {
+        to: Some("addr0001".to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    // 100 blocks passed
+    env.block.height += 100;
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "distributor".to_string(),
+            funds: vec![],
+            msg: to_binary(&FaucetExecuteMsg::Spend {
+                recipient: "addr0001".to_string(),
+                amount: Uint128::from(33u128),
+            })
+            .unwrap(),
+        }))]
+    );
+
+    let res: BorrowerInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BorrowerInfo {
+                borrower: "addr0000".to_string(),
+                block_height: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.pending_rewards,
+        Decimal256::from_str("0.333333333333").unwrap()
+    );
+    assert_eq!(
+        res.reward_index,
+        Decimal256::from_str("0.000066666666666666").unwrap()
+    );
+}
+
+#[test]
+fn execute_epoch_operations() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        }],
+    );
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Register anchor token contract
+    let mut token_inst_res = MsgInstantiateContractResponse::new();
+    token_inst_res.set_contract_address("AT-uusd".to_string());
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(token_inst_res.write_to_bytes().unwrap().into()),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // Register overseer contract
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
+    let mut env = mock_env();
+    let mut info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
+    deps.querier
+        .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
+
+    store_state(
+        deps.as_mut().storage,
+        &State {
+            total_liabilities: Decimal256::from_uint256(1000000u128),
+            total_reserves: Decimal256::from_uint256(3000u128),
+            last_interest_updated: env.block.height,
+            last_reward_updated: env.block.height,
+            global_interest_index: Decimal256::one(),
+            global_reward_index: Decimal256::zero(),
+            anc_emission_rate: Decimal256::one(),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
+        },
+    )
+    .unwrap();
+
+    env.block.height += 100;
+
+    // reserve == 3000
+    let msg = ExecuteMsg::ExecuteEpochOperations {
+        deposit_rate: Decimal256::one(),
+        target_deposit_rate: Decimal256::one(),
+        threshold_deposit_rate: Decimal256::one(),
+        distributed_interest: Uint256::zero(),
+    };
+
+    // only overseer can execute this
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    info.sender = Addr::unchecked("overseer");
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "interest".to_string(),
+                funds: vec![],
+                msg: to_binary(&InterestExecuteMsg::ExecuteEpochOperations {
+                    market_balance: Uint256::from(INITIAL_DEPOSIT_AMOUNT),
+                    total_liabilities: Decimal256::from_uint256(2000000u128),
+                    total_reserves: Decimal256::from_uint256(3000u128),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(2970u128), // 1% tax
+                }],
+            }))
+        ]
+    );
+
+    let state = read_state(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        state,
+        State {
+            total_liabilities: Decimal256::from_uint256(2000000u128),
+            total_reserves: Decimal256::zero(),
+            last_interest_updated: env.block.height,
+            last_reward_updated: env.block.height,
+            global_interest_index: Decimal256::from_uint256(2u64),
+            global_reward_index: Decimal256::from_str("0.0001").unwrap(),
+            anc_emission_rate: Decimal256::from_uint256(5u64),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::from_uint256(100u64),
+        }
+    );
+
+    // When there is not enough balance to cover reserve
+    // no message will be sent and reserve will be left as same
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(2999u128),
+        }],
+    );
+
+    let mut env = mock_env();
+    let info = mock_info("overseer", &[]);
+    store_state(
+        deps.as_mut().storage,
+        &State {
+            total_liabilities: Decimal256::from_uint256(1000000u128),
+            total_reserves: Decimal256::from_uint256(3000u128),
+            last_interest_updated: env.block.height,
+            last_reward_updated: env.block.height,
+            global_interest_index: Decimal256::one(),
+            global_reward_index: Decimal256::zero(),
+            anc_emission_rate: Decimal256::one(),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::zero(),
+        },
+    )
+    .unwrap();
+
+    env.block.height += 100;
+
+    // reserve == 3000
+    let msg = ExecuteMsg::ExecuteEpochOperations {
+        deposit_rate: Decimal256::one(),
+        target_deposit_rate: Decimal256::one(),
+        threshold_deposit_rate: Decimal256::one(),
+        distributed_interest: Uint256::zero(),
+    };
+
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "interest".to_string(),
+            funds: vec![],
+            msg: to_binary(&InterestExecuteMsg::ExecuteEpochOperations {
+                market_balance: Uint256::from(2999u128),
+                total_liabilities: Decimal256::from_uint256(2000000u128),
+                total_reserves: Decimal256::from_uint256(3000u128),
+            })
+            .unwrap(),
+        }))]
+    );
+
+    let state = read_state(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        state,
+        State {
+            total_liabilities: Decimal256::from_uint256(2000000u128),
+            total_reserves: Decimal256::from_uint256(3000u128),
+            last_interest_updated: env.block.height,
+            last_reward_updated: env.block.height,
+            global_interest_index: Decimal256::from_uint256(2u64),
+            global_reward_index: Decimal256::from_str("0.0001").unwrap(),
+            anc_emission_rate: Decimal256::from_uint256(5u64),
+            prev_aterra_supply: Uint256::zero(),
+            prev_exchange_rate: Decimal256::one(),
+            total_fixed_liabilities: Decimal256::zero(),
+            fixed_interest_per_block: Decimal256::zero(),
+            fixed_interest_index: Decimal256::from_uint256(100u64),
+        }
+    );
+}
+
+// #[test]
+// fn borrow_repay_execute_operations() {
+//     let mut deps = mock_dependencies(
+//         20,
+//         &[Coin {
+//             denom: "uusd".to_string(),
+//             amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+//         }],
+//     );
+
+//     let msg = InstantiateMsg {
+//         owner_addr: "owner".to_string(),
+//         stable_denom: "uusd".to_string(),
+//         aterra_code_id: 123u64,
+//         anc_emission_rate: Decimal256::one(),
+//         max_borrow_factor: Decimal256::one(),
+//     };
+
+//     let env = mock_env(
+//         "addr0000",
+//         &[Coin {
+//             denom: "uusd".to_string(),
+//             amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+//         }],
+//     );
+
+//     // we can just call .unwrap() to assert this was a success
+//     let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+//     // Register anchor token contract
+//     let msg = ExecuteMsg::RegisterATerra {};
+//     let env = mock_env("AT-uusd", &[]);
+//     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+//     // Register overseer contract
+//     let msg = ExecuteMsg::RegisterContracts {
+//         overseer_contract: "overseer".to_string(),
+//         interest_model: "interest".to_string(),
+//         distribution_model: "distribution".to_string(),
+//         collector_contract: "collector".to_string(),
+//         distributor_contract: "distributor".to_string(),
+//     };
+//     let env = mock_env("addr0000", &[]);
+//     let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+//     deps.querier
+//         .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
+//     deps.querier.with_token_balances(&[(
+//         &HumanAddr::from("AT-uusd"),
+//         &[(
+//             &MOCK_CONTRACT_ADDR.to_string(),
+//             &Uint128::from(1000000u128),
+//         )],
+//     )]);
+//     deps.querier.update_balance(
+//         MOCK_CONTRACT_ADDR.to_string(),
+//         vec![Coin {
+//             denom: "uusd".to_string(),
+//             amount: Uint128::from(373025692u128),
+//         }],
+//     );
+
+//     let mut env = mock_env("overseer", &[]);
+
+//     store_state(
+//         deps.as_mut().storage,
+//         &State {
+//             total_liabilities: Decimal256::from_str("8.198749212085782102").unwrap(),
+//             total_reserves: Decimal256::from_str("372025697.802295205294219818").unwrap(),
+//             last_interest_updated: env.block.height,
+//             last_reward_updated: env.block.height,
+//             global_interest_index: Decimal256::from_str("1.000005078160215988").unwrap(),
+//             global_reward_index: Decimal256::from_str("119531.277425251814227128").unwrap(),
+//             anc_emission_rate: Decimal256::from_str("980001.99").unwrap(),
+//             prev_aterra_supply: Uint256::from(1000000001000000u128),
+//             prev_exchange_rate: Decimal256::from_str("1.0000022").unwrap(),
+//         },
+//     )
+//     .unwrap();
+
+//     env.block.height += 100;
+
+//     let msg = ExecuteMsg::ExecuteEpochOperations {
+//         deposit_rate: Decimal256::one(),
+//         target_deposit_rate: Decimal256::from_str("0.000000040762727704").unwrap(),
+//         threshold_deposit_rate: Decimal256::from_str("0.000000030572045778").unwrap(),
+//     };
+
+//     // only overseer can execute this
+//     let _ = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+// }
+
+#[test]
+fn pause_operations() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        }],
+    );
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Register anchor token contract
+    let mut token_inst_res = MsgInstantiateContractResponse::new();
+    token_inst_res.set_contract_address("AT-uusd".to_string());
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(token_inst_res.write_to_bytes().unwrap().into()),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // Register overseer contract
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
+    deps.querier
+        .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
+
+    // Unauthorized err
+    let msg = ExecuteMsg::UpdateGuardian {
+        guardian: Some("guardian".to_string()),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::UpdatePause {
+        paused: PAUSE_DEPOSIT | PAUSE_BORROW,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("guardian", &[]), msg).unwrap();
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::PauseInfo {}).unwrap();
+    let pause_info: PauseInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        pause_info,
+        PauseInfoResponse {
+            guardian: Some("guardian".to_string()),
+            paused: PAUSE_DEPOSIT | PAUSE_BORROW,
+        }
+    );
+
+    let msg = ExecuteMsg::DepositStable {};
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::BorrowStable {
+        borrow_amount: Uint256::from(500000u64),
+        to: None,
+        borrower: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // guardian cannot unpause
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("guardian", &[]),
+        ExecuteMsg::UpdatePause {
+            paused: PAUSE_DEPOSIT,
+        },
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // unknown flags
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdatePause { paused: 1 << 4 },
+    );
+    match res {
+        Err(ContractError::InvalidPause {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 
-// #[test]
-// fn borrow_repay_execute_operations() {
-//     let mut deps = mock_dependencies(
-//         20,
-//         &[Coin {
-//             denom: "uusd".to_string(),
-//             amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
-//         }],
-//     );
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdatePause {
+            paused: PAUSE_DEPOSIT,
+        },
+    )
+    .unwrap();
 
-//     let msg = InstantiateMsg {
-//         owner_addr: "owner".to_string(),
-//         stable_denom: "uusd".to_string(),
-//         aterra_code_id: 123u64,
-//         anc_emission_rate: Decimal256::one(),
-//         max_borrow_factor: Decimal256::one(),
-//     };
+    let res = execute(deps.as_mut(), env, mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(res.attributes[0], attr("action", "borrow_stable"));
+}
 
-//     let env = mock_env(
-//         "addr0000",
-//         &[Coin {
-//             denom: "uusd".to_string(),
-//             amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
-//         }],
-//     );
+#[test]
+fn borrow_allowance() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
 
-//     // we can just call .unwrap() to assert this was a success
-//     let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-//     // Register anchor token contract
-//     let msg = ExecuteMsg::RegisterATerra {};
-//     let env = mock_env("AT-uusd", &[]);
-//     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        }],
+    );
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-//     // Register overseer contract
-//     let msg = ExecuteMsg::RegisterContracts {
-//         overseer_contract: "overseer".to_string(),
-//         interest_model: "interest".to_string(),
-//         distribution_model: "distribution".to_string(),
-//         collector_contract: "collector".to_string(),
-//         distributor_contract: "distributor".to_string(),
-//     };
-//     let env = mock_env("addr0000", &[]);
-//     let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
+    let env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
 
-//     deps.querier
-//         .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
-//     deps.querier.with_token_balances(&[(
-//         &HumanAddr::from("AT-uusd"),
-//         &[(
-//             &MOCK_CONTRACT_ADDR.to_string(),
-//             &Uint128::from(1000000u128),
-//         )],
-//     )]);
-//     deps.querier.update_balance(
-//         MOCK_CONTRACT_ADDR.to_string(),
-//         vec![Coin {
-//             denom: "uusd".to_string(),
-//             amount: Uint128::from(373025692u128),
-//         }],
-//     );
+    deps.querier
+        .with_borrow_rate(&[(&"interest".to_string(), &Decimal256::percent(1))]);
+    deps.querier
+        .with_borrow_limit(&[(&"addr0000".to_string(), &Uint256::from(1000000u64))]);
 
-//     let mut env = mock_env("overseer", &[]);
+    let msg = ExecuteMsg::BorrowStable {
+        borrow_amount: Uint256::from(100000u64),
+        to: None,
+        borrower: Some("addr0000".to_string()),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vault", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::BorrowAllowanceExceeded(0)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 
-//     store_state(
-//         deps.as_mut().storage,
-//         &State {
-//             total_liabilities: Decimal256::from_str("8.198749212085782102").unwrap(),
-//             total_reserves: Decimal256::from_str("372025697.802295205294219818").unwrap(),
-//             last_interest_updated: env.block.height,
-//             last_reward_updated: env.block.height,
-//             global_interest_index: Decimal256::from_str("1.000005078160215988").unwrap(),
-//             global_reward_index: Decimal256::from_str("119531.277425251814227128").unwrap(),
-//             anc_emission_rate: Decimal256::from_str("980001.99").unwrap(),
-//             prev_aterra_supply: Uint256::from(1000000001000000u128),
-//             prev_exchange_rate: Decimal256::from_str("1.0000022").unwrap(),
-//         },
-//     )
-//     .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::IncreaseBorrowAllowance {
+            spender: "vault".to_string(),
+            amount: Uint256::from(150000u64),
+            expires_at: Some(env.block.time.seconds()),
+        },
+    );
+    match res {
+        Err(ContractError::InvalidAllowanceExpiry {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
 
-//     env.block.height += 100;
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::IncreaseBorrowAllowance {
+            spender: "vault".to_string(),
+            amount: Uint256::from(150000u64),
+            expires_at: Some(env.block.time.seconds() + 100),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "increase_borrow_allowance"),
+            attr("borrower", "addr0000"),
+            attr("spender", "vault"),
+            attr("amount", "150000"),
+        ]
+    );
 
-//     let msg = ExecuteMsg::ExecuteEpochOperations {
-//         deposit_rate: Decimal256::one(),
-//         target_deposit_rate: Decimal256::from_str("0.000000040762727704").unwrap(),
-//         threshold_deposit_rate: Decimal256::from_str("0.000000030572045778").unwrap(),
-//     };
+    // the spender receives the stable, the borrower owes the loan
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vault", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "borrow_stable"),
+            attr("borrower", "addr0000"),
+            attr("borrow_amount", "100000"),
+            attr("spender", "vault"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "vault".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(100000u128),
+            }],
+        }))]
+    );
 
-//     // only overseer can execute this
-//     let _ = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
-// }
+    let res: BorrowerInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BorrowerInfo {
+                borrower: "addr0000".to_string(),
+                block_height: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.loan_amount, Uint256::from(100000u64));
+
+    let allowance_query = QueryMsg::BorrowAllowance {
+        borrower: "addr0000".to_string(),
+        spender: "vault".to_string(),
+    };
+    let res: BorrowAllowanceResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), allowance_query.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        BorrowAllowanceResponse {
+            borrower: "addr0000".to_string(),
+            spender: "vault".to_string(),
+            amount: Uint256::from(50000u64),
+            expires_at: Some(env.block.time.seconds() + 100),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vault", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::BorrowAllowanceExceeded(50000)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // nothing is left once the allowance expires
+    let mut expired_env = env.clone();
+    expired_env.block.time = expired_env.block.time.plus_seconds(100);
+    let res: BorrowAllowanceResponse =
+        from_binary(&query(deps.as_ref(), expired_env, allowance_query.clone()).unwrap()).unwrap();
+    assert_eq!(res.amount, Uint256::zero());
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RevokeBorrowAllowance {
+            spender: "vault".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "revoke_borrow_allowance"),
+            attr("borrower", "addr0000"),
+            attr("spender", "vault"),
+        ]
+    );
+
+    let res: BorrowAllowanceResponse =
+        from_binary(&query(deps.as_ref(), env, allowance_query).unwrap()).unwrap();
+    assert_eq!(res.amount, Uint256::zero());
+    assert_eq!(res.expires_at, None);
+}
+
+#[test]
+fn spend_borrow_allowance() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+    }]);
+
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        stable_denom: "uusd".to_string(),
+        aterra_code_id: 123u64,
+        anc_emission_rate: Decimal256::one(),
+        max_borrow_factor: Decimal256::one(),
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(INITIAL_DEPOSIT_AMOUNT),
+        }],
+    );
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterContracts {
+        overseer_contract: "overseer".to_string(),
+        interest_model: "interest".to_string(),
+        distribution_model: "distribution".to_string(),
+        collector_contract: "collector".to_string(),
+        distributor_contract: "distributor".to_string(),
+    };
+    let env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::IncreaseBorrowAllowance {
+            spender: "vault".to_string(),
+            amount: Uint256::from(150000u64),
+            expires_at: None,
+        },
+    )
+    .unwrap();
+
+    let spend_msg = |amount: u64| ExecuteMsg::SpendBorrowAllowance {
+        borrower: "addr0000".to_string(),
+        spender: "vault".to_string(),
+        amount: Uint256::from(amount),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vault", &[]),
+        spend_msg(100000u64),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("overseer", &[]),
+        spend_msg(200000u64),
+    );
+    match res {
+        Err(ContractError::BorrowAllowanceExceeded(150000)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("overseer", &[]),
+        spend_msg(100000u64),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "spend_borrow_allowance"),
+            attr("borrower", "addr0000"),
+            attr("spender", "vault"),
+            attr("amount", "100000"),
+        ]
+    );
+
+    let res: BorrowAllowanceResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::BorrowAllowance {
+                borrower: "addr0000".to_string(),
+                spender: "vault".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.amount, Uint256::from(50000u64));
+}
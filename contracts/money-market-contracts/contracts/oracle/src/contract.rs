@@ -1,15 +1,21 @@
 use crate::error::ContractError;
 use crate::state::{
-    read_config, read_feeder, read_price, read_prices, store_config, store_feeder, store_price,
-    Config, PriceInfo,
+    read_config, read_feed_config, read_feeder_price, read_feeders, read_observations, read_price,
+    read_price_jump, read_price_jump_config, read_prices, remove_feeder_price, remove_price_jump,
+    store_config, store_feed_config, store_feeder_price, store_feeders, store_observation,
+    store_price, store_price_jump, store_price_jump_config, Config, FeedConfig, FeederPrice,
+    PriceInfo, PriceJump, PriceJumpConfig, PriceObservation,
 };
 use cosmwasm_bignumber::Decimal256;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{attr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    attr, to_binary, Binary, CanonicalAddr, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Storage,
+};
 use moneymarket::oracle::{
-    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, PriceResponse, PricesResponse,
-    PricesResponseElem, QueryMsg,
+    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, PriceJumpResponse, PriceResponse,
+    PricesResponse, PricesResponseElem, QueryMsg, TwapResponse,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -40,7 +46,22 @@ pub fn execute(
     match msg {
         ExecuteMsg::UpdateConfig { owner } => update_config(deps, info, owner),
         ExecuteMsg::RegisterFeeder { asset, feeder } => register_feeder(deps, info, asset, feeder),
+        ExecuteMsg::DeregisterFeeder { asset, feeder } => {
+            deregister_feeder(deps, info, asset, feeder)
+        }
+        ExecuteMsg::UpdateFeedConfig {
+            asset,
+            min_feeders,
+            max_deviation,
+            max_age,
+        } => update_feed_config(deps, info, asset, min_feeders, max_deviation, max_age),
         ExecuteMsg::FeedPrice { prices } => feed_prices(deps, env, info, prices),
+        ExecuteMsg::UpdatePriceJumpConfig {
+            asset,
+            max_price_jump,
+            cooldown,
+        } => update_price_jump_config(deps, info, asset, max_price_jump, cooldown),
+        ExecuteMsg::ClearPriceJump { asset } => clear_price_jump(deps, info, asset),
     }
 }
 
@@ -73,7 +94,14 @@ pub fn register_feeder(
         return Err(ContractError::Unauthorized {});
     }
 
-    store_feeder(deps.storage, &asset, &deps.api.addr_canonicalize(&feeder)?)?;
+    let feeder_raw = deps.api.addr_canonicalize(&feeder)?;
+    let mut feeders = read_feeders(deps.storage, &asset).unwrap_or_default();
+    if feeders.contains(&feeder_raw) {
+        return Err(ContractError::FeederAlreadyRegistered {});
+    }
+
+    feeders.push(feeder_raw);
+    store_feeders(deps.storage, &asset, &feeders)?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "register_feeder"),
@@ -82,6 +110,68 @@ pub fn register_feeder(
     ]))
 }
 
+pub fn deregister_feeder(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: String,
+    feeder: String,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let feeder_raw = deps.api.addr_canonicalize(&feeder)?;
+    let mut feeders = read_feeders(deps.storage, &asset)?;
+    if !feeders.contains(&feeder_raw) {
+        return Err(ContractError::FeederNotRegistered {});
+    }
+
+    feeders.retain(|f| *f != feeder_raw);
+    store_feeders(deps.storage, &asset, &feeders)?;
+    remove_feeder_price(deps.storage, &asset, &feeder_raw);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "deregister_feeder"),
+        attr("asset", asset),
+        attr("feeder", feeder),
+    ]))
+}
+
+pub fn update_feed_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: String,
+    min_feeders: u32,
+    max_deviation: Option<Decimal256>,
+    max_age: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if min_feeders == 0 {
+        return Err(ContractError::InvalidFeedConfig {});
+    }
+
+    store_feed_config(
+        deps.storage,
+        &asset,
+        &FeedConfig {
+            min_feeders,
+            max_deviation,
+            max_age,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_feed_config"),
+        attr("asset", asset),
+        attr("min_feeders", min_feeders.to_string()),
+    ]))
+}
+
 pub fn feed_prices(
     deps: DepsMut,
     env: Env,
@@ -95,29 +185,208 @@ pub fn feed_prices(
         let price: Decimal256 = price.1;
 
         // Check feeder permission
-        let feeder = read_feeder(deps.storage, &asset)?;
-        if feeder != sender_raw {
+        let feeders = read_feeders(deps.storage, &asset)?;
+        if !feeders.contains(&sender_raw) {
             return Err(ContractError::Unauthorized {});
         }
 
         attributes.push(attr("asset", asset.to_string()));
         attributes.push(attr("price", price.to_string()));
 
-        store_price(
+        store_feeder_price(
             deps.storage,
             &asset,
-            &PriceInfo {
-                last_updated_time: env.block.time.seconds(),
+            &sender_raw,
+            &FeederPrice {
                 price,
+                last_updated_time: env.block.time.seconds(),
             },
         )?;
+
+        // Keep the last aggregated price when quorum is not reached
+        if let Some(price_info) = aggregate_price(deps.storage, &env, &asset, &feeders)? {
+            if detect_price_jump(deps.storage, &env, &asset, price_info.price)? {
+                attributes.push(attr("price_jump", asset.to_string()));
+            }
+
+            store_observation(
+                deps.storage,
+                &asset,
+                &PriceObservation {
+                    price: price_info.price,
+                    timestamp: env.block.time.seconds(),
+                },
+            )?;
+            store_price(deps.storage, &asset, &price_info)?;
+        }
     }
 
     Ok(Response::new().add_attributes(attributes))
 }
 
+pub fn update_price_jump_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: String,
+    max_price_jump: Option<Decimal256>,
+    cooldown: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    store_price_jump_config(
+        deps.storage,
+        &asset,
+        &PriceJumpConfig {
+            max_price_jump,
+            cooldown,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_price_jump_config"),
+        attr("asset", asset),
+    ]))
+}
+
+pub fn clear_price_jump(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: String,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    remove_price_jump(deps.storage, &asset);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "clear_price_jump"),
+        attr("asset", asset),
+    ]))
+}
+
+/// Flags a price jump when the new aggregated price moves more than
+/// the configured ratio away from the current one
+fn detect_price_jump(
+    storage: &mut dyn Storage,
+    env: &Env,
+    asset: &str,
+    price: Decimal256,
+) -> StdResult<bool> {
+    let max_price_jump = match read_price_jump_config(storage, asset)?.max_price_jump {
+        Some(max_price_jump) => max_price_jump,
+        None => return Ok(false),
+    };
+
+    let previous_price = match read_price(storage, asset) {
+        Ok(price_info) => price_info.price,
+        Err(_) => return Ok(false),
+    };
+
+    let change = if price > previous_price {
+        price - previous_price
+    } else {
+        previous_price - price
+    };
+
+    if change <= previous_price * max_price_jump {
+        return Ok(false);
+    }
+
+    store_price_jump(
+        storage,
+        asset,
+        &PriceJump {
+            previous_price,
+            price,
+            detected_time: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(true)
+}
+
+/// Computes the median of fresh feeder submissions, excluding
+/// the ones too far from it; returns `None` below quorum
+fn aggregate_price(
+    storage: &dyn Storage,
+    env: &Env,
+    asset: &str,
+    feeders: &[CanonicalAddr],
+) -> StdResult<Option<PriceInfo>> {
+    let feed_config: FeedConfig = read_feed_config(storage, asset)?;
+
+    let mut submissions: Vec<(CanonicalAddr, FeederPrice)> = vec![];
+    for feeder in feeders.iter() {
+        if let Some(feeder_price) = read_feeder_price(storage, asset, feeder)? {
+            if let Some(max_age) = feed_config.max_age {
+                if feeder_price.last_updated_time + max_age < env.block.time.seconds() {
+                    continue;
+                }
+            }
+
+            submissions.push((feeder.clone(), feeder_price));
+        }
+    }
+
+    if (submissions.len() as u32) < feed_config.min_feeders {
+        return Ok(None);
+    }
+
+    let mut price = compute_median(&submissions);
+    if let Some(max_deviation) = feed_config.max_deviation {
+        submissions.retain(|(_, feeder_price)| {
+            let deviation = if feeder_price.price > price {
+                feeder_price.price - price
+            } else {
+                price - feeder_price.price
+            };
+
+            deviation <= price * max_deviation
+        });
+
+        if (submissions.len() as u32) < feed_config.min_feeders {
+            return Ok(None);
+        }
+
+        price = compute_median(&submissions);
+    }
+
+    // the aggregate is as recent as the last time a quorum of its
+    // inputs was refreshed, so a stalled feeder does not hold it back
+    let mut update_times: Vec<u64> = submissions
+        .iter()
+        .map(|(_, feeder_price)| feeder_price.last_updated_time)
+        .collect();
+    update_times.sort_unstable_by(|a, b| b.cmp(a));
+    let last_updated_time = update_times[feed_config.min_feeders as usize - 1];
+
+    Ok(Some(PriceInfo {
+        price,
+        last_updated_time,
+        feeders: submissions.into_iter().map(|(feeder, _)| feeder).collect(),
+    }))
+}
+
+fn compute_median(submissions: &[(CanonicalAddr, FeederPrice)]) -> Decimal256 {
+    let mut prices: Vec<Decimal256> = submissions
+        .iter()
+        .map(|(_, feeder_price)| feeder_price.price)
+        .collect();
+    prices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    // both indexes point to the middle element when the count is odd
+    let low = prices[(prices.len() - 1) / 2];
+    let high = prices[prices.len() / 2];
+    (low + high) / Decimal256::from_uint256(2u64)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::Feeder { asset } => to_binary(&query_feeder(deps, asset)?),
@@ -125,6 +394,12 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Prices { start_after, limit } => {
             to_binary(&query_prices(deps, start_after, limit)?)
         }
+        QueryMsg::Twap {
+            base,
+            quote,
+            window,
+        } => to_binary(&query_twap(deps, env, base, quote, window)?),
+        QueryMsg::PriceJump { asset } => to_binary(&query_price_jump(deps, env, asset)?),
     }
 }
 
@@ -139,21 +414,33 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 }
 
 fn query_feeder(deps: Deps, asset: String) -> StdResult<FeederResponse> {
-    let feeder = read_feeder(deps.storage, &asset)?;
+    let feeders = read_feeders(deps.storage, &asset)?;
+    let feed_config: FeedConfig = read_feed_config(deps.storage, &asset)?;
     let resp = FeederResponse {
         asset,
-        feeder: deps.api.addr_humanize(&feeder)?.to_string(),
+        feeders: humanize_feeders(deps, &feeders)?,
+        min_feeders: feed_config.min_feeders,
+        max_deviation: feed_config.max_deviation,
+        max_age: feed_config.max_age,
     };
 
     Ok(resp)
 }
 
+fn humanize_feeders(deps: Deps, feeders: &[CanonicalAddr]) -> StdResult<Vec<String>> {
+    feeders
+        .iter()
+        .map(|feeder| Ok(deps.api.addr_humanize(feeder)?.to_string()))
+        .collect()
+}
+
 fn query_price(deps: Deps, base: String, quote: String) -> StdResult<PriceResponse> {
     let config: Config = read_config(deps.storage)?;
     let quote_price = if config.base_asset == quote {
         PriceInfo {
             price: Decimal256::one(),
             last_updated_time: 9999999999,
+            feeders: vec![],
         }
     } else {
         read_price(deps.storage, &quote)?
@@ -163,6 +450,7 @@ fn query_price(deps: Deps, base: String, quote: String) -> StdResult<PriceRespon
         PriceInfo {
             price: Decimal256::one(),
             last_updated_time: 9999999999,
+            feeders: vec![],
         }
     } else {
         read_price(deps.storage, &base)?
@@ -172,6 +460,8 @@ fn query_price(deps: Deps, base: String, quote: String) -> StdResult<PriceRespon
         rate: base_price.price / quote_price.price,
         last_updated_base: base_price.last_updated_time,
         last_updated_quote: quote_price.last_updated_time,
+        feeders_base: humanize_feeders(deps, &base_price.feeders)?,
+        feeders_quote: humanize_feeders(deps, &quote_price.feeders)?,
     })
 }
 
@@ -180,6 +470,97 @@ fn query_prices(
     start_after: Option<String>,
     limit: Option<u32>,
 ) -> StdResult<PricesResponse> {
-    let prices: Vec<PricesResponseElem> = read_prices(deps.storage, start_after, limit)?;
+    let prices: Vec<PricesResponseElem> = read_prices(deps, start_after, limit)?;
     Ok(PricesResponse { prices })
 }
+
+fn query_price_jump(deps: Deps, env: Env, asset: String) -> StdResult<PriceJumpResponse> {
+    let price_jump_config: PriceJumpConfig = read_price_jump_config(deps.storage, &asset)?;
+    let price_jump: Option<PriceJump> = read_price_jump(deps.storage, &asset)?;
+
+    let active = match (&price_jump, price_jump_config.cooldown) {
+        (Some(price_jump), Some(cooldown)) => {
+            env.block.time.seconds() < price_jump.detected_time + cooldown
+        }
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+
+    Ok(PriceJumpResponse {
+        asset,
+        max_price_jump: price_jump_config.max_price_jump,
+        cooldown: price_jump_config.cooldown,
+        active,
+        detected_time: price_jump.map(|price_jump| price_jump.detected_time),
+    })
+}
+
+fn query_twap(
+    deps: Deps,
+    env: Env,
+    base: String,
+    quote: String,
+    window: u64,
+) -> StdResult<TwapResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let now = env.block.time.seconds();
+    let (quote_twap, quote_window) = if config.base_asset == quote {
+        (Decimal256::one(), window)
+    } else {
+        compute_twap(deps.storage, now, &quote, window)?
+    };
+
+    let (base_twap, base_window) = if config.base_asset == base {
+        (Decimal256::one(), window)
+    } else {
+        compute_twap(deps.storage, now, &base, window)?
+    };
+
+    Ok(TwapResponse {
+        rate: base_twap / quote_twap,
+        observed_window: base_window.min(quote_window),
+    })
+}
+
+/// Returns the time-weighted average of the observations within
+/// the window, along with the number of seconds they cover
+fn compute_twap(
+    storage: &dyn Storage,
+    now: u64,
+    asset: &str,
+    window: u64,
+) -> StdResult<(Decimal256, u64)> {
+    let observations = read_observations(storage, asset)?;
+    if observations.is_empty() {
+        return Err(StdError::generic_err(
+            "No price data for the specified asset exist",
+        ));
+    }
+
+    let window_start = now.saturating_sub(window);
+    let mut cumulative_price = Decimal256::zero();
+    let mut period_end = now;
+    for observation in observations.iter() {
+        let period_start = observation.timestamp.max(window_start);
+        if period_start < period_end {
+            cumulative_price +=
+                observation.price * Decimal256::from_uint256(period_end - period_start);
+            period_end = period_start;
+        }
+
+        if observation.timestamp <= window_start {
+            break;
+        }
+    }
+
+    let observed_window = now - period_end;
+    if observed_window == 0 {
+        // the latest price has not been effective for any time yet
+        return Ok((observations[0].price, 0));
+    }
+
+    Ok((
+        cumulative_price / Decimal256::from_uint256(observed_window),
+        observed_window,
+    ))
+}
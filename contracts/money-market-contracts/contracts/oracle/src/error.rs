@@ -11,4 +11,13 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Feeder is already registered for the asset")]
+    FeederAlreadyRegistered {},
+
+    #[error("Feeder is not registered for the asset")]
+    FeederNotRegistered {},
+
+    #[error("Invalid feed config; min_feeders must be greater than 0")]
+    InvalidFeedConfig {},
 }
@@ -2,13 +2,23 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_bignumber::Decimal256;
-use cosmwasm_std::{CanonicalAddr, Order, StdError, StdResult, Storage};
+use cosmwasm_std::{CanonicalAddr, Deps, Order, StdError, StdResult, Storage};
 use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
 
 use moneymarket::oracle::PricesResponseElem;
 
 static PREFIX_PRICE: &[u8] = b"price";
 static PREFIX_FEEDER: &[u8] = b"feeder";
+static PREFIX_FEEDERS: &[u8] = b"feeders";
+static PREFIX_FEEDER_PRICE: &[u8] = b"feeder_price";
+static PREFIX_FEED_CONFIG: &[u8] = b"feed_config";
+static PREFIX_PRICE_JUMP_CONFIG: &[u8] = b"price_jump_config";
+static PREFIX_PRICE_JUMP: &[u8] = b"price_jump";
+static PREFIX_OBSERVATION: &[u8] = b"observation";
+static PREFIX_OBSERVATION_INDEX: &[u8] = b"observation_index";
+
+/// Number of price observations kept per asset
+pub const MAX_OBSERVATIONS: u64 = 100;
 
 static KEY_CONFIG: &[u8] = b"config";
 
@@ -26,10 +36,28 @@ pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     singleton_read(storage, KEY_CONFIG).load()
 }
 
+/// Aggregated price of an asset
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PriceInfo {
     pub price: Decimal256,
     pub last_updated_time: u64,
+    /// Empty for prices stored before multiple feeders were supported
+    #[serde(default)]
+    pub feeders: Vec<CanonicalAddr>,
+}
+
+/// Last price submitted by a single feeder
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeederPrice {
+    pub price: Decimal256,
+    pub last_updated_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeedConfig {
+    pub min_feeders: u32,
+    pub max_deviation: Option<Decimal256>,
+    pub max_age: Option<u64>,
 }
 
 pub fn store_price(storage: &mut dyn Storage, asset: &str, price: &PriceInfo) -> StdResult<()> {
@@ -39,24 +67,20 @@ pub fn store_price(storage: &mut dyn Storage, asset: &str, price: &PriceInfo) ->
 
 pub fn read_price(storage: &dyn Storage, asset: &str) -> StdResult<PriceInfo> {
     let price_bucket: ReadonlyBucket<PriceInfo> = ReadonlyBucket::new(storage, PREFIX_PRICE);
-    let res = price_bucket.load(asset.as_bytes());
-    match res {
-        Ok(data) => Ok(data),
-        Err(_err) => Err(StdError::generic_err(
-            "No price data for the specified asset exist",
-        )),
-    }
+    price_bucket
+        .may_load(asset.as_bytes())?
+        .ok_or_else(|| StdError::generic_err("No price data for the specified asset exist"))
 }
 
 // settings for pagination
 const MAX_LIMIT: u32 = 30;
 const DEFAULT_LIMIT: u32 = 10;
 pub fn read_prices(
-    storage: &dyn Storage,
+    deps: Deps,
     start_after: Option<String>,
     limit: Option<u32>,
 ) -> StdResult<Vec<PricesResponseElem>> {
-    let price_bucket: ReadonlyBucket<PriceInfo> = ReadonlyBucket::new(storage, PREFIX_PRICE);
+    let price_bucket: ReadonlyBucket<PriceInfo> = ReadonlyBucket::new(deps.storage, PREFIX_PRICE);
 
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = calc_range_start(start_after);
@@ -72,31 +96,212 @@ pub fn read_prices(
                 asset,
                 price: v.price,
                 last_updated_time: v.last_updated_time,
+                feeders: v
+                    .feeders
+                    .iter()
+                    .map(|feeder| Ok(deps.api.addr_humanize(feeder)?.to_string()))
+                    .collect::<StdResult<Vec<String>>>()?,
             })
         })
         .collect()
 }
 
-pub fn store_feeder(
+pub fn store_feeders(
     storage: &mut dyn Storage,
     asset: &str,
-    feeder: &CanonicalAddr,
+    feeders: &[CanonicalAddr],
 ) -> StdResult<()> {
-    let mut price_bucket: Bucket<CanonicalAddr> = Bucket::new(storage, PREFIX_FEEDER);
-    price_bucket.save(asset.as_bytes(), feeder)
+    // the single feeder of the previous version is superseded
+    let mut feeder_bucket: Bucket<CanonicalAddr> = Bucket::new(storage, PREFIX_FEEDER);
+    feeder_bucket.remove(asset.as_bytes());
+
+    let mut feeders_bucket: Bucket<Vec<CanonicalAddr>> = Bucket::new(storage, PREFIX_FEEDERS);
+    if feeders.is_empty() {
+        feeders_bucket.remove(asset.as_bytes());
+        Ok(())
+    } else {
+        feeders_bucket.save(asset.as_bytes(), &feeders.to_vec())
+    }
 }
 
-pub fn read_feeder(storage: &dyn Storage, asset: &str) -> StdResult<CanonicalAddr> {
-    let price_bucket: ReadonlyBucket<CanonicalAddr> = ReadonlyBucket::new(storage, PREFIX_FEEDER);
-    let res = price_bucket.load(asset.as_bytes());
-    match res {
-        Ok(data) => Ok(data),
-        Err(_err) => Err(StdError::generic_err(
+pub fn read_feeders(storage: &dyn Storage, asset: &str) -> StdResult<Vec<CanonicalAddr>> {
+    let feeders_bucket: ReadonlyBucket<Vec<CanonicalAddr>> =
+        ReadonlyBucket::new(storage, PREFIX_FEEDERS);
+    if let Some(feeders) = feeders_bucket.may_load(asset.as_bytes())? {
+        return Ok(feeders);
+    }
+
+    // assets registered by the previous version have a single feeder
+    let feeder_bucket: ReadonlyBucket<CanonicalAddr> = ReadonlyBucket::new(storage, PREFIX_FEEDER);
+    match feeder_bucket.may_load(asset.as_bytes())? {
+        Some(feeder) => Ok(vec![feeder]),
+        None => Err(StdError::generic_err(
             "No feeder data for the specified asset exist",
         )),
     }
 }
 
+pub fn store_feeder_price(
+    storage: &mut dyn Storage,
+    asset: &str,
+    feeder: &CanonicalAddr,
+    price: &FeederPrice,
+) -> StdResult<()> {
+    let mut feeder_price_bucket: Bucket<FeederPrice> =
+        Bucket::multilevel(storage, &[PREFIX_FEEDER_PRICE, asset.as_bytes()]);
+    feeder_price_bucket.save(feeder.as_slice(), price)
+}
+
+pub fn remove_feeder_price(storage: &mut dyn Storage, asset: &str, feeder: &CanonicalAddr) {
+    let mut feeder_price_bucket: Bucket<FeederPrice> =
+        Bucket::multilevel(storage, &[PREFIX_FEEDER_PRICE, asset.as_bytes()]);
+    feeder_price_bucket.remove(feeder.as_slice())
+}
+
+pub fn read_feeder_price(
+    storage: &dyn Storage,
+    asset: &str,
+    feeder: &CanonicalAddr,
+) -> StdResult<Option<FeederPrice>> {
+    let feeder_price_bucket: ReadonlyBucket<FeederPrice> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_FEEDER_PRICE, asset.as_bytes()]);
+    feeder_price_bucket.may_load(feeder.as_slice())
+}
+
+pub fn store_feed_config(
+    storage: &mut dyn Storage,
+    asset: &str,
+    feed_config: &FeedConfig,
+) -> StdResult<()> {
+    let mut feed_config_bucket: Bucket<FeedConfig> = Bucket::new(storage, PREFIX_FEED_CONFIG);
+    feed_config_bucket.save(asset.as_bytes(), feed_config)
+}
+
+/// Assets without explicit config accept any single feeder submission
+pub fn read_feed_config(storage: &dyn Storage, asset: &str) -> StdResult<FeedConfig> {
+    let feed_config_bucket: ReadonlyBucket<FeedConfig> =
+        ReadonlyBucket::new(storage, PREFIX_FEED_CONFIG);
+    match feed_config_bucket.may_load(asset.as_bytes())? {
+        Some(feed_config) => Ok(feed_config),
+        None => Ok(FeedConfig {
+            min_feeders: 1,
+            max_deviation: None,
+            max_age: None,
+        }),
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PriceJumpConfig {
+    pub max_price_jump: Option<Decimal256>,
+    pub cooldown: Option<u64>,
+}
+
+/// Last detected price jump of an asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceJump {
+    pub previous_price: Decimal256,
+    pub price: Decimal256,
+    pub detected_time: u64,
+}
+
+pub fn store_price_jump_config(
+    storage: &mut dyn Storage,
+    asset: &str,
+    price_jump_config: &PriceJumpConfig,
+) -> StdResult<()> {
+    let mut config_bucket: Bucket<PriceJumpConfig> = Bucket::new(storage, PREFIX_PRICE_JUMP_CONFIG);
+    config_bucket.save(asset.as_bytes(), price_jump_config)
+}
+
+pub fn read_price_jump_config(storage: &dyn Storage, asset: &str) -> StdResult<PriceJumpConfig> {
+    let config_bucket: ReadonlyBucket<PriceJumpConfig> =
+        ReadonlyBucket::new(storage, PREFIX_PRICE_JUMP_CONFIG);
+    Ok(config_bucket
+        .may_load(asset.as_bytes())?
+        .unwrap_or_default())
+}
+
+pub fn store_price_jump(
+    storage: &mut dyn Storage,
+    asset: &str,
+    price_jump: &PriceJump,
+) -> StdResult<()> {
+    let mut price_jump_bucket: Bucket<PriceJump> = Bucket::new(storage, PREFIX_PRICE_JUMP);
+    price_jump_bucket.save(asset.as_bytes(), price_jump)
+}
+
+pub fn remove_price_jump(storage: &mut dyn Storage, asset: &str) {
+    let mut price_jump_bucket: Bucket<PriceJump> = Bucket::new(storage, PREFIX_PRICE_JUMP);
+    price_jump_bucket.remove(asset.as_bytes())
+}
+
+pub fn read_price_jump(storage: &dyn Storage, asset: &str) -> StdResult<Option<PriceJump>> {
+    let price_jump_bucket: ReadonlyBucket<PriceJump> =
+        ReadonlyBucket::new(storage, PREFIX_PRICE_JUMP);
+    price_jump_bucket.may_load(asset.as_bytes())
+}
+
+/// Aggregated price effective from `timestamp` on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceObservation {
+    pub price: Decimal256,
+    pub timestamp: u64,
+}
+
+/// Position of the ring buffer; `head` is the next slot to write
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ObservationIndex {
+    pub head: u64,
+    pub len: u64,
+}
+
+/// Appends an observation to the ring buffer of the asset, overwriting
+/// the oldest one when full. Observations within the same block
+/// replace each other.
+pub fn store_observation(
+    storage: &mut dyn Storage,
+    asset: &str,
+    observation: &PriceObservation,
+) -> StdResult<()> {
+    let index_bucket: ReadonlyBucket<ObservationIndex> =
+        ReadonlyBucket::new(storage, PREFIX_OBSERVATION_INDEX);
+    let mut index = index_bucket.may_load(asset.as_bytes())?.unwrap_or_default();
+
+    let mut observation_bucket: Bucket<PriceObservation> =
+        Bucket::multilevel(storage, &[PREFIX_OBSERVATION, asset.as_bytes()]);
+    if index.len > 0 {
+        let last_slot = (index.head + MAX_OBSERVATIONS - 1) % MAX_OBSERVATIONS;
+        let last = observation_bucket.load(&last_slot.to_be_bytes())?;
+        if last.timestamp == observation.timestamp {
+            return observation_bucket.save(&last_slot.to_be_bytes(), observation);
+        }
+    }
+
+    observation_bucket.save(&index.head.to_be_bytes(), observation)?;
+    index.head = (index.head + 1) % MAX_OBSERVATIONS;
+    index.len = (index.len + 1).min(MAX_OBSERVATIONS);
+
+    let mut index_bucket: Bucket<ObservationIndex> = Bucket::new(storage, PREFIX_OBSERVATION_INDEX);
+    index_bucket.save(asset.as_bytes(), &index)
+}
+
+/// Returns the observations of the asset, newest first
+pub fn read_observations(storage: &dyn Storage, asset: &str) -> StdResult<Vec<PriceObservation>> {
+    let index_bucket: ReadonlyBucket<ObservationIndex> =
+        ReadonlyBucket::new(storage, PREFIX_OBSERVATION_INDEX);
+    let index = index_bucket.may_load(asset.as_bytes())?.unwrap_or_default();
+
+    let observation_bucket: ReadonlyBucket<PriceObservation> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_OBSERVATION, asset.as_bytes()]);
+    (1..=index.len)
+        .map(|i| {
+            let slot = (index.head + MAX_OBSERVATIONS - i) % MAX_OBSERVATIONS;
+            observation_bucket.load(&slot.to_be_bytes())
+        })
+        .collect()
+}
+
 // this will set the first key after the provided key, by appending a 1 byte
 fn calc_range_start(start_after: Option<String>) -> Option<Vec<u8>> {
     start_after.map(|idx| {
@@ -1,12 +1,15 @@
 use crate::contract::{execute, instantiate, query};
 use crate::error::ContractError;
+use crate::state::MAX_OBSERVATIONS;
 use cosmwasm_bignumber::Decimal256;
-use cosmwasm_std::from_binary;
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{attr, from_binary, Api, Deps, DepsMut, StdError, StdResult};
+use cosmwasm_storage::Bucket;
 use moneymarket::oracle::{
-    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, PriceResponse, PricesResponse,
-    PricesResponseElem, QueryMsg,
+    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, PriceJumpResponse, PriceResponse,
+    PricesResponse, PricesResponseElem, QueryMsg, TwapResponse,
 };
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 #[test]
@@ -110,7 +113,10 @@ fn register_feeder() {
         feeder_res,
         FeederResponse {
             asset: "mAAPL".to_string(),
-            feeder: "feeder0000".to_string(),
+            feeders: vec!["feeder0000".to_string()],
+            min_feeders: 1,
+            max_deviation: None,
+            max_age: None,
         }
     );
 }
@@ -170,6 +176,8 @@ fn feed_price() {
             rate: Decimal256::from_str("1.2").unwrap(),
             last_updated_base: env.block.time.seconds(),
             last_updated_quote: 9999999999,
+            feeders_base: vec!["feeder0000".to_string()],
+            feeders_quote: vec![],
         }
     );
 
@@ -190,6 +198,8 @@ fn feed_price() {
             rate: Decimal256::from_str("1.833333333333333333").unwrap(),
             last_updated_base: env.block.time.seconds(),
             last_updated_quote: env.block.time.seconds(),
+            feeders_base: vec!["feeder0000".to_string()],
+            feeders_quote: vec!["feeder0000".to_string()],
         }
     );
 
@@ -212,11 +222,13 @@ fn feed_price() {
                     asset: "mAAPL".to_string(),
                     price: Decimal256::from_str("1.2").unwrap(),
                     last_updated_time: env.block.time.seconds(),
+                    feeders: vec!["feeder0000".to_string()],
                 },
                 PricesResponseElem {
                     asset: "mGOGL".to_string(),
                     price: Decimal256::from_str("2.2").unwrap(),
                     last_updated_time: env.block.time.seconds(),
+                    feeders: vec!["feeder0000".to_string()],
                 }
             ],
         }
@@ -234,3 +246,592 @@ fn feed_price() {
         _ => panic!("Must return unauthorized error"),
     }
 }
+
+#[test]
+fn aggregate_feeder_prices() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for feeder in ["feeder0000", "feeder0001", "feeder0002"].iter() {
+        let msg = ExecuteMsg::RegisterFeeder {
+            asset: "mAAPL".to_string(),
+            feeder: feeder.to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    // duplicate registration
+    let msg = ExecuteMsg::RegisterFeeder {
+        asset: "mAAPL".to_string(),
+        feeder: "feeder0000".to_string(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::FeederAlreadyRegistered {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // invalid config
+    let msg = ExecuteMsg::UpdateFeedConfig {
+        asset: "mAAPL".to_string(),
+        min_feeders: 0,
+        max_deviation: None,
+        max_age: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::InvalidFeedConfig {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Unauthorized err
+    let msg = ExecuteMsg::UpdateFeedConfig {
+        asset: "mAAPL".to_string(),
+        min_feeders: 2,
+        max_deviation: Some(Decimal256::percent(10)),
+        max_age: Some(60),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let feed = |deps: DepsMut, feeder: &str, price: &str, env| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("mAAPL".to_string(), Decimal256::from_str(price).unwrap())],
+        };
+        execute(deps, env, mock_info(feeder, &[]), msg).unwrap();
+    };
+    let query_price = |deps: Deps| -> StdResult<PriceResponse> {
+        query(
+            deps,
+            mock_env(),
+            QueryMsg::Price {
+                base: "mAAPL".to_string(),
+                quote: "base0000".to_string(),
+            },
+        )
+        .map(|res| from_binary(&res).unwrap())
+    };
+
+    // single submission does not reach quorum
+    let mut env = mock_env();
+    feed(deps.as_mut(), "feeder0000", "1.0", env.clone());
+    assert!(query_price(deps.as_ref()).is_err());
+
+    // two submissions are averaged
+    feed(deps.as_mut(), "feeder0001", "1.1", env.clone());
+    let value = query_price(deps.as_ref()).unwrap();
+    assert_eq!(value.rate, Decimal256::from_str("1.05").unwrap());
+    assert_eq!(
+        value.feeders_base,
+        vec!["feeder0000".to_string(), "feeder0001".to_string()]
+    );
+
+    // outlier is excluded from the median
+    feed(deps.as_mut(), "feeder0002", "5.0", env.clone());
+    let value = query_price(deps.as_ref()).unwrap();
+    assert_eq!(value.rate, Decimal256::from_str("1.05").unwrap());
+    assert_eq!(
+        value.feeders_base,
+        vec!["feeder0000".to_string(), "feeder0001".to_string()]
+    );
+
+    // stale submissions are ignored; price is kept until quorum is back
+    env.block.time = env.block.time.plus_seconds(100);
+    feed(deps.as_mut(), "feeder0002", "1.2", env.clone());
+    let value = query_price(deps.as_ref()).unwrap();
+    assert_eq!(value.rate, Decimal256::from_str("1.05").unwrap());
+    assert_eq!(value.last_updated_base, mock_env().block.time.seconds());
+
+    feed(deps.as_mut(), "feeder0000", "1.0", env.clone());
+    let value = query_price(deps.as_ref()).unwrap();
+    assert_eq!(value.rate, Decimal256::from_str("1.1").unwrap());
+    assert_eq!(value.last_updated_base, env.block.time.seconds());
+    assert_eq!(
+        value.feeders_base,
+        vec!["feeder0000".to_string(), "feeder0002".to_string()]
+    );
+
+    // deregistered feeders can no longer feed
+    let msg = ExecuteMsg::DeregisterFeeder {
+        asset: "mAAPL".to_string(),
+        feeder: "feeder0002".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("mAAPL".to_string(), Decimal256::from_str("1.2").unwrap())],
+    };
+    let res = execute(deps.as_mut(), env, mock_info("feeder0002", &[]), msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let feeder_res: FeederResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Feeder {
+                asset: "mAAPL".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        feeder_res,
+        FeederResponse {
+            asset: "mAAPL".to_string(),
+            feeders: vec!["feeder0000".to_string(), "feeder0001".to_string()],
+            min_feeders: 2,
+            max_deviation: Some(Decimal256::percent(10)),
+            max_age: Some(60),
+        }
+    );
+}
+
+#[test]
+fn stalled_feeder() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for feeder in ["feeder0000", "feeder0001", "feeder0002"].iter() {
+        let msg = ExecuteMsg::RegisterFeeder {
+            asset: "mAAPL".to_string(),
+            feeder: feeder.to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::UpdateFeedConfig {
+        asset: "mAAPL".to_string(),
+        min_feeders: 2,
+        max_deviation: None,
+        max_age: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let feed = |deps: DepsMut, feeder: &str, price: &str, env| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("mAAPL".to_string(), Decimal256::from_str(price).unwrap())],
+        };
+        execute(deps, env, mock_info(feeder, &[]), msg).unwrap();
+    };
+
+    let mut env = mock_env();
+    feed(deps.as_mut(), "feeder0000", "1.0", env.clone());
+    feed(deps.as_mut(), "feeder0001", "1.0", env.clone());
+    feed(deps.as_mut(), "feeder0002", "1.0", env.clone());
+
+    // feeder0002 stalls while the others keep reporting
+    env.block.time = env.block.time.plus_seconds(1000);
+    feed(deps.as_mut(), "feeder0000", "1.1", env.clone());
+    feed(deps.as_mut(), "feeder0001", "1.2", env.clone());
+
+    let value: PriceResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base: "mAAPL".to_string(),
+                quote: "base0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(value.rate, Decimal256::from_str("1.1").unwrap());
+    assert_eq!(value.last_updated_base, env.block.time.seconds());
+}
+
+#[test]
+fn legacy_feeder_and_price() {
+    #[derive(Serialize, Deserialize)]
+    struct LegacyPriceInfo {
+        price: Decimal256,
+        last_updated_time: u64,
+    }
+
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // feeder and price stored by the single feeder version
+    let feeder = deps.api.addr_canonicalize("feeder0000").unwrap();
+    Bucket::new(&mut deps.storage, b"feeder")
+        .save(b"mAAPL", &feeder)
+        .unwrap();
+    Bucket::new(&mut deps.storage, b"price")
+        .save(
+            b"mAAPL",
+            &LegacyPriceInfo {
+                price: Decimal256::one(),
+                last_updated_time: mock_env().block.time.seconds(),
+            },
+        )
+        .unwrap();
+
+    let query_price = |deps: Deps| -> PriceResponse {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::Price {
+                    base: "mAAPL".to_string(),
+                    quote: "base0000".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    let value = query_price(deps.as_ref());
+    assert_eq!(value.rate, Decimal256::one());
+    assert!(value.feeders_base.is_empty());
+
+    let feeder_res: FeederResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Feeder {
+                asset: "mAAPL".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(feeder_res.feeders, vec!["feeder0000".to_string()]);
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("mAAPL".to_string(), Decimal256::from_str("1.2").unwrap())],
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("feeder0000", &[]), msg).unwrap();
+
+    let value = query_price(deps.as_ref());
+    assert_eq!(value.rate, Decimal256::from_str("1.2").unwrap());
+    assert_eq!(value.feeders_base, vec!["feeder0000".to_string()]);
+
+    // registering a second feeder keeps the existing one
+    let msg = ExecuteMsg::RegisterFeeder {
+        asset: "mAAPL".to_string(),
+        feeder: "feeder0001".to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::DeregisterFeeder {
+        asset: "mAAPL".to_string(),
+        feeder: "feeder0000".to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner0000", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::FeedPrice {
+        prices: vec![("mAAPL".to_string(), Decimal256::from_str("1.3").unwrap())],
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("feeder0000", &[]), msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+}
+
+#[test]
+fn twap_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    for asset in ["mAAPL", "mGOGL"].iter() {
+        let msg = ExecuteMsg::RegisterFeeder {
+            asset: asset.to_string(),
+            feeder: "feeder0000".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let feed = |deps: DepsMut, asset: &str, price: &str, env| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![(asset.to_string(), Decimal256::from_str(price).unwrap())],
+        };
+        execute(deps, env, mock_info("feeder0000", &[]), msg).unwrap();
+    };
+    let query_twap = |deps: Deps, base: &str, quote: &str, window, env| -> TwapResponse {
+        let res = query(
+            deps,
+            env,
+            QueryMsg::Twap {
+                base: base.to_string(),
+                quote: quote.to_string(),
+                window,
+            },
+        )
+        .unwrap();
+        from_binary(&res).unwrap()
+    };
+
+    // no observations
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Twap {
+            base: "mAAPL".to_string(),
+            quote: "base0000".to_string(),
+            window: 100,
+        },
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "No price data for the specified asset exist")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let mut env = mock_env();
+    feed(deps.as_mut(), "mAAPL", "1.0", env.clone());
+    feed(deps.as_mut(), "mGOGL", "2.0", env.clone());
+
+    // latest price has not been effective yet
+    assert_eq!(
+        query_twap(deps.as_ref(), "mAAPL", "base0000", 100, env.clone()),
+        TwapResponse {
+            rate: Decimal256::from_str("1.0").unwrap(),
+            observed_window: 0,
+        }
+    );
+
+    env.block.time = env.block.time.plus_seconds(100);
+    feed(deps.as_mut(), "mAAPL", "3.0", env.clone());
+    // same block submission replaces the observation
+    feed(deps.as_mut(), "mAAPL", "2.0", env.clone());
+
+    env.block.time = env.block.time.plus_seconds(100);
+    assert_eq!(
+        query_twap(deps.as_ref(), "mAAPL", "base0000", 200, env.clone()),
+        TwapResponse {
+            rate: Decimal256::from_str("1.5").unwrap(),
+            observed_window: 200,
+        }
+    );
+    assert_eq!(
+        query_twap(deps.as_ref(), "mAAPL", "base0000", 50, env.clone()),
+        TwapResponse {
+            rate: Decimal256::from_str("2.0").unwrap(),
+            observed_window: 50,
+        }
+    );
+    assert_eq!(
+        query_twap(deps.as_ref(), "mAAPL", "mGOGL", 200, env.clone()),
+        TwapResponse {
+            rate: Decimal256::from_str("0.75").unwrap(),
+            observed_window: 200,
+        }
+    );
+    // history does not cover the whole window
+    assert_eq!(
+        query_twap(deps.as_ref(), "mAAPL", "base0000", 1000, env.clone()),
+        TwapResponse {
+            rate: Decimal256::from_str("1.5").unwrap(),
+            observed_window: 200,
+        }
+    );
+
+    // oldest observations are overwritten once the buffer is full
+    for _ in 0..MAX_OBSERVATIONS {
+        env.block.time = env.block.time.plus_seconds(10);
+        feed(deps.as_mut(), "mAAPL", "4.0", env.clone());
+    }
+
+    env.block.time = env.block.time.plus_seconds(10);
+    assert_eq!(
+        query_twap(deps.as_ref(), "mAAPL", "base0000", 100000, env),
+        TwapResponse {
+            rate: Decimal256::from_str("4.0").unwrap(),
+            observed_window: MAX_OBSERVATIONS * 10,
+        }
+    );
+}
+
+#[test]
+fn price_jump_circuit_breaker() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::RegisterFeeder {
+        asset: "mAAPL".to_string(),
+        feeder: "feeder0000".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // Unauthorized err
+    let msg = ExecuteMsg::UpdatePriceJumpConfig {
+        asset: "mAAPL".to_string(),
+        max_price_jump: Some(Decimal256::percent(20)),
+        cooldown: Some(600),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let feed = |deps: DepsMut, price: &str, env| {
+        let msg = ExecuteMsg::FeedPrice {
+            prices: vec![("mAAPL".to_string(), Decimal256::from_str(price).unwrap())],
+        };
+        execute(deps, env, mock_info("feeder0000", &[]), msg).unwrap()
+    };
+    let query_price_jump = |deps: Deps, env| -> PriceJumpResponse {
+        let res = query(
+            deps,
+            env,
+            QueryMsg::PriceJump {
+                asset: "mAAPL".to_string(),
+            },
+        )
+        .unwrap();
+        from_binary(&res).unwrap()
+    };
+
+    // first price and moves within the threshold do not trip the breaker
+    let mut env = mock_env();
+    feed(deps.as_mut(), "10.0", env.clone());
+    env.block.time = env.block.time.plus_seconds(10);
+    let res = feed(deps.as_mut(), "12.0", env.clone());
+    assert!(!res.attributes.contains(&attr("price_jump", "mAAPL")));
+    assert!(!query_price_jump(deps.as_ref(), env.clone()).active);
+
+    env.block.time = env.block.time.plus_seconds(10);
+    let res = feed(deps.as_mut(), "6.0", env.clone());
+    assert!(res.attributes.contains(&attr("price_jump", "mAAPL")));
+
+    // the jumped price is still published
+    let res: PriceResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Price {
+                base: "mAAPL".to_string(),
+                quote: "base0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.rate, Decimal256::from_str("6.0").unwrap());
+
+    assert_eq!(
+        query_price_jump(deps.as_ref(), env.clone()),
+        PriceJumpResponse {
+            asset: "mAAPL".to_string(),
+            max_price_jump: Some(Decimal256::percent(20)),
+            cooldown: Some(600),
+            active: true,
+            detected_time: Some(env.block.time.seconds()),
+        }
+    );
+
+    // expires after the cooldown
+    let detected_time = env.block.time.seconds();
+    env.block.time = env.block.time.plus_seconds(600);
+    assert_eq!(
+        query_price_jump(deps.as_ref(), env.clone()),
+        PriceJumpResponse {
+            asset: "mAAPL".to_string(),
+            max_price_jump: Some(Decimal256::percent(20)),
+            cooldown: Some(600),
+            active: false,
+            detected_time: Some(detected_time),
+        }
+    );
+
+    // without cooldown, only the owner can clear it
+    let msg = ExecuteMsg::UpdatePriceJumpConfig {
+        asset: "mAAPL".to_string(),
+        max_price_jump: Some(Decimal256::percent(20)),
+        cooldown: None,
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    feed(deps.as_mut(), "10.0", env.clone());
+    env.block.time = env.block.time.plus_seconds(1000000);
+    assert!(query_price_jump(deps.as_ref(), env.clone()).active);
+
+    let msg = ExecuteMsg::ClearPriceJump {
+        asset: "mAAPL".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        query_price_jump(deps.as_ref(), env),
+        PriceJumpResponse {
+            asset: "mAAPL".to_string(),
+            max_price_jump: Some(Decimal256::percent(20)),
+            cooldown: None,
+            active: false,
+            detected_time: None,
+        }
+    );
+}
@@ -4,9 +4,11 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use moneymarket::overseer::{
-    AllCollateralsResponse, BorrowLimitResponse, CollateralsResponse, ConfigResponse, ExecuteMsg,
-    InstantiateMsg, QueryMsg, WhitelistResponse,
+    AllCollateralsResponse, AllHealthFactorsResponse, BorrowLimitResponse, BufferRunwayResponse,
+    CollateralsResponse, ConfigResponse, EpochHistoryResponse, ExecuteMsg, HealthFactorResponse,
+    InstantiateMsg, MarketsResponse, MigrateMsg, QueryMsg, WhitelistResponse,
 };
+use moneymarket::pause::PauseInfoResponse;
 use moneymarket_overseer::state::EpochState;
 
 fn main() {
@@ -17,11 +19,18 @@ fn main() {
 
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(AllCollateralsResponse), &out_dir);
+    export_schema(&schema_for!(AllHealthFactorsResponse), &out_dir);
     export_schema(&schema_for!(BorrowLimitResponse), &out_dir);
+    export_schema(&schema_for!(BufferRunwayResponse), &out_dir);
     export_schema(&schema_for!(CollateralsResponse), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(EpochState), &out_dir);
+    export_schema(&schema_for!(EpochHistoryResponse), &out_dir);
+    export_schema(&schema_for!(HealthFactorResponse), &out_dir);
+    export_schema(&schema_for!(MarketsResponse), &out_dir);
     export_schema(&schema_for!(WhitelistResponse), &out_dir);
+    export_schema(&schema_for!(PauseInfoResponse), &out_dir);
 }
@@ -1,23 +1,36 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{
-    attr, to_binary, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg,
-    WasmMsg,
+    attr, to_binary, Addr, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    SubMsg, WasmMsg,
 };
 
 use crate::error::ContractError;
-use crate::querier::{query_borrower_info, query_liquidation_amount};
+use crate::querier::{
+    query_borrow_allowance, query_borrower_info, query_liquidation_amount, query_market_state,
+    query_pending_collaterals,
+};
 use crate::state::{
-    read_all_collaterals, read_collaterals, read_config, read_whitelist_elem, store_collaterals,
-    Config, WhitelistElem,
+    read_all_collaterals, read_borrowers_collaterals, read_collaterals, read_config,
+    read_epoch_state, read_market_elem, read_market_epoch_state, read_markets, read_pause_info,
+    read_whitelist_elem, store_collaterals, store_epoch_state, store_market_epoch_state, Config,
+    EpochState, MarketElem, PauseInfo, WhitelistElem,
 };
 
 use moneymarket::custody::ExecuteMsg as CustodyExecuteMsg;
 use moneymarket::liquidation::LiquidationAmountResponse;
-use moneymarket::market::{BorrowerInfoResponse, ExecuteMsg as MarketExecuteMsg};
-use moneymarket::oracle::PriceResponse;
-use moneymarket::overseer::{AllCollateralsResponse, BorrowLimitResponse, CollateralsResponse};
-use moneymarket::querier::{query_balance, query_price, TimeConstraints};
-use moneymarket::tokens::{Tokens, TokensHuman, TokensMath, TokensToHuman, TokensToRaw};
+use moneymarket::market::{BorrowerInfoResponse, ExecuteMsg as MarketExecuteMsg, StateResponse};
+use moneymarket::oracle::{PriceJumpResponse, PriceResponse};
+use moneymarket::overseer::{
+    AllCollateralsResponse, AllHealthFactorsResponse, BorrowLimitResponse, CollateralsResponse,
+    HealthFactorResponse,
+};
+use moneymarket::pause::{PAUSE_BORROW, PAUSE_DEPOSIT, PAUSE_LIQUIDATION, PAUSE_UNLOCK};
+use moneymarket::querier::{
+    deduct_tax, query_balance, query_price, query_price_jump, TimeConstraints,
+};
+use moneymarket::tokens::{
+    AssetInfo, AssetInfoRaw, Tokens, TokensHuman, TokensMath, TokensToHuman, TokensToRaw,
+};
 
 pub fn lock_collateral(
     deps: DepsMut,
@@ -25,10 +38,70 @@ pub fn lock_collateral(
     collaterals_human: TokensHuman,
 ) -> Result<Response, ContractError> {
     let borrower_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-    let mut cur_collaterals: Tokens = read_collaterals(deps.storage, &borrower_raw);
+    let cur_collaterals: Tokens = read_collaterals(deps.storage, &borrower_raw)?;
+
+    let collaterals: Tokens = collaterals_human.to_raw(deps.as_ref())?;
+
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    for collateral in collaterals.iter() {
+        let whitelist_elem: WhitelistElem = read_whitelist_elem(deps.storage, &collateral.0)?;
+        assert_not_paused(&pause_info, &whitelist_elem, PAUSE_DEPOSIT)?;
+    }
+
+    let messages = add_collaterals(deps, &info.sender, cur_collaterals, collaterals)?;
+
+    // Logging stuff, so can be removed
+    let collateral_logs: Vec<String> = collaterals_human
+        .iter()
+        .map(|c| format!("{}{}", c.1, c.0.to_string()))
+        .collect();
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "lock_collateral"),
+        attr("borrower", info.sender),
+        attr("collaterals", collateral_logs.join(",")),
+    ]))
+}
+
+/// Liquidation contract locks the collaterals of a cancelled auction
+/// again, after depositing them back to the custodies for the borrower
+pub fn restore_collateral(
+    deps: DepsMut,
+    info: MessageInfo,
+    borrower: Addr,
+    collaterals_human: TokensHuman,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if !read_liquidation_contracts(deps.as_ref(), &config)?.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
 
+    let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
+    let cur_collaterals: Tokens = read_collaterals(deps.storage, &borrower_raw)?;
     let collaterals: Tokens = collaterals_human.to_raw(deps.as_ref())?;
+    let messages = add_collaterals(deps, &borrower, cur_collaterals, collaterals)?;
+
+    let collateral_logs: Vec<String> = collaterals_human
+        .iter()
+        .map(|c| format!("{}{}", c.1, c.0))
+        .collect();
 
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "restore_collateral"),
+        attr("borrower", borrower),
+        attr("collaterals", collateral_logs.join(",")),
+    ]))
+}
+
+/// Adds `collaterals` to the locked collaterals of the borrower
+/// and returns the messages locking them at the custodies
+fn add_collaterals(
+    deps: DepsMut,
+    borrower: &Addr,
+    mut cur_collaterals: Tokens,
+    collaterals: Tokens,
+) -> StdResult<Vec<CosmosMsg>> {
+    let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
     cur_collaterals.add(collaterals.clone());
     store_collaterals(deps.storage, &borrower_raw, &cur_collaterals)?;
 
@@ -42,38 +115,78 @@ pub fn lock_collateral(
                 .to_string(),
             funds: vec![],
             msg: to_binary(&CustodyExecuteMsg::LockCollateral {
-                borrower: info.sender.to_string(),
+                borrower: borrower.to_string(),
                 amount: collateral.1,
             })?,
         }));
     }
 
-    // Logging stuff, so can be removed
-    let collateral_logs: Vec<String> = collaterals_human
-        .iter()
-        .map(|c| format!("{}{}", c.1, c.0.to_string()))
-        .collect();
-
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "lock_collateral"),
-        attr("borrower", info.sender),
-        attr("collaterals", collateral_logs.join(",")),
-    ]))
+    Ok(messages)
 }
 
 pub fn unlock_collateral(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    borrower: Option<Addr>,
     collaterals_human: TokensHuman,
+    stable_denom: Option<String>,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
-    let market = deps.api.addr_humanize(&config.market_contract)?;
+    let collaterals: Tokens = collaterals_human.to_raw(deps.as_ref())?;
+    let mut messages: Vec<SubMsg> = vec![];
+
+    // Unlocked collaterals stay with the borrower, whoever unlocks them
+    let borrower = borrower.unwrap_or_else(|| info.sender.clone());
+    if borrower != info.sender {
+        let stable_denom = stable_denom.unwrap_or_else(|| config.stable_denom.clone());
+        let (market_contract, _) = read_market(deps.as_ref(), &config, &stable_denom)?;
+        let allowance = query_borrow_allowance(
+            deps.as_ref(),
+            market_contract.clone(),
+            borrower.clone(),
+            info.sender.clone(),
+        )?;
+        if allowance.amount.is_zero() {
+            return Err(ContractError::NoBorrowAllowance {});
+        }
+
+        // the allowance is spent by the value of the unlocked collaterals
+        // in the stable denom of the market that granted it
+        let block_time = Some(env.block.time.seconds());
+        let (_, _, collateral_prices) =
+            compute_borrow_limit(deps.as_ref(), &collaterals, block_time)?;
+        let mut unlock_value = Uint256::zero();
+        for (collateral, price) in collaterals.iter().zip(collateral_prices) {
+            unlock_value += collateral.1 * price;
+        }
+        let unlock_value =
+            unlock_value / query_stable_price(deps.as_ref(), &config, &stable_denom, block_time)?;
+        if unlock_value > allowance.amount {
+            return Err(ContractError::UnlockExceedsAllowance(
+                allowance.amount.into(),
+            ));
+        }
+
+        messages.push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: market_contract.to_string(),
+            funds: vec![],
+            msg: to_binary(&MarketExecuteMsg::SpendBorrowAllowance {
+                borrower: borrower.to_string(),
+                spender: info.sender.to_string(),
+                amount: unlock_value,
+            })?,
+        })));
+    }
 
-    let borrower = info.sender;
     let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
-    let mut cur_collaterals: Tokens = read_collaterals(deps.storage, &borrower_raw);
-    let collaterals: Tokens = collaterals_human.to_raw(deps.as_ref())?;
+    let mut cur_collaterals: Tokens = read_collaterals(deps.storage, &borrower_raw)?;
+
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    for collateral in collaterals.iter() {
+        let whitelist_elem: WhitelistElem = read_whitelist_elem(deps.storage, &collateral.0)?;
+        assert_not_paused(&pause_info, &whitelist_elem, PAUSE_UNLOCK)?;
+    }
 
     // Underflow check is done in sub_collateral
     if cur_collaterals.sub(collaterals.clone()).is_err() {
@@ -81,20 +194,30 @@ pub fn unlock_collateral(
     }
 
     // Compute borrow limit with collaterals except unlock target collaterals
-    let (borrow_limit, _) = compute_borrow_limit(
+    let mut limit_collaterals = cur_collaterals.clone();
+    limit_collaterals.add(query_auctioned_collaterals(
         deps.as_ref(),
-        &cur_collaterals,
+        &config,
+        &borrower,
+    )?);
+    let (borrow_limit, _, _) = compute_borrow_limit(
+        deps.as_ref(),
+        &limit_collaterals,
         Some(env.block.time.seconds()),
     )?;
-    let borrow_amount_res: BorrowerInfoResponse =
-        query_borrower_info(deps.as_ref(), market, borrower.clone(), env.block.height)?;
-    if borrow_limit < borrow_amount_res.loan_amount {
+    let loans = query_loans(
+        deps.as_ref(),
+        &config,
+        &borrower,
+        env.block.height,
+        Some(env.block.time.seconds()),
+    )?;
+    if borrow_limit < loans_value(&loans, None) {
         return Err(ContractError::UnlockTooLarge(borrow_limit.into()));
     }
 
     store_collaterals(deps.storage, &borrower_raw, &cur_collaterals)?;
 
-    let mut messages: Vec<SubMsg> = vec![];
     for collateral in collaterals.clone() {
         let whitelist_elem: WhitelistElem = read_whitelist_elem(deps.storage, &collateral.0)?;
         messages.push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -130,47 +253,139 @@ pub fn liquidate_collateral(
     env: Env,
     info: MessageInfo,
     borrower: Addr,
+    collateral_token: Option<AssetInfo>,
+    stable_denom: Option<String>,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
-    let market = deps.api.addr_humanize(&config.market_contract)?;
+    let stable_denom = stable_denom.unwrap_or_else(|| config.stable_denom.clone());
+    let (market_contract, liquidation_contract) =
+        read_market(deps.as_ref(), &config, &stable_denom)?;
 
     let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
-    let mut cur_collaterals: Tokens = read_collaterals(deps.storage, &borrower_raw);
+    let mut cur_collaterals: Tokens = read_collaterals(deps.storage, &borrower_raw)?;
+
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    if pause_info.paused & PAUSE_LIQUIDATION != 0 {
+        return Err(ContractError::Paused {});
+    }
+
+    // Liquidations are paused while the oracle flags a price jump
+    let oracle_contract = deps.api.addr_humanize(&config.oracle_contract)?;
+    for collateral in cur_collaterals.iter() {
+        let collateral_token = collateral.0.to_normal(deps.api)?.to_string();
+        let price_jump: PriceJumpResponse = query_price_jump(
+            deps.as_ref(),
+            oracle_contract.clone(),
+            collateral_token.clone(),
+        )?;
+        if price_jump.active {
+            return Err(ContractError::PriceJumpDetected(collateral_token));
+        }
+    }
 
     // Compute borrow limit with collaterals except unlock target collaterals
-    let (borrow_limit, collateral_prices) = compute_borrow_limit(
+    let (mut borrow_limit, mut liquidation_limit, collateral_prices) = compute_borrow_limit(
         deps.as_ref(),
         &cur_collaterals,
         Some(env.block.time.seconds()),
     )?;
-    let borrow_amount_res: BorrowerInfoResponse =
-        query_borrower_info(deps.as_ref(), market, borrower.clone(), env.block.height)?;
-    let borrow_amount = borrow_amount_res.loan_amount;
 
-    // borrow limit is equal or bigger than loan amount
+    // Collaterals up for auction back the loan, but can not be seized again
+    let auctioned_collaterals = query_auctioned_collaterals(deps.as_ref(), &config, &borrower)?;
+    let (auctioned_borrow_limit, auctioned_liquidation_limit, _) = compute_borrow_limit(
+        deps.as_ref(),
+        &auctioned_collaterals,
+        Some(env.block.time.seconds()),
+    )?;
+    borrow_limit += auctioned_borrow_limit;
+    liquidation_limit += auctioned_liquidation_limit;
+    let loans = query_loans(
+        deps.as_ref(),
+        &config,
+        &borrower,
+        env.block.height,
+        Some(env.block.time.seconds()),
+    )?;
+    let borrow_amount = loans_value(&loans, None);
+
+    // liquidation limit is equal or bigger than loan amount
     // cannot liquidation collaterals
-    if borrow_limit >= borrow_amount {
+    if liquidation_limit >= borrow_amount {
         return Err(ContractError::CannotLiquidateSafeLoan {});
     }
 
+    // Only the loan of the chosen market is repaid
+    let market_loan_value = borrow_amount - loans_value(&loans, Some(&stable_denom));
+
+    // The liquidator may choose to seize a single collateral
+    let (target_collaterals, target_prices): (Tokens, Vec<Decimal256>) =
+        if let Some(collateral_token) = collateral_token {
+            let collateral_token_raw = collateral_token.to_raw(deps.api)?;
+            let index = cur_collaterals
+                .iter()
+                .position(|c| c.0 == collateral_token_raw)
+                .ok_or(ContractError::CollateralNotLocked {})?;
+            let whitelist_elem: WhitelistElem =
+                read_whitelist_elem(deps.storage, &collateral_token_raw)?;
+            assert_not_paused(&pause_info, &whitelist_elem, PAUSE_LIQUIDATION)?;
+
+            (
+                vec![cur_collaterals[index].clone()],
+                vec![collateral_prices[index]],
+            )
+        } else {
+            // Collaterals paused for liquidation are left untouched
+            let mut target_collaterals: Tokens = vec![];
+            let mut target_prices: Vec<Decimal256> = vec![];
+            for (collateral, price) in cur_collaterals.iter().zip(collateral_prices) {
+                let whitelist_elem: WhitelistElem =
+                    read_whitelist_elem(deps.storage, &collateral.0)?;
+                if whitelist_elem.paused & PAUSE_LIQUIDATION == 0 {
+                    target_collaterals.push(collateral.clone());
+                    target_prices.push(price);
+                }
+            }
+
+            if target_collaterals.is_empty() {
+                return Err(ContractError::Paused {});
+            }
+
+            (target_collaterals, target_prices)
+        };
+
     let liquidation_amount_res: LiquidationAmountResponse = query_liquidation_amount(
         deps.as_ref(),
-        deps.api.addr_humanize(&config.liquidation_contract)?,
+        liquidation_contract.clone(),
         borrow_amount,
         borrow_limit,
-        &cur_collaterals.to_human(deps.as_ref())?,
-        collateral_prices,
+        &target_collaterals.to_human(deps.as_ref())?,
+        target_prices.clone(),
     )?;
 
-    let liquidation_amount = liquidation_amount_res.collaterals.to_raw(deps.as_ref())?;
+    let liquidation_amount = apply_close_factor(
+        liquidation_amount_res.collaterals.to_raw(deps.as_ref())?,
+        &target_collaterals,
+        &target_prices,
+        market_loan_value * config.close_factor,
+    );
 
     // Store left collaterals
     cur_collaterals.sub(liquidation_amount.clone())?;
     store_collaterals(deps.storage, &borrower_raw, &cur_collaterals)?;
 
-    let market_contract = deps.api.addr_humanize(&config.market_contract)?;
     let prev_balance: Uint256 =
-        query_balance(deps.as_ref(), market_contract.clone(), config.stable_denom)?;
+        query_balance(deps.as_ref(), market_contract.clone(), stable_denom.clone())?;
+
+    // Custody contracts default to the liquidation contract and market of the base denom
+    let (custody_liquidation_contract, custody_market_contract) =
+        if stable_denom == config.stable_denom {
+            (None, None)
+        } else {
+            (
+                Some(liquidation_contract.to_string()),
+                Some(market_contract.to_string()),
+            )
+        };
 
     let liquidation_messages: Vec<CosmosMsg> = liquidation_amount
         .iter()
@@ -187,6 +402,8 @@ pub fn liquidate_collateral(
                     liquidator: info.sender.to_string(),
                     borrower: borrower.to_string(),
                     amount: collateral.1,
+                    liquidation_contract: custody_liquidation_contract.clone(),
+                    market_contract: custody_market_contract.clone(),
                 })?,
             }))
         })
@@ -205,18 +422,122 @@ pub fn liquidate_collateral(
         })))
 }
 
+/// Anyone can write off the loans left behind once all collaterals of a
+/// borrower are liquidated. At each market, the interest buffer held in its
+/// denom covers the part of the loss the market reserves can not, and the
+/// rest is socialized
+pub fn write_off_bad_debt(
+    deps: DepsMut,
+    env: Env,
+    borrower: Addr,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+
+    let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
+    let cur_collaterals: Tokens = read_collaterals(deps.storage, &borrower_raw)?;
+    if cur_collaterals.iter().any(|c| !c.1.is_zero()) {
+        return Err(ContractError::CollateralNotExhausted {});
+    }
+
+    let mut markets: Vec<(String, Addr)> = vec![(
+        config.stable_denom.clone(),
+        deps.api.addr_humanize(&config.market_contract)?,
+    )];
+    for (stable_denom, market_elem) in read_markets(deps.storage)? {
+        markets.push((
+            stable_denom,
+            deps.api.addr_humanize(&market_elem.market_contract)?,
+        ));
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![
+        attr("action", "write_off_bad_debt"),
+        attr("borrower", borrower.clone()),
+    ];
+    for (stable_denom, market) in markets {
+        let borrow_amount_res: BorrowerInfoResponse = query_borrower_info(
+            deps.as_ref(),
+            market.clone(),
+            borrower.clone(),
+            env.block.height,
+        )?;
+        let bad_debt = borrow_amount_res.loan_amount;
+        if bad_debt.is_zero() {
+            continue;
+        }
+
+        // market reserves cover the loss first
+        let market_state: StateResponse =
+            query_market_state(deps.as_ref(), market.clone(), env.block.height)?;
+        let reserve_cover = std::cmp::min(market_state.total_reserves * Uint256::one(), bad_debt);
+
+        let interest_buffer = query_balance(
+            deps.as_ref(),
+            env.contract.address.clone(),
+            stable_denom.clone(),
+        )?;
+        let buffer_cover = std::cmp::min(interest_buffer, bad_debt - reserve_cover);
+
+        // spent buffer must not be counted as accrued buffer in the next epoch
+        let mut epoch_state: EpochState = if stable_denom == config.stable_denom {
+            read_epoch_state(deps.storage)?
+        } else {
+            read_market_epoch_state(deps.storage, &stable_denom)?
+        };
+        epoch_state.prev_interest_buffer = if epoch_state.prev_interest_buffer > buffer_cover {
+            epoch_state.prev_interest_buffer - buffer_cover
+        } else {
+            Uint256::zero()
+        };
+        if stable_denom == config.stable_denom {
+            store_epoch_state(deps.storage, &epoch_state)?;
+        } else {
+            store_market_epoch_state(deps.storage, &stable_denom, &epoch_state)?;
+        }
+
+        let funds: Vec<Coin> = if buffer_cover.is_zero() {
+            vec![]
+        } else {
+            vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: stable_denom.clone(),
+                    amount: buffer_cover.into(),
+                },
+            )?]
+        };
+
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: market.to_string(),
+            funds,
+            msg: to_binary(&MarketExecuteMsg::WriteOffBadDebt {
+                borrower: borrower.to_string(),
+            })?,
+        }));
+        attributes.push(attr("stable_denom", stable_denom));
+        attributes.push(attr("bad_debt", bad_debt));
+        attributes.push(attr("buffer_cover", buffer_cover));
+    }
+
+    if messages.is_empty() {
+        return Err(ContractError::NoBadDebt {});
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
 pub fn query_collaterals(deps: Deps, borrower: Addr) -> StdResult<CollateralsResponse> {
     let collaterals: Tokens = read_collaterals(
         deps.storage,
         &deps.api.addr_canonicalize(borrower.as_str())?,
-    );
+    )?;
 
     Ok(CollateralsResponse {
         borrower: borrower.to_string(),
-        collaterals: collaterals
-            .iter()
-            .map(|c| Ok((deps.api.addr_humanize(&c.0)?.to_string(), c.1)))
-            .collect::<StdResult<Vec<(String, Uint256)>>>()?,
+        collaterals: collaterals.to_human(deps)?,
     })
 }
 
@@ -236,25 +557,76 @@ pub fn query_all_collaterals(
     Ok(AllCollateralsResponse { all_collaterals })
 }
 
+/// Scales down the liquidated collaterals so that their value
+/// does not exceed `max_repay_amount`
+#[allow(clippy::ptr_arg)]
+fn apply_close_factor(
+    liquidation_amount: Tokens,
+    collaterals: &Tokens,
+    collateral_prices: &[Decimal256],
+    max_repay_amount: Uint256,
+) -> Tokens {
+    let price_of = |token: &AssetInfoRaw| {
+        collaterals
+            .iter()
+            .position(|c| &c.0 == token)
+            .map(|index| collateral_prices[index])
+            .unwrap_or_else(Decimal256::zero)
+    };
+
+    let mut liquidation_value = Uint256::zero();
+    for collateral in liquidation_amount.iter() {
+        liquidation_value += collateral.1 * price_of(&collateral.0);
+    }
+
+    if liquidation_value <= max_repay_amount {
+        return liquidation_amount;
+    }
+
+    let ratio =
+        Decimal256::from_uint256(max_repay_amount) / Decimal256::from_uint256(liquidation_value);
+    liquidation_amount
+        .into_iter()
+        .map(|(token, amount)| (token, amount * ratio))
+        .filter(|c| !c.1.is_zero())
+        .collect()
+}
+
+/// Fails when the operation is paused globally or for the collateral
+fn assert_not_paused(
+    pause_info: &PauseInfo,
+    whitelist_elem: &WhitelistElem,
+    flag: u8,
+) -> Result<(), ContractError> {
+    if (pause_info.paused | whitelist_elem.paused) & flag != 0 {
+        return Err(ContractError::Paused {});
+    }
+
+    Ok(())
+}
+
+/// Returns the borrow limit (`max_ltv`), the liquidation limit
+/// (`liquidation_threshold`) and the collateral prices
 #[allow(clippy::ptr_arg)]
 pub(crate) fn compute_borrow_limit(
     deps: Deps,
     collaterals: &Tokens,
     block_time: Option<u64>,
-) -> StdResult<(Uint256, Vec<Decimal256>)> {
+) -> StdResult<(Uint256, Uint256, Vec<Decimal256>)> {
     let config: Config = read_config(deps.storage)?;
     let oracle_contract = deps.api.addr_humanize(&config.oracle_contract)?;
 
     let mut borrow_limit: Uint256 = Uint256::zero();
+    let mut liquidation_limit: Uint256 = Uint256::zero();
     let mut collateral_prices: Vec<Decimal256> = vec![];
     for collateral in collaterals.iter() {
-        let collateral_token = collateral.0.clone();
+        let collateral_token = collateral.0.to_normal(deps.api)?;
         let collateral_amount = collateral.1;
 
         let price: PriceResponse = query_price(
             deps,
             oracle_contract.clone(),
-            (deps.api.addr_humanize(&collateral_token)?).to_string(),
+            collateral_token.to_string(),
             config.stable_denom.to_string(),
             block_time.map(|block_time| TimeConstraints {
                 block_time,
@@ -265,28 +637,293 @@ pub(crate) fn compute_borrow_limit(
         let elem: WhitelistElem = read_whitelist_elem(deps.storage, &collateral.0)?;
         let collateral_value = collateral_amount * price.rate;
         borrow_limit += collateral_value * elem.max_ltv;
+        liquidation_limit += collateral_value * elem.liquidation_threshold;
         collateral_prices.push(price.rate);
     }
 
     // returns borrow_limit with collaterals value in stable denom
-    Ok((borrow_limit, collateral_prices))
+    Ok((borrow_limit, liquidation_limit, collateral_prices))
 }
 
 pub fn query_borrow_limit(
     deps: Deps,
+    env: Env,
     borrower: Addr,
     block_time: Option<u64>,
+    stable_denom: Option<String>,
 ) -> StdResult<BorrowLimitResponse> {
-    let collaterals = read_collaterals(
+    let config: Config = read_config(deps.storage)?;
+    let mut borrower_collaterals: Tokens = read_collaterals(
         deps.storage,
         &deps.api.addr_canonicalize(borrower.as_str())?,
-    );
+    )?;
+    borrower_collaterals.add(query_auctioned_collaterals(deps, &config, &borrower)?);
+
+    // Collaterals paused for borrowing do not back new loans
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    let mut collaterals: Tokens = vec![];
+    for collateral in borrower_collaterals {
+        let whitelist_elem: WhitelistElem = read_whitelist_elem(deps.storage, &collateral.0)?;
+        if (pause_info.paused | whitelist_elem.paused) & PAUSE_BORROW == 0 {
+            collaterals.push(collateral);
+        }
+    }
 
     // Compute borrow limit with collaterals
-    let (borrow_limit, _) = compute_borrow_limit(deps, &collaterals, block_time)?;
+    let (mut borrow_limit, _, _) = compute_borrow_limit(deps, &collaterals, block_time)?;
+
+    // Loans at the other markets use up the shared borrow limit
+    if let Some(stable_denom) = stable_denom {
+        read_market(deps, &config, &stable_denom)?;
+
+        let loans = query_loans(deps, &config, &borrower, env.block.height, block_time)?;
+        let other_loans_value = loans_value(&loans, Some(&stable_denom));
+        let available = if borrow_limit > other_loans_value {
+            borrow_limit - other_loans_value
+        } else {
+            Uint256::zero()
+        };
+
+        let price = query_stable_price(deps, &config, &stable_denom, block_time)?;
+        borrow_limit = if price.is_zero() {
+            Uint256::zero()
+        } else {
+            available / price
+        };
+    }
 
     Ok(BorrowLimitResponse {
         borrower: borrower.to_string(),
         borrow_limit,
     })
 }
+
+pub fn query_health_factor(
+    deps: Deps,
+    env: Env,
+    borrower: Addr,
+    block_time: Option<u64>,
+) -> StdResult<HealthFactorResponse> {
+    let collaterals: Tokens = read_collaterals(
+        deps.storage,
+        &deps.api.addr_canonicalize(borrower.as_str())?,
+    )?;
+
+    compute_health_factor(deps, &env, borrower, &collaterals, block_time)
+}
+
+pub fn query_all_health_factors(
+    deps: Deps,
+    env: Env,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    block_time: Option<u64>,
+) -> StdResult<AllHealthFactorsResponse> {
+    read_health_factors(deps, env, start_after, limit, block_time, None)
+}
+
+pub fn query_at_risk(
+    deps: Deps,
+    env: Env,
+    max_health: Decimal256,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    block_time: Option<u64>,
+) -> StdResult<AllHealthFactorsResponse> {
+    read_health_factors(deps, env, start_after, limit, block_time, Some(max_health))
+}
+
+fn read_health_factors(
+    deps: Deps,
+    env: Env,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    block_time: Option<u64>,
+    max_health: Option<Decimal256>,
+) -> StdResult<AllHealthFactorsResponse> {
+    let start_after = if let Some(start_after) = start_after {
+        Some(deps.api.addr_canonicalize(start_after.as_str())?)
+    } else {
+        None
+    };
+
+    let borrowers = read_borrowers_collaterals(deps.storage, start_after, limit)?;
+    let last_scanned = match borrowers.last() {
+        Some((borrower, _)) => Some(deps.api.addr_humanize(borrower)?.to_string()),
+        None => None,
+    };
+
+    let mut health_factors: Vec<HealthFactorResponse> = vec![];
+    for (borrower, collaterals) in borrowers {
+        let borrower = deps.api.addr_humanize(&borrower)?;
+        let health_factor = compute_health_factor(deps, &env, borrower, &collaterals, block_time)?;
+
+        // borrowers without a loan are never at risk
+        let at_risk = match (max_health, health_factor.health_factor) {
+            (None, _) => true,
+            (Some(max_health), Some(health)) => health <= max_health,
+            (Some(_), None) => false,
+        };
+
+        if at_risk {
+            health_factors.push(health_factor);
+        }
+    }
+
+    Ok(AllHealthFactorsResponse {
+        health_factors,
+        last_scanned,
+    })
+}
+
+#[allow(clippy::ptr_arg)]
+fn compute_health_factor(
+    deps: Deps,
+    env: &Env,
+    borrower: Addr,
+    collaterals: &Tokens,
+    block_time: Option<u64>,
+) -> StdResult<HealthFactorResponse> {
+    let config: Config = read_config(deps.storage)?;
+
+    let mut collaterals = collaterals.clone();
+    collaterals.add(query_auctioned_collaterals(deps, &config, &borrower)?);
+    let (borrow_limit, liquidation_limit, _) =
+        compute_borrow_limit(deps, &collaterals, block_time)?;
+    let loans = query_loans(deps, &config, &borrower, env.block.height, block_time)?;
+    let loan_amount = loans_value(&loans, None);
+
+    let health_factor = if loan_amount.is_zero() {
+        None
+    } else {
+        Some(Decimal256::from_uint256(liquidation_limit) / Decimal256::from_uint256(loan_amount))
+    };
+
+    Ok(HealthFactorResponse {
+        borrower: borrower.to_string(),
+        loan_amount,
+        borrow_limit,
+        liquidation_limit,
+        health_factor,
+        liquidatable: loan_amount > liquidation_limit,
+    })
+}
+
+/// Returns the borrower's collaterals put up for auction by the liquidation
+/// contracts. They are no longer locked, but still back the loan until sold
+fn query_auctioned_collaterals(deps: Deps, config: &Config, borrower: &Addr) -> StdResult<Tokens> {
+    let mut collaterals: Tokens = vec![];
+    for liquidation_contract in read_liquidation_contracts(deps, config)? {
+        let pending_collaterals =
+            query_pending_collaterals(deps, liquidation_contract, borrower.clone())?.collaterals;
+        collaterals.add(pending_collaterals.to_raw(deps)?);
+    }
+
+    Ok(collaterals)
+}
+
+/// Liquidation contracts of the base and the registered markets
+fn read_liquidation_contracts(deps: Deps, config: &Config) -> StdResult<Vec<Addr>> {
+    let mut liquidation_contracts = vec![deps.api.addr_humanize(&config.liquidation_contract)?];
+    for (_, market_elem) in read_markets(deps.storage)? {
+        let liquidation_contract = deps.api.addr_humanize(&market_elem.liquidation_contract)?;
+        if !liquidation_contracts.contains(&liquidation_contract) {
+            liquidation_contracts.push(liquidation_contract);
+        }
+    }
+
+    Ok(liquidation_contracts)
+}
+
+/// Returns the market and liquidation contracts of the stable denom
+fn read_market(deps: Deps, config: &Config, stable_denom: &str) -> StdResult<(Addr, Addr)> {
+    if stable_denom == config.stable_denom {
+        return Ok((
+            deps.api.addr_humanize(&config.market_contract)?,
+            deps.api.addr_humanize(&config.liquidation_contract)?,
+        ));
+    }
+
+    let market_elem: MarketElem = read_market_elem(deps.storage, stable_denom)?;
+    Ok((
+        deps.api.addr_humanize(&market_elem.market_contract)?,
+        deps.api.addr_humanize(&market_elem.liquidation_contract)?,
+    ))
+}
+
+/// Price of the stable denom in the base denom
+fn query_stable_price(
+    deps: Deps,
+    config: &Config,
+    stable_denom: &str,
+    block_time: Option<u64>,
+) -> StdResult<Decimal256> {
+    if stable_denom == config.stable_denom {
+        return Ok(Decimal256::one());
+    }
+
+    let price: PriceResponse = query_price(
+        deps,
+        deps.api.addr_humanize(&config.oracle_contract)?,
+        stable_denom.to_string(),
+        config.stable_denom.to_string(),
+        block_time.map(|block_time| TimeConstraints {
+            block_time,
+            valid_timeframe: config.price_timeframe,
+        }),
+    )?;
+
+    Ok(price.rate)
+}
+
+/// Returns the borrower's loans at the base market and every
+/// registered market, with the price of their stable denom
+fn query_loans(
+    deps: Deps,
+    config: &Config,
+    borrower: &Addr,
+    block_height: u64,
+    block_time: Option<u64>,
+) -> StdResult<Vec<(String, Uint256, Decimal256)>> {
+    let base_loan: BorrowerInfoResponse = query_borrower_info(
+        deps,
+        deps.api.addr_humanize(&config.market_contract)?,
+        borrower.clone(),
+        block_height,
+    )?;
+
+    let mut loans = vec![(
+        config.stable_denom.clone(),
+        base_loan.loan_amount,
+        Decimal256::one(),
+    )];
+    for (stable_denom, market_elem) in read_markets(deps.storage)? {
+        let loan_amount = query_borrower_info(
+            deps,
+            deps.api.addr_humanize(&market_elem.market_contract)?,
+            borrower.clone(),
+            block_height,
+        )?
+        .loan_amount;
+
+        // stable denoms without a loan need no oracle price
+        if !loan_amount.is_zero() {
+            let price = query_stable_price(deps, config, &stable_denom, block_time)?;
+            loans.push((stable_denom, loan_amount, price));
+        }
+    }
+
+    Ok(loans)
+}
+
+/// Value of the loans in the base denom, leaving out the `exclude` market
+fn loans_value(loans: &[(String, Uint256, Decimal256)], exclude: Option<&str>) -> Uint256 {
+    let mut value = Uint256::zero();
+    for (stable_denom, loan_amount, price) in loans.iter() {
+        if exclude != Some(stable_denom.as_str()) {
+            value += *loan_amount * *price;
+        }
+    }
+
+    value
+}
@@ -6,14 +6,18 @@ use cosmwasm_std::{
 };
 
 use crate::collateral::{
-    liquidate_collateral, lock_collateral, query_all_collaterals, query_borrow_limit,
-    query_collaterals, unlock_collateral,
+    liquidate_collateral, lock_collateral, query_all_collaterals, query_all_health_factors,
+    query_at_risk, query_borrow_limit, query_collaterals, query_health_factor, restore_collateral,
+    unlock_collateral, write_off_bad_debt,
 };
 use crate::error::ContractError;
 use crate::querier::query_epoch_state;
 use crate::state::{
-    read_config, read_epoch_state, read_whitelist, read_whitelist_elem, store_config,
-    store_epoch_state, store_whitelist_elem, Config, EpochState, WhitelistElem,
+    migrate_whitelist, read_config, read_epoch_history, read_epoch_state,
+    read_last_epoch_history_elem, read_market_elem, read_market_epoch_state, read_markets,
+    read_pause_info, read_whitelist, read_whitelist_elem, store_config, store_epoch_history_elem,
+    store_epoch_state, store_market_elem, store_market_epoch_state, store_pause_info,
+    store_whitelist_elem, Config, EpochState, MarketElem, PauseInfo, WhitelistElem,
 };
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
@@ -22,9 +26,13 @@ use moneymarket::custody::ExecuteMsg as CustodyExecuteMsg;
 use moneymarket::market::EpochStateResponse;
 use moneymarket::market::ExecuteMsg as MarketExecuteMsg;
 use moneymarket::overseer::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, WhitelistResponse, WhitelistResponseElem,
+    BufferRunwayResponse, ConfigResponse, EpochHistoryElem, EpochHistoryResponse, ExecuteMsg,
+    InstantiateMsg, MarketResponseElem, MarketsResponse, MigrateMsg, QueryMsg, WhitelistResponse,
+    WhitelistResponseElem,
 };
+use moneymarket::pause::{is_guardian_pause, is_valid_pause, PauseInfoResponse};
 use moneymarket::querier::{deduct_tax, query_balance};
+use moneymarket::tokens::AssetInfo;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -48,6 +56,7 @@ pub fn instantiate(
             buffer_distribution_factor: msg.buffer_distribution_factor,
             anc_purchase_factor: msg.anc_purchase_factor,
             price_timeframe: msg.price_timeframe,
+            close_factor: msg.close_factor,
         },
     )?;
 
@@ -83,6 +92,7 @@ pub fn execute(
             anc_purchase_factor,
             epoch_period,
             price_timeframe,
+            close_factor,
         } => {
             let api = deps.api;
             update_config(
@@ -97,6 +107,7 @@ pub fn execute(
                 anc_purchase_factor,
                 epoch_period,
                 price_timeframe,
+                close_factor,
             )
         }
         ExecuteMsg::Whitelist {
@@ -105,6 +116,8 @@ pub fn execute(
             collateral_token,
             custody_contract,
             max_ltv,
+            liquidation_threshold,
+            deposit_cap,
         } => {
             let api = deps.api;
             register_whitelist(
@@ -112,37 +125,138 @@ pub fn execute(
                 info,
                 name,
                 symbol,
-                api.addr_validate(&collateral_token)?,
+                collateral_token,
                 api.addr_validate(&custody_contract)?,
                 max_ltv,
+                liquidation_threshold,
+                deposit_cap,
             )
         }
         ExecuteMsg::UpdateWhitelist {
             collateral_token,
             custody_contract,
             max_ltv,
+            liquidation_threshold,
+            deposit_cap,
         } => {
             let api = deps.api;
             update_whitelist(
                 deps,
                 info,
-                api.addr_validate(&collateral_token)?,
+                collateral_token,
                 optional_addr_validate(api, custody_contract)?,
                 max_ltv,
+                liquidation_threshold,
+                deposit_cap,
             )
         }
+        ExecuteMsg::RegisterMarket {
+            stable_denom,
+            market_contract,
+            liquidation_contract,
+        } => {
+            let api = deps.api;
+            register_market(
+                deps,
+                env,
+                info,
+                stable_denom,
+                api.addr_validate(&market_contract)?,
+                api.addr_validate(&liquidation_contract)?,
+            )
+        }
+        ExecuteMsg::UpdateMarket {
+            stable_denom,
+            market_contract,
+            liquidation_contract,
+        } => {
+            let api = deps.api;
+            update_market(
+                deps,
+                env,
+                info,
+                stable_denom,
+                optional_addr_validate(api, market_contract)?,
+                optional_addr_validate(api, liquidation_contract)?,
+            )
+        }
+        ExecuteMsg::UpdateGuardian { guardian } => {
+            let api = deps.api;
+            update_guardian(deps, info, optional_addr_validate(api, guardian)?)
+        }
+        ExecuteMsg::UpdatePause { paused } => update_pause(deps, info, paused),
+        ExecuteMsg::UpdateCollateralPause {
+            collateral_token,
+            paused,
+        } => update_collateral_pause(deps, info, collateral_token, paused),
         ExecuteMsg::ExecuteEpochOperations {} => execute_epoch_operations(deps, env),
         ExecuteMsg::UpdateEpochState {
             interest_buffer,
             distributed_interest,
-        } => update_epoch_state(deps, env, info, interest_buffer, distributed_interest),
+            accrued_buffer,
+            anc_purchase_amount,
+        } => update_epoch_state(
+            deps,
+            env,
+            info,
+            interest_buffer,
+            distributed_interest,
+            accrued_buffer,
+            anc_purchase_amount,
+        ),
+        ExecuteMsg::UpdateMarketEpochState {
+            stable_denom,
+            interest_buffer,
+            distributed_interest,
+        } => update_market_epoch_state(
+            deps,
+            env,
+            info,
+            stable_denom,
+            interest_buffer,
+            distributed_interest,
+        ),
         ExecuteMsg::LockCollateral { collaterals } => lock_collateral(deps, info, collaterals),
-        ExecuteMsg::UnlockCollateral { collaterals } => {
-            unlock_collateral(deps, env, info, collaterals)
+        ExecuteMsg::RestoreCollateral {
+            borrower,
+            collaterals,
+        } => {
+            let borrower_addr = deps.api.addr_validate(&borrower)?;
+            restore_collateral(deps, info, borrower_addr, collaterals)
         }
-        ExecuteMsg::LiquidateCollateral { borrower } => {
+        ExecuteMsg::UnlockCollateral {
+            collaterals,
+            borrower,
+            stable_denom,
+        } => {
             let api = deps.api;
-            liquidate_collateral(deps, env, info, api.addr_validate(&borrower)?)
+            unlock_collateral(
+                deps,
+                env,
+                info,
+                optional_addr_validate(api, borrower)?,
+                collaterals,
+                stable_denom,
+            )
+        }
+        ExecuteMsg::LiquidateCollateral {
+            borrower,
+            collateral_token,
+            stable_denom,
+        } => {
+            let api = deps.api;
+            liquidate_collateral(
+                deps,
+                env,
+                info,
+                api.addr_validate(&borrower)?,
+                collateral_token,
+                stable_denom,
+            )
+        }
+        ExecuteMsg::WriteOffBadDebt { borrower } => {
+            let api = deps.api;
+            write_off_bad_debt(deps, env, api.addr_validate(&borrower)?)
         }
     }
 }
@@ -160,6 +274,7 @@ pub fn update_config(
     anc_purchase_factor: Option<Decimal256>,
     epoch_period: Option<u64>,
     price_timeframe: Option<u64>,
+    close_factor: Option<Decimal256>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = read_config(deps.storage)?;
 
@@ -205,30 +320,42 @@ pub fn update_config(
         config.price_timeframe = price_timeframe;
     }
 
+    if let Some(close_factor) = close_factor {
+        config.close_factor = close_factor;
+    }
+
     store_config(deps.storage, &config)?;
 
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn register_whitelist(
     deps: DepsMut,
     info: MessageInfo,
     name: String,
     symbol: String,
-    collateral_token: Addr,
+    collateral_token: AssetInfo,
     custody_contract: Addr,
     max_ltv: Decimal256,
+    liquidation_threshold: Decimal256,
+    deposit_cap: Option<Uint256>,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner_addr {
         return Err(ContractError::Unauthorized {});
     }
 
-    let collateral_token_raw = deps.api.addr_canonicalize(collateral_token.as_str())?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
     if read_whitelist_elem(deps.storage, &collateral_token_raw).is_ok() {
         return Err(ContractError::TokenAlreadyRegistered {});
     }
 
+    assert_liquidation_threshold(max_ltv, liquidation_threshold)?;
+
+    // a zero cap means no cap, as in update_whitelist
+    let sync_deposit_cap = deposit_cap.is_some();
+    let deposit_cap = deposit_cap.filter(|cap| !cap.is_zero());
     store_whitelist_elem(
         deps.storage,
         &collateral_token_raw,
@@ -236,36 +363,55 @@ pub fn register_whitelist(
             name: name.to_string(),
             symbol: symbol.to_string(),
             custody_contract: deps.api.addr_canonicalize(custody_contract.as_str())?,
+            collateral_token: collateral_token_raw.clone(),
             max_ltv,
+            liquidation_threshold,
+            paused: 0,
+            deposit_cap,
         },
     )?;
 
-    Ok(Response::new().add_attributes(vec![
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if sync_deposit_cap {
+        messages.push(update_deposit_cap_msg(
+            custody_contract.to_string(),
+            deposit_cap,
+        )?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "register_whitelist"),
         attr("name", name),
         attr("symbol", symbol),
-        attr("collateral_token", collateral_token),
+        attr("collateral_token", collateral_token.to_string()),
         attr("custody_contract", custody_contract),
         attr("LTV", max_ltv.to_string()),
+        attr("liquidation_threshold", liquidation_threshold.to_string()),
     ]))
 }
 
 pub fn update_whitelist(
     deps: DepsMut,
     info: MessageInfo,
-    collateral_token: Addr,
+    collateral_token: AssetInfo,
     custody_contract: Option<Addr>,
     max_ltv: Option<Decimal256>,
+    liquidation_threshold: Option<Decimal256>,
+    deposit_cap: Option<Uint256>,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner_addr {
         return Err(ContractError::Unauthorized {});
     }
 
-    let collateral_token_raw = deps.api.addr_canonicalize(collateral_token.as_str())?;
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
     let mut whitelist_elem: WhitelistElem =
         read_whitelist_elem(deps.storage, &collateral_token_raw)?;
 
+    // the custody contract keeps its own copy of the deposit cap,
+    // so push it whenever the cap or the custody contract changes
+    let sync_deposit_cap = custody_contract.is_some() || deposit_cap.is_some();
+
     if let Some(custody_contract) = custody_contract {
         whitelist_elem.custody_contract = deps.api.addr_canonicalize(custody_contract.as_str())?;
     }
@@ -274,16 +420,262 @@ pub fn update_whitelist(
         whitelist_elem.max_ltv = max_ltv;
     }
 
+    if let Some(liquidation_threshold) = liquidation_threshold {
+        whitelist_elem.liquidation_threshold = liquidation_threshold;
+    }
+
+    assert_liquidation_threshold(whitelist_elem.max_ltv, whitelist_elem.liquidation_threshold)?;
+
+    if let Some(deposit_cap) = deposit_cap {
+        whitelist_elem.deposit_cap = if deposit_cap.is_zero() {
+            None
+        } else {
+            Some(deposit_cap)
+        };
+    }
+
     store_whitelist_elem(deps.storage, &collateral_token_raw, &whitelist_elem)?;
 
-    Ok(Response::new().add_attributes(vec![
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if sync_deposit_cap {
+        messages.push(update_deposit_cap_msg(
+            deps.api
+                .addr_humanize(&whitelist_elem.custody_contract)?
+                .to_string(),
+            whitelist_elem.deposit_cap,
+        )?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "update_whitelist"),
-        attr("collateral_token", collateral_token),
+        attr("collateral_token", collateral_token.to_string()),
         attr(
             "custody_contract",
             deps.api.addr_humanize(&whitelist_elem.custody_contract)?,
         ),
         attr("LTV", whitelist_elem.max_ltv.to_string()),
+        attr(
+            "liquidation_threshold",
+            whitelist_elem.liquidation_threshold.to_string(),
+        ),
+    ]))
+}
+
+fn assert_liquidation_threshold(
+    max_ltv: Decimal256,
+    liquidation_threshold: Decimal256,
+) -> Result<(), ContractError> {
+    if liquidation_threshold < max_ltv || liquidation_threshold > Decimal256::one() {
+        return Err(ContractError::InvalidLiquidationThreshold {});
+    }
+
+    Ok(())
+}
+
+fn update_deposit_cap_msg(
+    custody_contract: String,
+    deposit_cap: Option<Uint256>,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: custody_contract,
+        funds: vec![],
+        msg: to_binary(&CustodyExecuteMsg::UpdateDepositCap { deposit_cap })?,
+    }))
+}
+
+pub fn register_market(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stable_denom: String,
+    market_contract: Addr,
+    liquidation_contract: Addr,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if stable_denom == config.stable_denom {
+        return Err(ContractError::BaseStableDenom {});
+    }
+
+    if read_market_elem(deps.storage, &stable_denom).is_ok() {
+        return Err(ContractError::MarketAlreadyRegistered {});
+    }
+
+    store_market_elem(
+        deps.storage,
+        &stable_denom,
+        &MarketElem {
+            market_contract: deps.api.addr_canonicalize(market_contract.as_str())?,
+            liquidation_contract: deps.api.addr_canonicalize(liquidation_contract.as_str())?,
+        },
+    )?;
+
+    // The epochs of the market start from its registration
+    start_market_epochs(deps, &env, &stable_denom, market_contract.clone())?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_market"),
+        attr("stable_denom", stable_denom),
+        attr("market_contract", market_contract),
+        attr("liquidation_contract", liquidation_contract),
+    ]))
+}
+
+pub fn update_market(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stable_denom: String,
+    market_contract: Option<Addr>,
+    liquidation_contract: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut market_elem: MarketElem = read_market_elem(deps.storage, &stable_denom)?;
+    if let Some(liquidation_contract) = liquidation_contract {
+        market_elem.liquidation_contract =
+            deps.api.addr_canonicalize(liquidation_contract.as_str())?;
+    }
+
+    if let Some(market_contract) = &market_contract {
+        market_elem.market_contract = deps.api.addr_canonicalize(market_contract.as_str())?;
+    }
+
+    store_market_elem(deps.storage, &stable_denom, &market_elem)?;
+
+    let res = Response::new().add_attributes(vec![
+        attr("action", "update_market"),
+        attr("stable_denom", stable_denom.clone()),
+        attr(
+            "market_contract",
+            deps.api.addr_humanize(&market_elem.market_contract)?,
+        ),
+        attr(
+            "liquidation_contract",
+            deps.api.addr_humanize(&market_elem.liquidation_contract)?,
+        ),
+    ]);
+
+    // The epoch state tracks the exchange rate of the market contract
+    if let Some(market_contract) = market_contract {
+        start_market_epochs(deps, &env, &stable_denom, market_contract)?;
+    }
+
+    Ok(res)
+}
+
+fn start_market_epochs(
+    deps: DepsMut,
+    env: &Env,
+    stable_denom: &str,
+    market_contract: Addr,
+) -> StdResult<()> {
+    let epoch_state: EpochStateResponse =
+        query_epoch_state(deps.as_ref(), market_contract, env.block.height, None)?;
+    let interest_buffer = query_balance(
+        deps.as_ref(),
+        env.contract.address.clone(),
+        stable_denom.to_string(),
+    )?;
+    store_market_epoch_state(
+        deps.storage,
+        stable_denom,
+        &EpochState {
+            deposit_rate: Decimal256::zero(),
+            prev_aterra_supply: epoch_state.aterra_supply,
+            prev_exchange_rate: epoch_state.exchange_rate,
+            prev_interest_buffer: interest_buffer,
+            last_executed_height: env.block.height,
+        },
+    )
+}
+
+pub fn update_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    pause_info.guardian = match guardian {
+        Some(guardian) => Some(deps.api.addr_canonicalize(guardian.as_str())?),
+        None => None,
+    };
+
+    store_pause_info(deps.storage, &pause_info)?;
+    Ok(Response::new().add_attributes(vec![attr("action", "update_guardian")]))
+}
+
+pub fn update_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: u8,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let mut pause_info: PauseInfo = read_pause_info(deps.storage)?;
+
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let is_guardian = pause_info.guardian.as_ref() == Some(&sender_raw);
+    if sender_raw != config.owner_addr
+        && !(is_guardian && is_guardian_pause(pause_info.paused, paused))
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !is_valid_pause(paused) {
+        return Err(ContractError::InvalidPause {});
+    }
+
+    pause_info.paused = paused;
+    store_pause_info(deps.storage, &pause_info)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_pause"),
+        attr("paused", paused.to_string()),
+    ]))
+}
+
+pub fn update_collateral_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    collateral_token: AssetInfo,
+    paused: u8,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+
+    let collateral_token_raw = collateral_token.to_raw(deps.api)?;
+    let mut whitelist_elem: WhitelistElem =
+        read_whitelist_elem(deps.storage, &collateral_token_raw)?;
+
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let is_guardian = pause_info.guardian.as_ref() == Some(&sender_raw);
+    if sender_raw != config.owner_addr
+        && !(is_guardian && is_guardian_pause(whitelist_elem.paused, paused))
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !is_valid_pause(paused) {
+        return Err(ContractError::InvalidPause {});
+    }
+
+    whitelist_elem.paused = paused;
+    store_whitelist_elem(deps.storage, &collateral_token_raw, &whitelist_elem)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_collateral_pause"),
+        attr("collateral_token", collateral_token.to_string()),
+        attr("paused", paused.to_string()),
     ]))
 }
 
@@ -294,17 +686,112 @@ pub fn execute_epoch_operations(deps: DepsMut, env: Env) -> Result<Response, Con
         return Err(ContractError::EpochNotPassed(state.last_executed_height));
     }
 
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let market_contract = deps.api.addr_humanize(&config.market_contract)?;
+    let epoch_operations = compute_epoch_operations(
+        deps.as_ref(),
+        &env,
+        &config,
+        &config.stable_denom,
+        &market_contract,
+        &state,
+        &mut messages,
+    )?;
+
+    // Execute DistributeRewards
+    let whitelist: Vec<WhitelistResponseElem> = read_whitelist(deps.as_ref(), None, None)?;
+    for elem in whitelist.iter() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: elem.custody_contract.clone(),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::DistributeRewards {})?,
+        }));
+    }
+
+    // TODO: Should this become a reply? If so which SubMsg to make reply_on?
+    // Execute store epoch state operation
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        funds: vec![],
+        msg: to_binary(&ExecuteMsg::UpdateEpochState {
+            interest_buffer: epoch_operations.interest_buffer,
+            distributed_interest: epoch_operations.distributed_interest,
+            accrued_buffer: epoch_operations.accrued_buffer,
+            anc_purchase_amount: epoch_operations.anc_purchase_amount,
+        })?,
+    }));
+
+    // Registered markets are served by the buffer held in their own denom
+    for (stable_denom, market_elem) in read_markets(deps.storage)? {
+        let market_state: EpochState = read_market_epoch_state(deps.storage, &stable_denom)?;
+        if market_state.last_executed_height >= env.block.height {
+            continue;
+        }
+
+        let market_operations = compute_epoch_operations(
+            deps.as_ref(),
+            &env,
+            &config,
+            &stable_denom,
+            &deps.api.addr_humanize(&market_elem.market_contract)?,
+            &market_state,
+            &mut messages,
+        )?;
+
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::UpdateMarketEpochState {
+                stable_denom,
+                interest_buffer: market_operations.interest_buffer,
+                distributed_interest: market_operations.distributed_interest,
+            })?,
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "epoch_operations"),
+        attr("deposit_rate", epoch_operations.deposit_rate.to_string()),
+        attr(
+            "exchange_rate",
+            epoch_operations.epoch_state.exchange_rate.to_string(),
+        ),
+        attr("aterra_supply", epoch_operations.epoch_state.aterra_supply),
+        attr(
+            "distributed_interest",
+            epoch_operations.distributed_interest,
+        ),
+        attr("anc_purchase_amount", epoch_operations.anc_purchase_amount),
+    ]))
+}
+
+/// Interest buffer operations of a market over an epoch
+struct EpochOperations {
+    deposit_rate: Decimal256,
+    epoch_state: EpochStateResponse,
+    interest_buffer: Uint256,
+    distributed_interest: Uint256,
+    accrued_buffer: Uint256,
+    anc_purchase_amount: Uint256,
+}
+
+/// Buys ANC with part of the buffer accrued in `stable_denom` and sends the
+/// market part of the buffer when its deposit rate falls below the threshold
+fn compute_epoch_operations(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    stable_denom: &str,
+    market_contract: &Addr,
+    state: &EpochState,
+    messages: &mut Vec<CosmosMsg>,
+) -> StdResult<EpochOperations> {
     // # of blocks from the last executed height
     let blocks = Uint256::from(env.block.height - state.last_executed_height);
 
     // Compute next epoch state
-    let market_contract = deps.api.addr_humanize(&config.market_contract)?;
-    let epoch_state: EpochStateResponse = query_epoch_state(
-        deps.as_ref(),
-        market_contract.clone(),
-        env.block.height,
-        None,
-    )?;
+    let epoch_state: EpochStateResponse =
+        query_epoch_state(deps, market_contract.clone(), env.block.height, None)?;
 
     // effective_deposit_rate = cur_exchange_rate / prev_exchange_rate
     // deposit_rate = (effective_deposit_rate - 1) / blocks
@@ -312,12 +799,8 @@ pub fn execute_epoch_operations(deps: DepsMut, env: Env) -> Result<Response, Con
     let deposit_rate =
         (effective_deposit_rate - Decimal256::one()) / Decimal256::from_uint256(blocks);
 
-    let mut messages: Vec<CosmosMsg> = vec![];
-    let mut interest_buffer = query_balance(
-        deps.as_ref(),
-        env.contract.address.clone(),
-        config.stable_denom.to_string(),
-    )?;
+    let mut interest_buffer =
+        query_balance(deps, env.contract.address.clone(), stable_denom.to_string())?;
 
     // Send accrued_buffer * config.anc_purchase_factor amount stable token to collector
     let accrued_buffer = interest_buffer - state.prev_interest_buffer;
@@ -329,9 +812,9 @@ pub fn execute_epoch_operations(deps: DepsMut, env: Env) -> Result<Response, Con
                 .addr_humanize(&config.collector_contract)?
                 .to_string(),
             amount: vec![deduct_tax(
-                deps.as_ref(),
+                deps,
                 Coin {
-                    denom: config.stable_denom.to_string(),
+                    denom: stable_denom.to_string(),
                     amount: anc_purchase_amount.into(),
                 },
             )?],
@@ -362,9 +845,9 @@ pub fn execute_epoch_operations(deps: DepsMut, env: Env) -> Result<Response, Con
             // deduct tax
             distributed_interest = Uint256::from(
                 deduct_tax(
-                    deps.as_ref(),
+                    deps,
                     Coin {
-                        denom: config.stable_denom.to_string(),
+                        denom: stable_denom.to_string(),
                         amount: distributed_interest.into(),
                     },
                 )?
@@ -375,42 +858,78 @@ pub fn execute_epoch_operations(deps: DepsMut, env: Env) -> Result<Response, Con
             messages.push(CosmosMsg::Bank(BankMsg::Send {
                 to_address: market_contract.to_string(),
                 amount: vec![Coin {
-                    denom: config.stable_denom,
+                    denom: stable_denom.to_string(),
                     amount: distributed_interest.into(),
                 }],
             }));
         }
     }
 
-    // Execute DistributeRewards
-    let whitelist: Vec<WhitelistResponseElem> = read_whitelist(deps.as_ref(), None, None)?;
-    for elem in whitelist.iter() {
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: elem.custody_contract.clone(),
-            funds: vec![],
-            msg: to_binary(&CustodyExecuteMsg::DistributeRewards {})?,
-        }));
-    }
+    Ok(EpochOperations {
+        deposit_rate,
+        epoch_state,
+        interest_buffer,
+        distributed_interest,
+        accrued_buffer,
+        anc_purchase_amount,
+    })
+}
 
-    // TODO: Should this become a reply? If so which SubMsg to make reply_on?
-    // Execute store epoch state operation
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: env.contract.address.to_string(),
+/// Epoch state of a market once the distributed interest has arrived
+fn compute_next_epoch_state(
+    deps: Deps,
+    env: &Env,
+    market_contract: Addr,
+    prev_epoch_state: &EpochState,
+    interest_buffer: Uint256,
+    distributed_interest: Uint256,
+) -> StdResult<(EpochState, EpochStateResponse)> {
+    // # of blocks from the last executed height
+    let blocks = Uint256::from(env.block.height - prev_epoch_state.last_executed_height);
+
+    // Compute next epoch state
+    let market_epoch_state: EpochStateResponse = query_epoch_state(
+        deps,
+        market_contract,
+        env.block.height,
+        Some(distributed_interest),
+    )?;
+
+    // effective_deposit_rate = cur_exchange_rate / prev_exchange_rate
+    // deposit_rate = (effective_deposit_rate - 1) / blocks
+    let effective_deposit_rate =
+        market_epoch_state.exchange_rate / prev_epoch_state.prev_exchange_rate;
+    let deposit_rate =
+        (effective_deposit_rate - Decimal256::one()) / Decimal256::from_uint256(blocks);
+
+    Ok((
+        EpochState {
+            last_executed_height: env.block.height,
+            prev_aterra_supply: market_epoch_state.aterra_supply,
+            prev_exchange_rate: market_epoch_state.exchange_rate,
+            prev_interest_buffer: interest_buffer,
+            deposit_rate,
+        },
+        market_epoch_state,
+    ))
+}
+
+fn market_epoch_operations_msg(
+    config: &Config,
+    market_contract: &Addr,
+    deposit_rate: Decimal256,
+    distributed_interest: Uint256,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: market_contract.to_string(),
         funds: vec![],
-        msg: to_binary(&ExecuteMsg::UpdateEpochState {
-            interest_buffer,
+        msg: to_binary(&MarketExecuteMsg::ExecuteEpochOperations {
+            deposit_rate,
+            target_deposit_rate: config.target_deposit_rate,
+            threshold_deposit_rate: config.threshold_deposit_rate,
             distributed_interest,
         })?,
-    }));
-
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "epoch_operations"),
-        attr("deposit_rate", deposit_rate.to_string()),
-        attr("exchange_rate", epoch_state.exchange_rate.to_string()),
-        attr("aterra_supply", epoch_state.aterra_supply),
-        attr("distributed_interest", distributed_interest),
-        attr("anc_purchase_amount", anc_purchase_amount),
-    ]))
+    }))
 }
 
 pub fn update_epoch_state(
@@ -421,6 +940,9 @@ pub fn update_epoch_state(
     // pass interest_buffer from execute_epoch_operations
     interest_buffer: Uint256,
     distributed_interest: Uint256,
+    // Only recorded in the epoch history
+    accrued_buffer: Uint256,
+    anc_purchase_amount: Uint256,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
     let overseer_epoch_state: EpochState = read_epoch_state(deps.storage)?;
@@ -428,48 +950,42 @@ pub fn update_epoch_state(
         return Err(ContractError::Unauthorized {});
     }
 
-    // # of blocks from the last executed height
-    let blocks = Uint256::from(env.block.height - overseer_epoch_state.last_executed_height);
-
-    // Compute next epoch state
     let market_contract = deps.api.addr_humanize(&config.market_contract)?;
-    let market_epoch_state: EpochStateResponse = query_epoch_state(
+    let (epoch_state, market_epoch_state) = compute_next_epoch_state(
         deps.as_ref(),
+        &env,
         market_contract.clone(),
-        env.block.height,
-        Some(distributed_interest),
+        &overseer_epoch_state,
+        interest_buffer,
+        distributed_interest,
     )?;
-
-    // effective_deposit_rate = cur_exchange_rate / prev_exchange_rate
-    // deposit_rate = (effective_deposit_rate - 1) / blocks
-    let effective_deposit_rate =
-        market_epoch_state.exchange_rate / overseer_epoch_state.prev_exchange_rate;
-    let deposit_rate =
-        (effective_deposit_rate - Decimal256::one()) / Decimal256::from_uint256(blocks);
+    let deposit_rate = epoch_state.deposit_rate;
 
     // store updated epoch state
-    store_epoch_state(
+    store_epoch_state(deps.storage, &epoch_state)?;
+
+    store_epoch_history_elem(
         deps.storage,
-        &EpochState {
-            last_executed_height: env.block.height,
-            prev_aterra_supply: market_epoch_state.aterra_supply,
-            prev_exchange_rate: market_epoch_state.exchange_rate,
-            prev_interest_buffer: interest_buffer,
+        &EpochHistoryElem {
+            height: env.block.height,
+            time: env.block.time.seconds(),
             deposit_rate,
+            exchange_rate: market_epoch_state.exchange_rate,
+            aterra_supply: market_epoch_state.aterra_supply,
+            interest_buffer,
+            accrued_buffer,
+            anc_purchase_amount,
+            distributed_interest,
         },
     )?;
 
     Ok(Response::new()
-        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: market_contract.to_string(),
-            funds: vec![],
-            msg: to_binary(&MarketExecuteMsg::ExecuteEpochOperations {
-                deposit_rate,
-                target_deposit_rate: config.target_deposit_rate,
-                threshold_deposit_rate: config.threshold_deposit_rate,
-                distributed_interest,
-            })?,
-        }))
+        .add_message(market_epoch_operations_msg(
+            &config,
+            &market_contract,
+            deposit_rate,
+            distributed_interest,
+        )?)
         .add_attributes(vec![
             attr("action", "update_epoch_state"),
             attr("deposit_rate", deposit_rate.to_string()),
@@ -482,8 +998,56 @@ pub fn update_epoch_state(
         ]))
 }
 
+pub fn update_market_epoch_state(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stable_denom: String,
+    interest_buffer: Uint256,
+    distributed_interest: Uint256,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let market_elem: MarketElem = read_market_elem(deps.storage, &stable_denom)?;
+    let prev_epoch_state: EpochState = read_market_epoch_state(deps.storage, &stable_denom)?;
+
+    let market_contract = deps.api.addr_humanize(&market_elem.market_contract)?;
+    let (epoch_state, market_epoch_state) = compute_next_epoch_state(
+        deps.as_ref(),
+        &env,
+        market_contract.clone(),
+        &prev_epoch_state,
+        interest_buffer,
+        distributed_interest,
+    )?;
+    let deposit_rate = epoch_state.deposit_rate;
+    store_market_epoch_state(deps.storage, &stable_denom, &epoch_state)?;
+
+    Ok(Response::new()
+        .add_message(market_epoch_operations_msg(
+            &config,
+            &market_contract,
+            deposit_rate,
+            distributed_interest,
+        )?)
+        .add_attributes(vec![
+            attr("action", "update_market_epoch_state"),
+            attr("stable_denom", stable_denom),
+            attr("deposit_rate", deposit_rate.to_string()),
+            attr("aterra_supply", market_epoch_state.aterra_supply),
+            attr(
+                "exchange_rate",
+                market_epoch_state.exchange_rate.to_string(),
+            ),
+            attr("interest_buffer", interest_buffer),
+        ]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::EpochState {} => to_binary(&query_state(deps)?),
@@ -493,8 +1057,8 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             limit,
         } => to_binary(&query_whitelist(
             deps,
-            optional_addr_validate(deps.api, collateral_token)?,
-            optional_addr_validate(deps.api, start_after)?,
+            collateral_token,
+            start_after,
             limit,
         )?),
         QueryMsg::Collaterals { borrower } => to_binary(&query_collaterals(
@@ -509,11 +1073,53 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::BorrowLimit {
             borrower,
             block_time,
+            stable_denom,
         } => to_binary(&query_borrow_limit(
             deps,
+            env,
+            deps.api.addr_validate(&borrower)?,
+            block_time,
+            stable_denom,
+        )?),
+        QueryMsg::HealthFactor {
+            borrower,
+            block_time,
+        } => to_binary(&query_health_factor(
+            deps,
+            env,
             deps.api.addr_validate(&borrower)?,
             block_time,
         )?),
+        QueryMsg::AllHealthFactors {
+            start_after,
+            limit,
+            block_time,
+        } => to_binary(&query_all_health_factors(
+            deps,
+            env,
+            optional_addr_validate(deps.api, start_after)?,
+            limit,
+            block_time,
+        )?),
+        QueryMsg::AtRisk {
+            max_health,
+            start_after,
+            limit,
+            block_time,
+        } => to_binary(&query_at_risk(
+            deps,
+            env,
+            max_health,
+            optional_addr_validate(deps.api, start_after)?,
+            limit,
+            block_time,
+        )?),
+        QueryMsg::PauseInfo {} => to_binary(&query_pause_info(deps)?),
+        QueryMsg::Markets {} => to_binary(&query_markets(deps)?),
+        QueryMsg::EpochHistory { start_after, limit } => {
+            to_binary(&query_epoch_history(deps, start_after, limit)?)
+        }
+        QueryMsg::BufferRunway {} => to_binary(&query_buffer_runway(deps, env)?),
     }
 }
 
@@ -538,6 +1144,7 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         buffer_distribution_factor: config.buffer_distribution_factor,
         anc_purchase_factor: config.anc_purchase_factor,
         price_timeframe: config.price_timeframe,
+        close_factor: config.close_factor,
     })
 }
 
@@ -545,32 +1152,118 @@ pub fn query_state(deps: Deps) -> StdResult<EpochState> {
     read_epoch_state(deps.storage)
 }
 
+pub fn query_epoch_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<EpochHistoryResponse> {
+    Ok(EpochHistoryResponse {
+        epochs: read_epoch_history(deps.storage, start_after, limit)?,
+    })
+}
+
+/// Replays the interest buffer distribution of `execute_epoch_operations`
+/// at the current deposit rate, with the buffer inflow of the last epoch
+pub fn query_buffer_runway(deps: Deps, env: Env) -> StdResult<BufferRunwayResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let state: EpochState = read_epoch_state(deps.storage)?;
+
+    let interest_buffer = query_balance(
+        deps,
+        env.contract.address.clone(),
+        config.stable_denom.to_string(),
+    )?;
+
+    // deposit_rate = (cur_exchange_rate / prev_exchange_rate - 1) / blocks
+    let market_epoch_state: EpochStateResponse = query_epoch_state(
+        deps,
+        deps.api.addr_humanize(&config.market_contract)?,
+        env.block.height,
+        None,
+    )?;
+    let blocks = env.block.height - state.last_executed_height;
+    let deposit_rate = if blocks == 0 {
+        state.deposit_rate
+    } else {
+        let effective_deposit_rate = market_epoch_state.exchange_rate / state.prev_exchange_rate;
+        (effective_deposit_rate - Decimal256::one())
+            / Decimal256::from_uint256(Uint256::from(blocks))
+    };
+
+    // missing_deposits = deposits * missing_deposit_rate(_per_block) * blocks
+    let missing_deposits = if deposit_rate < config.threshold_deposit_rate {
+        let deposits = market_epoch_state.aterra_supply * market_epoch_state.exchange_rate;
+        deposits
+            * Uint256::from(config.epoch_period)
+            * (config.threshold_deposit_rate - deposit_rate)
+    } else {
+        Uint256::zero()
+    };
+
+    let buffer_inflow = match read_last_epoch_history_elem(deps.storage)? {
+        Some(epoch) => epoch.accrued_buffer - epoch.anc_purchase_amount,
+        None => Uint256::zero(),
+    };
+
+    // Each epoch distributes at most buffer_distribution_factor of the buffer,
+    // so the buffer covers the k-th epoch while
+    // (interest_buffer + buffer_inflow - (k - 1) * drawdown) * factor >= missing_deposits
+    let runway_epochs = if missing_deposits <= buffer_inflow {
+        None
+    } else if config.buffer_distribution_factor.is_zero() {
+        Some(Uint256::zero())
+    } else {
+        let drawdown = missing_deposits - buffer_inflow;
+        let available = interest_buffer + buffer_inflow;
+        let required = missing_deposits / config.buffer_distribution_factor;
+        if available < required {
+            Some(Uint256::zero())
+        } else {
+            Some(
+                Uint256::one()
+                    * (Decimal256::from_uint256(available - required)
+                        / Decimal256::from_uint256(drawdown))
+                    + Uint256::one(),
+            )
+        }
+    };
+
+    Ok(BufferRunwayResponse {
+        interest_buffer,
+        deposit_rate,
+        missing_deposits,
+        buffer_inflow,
+        runway_epochs,
+    })
+}
+
 pub fn query_whitelist(
     deps: Deps,
-    collateral_token: Option<Addr>,
-    start_after: Option<Addr>,
+    collateral_token: Option<AssetInfo>,
+    start_after: Option<AssetInfo>,
     limit: Option<u32>,
 ) -> StdResult<WhitelistResponse> {
     if let Some(collateral_token) = collateral_token {
-        let whitelist_elem: WhitelistElem = read_whitelist_elem(
-            deps.storage,
-            &deps.api.addr_canonicalize(collateral_token.as_str())?,
-        )?;
+        let whitelist_elem: WhitelistElem =
+            read_whitelist_elem(deps.storage, &collateral_token.to_raw(deps.api)?)?;
         Ok(WhitelistResponse {
             elems: vec![WhitelistResponseElem {
                 name: whitelist_elem.name,
                 symbol: whitelist_elem.symbol,
                 max_ltv: whitelist_elem.max_ltv,
+                liquidation_threshold: whitelist_elem.liquidation_threshold,
                 custody_contract: deps
                     .api
                     .addr_humanize(&whitelist_elem.custody_contract)?
                     .to_string(),
-                collateral_token: collateral_token.to_string(),
+                collateral_token,
+                paused: whitelist_elem.paused,
+                deposit_cap: whitelist_elem.deposit_cap,
             }],
         })
     } else {
         let start_after = if let Some(start_after) = start_after {
-            Some(deps.api.addr_canonicalize(start_after.as_str())?)
+            Some(start_after.to_raw(deps.api)?)
         } else {
             None
         };
@@ -579,3 +1272,52 @@ pub fn query_whitelist(
         Ok(WhitelistResponse { elems: whitelist })
     }
 }
+
+pub fn query_pause_info(deps: Deps) -> StdResult<PauseInfoResponse> {
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    Ok(PauseInfoResponse {
+        guardian: match pause_info.guardian {
+            Some(guardian) => Some(deps.api.addr_humanize(&guardian)?.to_string()),
+            None => None,
+        },
+        paused: pause_info.paused,
+    })
+}
+
+pub fn query_markets(deps: Deps) -> StdResult<MarketsResponse> {
+    let config: Config = read_config(deps.storage)?;
+
+    let mut markets: Vec<MarketResponseElem> = vec![MarketResponseElem {
+        stable_denom: config.stable_denom,
+        market_contract: deps.api.addr_humanize(&config.market_contract)?.to_string(),
+        liquidation_contract: deps
+            .api
+            .addr_humanize(&config.liquidation_contract)?
+            .to_string(),
+    }];
+    for (stable_denom, market_elem) in read_markets(deps.storage)? {
+        markets.push(MarketResponseElem {
+            stable_denom,
+            market_contract: deps
+                .api
+                .addr_humanize(&market_elem.market_contract)?
+                .to_string(),
+            liquidation_contract: deps
+                .api
+                .addr_humanize(&market_elem.liquidation_contract)?
+                .to_string(),
+        });
+    }
+
+    Ok(MarketsResponse { markets })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let migrated_whitelist = migrate_whitelist(deps.storage)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("migrated_whitelist", migrated_whitelist.to_string()),
+    ]))
+}
@@ -15,9 +15,42 @@ pub enum ContractError {
     #[error("Cannot liquidate safely collateralized loan")]
     CannotLiquidateSafeLoan {},
 
+    #[error("Collateral is not locked by the borrower")]
+    CollateralNotLocked {},
+
+    #[error("Borrower still has collaterals locked")]
+    CollateralNotExhausted {},
+
+    #[error("Borrower has no bad debt")]
+    NoBadDebt {},
+
+    #[error("Sender has no borrow allowance of the borrower")]
+    NoBorrowAllowance {},
+
+    #[error("Unlocked collateral value exceeds the borrow allowance: {0}")]
+    UnlockExceedsAllowance(u128),
+
+    #[error("Liquidations are paused; price jump detected for {0}")]
+    PriceJumpDetected(String),
+
     #[error("An epoch has not passed yet; last executed height: {0}")]
     EpochNotPassed(u64),
 
+    #[error("Liquidation threshold must be between max LTV and 1")]
+    InvalidLiquidationThreshold {},
+
+    #[error("Invalid pause bitmask")]
+    InvalidPause {},
+
+    #[error("Operation is paused")]
+    Paused {},
+
+    #[error("The base stable denom is served by the configured market")]
+    BaseStableDenom {},
+
+    #[error("A market is already registered for this stable denom")]
+    MarketAlreadyRegistered {},
+
     #[error("Token is already registered as collateral")]
     TokenAlreadyRegistered {},
 
@@ -1,8 +1,13 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{to_binary, Addr, Deps, QueryRequest, StdResult, WasmQuery};
 
-use moneymarket::liquidation::{LiquidationAmountResponse, QueryMsg as LiquidationQueryMsg};
-use moneymarket::market::{BorrowerInfoResponse, EpochStateResponse, QueryMsg as MarketQueryMsg};
+use moneymarket::liquidation::{
+    LiquidationAmountResponse, PendingCollateralsResponse, QueryMsg as LiquidationQueryMsg,
+};
+use moneymarket::market::{
+    BorrowAllowanceResponse, BorrowerInfoResponse, EpochStateResponse, QueryMsg as MarketQueryMsg,
+    StateResponse,
+};
 use moneymarket::tokens::TokensHuman;
 
 pub fn query_epoch_state(
@@ -42,6 +47,39 @@ pub fn query_borrower_info(
     Ok(borrower_amount)
 }
 
+pub fn query_borrow_allowance(
+    deps: Deps,
+    market_addr: Addr,
+    borrower: Addr,
+    spender: Addr,
+) -> StdResult<BorrowAllowanceResponse> {
+    let allowance: BorrowAllowanceResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: market_addr.to_string(),
+            msg: to_binary(&MarketQueryMsg::BorrowAllowance {
+                borrower: borrower.to_string(),
+                spender: spender.to_string(),
+            })?,
+        }))?;
+
+    Ok(allowance)
+}
+
+pub fn query_market_state(
+    deps: Deps,
+    market_addr: Addr,
+    block_height: u64,
+) -> StdResult<StateResponse> {
+    let state: StateResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: market_addr.to_string(),
+        msg: to_binary(&MarketQueryMsg::State {
+            block_height: Some(block_height),
+        })?,
+    }))?;
+
+    Ok(state)
+}
+
 #[allow(clippy::ptr_arg)]
 pub fn query_liquidation_amount(
     deps: Deps,
@@ -64,3 +102,19 @@ pub fn query_liquidation_amount(
 
     Ok(liquidation_amount_res)
 }
+
+pub fn query_pending_collaterals(
+    deps: Deps,
+    liquidation_contract: Addr,
+    borrower: Addr,
+) -> StdResult<PendingCollateralsResponse> {
+    let pending_collaterals_res: PendingCollateralsResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: liquidation_contract.to_string(),
+            msg: to_binary(&LiquidationQueryMsg::PendingCollaterals {
+                borrower: borrower.to_string(),
+            })?,
+        }))?;
+
+    Ok(pending_collaterals_res)
+}
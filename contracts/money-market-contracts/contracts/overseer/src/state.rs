@@ -5,14 +5,18 @@ use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{CanonicalAddr, Deps, Order, StdError, StdResult, Storage};
 use cosmwasm_storage::{Bucket, ReadonlyBucket, ReadonlySingleton, Singleton};
 
-use moneymarket::overseer::{CollateralsResponse, WhitelistResponseElem};
-use moneymarket::tokens::Tokens;
+use moneymarket::overseer::{CollateralsResponse, EpochHistoryElem, WhitelistResponseElem};
+use moneymarket::tokens::{AssetInfoRaw, Tokens, TokensHuman, TokensToHuman};
 
 const KEY_CONFIG: &[u8] = b"config";
 const KEY_EPOCH_STATE: &[u8] = b"epoch_state";
+const KEY_PAUSE_INFO: &[u8] = b"pause_info";
 
 const PREFIX_WHITELIST: &[u8] = b"whitelist";
 const PREFIX_COLLATERALS: &[u8] = b"collateral";
+const PREFIX_MARKET: &[u8] = b"market";
+const PREFIX_MARKET_EPOCH_STATE: &[u8] = b"market_epoch_state";
+const PREFIX_EPOCH_HISTORY: &[u8] = b"epoch_history";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -28,6 +32,13 @@ pub struct Config {
     pub buffer_distribution_factor: Decimal256,
     pub anc_purchase_factor: Decimal256,
     pub price_timeframe: u64,
+    /// Missing from configs stored before close factors were introduced
+    #[serde(default = "default_close_factor")]
+    pub close_factor: Decimal256,
+}
+
+fn default_close_factor() -> Decimal256 {
+    Decimal256::one()
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -44,7 +55,27 @@ pub struct WhitelistElem {
     pub name: String,
     pub symbol: String,
     pub max_ltv: Decimal256,
+    pub liquidation_threshold: Decimal256,
     pub custody_contract: CanonicalAddr,
+    pub collateral_token: AssetInfoRaw,
+    pub paused: u8,
+    pub deposit_cap: Option<Uint256>,
+}
+
+/// Whitelist element stored before liquidation thresholds were introduced
+#[derive(Serialize, Deserialize)]
+struct LegacyWhitelistElem {
+    name: String,
+    symbol: String,
+    max_ltv: Decimal256,
+    custody_contract: CanonicalAddr,
+}
+
+/// Market of a stable denom other than the base denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketElem {
+    pub market_contract: CanonicalAddr,
+    pub liquidation_contract: CanonicalAddr,
 }
 
 pub fn store_config(storage: &mut dyn Storage, data: &Config) -> StdResult<()> {
@@ -63,48 +94,144 @@ pub fn read_epoch_state(storage: &dyn Storage) -> StdResult<EpochState> {
     ReadonlySingleton::new(storage, KEY_EPOCH_STATE).load()
 }
 
+pub fn store_epoch_history_elem(
+    storage: &mut dyn Storage,
+    epoch_history_elem: &EpochHistoryElem,
+) -> StdResult<()> {
+    let mut epoch_history_bucket: Bucket<EpochHistoryElem> =
+        Bucket::new(storage, PREFIX_EPOCH_HISTORY);
+    epoch_history_bucket.save(&epoch_history_elem.height.to_be_bytes(), epoch_history_elem)
+}
+
+pub fn read_epoch_history(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<EpochHistoryElem>> {
+    let epoch_history_bucket: ReadonlyBucket<EpochHistoryElem> =
+        ReadonlyBucket::new(storage, PREFIX_EPOCH_HISTORY);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|height| (height + 1).to_be_bytes().to_vec());
+
+    epoch_history_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|elem| {
+            let (_, v) = elem?;
+            Ok(v)
+        })
+        .collect()
+}
+
+pub fn read_last_epoch_history_elem(storage: &dyn Storage) -> StdResult<Option<EpochHistoryElem>> {
+    let epoch_history_bucket: ReadonlyBucket<EpochHistoryElem> =
+        ReadonlyBucket::new(storage, PREFIX_EPOCH_HISTORY);
+
+    let last = epoch_history_bucket
+        .range(None, None, Order::Descending)
+        .next();
+    match last {
+        Some(elem) => Ok(Some(elem?.1)),
+        None => Ok(None),
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseInfo {
+    pub guardian: Option<CanonicalAddr>,
+    pub paused: u8,
+}
+
+pub fn store_pause_info(storage: &mut dyn Storage, data: &PauseInfo) -> StdResult<()> {
+    Singleton::new(storage, KEY_PAUSE_INFO).save(data)
+}
+
+pub fn read_pause_info(storage: &dyn Storage) -> StdResult<PauseInfo> {
+    Ok(ReadonlySingleton::new(storage, KEY_PAUSE_INFO)
+        .may_load()?
+        .unwrap_or_default())
+}
+
 pub fn store_whitelist_elem(
     storage: &mut dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
     whitelist_elem: &WhitelistElem,
 ) -> StdResult<()> {
     let mut whitelist_bucket: Bucket<WhitelistElem> = Bucket::new(storage, PREFIX_WHITELIST);
-    whitelist_bucket.save(collateral_token.as_slice(), whitelist_elem)?;
+    whitelist_bucket.save(collateral_token.as_bytes(), whitelist_elem)?;
 
     Ok(())
 }
 
 pub fn read_whitelist_elem(
     storage: &dyn Storage,
-    collateral_token: &CanonicalAddr,
+    collateral_token: &AssetInfoRaw,
 ) -> StdResult<WhitelistElem> {
     let whitelist_bucket: ReadonlyBucket<WhitelistElem> =
         ReadonlyBucket::new(storage, PREFIX_WHITELIST);
-    match whitelist_bucket.load(collateral_token.as_slice()) {
-        Ok(v) => Ok(v),
-        _ => Err(StdError::generic_err(
-            "Token is not registered as collateral",
-        )),
+    whitelist_bucket
+        .may_load(collateral_token.as_bytes())?
+        .ok_or_else(|| StdError::generic_err("Token is not registered as collateral"))
+}
+
+/// Rewrites whitelist elements of the previous version, which were all
+/// CW20 collaterals liquidated at their max LTV. Returns the number of
+/// migrated elements.
+pub fn migrate_whitelist(storage: &mut dyn Storage) -> StdResult<u32> {
+    let legacy_elems: Vec<(Vec<u8>, LegacyWhitelistElem)> = {
+        let whitelist_bucket: ReadonlyBucket<WhitelistElem> =
+            ReadonlyBucket::new(storage, PREFIX_WHITELIST);
+        let legacy_bucket: ReadonlyBucket<LegacyWhitelistElem> =
+            ReadonlyBucket::new(storage, PREFIX_WHITELIST);
+        legacy_bucket
+            .range(None, None, Order::Ascending)
+            .filter(|elem| match elem {
+                Ok((k, _)) => whitelist_bucket.load(k).is_err(),
+                Err(_) => true,
+            })
+            .collect::<StdResult<Vec<(Vec<u8>, LegacyWhitelistElem)>>>()?
+    };
+
+    let mut whitelist_bucket: Bucket<WhitelistElem> = Bucket::new(storage, PREFIX_WHITELIST);
+    for (k, v) in legacy_elems.iter() {
+        whitelist_bucket.save(
+            k,
+            &WhitelistElem {
+                name: v.name.clone(),
+                symbol: v.symbol.clone(),
+                max_ltv: v.max_ltv,
+                liquidation_threshold: v.max_ltv,
+                custody_contract: v.custody_contract.clone(),
+                collateral_token: AssetInfoRaw::Token {
+                    contract_addr: CanonicalAddr::from(k.as_slice()),
+                },
+                paused: 0,
+                deposit_cap: None,
+            },
+        )?;
     }
+
+    Ok(legacy_elems.len() as u32)
 }
 
 pub fn read_whitelist(
     deps: Deps,
-    start_after: Option<CanonicalAddr>,
+    start_after: Option<AssetInfoRaw>,
     limit: Option<u32>,
 ) -> StdResult<Vec<WhitelistResponseElem>> {
     let whitelist_bucket: ReadonlyBucket<WhitelistElem> =
         ReadonlyBucket::new(deps.storage, PREFIX_WHITELIST);
 
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = calc_range_start(start_after);
+    let start = calc_range_start(start_after.as_ref().map(|asset| asset.as_bytes()));
 
     whitelist_bucket
         .range(start.as_deref(), None, Order::Ascending)
         .take(limit)
         .map(|elem| {
-            let (k, v) = elem?;
-            let collateral_token = deps.api.addr_humanize(&CanonicalAddr::from(k))?.to_string();
+            let (_, v) = elem?;
+            let collateral_token = v.collateral_token.to_normal(deps.api)?;
             let custody_contract = deps.api.addr_humanize(&v.custody_contract)?.to_string();
             Ok(WhitelistResponseElem {
                 name: v.name,
@@ -112,11 +239,68 @@ pub fn read_whitelist(
                 collateral_token,
                 custody_contract,
                 max_ltv: v.max_ltv,
+                liquidation_threshold: v.liquidation_threshold,
+                paused: v.paused,
+                deposit_cap: v.deposit_cap,
             })
         })
         .collect()
 }
 
+pub fn store_market_elem(
+    storage: &mut dyn Storage,
+    stable_denom: &str,
+    market_elem: &MarketElem,
+) -> StdResult<()> {
+    let mut market_bucket: Bucket<MarketElem> = Bucket::new(storage, PREFIX_MARKET);
+    market_bucket.save(stable_denom.as_bytes(), market_elem)?;
+
+    Ok(())
+}
+
+pub fn read_market_elem(storage: &dyn Storage, stable_denom: &str) -> StdResult<MarketElem> {
+    let market_bucket: ReadonlyBucket<MarketElem> = ReadonlyBucket::new(storage, PREFIX_MARKET);
+    market_bucket
+        .may_load(stable_denom.as_bytes())?
+        .ok_or_else(|| {
+            StdError::generic_err(format!("No market is registered for {}", stable_denom))
+        })
+}
+
+pub fn store_market_epoch_state(
+    storage: &mut dyn Storage,
+    stable_denom: &str,
+    epoch_state: &EpochState,
+) -> StdResult<()> {
+    let mut epoch_state_bucket: Bucket<EpochState> =
+        Bucket::new(storage, PREFIX_MARKET_EPOCH_STATE);
+    epoch_state_bucket.save(stable_denom.as_bytes(), epoch_state)
+}
+
+pub fn read_market_epoch_state(storage: &dyn Storage, stable_denom: &str) -> StdResult<EpochState> {
+    let epoch_state_bucket: ReadonlyBucket<EpochState> =
+        ReadonlyBucket::new(storage, PREFIX_MARKET_EPOCH_STATE);
+    epoch_state_bucket
+        .may_load(stable_denom.as_bytes())?
+        .ok_or_else(|| {
+            StdError::generic_err(format!("No epoch state is stored for {}", stable_denom))
+        })
+}
+
+/// Registered markets are few, so they are read at once
+pub fn read_markets(storage: &dyn Storage) -> StdResult<Vec<(String, MarketElem)>> {
+    let market_bucket: ReadonlyBucket<MarketElem> = ReadonlyBucket::new(storage, PREFIX_MARKET);
+    market_bucket
+        .range(None, None, Order::Ascending)
+        .map(|elem| {
+            let (k, v) = elem?;
+            let stable_denom =
+                String::from_utf8(k).map_err(|_| StdError::invalid_utf8("invalid stable denom"))?;
+            Ok((stable_denom, v))
+        })
+        .collect()
+}
+
 #[allow(clippy::ptr_arg)]
 pub fn store_collaterals(
     storage: &mut dyn Storage,
@@ -133,13 +317,12 @@ pub fn store_collaterals(
     Ok(())
 }
 
-pub fn read_collaterals(storage: &dyn Storage, borrower: &CanonicalAddr) -> Tokens {
+pub fn read_collaterals(storage: &dyn Storage, borrower: &CanonicalAddr) -> StdResult<Tokens> {
     let collaterals_bucket: ReadonlyBucket<Tokens> =
         ReadonlyBucket::new(storage, PREFIX_COLLATERALS);
-    match collaterals_bucket.load(borrower.as_slice()) {
-        Ok(v) => v,
-        _ => vec![],
-    }
+    Ok(collaterals_bucket
+        .may_load(borrower.as_slice())?
+        .unwrap_or_default())
 }
 
 // settings for pagination
@@ -154,7 +337,7 @@ pub fn read_all_collaterals(
         ReadonlyBucket::new(deps.storage, PREFIX_COLLATERALS);
 
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = calc_range_start(start_after);
+    let start = calc_range_start(start_after.as_ref().map(|addr| addr.as_slice()));
 
     whitelist_bucket
         .range(start.as_deref(), None, Order::Ascending)
@@ -162,10 +345,7 @@ pub fn read_all_collaterals(
         .map(|elem| {
             let (k, v) = elem?;
             let borrower = deps.api.addr_humanize(&CanonicalAddr::from(k))?.to_string();
-            let collaterals: Vec<(String, Uint256)> = v
-                .iter()
-                .map(|c| Ok((deps.api.addr_humanize(&c.0)?.to_string(), c.1)))
-                .collect::<StdResult<Vec<(String, Uint256)>>>()?;
+            let collaterals: TokensHuman = v.to_human(deps)?;
 
             Ok(CollateralsResponse {
                 borrower,
@@ -175,10 +355,31 @@ pub fn read_all_collaterals(
         .collect()
 }
 
+pub fn read_borrowers_collaterals(
+    storage: &dyn Storage,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(CanonicalAddr, Tokens)>> {
+    let collaterals_bucket: ReadonlyBucket<Tokens> =
+        ReadonlyBucket::new(storage, PREFIX_COLLATERALS);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after.as_ref().map(|addr| addr.as_slice()));
+
+    collaterals_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|elem| {
+            let (k, v) = elem?;
+            Ok((CanonicalAddr::from(k), v))
+        })
+        .collect()
+}
+
 // this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_start(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
-    start_after.map(|addr| {
-        let mut v = addr.as_slice().to_vec();
+fn calc_range_start(start_after: Option<&[u8]>) -> Option<Vec<u8>> {
+    start_after.map(|key| {
+        let mut v = key.to_vec();
         v.push(1);
         v
     })
@@ -7,7 +7,7 @@ use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::Api;
 
 use moneymarket::overseer::{ExecuteMsg, InstantiateMsg};
-use moneymarket::tokens::{Token, Tokens};
+use moneymarket::tokens::{AssetInfo, AssetInfoRaw, Token, Tokens};
 
 #[test]
 fn proper_compute_borrow_limit() {
@@ -28,6 +28,7 @@ fn proper_compute_borrow_limit() {
         buffer_distribution_factor: Decimal256::percent(20),
         anc_purchase_factor: Decimal256::percent(20),
         price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -37,9 +38,13 @@ fn proper_compute_borrow_limit() {
     let msg = ExecuteMsg::Whitelist {
         name: "bluna".to_string(),
         symbol: "bluna".to_string(),
-        collateral_token: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
         custody_contract: "custody_bluna".to_string(),
         max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
     };
 
     let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
@@ -47,9 +52,13 @@ fn proper_compute_borrow_limit() {
     let msg = ExecuteMsg::Whitelist {
         name: "batom".to_string(),
         symbol: "batom".to_string(),
-        collateral_token: "batom".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "batom".to_string(),
+        },
         custody_contract: "custody_batom".to_string(),
         max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(70),
+        deposit_cap: None,
     };
 
     let _res = execute(deps.as_mut(), env.clone(), info, msg);
@@ -75,12 +84,16 @@ fn proper_compute_borrow_limit() {
 
     let mut collaterals: Tokens = vec![];
     let token1: Token = (
-        deps.api.addr_canonicalize("bluna").unwrap(),
+        AssetInfoRaw::Token {
+            contract_addr: deps.api.addr_canonicalize("bluna").unwrap(),
+        },
         Uint256::from(1000u128),
     );
     collaterals.push(token1);
     let token2: Token = (
-        deps.api.addr_canonicalize("batom").unwrap(),
+        AssetInfoRaw::Token {
+            contract_addr: deps.api.addr_canonicalize("batom").unwrap(),
+        },
         Uint256::from(1000u128),
     );
     collaterals.push(token2);
@@ -91,6 +104,6 @@ fn proper_compute_borrow_limit() {
         Decimal256::from_uint256(2000u128),
     ];
 
-    let res2 = (Uint256::from(1800000u128), vec);
+    let res2 = (Uint256::from(1800000u128), Uint256::from(2000000u128), vec);
     assert_eq!(res, res2);
 }
@@ -9,9 +9,11 @@ use cosmwasm_std::{
 };
 use std::collections::HashMap;
 
-use moneymarket::liquidation::LiquidationAmountResponse;
-use moneymarket::market::{BorrowerInfoResponse, EpochStateResponse};
-use moneymarket::oracle::PriceResponse;
+use moneymarket::liquidation::{LiquidationAmountResponse, PendingCollateralsResponse};
+use moneymarket::market::{
+    BorrowAllowanceResponse, BorrowerInfoResponse, EpochStateResponse, StateResponse,
+};
+use moneymarket::oracle::{PriceJumpResponse, PriceResponse};
 use moneymarket::tokens::TokensHuman;
 
 use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
@@ -24,13 +26,19 @@ pub enum QueryMsg {
         block_height: Option<u64>,
         distributed_interest: Option<Uint256>,
     },
+    /// Query state to market contract
+    State { block_height: Option<u64> },
     /// Query loan amount to market contract
     BorrowerInfo {
         borrower: String,
         block_height: Option<u64>,
     },
+    /// Query borrow allowance to market contract
+    BorrowAllowance { borrower: String, spender: String },
     /// Query oracle price to oracle contract
     Price { base: String, quote: String },
+    /// Query price jump flag to oracle contract
+    PriceJump { asset: String },
     /// Query liquidation amount to liquidation model contract
     LiquidationAmount {
         borrow_amount: Uint256,
@@ -38,6 +46,8 @@ pub enum QueryMsg {
         collaterals: TokensHuman,
         collateral_prices: Vec<Decimal256>,
     },
+    /// Query collaterals up for auction to liquidation contract
+    PendingCollaterals { borrower: String },
 }
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
@@ -60,8 +70,12 @@ pub struct WasmMockQuerier {
     tax_querier: TaxQuerier,
     epoch_state_querier: EpochStateQuerier,
     oracle_price_querier: OraclePriceQuerier,
+    price_jump_querier: PriceJumpQuerier,
     loan_amount_querier: LoanAmountQuerier,
     liquidation_percent_querier: LiquidationPercentQuerier,
+    market_reserves: Decimal256,
+    borrow_allowances: HashMap<(String, String, String), Uint256>,
+    pending_collaterals: HashMap<(String, String), TokensHuman>,
 }
 
 #[derive(Clone, Default)]
@@ -88,6 +102,20 @@ pub(crate) fn caps_to_map(caps: &[(&String, &Uint128)]) -> HashMap<String, Uint1
     owner_map
 }
 
+#[derive(Clone, Default)]
+pub struct PriceJumpQuerier {
+    // assets with an active price jump
+    price_jumps: Vec<String>,
+}
+
+impl PriceJumpQuerier {
+    pub fn new(price_jumps: &[&String]) -> Self {
+        PriceJumpQuerier {
+            price_jumps: price_jumps.iter().map(|asset| asset.to_string()).collect(),
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct OraclePriceQuerier {
     // this lets us iterate over all pairs that match the first string
@@ -143,12 +171,15 @@ pub(crate) fn epoch_state_to_map(
 pub struct LoanAmountQuerier {
     // this lets us iterate over all pairs that match the first string
     borrower_amount: HashMap<String, Uint256>,
+    // loans at a given market, looked up before `borrower_amount`
+    market_borrower_amount: HashMap<(String, String), Uint256>,
 }
 
 impl LoanAmountQuerier {
     pub fn new(borrower_amount: &[(&String, &Uint256)]) -> Self {
         LoanAmountQuerier {
             borrower_amount: borrower_amount_to_map(borrower_amount),
+            market_borrower_amount: HashMap::new(),
         }
     }
 }
@@ -248,10 +279,32 @@ impl WasmMockQuerier {
                             request: msg.as_slice().into(),
                         }),
                     },
+                    QueryMsg::State { block_height } => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&StateResponse {
+                            total_liabilities: Decimal256::zero(),
+                            total_reserves: self.market_reserves,
+                            last_interest_updated: block_height.unwrap_or_default(),
+                            last_reward_updated: block_height.unwrap_or_default(),
+                            global_interest_index: Decimal256::one(),
+                            global_reward_index: Decimal256::zero(),
+                            anc_emission_rate: Decimal256::zero(),
+                            prev_aterra_supply: Uint256::zero(),
+                            prev_exchange_rate: Decimal256::one(),
+                            borrow_cap: None,
+                            total_fixed_liabilities: Decimal256::zero(),
+                            fixed_interest_per_block: Decimal256::zero(),
+                            fixed_interest_index: Decimal256::zero(),
+                        })))
+                    }
                     QueryMsg::BorrowerInfo {
                         borrower,
                         block_height: _,
-                    } => match self.loan_amount_querier.borrower_amount.get(&borrower) {
+                    } => match self
+                        .loan_amount_querier
+                        .market_borrower_amount
+                        .get(&(contract_addr.to_string(), borrower.clone()))
+                        .or_else(|| self.loan_amount_querier.borrower_amount.get(&borrower))
+                    {
                         Some(v) => SystemResult::Ok(ContractResult::from(to_binary(
                             &BorrowerInfoResponse {
                                 borrower,
@@ -259,6 +312,10 @@ impl WasmMockQuerier {
                                 reward_index: Decimal256::zero(),
                                 loan_amount: *v,
                                 pending_rewards: Decimal256::zero(),
+                                floating_loan_amount: *v,
+                                fixed_loan_amount: Uint256::zero(),
+                                fixed_rate: Decimal256::zero(),
+                                fixed_interest_index: Decimal256::one(),
                             },
                         ))),
                         None => SystemResult::Err(SystemError::InvalidRequest {
@@ -266,6 +323,31 @@ impl WasmMockQuerier {
                             request: msg.as_slice().into(),
                         }),
                     },
+                    QueryMsg::BorrowAllowance { borrower, spender } => {
+                        let amount = self
+                            .borrow_allowances
+                            .get(&(contract_addr.to_string(), borrower.clone(), spender.clone()))
+                            .copied()
+                            .unwrap_or_default();
+                        SystemResult::Ok(ContractResult::from(to_binary(
+                            &BorrowAllowanceResponse {
+                                borrower,
+                                spender,
+                                amount,
+                                expires_at: None,
+                            },
+                        )))
+                    }
+                    QueryMsg::PriceJump { asset } => {
+                        let active = self.price_jump_querier.price_jumps.contains(&asset);
+                        SystemResult::Ok(ContractResult::from(to_binary(&PriceJumpResponse {
+                            asset,
+                            max_price_jump: None,
+                            cooldown: None,
+                            active,
+                            detected_time: None,
+                        })))
+                    }
                     QueryMsg::Price { base, quote } => {
                         match self.oracle_price_querier.oracle_price.get(&(base, quote)) {
                             Some(v) => {
@@ -273,6 +355,8 @@ impl WasmMockQuerier {
                                     rate: v.0,
                                     last_updated_base: v.1,
                                     last_updated_quote: v.2,
+                                    feeders_base: vec![],
+                                    feeders_quote: vec![],
                                 })))
                             }
                             None => SystemResult::Err(SystemError::InvalidRequest {
@@ -317,6 +401,15 @@ impl WasmMockQuerier {
                             }),
                         }
                     }
+                    QueryMsg::PendingCollaterals { borrower } => SystemResult::Ok(
+                        ContractResult::from(to_binary(&PendingCollateralsResponse {
+                            collaterals: self
+                                .pending_collaterals
+                                .get(&(contract_addr.to_string(), borrower))
+                                .cloned()
+                                .unwrap_or_default(),
+                        })),
+                    ),
                 }
             }
             _ => self.base.handle_query(request),
@@ -331,8 +424,12 @@ impl WasmMockQuerier {
             tax_querier: TaxQuerier::default(),
             epoch_state_querier: EpochStateQuerier::default(),
             oracle_price_querier: OraclePriceQuerier::default(),
+            price_jump_querier: PriceJumpQuerier::default(),
             loan_amount_querier: LoanAmountQuerier::default(),
             liquidation_percent_querier: LiquidationPercentQuerier::default(),
+            market_reserves: Decimal256::zero(),
+            borrow_allowances: HashMap::new(),
+            pending_collaterals: HashMap::new(),
         }
     }
 
@@ -353,11 +450,56 @@ impl WasmMockQuerier {
         self.oracle_price_querier = OraclePriceQuerier::new(oracle_price);
     }
 
+    pub fn with_price_jump(&mut self, price_jumps: &[&String]) {
+        self.price_jump_querier = PriceJumpQuerier::new(price_jumps);
+    }
+
     pub fn with_loan_amount(&mut self, loan_amount: &[(&String, &Uint256)]) {
         self.loan_amount_querier = LoanAmountQuerier::new(loan_amount);
     }
 
+    pub fn with_market_loan_amount(&mut self, loan_amount: &[(&String, &String, &Uint256)]) {
+        for (market_contract, borrower, amount) in loan_amount.iter() {
+            self.loan_amount_querier
+                .market_borrower_amount
+                .insert(((*market_contract).clone(), (*borrower).clone()), **amount);
+        }
+    }
+
     pub fn with_liquidation_percent(&mut self, liquidation_percent: &[(&String, &Decimal256)]) {
         self.liquidation_percent_querier = LiquidationPercentQuerier::new(liquidation_percent);
     }
+
+    pub fn with_borrow_allowance(
+        &mut self,
+        market_contract: &str,
+        borrower: &str,
+        spender: &str,
+        amount: Uint256,
+    ) {
+        self.borrow_allowances.insert(
+            (
+                market_contract.to_string(),
+                borrower.to_string(),
+                spender.to_string(),
+            ),
+            amount,
+        );
+    }
+
+    pub fn with_pending_collaterals(
+        &mut self,
+        liquidation_contract: &str,
+        borrower: &str,
+        collaterals: TokensHuman,
+    ) {
+        self.pending_collaterals.insert(
+            (liquidation_contract.to_string(), borrower.to_string()),
+            collaterals,
+        );
+    }
+
+    pub fn with_market_reserves(&mut self, market_reserves: Decimal256) {
+        self.market_reserves = market_reserves;
+    }
 }
@@ -1,7 +1,10 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::error::ContractError;
 use crate::querier::query_epoch_state;
-use crate::state::{read_epoch_state, store_epoch_state, EpochState};
+use crate::state::{
+    read_epoch_state, read_market_epoch_state, store_epoch_state, store_market_epoch_state,
+    EpochState,
+};
 use crate::testing::mock_querier::mock_dependencies;
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
@@ -10,14 +13,22 @@ use cosmwasm_std::{
     attr, from_binary, to_binary, Addr, Api, BankMsg, CanonicalAddr, Coin, CosmosMsg, Decimal,
     SubMsg, Uint128, WasmMsg,
 };
+use cosmwasm_storage::Bucket;
 
 use moneymarket::custody::ExecuteMsg as CustodyExecuteMsg;
 use moneymarket::market::ExecuteMsg as MarketExecuteMsg;
 use moneymarket::overseer::{
-    AllCollateralsResponse, BorrowLimitResponse, CollateralsResponse, ConfigResponse, ExecuteMsg,
-    InstantiateMsg, QueryMsg, WhitelistResponse, WhitelistResponseElem,
+    AllCollateralsResponse, AllHealthFactorsResponse, BorrowLimitResponse, BufferRunwayResponse,
+    CollateralsResponse, ConfigResponse, EpochHistoryElem, EpochHistoryResponse, ExecuteMsg,
+    HealthFactorResponse, InstantiateMsg, MarketResponseElem, MarketsResponse, MigrateMsg,
+    QueryMsg, WhitelistResponse, WhitelistResponseElem,
+};
+use moneymarket::pause::{
+    PauseInfoResponse, PAUSE_BORROW, PAUSE_DEPOSIT, PAUSE_LIQUIDATION, PAUSE_UNLOCK,
 };
 use moneymarket::querier::deduct_tax;
+use moneymarket::tokens::AssetInfo;
+use serde::{Deserialize, Serialize};
 
 use std::str::FromStr;
 
@@ -38,6 +49,7 @@ fn proper_initialization() {
         buffer_distribution_factor: Decimal256::percent(20),
         anc_purchase_factor: Decimal256::percent(20),
         price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
     };
 
     let info = mock_info("addr0000", &[]);
@@ -62,6 +74,7 @@ fn proper_initialization() {
             buffer_distribution_factor: Decimal256::percent(20),
             anc_purchase_factor: Decimal256::percent(20),
             price_timeframe: 60u64,
+            close_factor: Decimal256::one(),
         }
     );
 
@@ -97,6 +110,7 @@ fn update_config() {
         buffer_distribution_factor: Decimal256::percent(20),
         anc_purchase_factor: Decimal256::percent(20),
         price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -114,6 +128,7 @@ fn update_config() {
         anc_purchase_factor: None,
         epoch_period: None,
         price_timeframe: None,
+        close_factor: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -136,6 +151,7 @@ fn update_config() {
         anc_purchase_factor: Some(Decimal256::percent(10)),
         epoch_period: Some(100000u64),
         price_timeframe: Some(120u64),
+        close_factor: Some(Decimal256::percent(50)),
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -156,6 +172,7 @@ fn update_config() {
     assert_eq!(Decimal256::percent(10), config_res.anc_purchase_factor);
     assert_eq!(100000u64, config_res.epoch_period);
     assert_eq!(120u64, config_res.price_timeframe);
+    assert_eq!(Decimal256::percent(50), config_res.close_factor);
 
     // Unauthorized err
     let info = mock_info("owner", &[]);
@@ -169,6 +186,7 @@ fn update_config() {
         anc_purchase_factor: None,
         epoch_period: None,
         price_timeframe: None,
+        close_factor: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -196,6 +214,7 @@ fn whitelist() {
         buffer_distribution_factor: Decimal256::percent(20),
         anc_purchase_factor: Decimal256::percent(20),
         price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -204,9 +223,13 @@ fn whitelist() {
     let msg = ExecuteMsg::Whitelist {
         name: "bluna".to_string(),
         symbol: "bluna".to_string(),
-        collateral_token: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
         custody_contract: "custody".to_string(),
         max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -227,6 +250,7 @@ fn whitelist() {
             attr("collateral_token", "bluna"),
             attr("custody_contract", "custody"),
             attr("LTV", "0.6"),
+            attr("liquidation_threshold", "0.6"),
         ]
     );
 
@@ -234,7 +258,9 @@ fn whitelist() {
         deps.as_ref(),
         mock_env(),
         QueryMsg::Whitelist {
-            collateral_token: Some("bluna".to_string()),
+            collateral_token: Some(AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            }),
             start_after: None,
             limit: None,
         },
@@ -247,9 +273,14 @@ fn whitelist() {
             elems: vec![WhitelistResponseElem {
                 name: "bluna".to_string(),
                 symbol: "bluna".to_string(),
-                collateral_token: "bluna".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "bluna".to_string()
+                },
                 custody_contract: "custody".to_string(),
                 max_ltv: Decimal256::percent(60),
+                liquidation_threshold: Decimal256::percent(60),
+                paused: 0,
+                deposit_cap: None,
             }]
         }
     );
@@ -258,9 +289,13 @@ fn whitelist() {
     let msg = ExecuteMsg::Whitelist {
         name: "bluna".to_string(),
         symbol: "bluna".to_string(),
-        collateral_token: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
         custody_contract: "custody".to_string(),
         max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
     };
 
     let info = mock_info("owner", &[]);
@@ -271,9 +306,13 @@ fn whitelist() {
     }
 
     let msg = ExecuteMsg::UpdateWhitelist {
-        collateral_token: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
         custody_contract: Some("custody2".to_string()),
         max_ltv: Some(Decimal256::percent(30)),
+        liquidation_threshold: None,
+        deposit_cap: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -292,6 +331,7 @@ fn whitelist() {
             attr("collateral_token", "bluna"),
             attr("custody_contract", "custody2"),
             attr("LTV", "0.3"),
+            attr("liquidation_threshold", "0.6"),
         ]
     );
 
@@ -299,7 +339,9 @@ fn whitelist() {
         deps.as_ref(),
         mock_env(),
         QueryMsg::Whitelist {
-            collateral_token: Some("bluna".to_string()),
+            collateral_token: Some(AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            }),
             start_after: None,
             limit: None,
         },
@@ -312,247 +354,101 @@ fn whitelist() {
             elems: vec![WhitelistResponseElem {
                 name: "bluna".to_string(),
                 symbol: "bluna".to_string(),
-                collateral_token: "bluna".to_string(),
+                collateral_token: AssetInfo::Token {
+                    contract_addr: "bluna".to_string()
+                },
                 custody_contract: "custody2".to_string(),
                 max_ltv: Decimal256::percent(30),
+                liquidation_threshold: Decimal256::percent(60),
+                paused: 0,
+                deposit_cap: None,
             }]
         }
     );
-}
-
-#[test]
-fn execute_epoch_operations() {
-    let mut deps = mock_dependencies(&[Coin {
-        denom: "uusd".to_string(),
-        amount: Uint128::from(10000000000u128),
-    }]);
-
-    let mut env = mock_env();
-    let info = mock_info("owner", &[]);
-    let msg = InstantiateMsg {
-        owner_addr: "owner".to_string(),
-        oracle_contract: "oracle".to_string(),
-        market_contract: "market".to_string(),
-        liquidation_contract: "liquidation".to_string(),
-        collector_contract: "collector".to_string(),
-        stable_denom: "uusd".to_string(),
-        epoch_period: 86400u64,
-        threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
-        target_deposit_rate: Decimal256::permille(5),
-        buffer_distribution_factor: Decimal256::percent(20),
-        anc_purchase_factor: Decimal256::percent(20),
-        price_timeframe: 60u64,
-    };
-
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-
-    let batom_collat_token = deps
-        .api
-        .addr_humanize(&CanonicalAddr::from(vec![
-            1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        ]))
-        .unwrap()
-        .to_string();
-
-    let bluna_collat_token = deps
-        .api
-        .addr_humanize(&CanonicalAddr::from(vec![
-            1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        ]))
-        .unwrap()
-        .to_string();
-
-    // store whitelist elems
-    let msg = ExecuteMsg::Whitelist {
-        name: "bluna".to_string(),
-        symbol: "bluna".to_string(),
-        collateral_token: bluna_collat_token,
-        custody_contract: "custody_bluna".to_string(),
-        max_ltv: Decimal256::percent(60),
-    };
-
-    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
 
-    let msg = ExecuteMsg::Whitelist {
-        name: "batom".to_string(),
-        symbol: "batom".to_string(),
-        collateral_token: batom_collat_token,
-        custody_contract: "custody_batom".to_string(),
-        max_ltv: Decimal256::percent(60),
+    // liquidation threshold cannot be lower than max ltv
+    let msg = ExecuteMsg::UpdateWhitelist {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: None,
+        max_ltv: None,
+        liquidation_threshold: Some(Decimal256::percent(20)),
+        deposit_cap: None,
     };
 
-    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-
-    let msg = ExecuteMsg::ExecuteEpochOperations {};
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
     match res {
-        Err(ContractError::EpochNotPassed(12345)) => (),
+        Err(ContractError::InvalidLiquidationThreshold {}) => (),
         _ => panic!("DO NOT ENTER HERE"),
     }
 
-    env.block.height += 86400u64;
-
-    // If deposit_rate is bigger than threshold_deposit_rate
-    deps.querier.with_epoch_state(&[(
-        &"market".to_string(),
-        &(Uint256::from(1000000u64), Decimal256::percent(120)),
-    )]);
-
-    // (120 / 100 - 1) / 86400
-    // deposit rate = 0.000002314814814814
-    // accrued_buffer = 10,000,000,000
-    // anc_purchase_amount = accrued_buffer * 0.2 = 2,000,000,000
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
-    assert_eq!(
-        res.messages,
-        vec![
-            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-                to_address: "collector".to_string(),
-                amount: vec![deduct_tax(
-                    deps.as_ref(),
-                    Coin {
-                        denom: "uusd".to_string(),
-                        amount: Uint128::from(2_000_000_000u128),
-                    }
-                )
-                .unwrap()],
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "custody_batom".to_string(),
-                funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::DistributeRewards {}).unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "custody_bluna".to_string(),
-                funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::DistributeRewards {}).unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::UpdateEpochState {
-                    interest_buffer: Uint256::from(8_000_000_000u128),
-                    distributed_interest: Uint256::zero(),
-                })
-                .unwrap(),
-            }))
-        ]
-    );
+    // native collateral is registered by denom
+    let msg = ExecuteMsg::Whitelist {
+        name: "luna".to_string(),
+        symbol: "luna".to_string(),
+        collateral_token: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        custody_contract: "custody_luna".to_string(),
+        max_ltv: Decimal256::percent(50),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
 
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
         res.attributes,
         vec![
-            attr("action", "epoch_operations"),
-            attr("deposit_rate", "0.000002314814814814"),
-            attr("exchange_rate", "1.2"),
-            attr("aterra_supply", "1000000"),
-            attr("distributed_interest", "0"),
-            attr("anc_purchase_amount", "2000000000"),
+            attr("action", "register_whitelist"),
+            attr("name", "luna"),
+            attr("symbol", "luna"),
+            attr("collateral_token", "uluna"),
+            attr("custody_contract", "custody_luna"),
+            attr("LTV", "0.5"),
+            attr("liquidation_threshold", "0.6"),
         ]
     );
 
-    // store epoch state for test purpose
-    store_epoch_state(
-        deps.as_mut().storage,
-        &EpochState {
-            last_executed_height: env.block.height,
-            prev_exchange_rate: Decimal256::from_str("1.2").unwrap(),
-            prev_aterra_supply: Uint256::from_str("1000000").unwrap(),
-            prev_interest_buffer: Uint256::from_str("9999000000").unwrap(),
-            deposit_rate: Decimal256::from_str("0.000002314814814814").unwrap(),
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Whitelist {
+            collateral_token: Some(AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            }),
+            start_after: None,
+            limit: None,
         },
     )
     .unwrap();
-
-    // If deposit rate is bigger than threshold
-    deps.querier.with_epoch_state(&[(
-        &"market".to_string(),
-        &(Uint256::from(1000000u64), Decimal256::percent(125)),
-    )]);
-
-    deps.querier.with_tax(
-        Decimal::percent(1),
-        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
-    );
-
-    env.block.height += 86400u64;
-
-    // accrued_buffer = 1,000,000
-    // interest_buffer = 9,999,000,000
-    // (125 / 120 - 1) / 86400
-    // deposit rate = 0.000000482253086419
-    let res = execute(deps.as_mut(), env, info, msg).unwrap();
-    assert_eq!(
-        res.messages,
-        vec![
-            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-                to_address: "collector".to_string(),
-                amount: vec![deduct_tax(
-                    deps.as_ref(),
-                    Coin {
-                        denom: "uusd".to_string(),
-                        amount: Uint128::from(200_000u128),
-                    }
-                )
-                .unwrap()]
-            })),
-            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-                to_address: "market".to_string(),
-                amount: vec![deduct_tax(
-                    deps.as_ref(),
-                    Coin {
-                        denom: "uusd".to_string(),
-                        amount: Uint128::from(53680u128),
-                    }
-                )
-                .unwrap()]
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "custody_batom".to_string(),
-                funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::DistributeRewards {}).unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "custody_bluna".to_string(),
-                funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::DistributeRewards {}).unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::UpdateEpochState {
-                    interest_buffer: Uint256::from(9999746320u128),
-                    distributed_interest: Uint256::from(53148u128),
-                })
-                .unwrap(),
-            }))
-        ]
-    );
-
+    let whitelist_res: WhitelistResponse = from_binary(&res).unwrap();
     assert_eq!(
-        res.attributes,
-        vec![
-            attr("action", "epoch_operations"),
-            attr("deposit_rate", "0.000000482253086419"),
-            attr("exchange_rate", "1.25"),
-            attr("aterra_supply", "1000000"),
-            attr("distributed_interest", "53148"),
-            attr("anc_purchase_amount", "200000")
-        ]
+        whitelist_res,
+        WhitelistResponse {
+            elems: vec![WhitelistResponseElem {
+                name: "luna".to_string(),
+                symbol: "luna".to_string(),
+                collateral_token: AssetInfo::NativeToken {
+                    denom: "uluna".to_string()
+                },
+                custody_contract: "custody_luna".to_string(),
+                max_ltv: Decimal256::percent(50),
+                liquidation_threshold: Decimal256::percent(60),
+                paused: 0,
+                deposit_cap: None,
+            }]
+        }
     );
 }
 
 #[test]
-fn update_epoch_state() {
-    let mut deps = mock_dependencies(&[Coin {
-        denom: "uusd".to_string(),
-        amount: Uint128::from(10000000000u128),
-    }]);
+fn whitelist_deposit_cap() {
+    let mut deps = mock_dependencies(&[]);
 
-    let info = mock_info("owner", &[]);
+    let info = mock_info("addr0000", &[]);
     let msg = InstantiateMsg {
         owner_addr: "owner".to_string(),
         oracle_contract: "oracle".to_string(),
@@ -561,144 +457,124 @@ fn update_epoch_state() {
         collector_contract: "collector".to_string(),
         stable_denom: "uusd".to_string(),
         epoch_period: 86400u64,
-        threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
+        threshold_deposit_rate: Decimal256::permille(3),
         target_deposit_rate: Decimal256::permille(5),
         buffer_distribution_factor: Decimal256::percent(20),
         anc_purchase_factor: Decimal256::percent(20),
         price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
     };
 
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // store whitelist elems
     let msg = ExecuteMsg::Whitelist {
         name: "bluna".to_string(),
         symbol: "bluna".to_string(),
-        collateral_token: "bluna".to_string(),
-        custody_contract: "custody_bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody".to_string(),
         max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: Some(Uint256::from(1000000u64)),
     };
 
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
-
-    let msg = ExecuteMsg::Whitelist {
-        name: "batom".to_string(),
-        symbol: "batom".to_string(),
-        collateral_token: "batom".to_string(),
-        custody_contract: "custody_batom".to_string(),
-        max_ltv: Decimal256::percent(60),
-    };
-
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
-
-    // only contract itself can execute update_epoch_state
-    let msg = ExecuteMsg::UpdateEpochState {
-        interest_buffer: Uint256::from(10000000000u128),
-        distributed_interest: Uint256::from(1000000u128),
-    };
-    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
-    match res {
-        Err(ContractError::Unauthorized {}) => (),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
-    // Assume execute epoch operation is executed
-    let mut env = mock_env();
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
-    env.block.height += 86400u64;
-
-    deps.querier.with_epoch_state(&[(
-        &"market".to_string(),
-        &(Uint256::from(1000000u64), Decimal256::percent(120)),
-    )]);
-
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
         res.messages,
         vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "market".to_string(),
+            contract_addr: "custody".to_string(),
             funds: vec![],
-            msg: to_binary(&MarketExecuteMsg::ExecuteEpochOperations {
-                deposit_rate: Decimal256::from_str("0.000002314814814814").unwrap(),
-                target_deposit_rate: Decimal256::permille(5),
-                threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
-                distributed_interest: Uint256::from(1000000u128),
+            msg: to_binary(&CustodyExecuteMsg::UpdateDepositCap {
+                deposit_cap: Some(Uint256::from(1000000u64)),
             })
             .unwrap(),
         }))]
     );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Whitelist {
+            collateral_token: Some(AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            }),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let whitelist_res: WhitelistResponse = from_binary(&res).unwrap();
     assert_eq!(
-        res.attributes,
-        vec![
-            attr("action", "update_epoch_state"),
-            attr("deposit_rate", "0.000002314814814814"),
-            attr("aterra_supply", "1000000"),
-            attr("exchange_rate", "1.2"),
-            attr("interest_buffer", "10000000000"),
-        ]
+        whitelist_res.elems[0].deposit_cap,
+        Some(Uint256::from(1000000u64))
     );
 
-    // Deposit rate increased
-    deps.querier.with_epoch_state(&[(
-        &"market".to_string(),
-        &(Uint256::from(1000000u64), Decimal256::percent(125)),
-    )]);
-
-    env.block.height += 86400u64;
-    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    // moving to a new custody contract carries the cap over
+    let msg = ExecuteMsg::UpdateWhitelist {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: Some("custody2".to_string()),
+        max_ltv: None,
+        liquidation_threshold: None,
+        deposit_cap: None,
+    };
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
         res.messages,
         vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "market".to_string(),
+            contract_addr: "custody2".to_string(),
             funds: vec![],
-            msg: to_binary(&MarketExecuteMsg::ExecuteEpochOperations {
-                deposit_rate: Decimal256::from_str("0.000000482253086419").unwrap(),
-                target_deposit_rate: Decimal256::permille(5),
-                threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
-                distributed_interest: Uint256::from(1000000u128),
+            msg: to_binary(&CustodyExecuteMsg::UpdateDepositCap {
+                deposit_cap: Some(Uint256::from(1000000u64)),
             })
             .unwrap(),
         }))]
     );
+
+    // zero cap removes the limit
+    let msg = ExecuteMsg::UpdateWhitelist {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: None,
+        max_ltv: None,
+        liquidation_threshold: None,
+        deposit_cap: Some(Uint256::zero()),
+    };
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
-        res.attributes,
-        vec![
-            attr("action", "update_epoch_state"),
-            attr("deposit_rate", "0.000000482253086419"),
-            attr("aterra_supply", "1000000"),
-            attr("exchange_rate", "1.25"),
-            attr("interest_buffer", "10000000000"),
-        ]
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "custody2".to_string(),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::UpdateDepositCap { deposit_cap: None }).unwrap(),
+        }))]
     );
 
-    let epoch_state_response = query_epoch_state(
+    let res = query(
         deps.as_ref(),
-        Addr::unchecked("market"),
-        env.block.height,
-        None,
+        mock_env(),
+        QueryMsg::Whitelist {
+            collateral_token: None,
+            start_after: None,
+            limit: None,
+        },
     )
     .unwrap();
-    let epoch_state = read_epoch_state(deps.as_ref().storage).unwrap();
-
-    // deposit rate = 0.000000482253078703
-    assert_eq!(
-        epoch_state,
-        EpochState {
-            deposit_rate: Decimal256::from_ratio(482253086419u64, 1000000000000000000u64),
-            prev_aterra_supply: epoch_state_response.aterra_supply,
-            prev_exchange_rate: epoch_state_response.exchange_rate,
-            prev_interest_buffer: Uint256::from(10000000000u128),
-            last_executed_height: env.block.height,
-        }
-    )
+    let whitelist_res: WhitelistResponse = from_binary(&res).unwrap();
+    assert_eq!(whitelist_res.elems[0].deposit_cap, None);
 }
 
 #[test]
-fn lock_collateral() {
+fn whitelist_zero_deposit_cap() {
     let mut deps = mock_dependencies(&[]);
 
-    let info = mock_info("owner", &[]);
+    let info = mock_info("addr0000", &[]);
     let msg = InstantiateMsg {
         owner_addr: "owner".to_string(),
         oracle_contract: "oracle".to_string(),
@@ -712,147 +588,62 @@ fn lock_collateral() {
         buffer_distribution_factor: Decimal256::percent(20),
         anc_purchase_factor: Decimal256::percent(20),
         price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
     };
 
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-
-    let batom_collat_token = deps
-        .api
-        .addr_humanize(&CanonicalAddr::from(vec![
-            1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        ]))
-        .unwrap()
-        .to_string();
-
-    let bluna_collat_token = deps
-        .api
-        .addr_humanize(&CanonicalAddr::from(vec![
-            1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        ]))
-        .unwrap()
-        .to_string();
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // store whitelist elems
+    // zero cap registers the collateral without a limit
     let msg = ExecuteMsg::Whitelist {
         name: "bluna".to_string(),
         symbol: "bluna".to_string(),
-        collateral_token: bluna_collat_token.clone(),
-        custody_contract: "custody_bluna".to_string(),
-        max_ltv: Decimal256::percent(60),
-    };
-
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
-
-    let msg = ExecuteMsg::Whitelist {
-        name: "batom".to_string(),
-        symbol: "batom".to_string(),
-        collateral_token: batom_collat_token.clone(),
-        custody_contract: "custody_batom".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody".to_string(),
         max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: Some(Uint256::zero()),
     };
 
-    let _res = execute(deps.as_mut(), mock_env(), info, msg);
-
-    let msg = ExecuteMsg::LockCollateral {
-        collaterals: vec![
-            (bluna_collat_token.clone(), Uint256::from(1000000u64)),
-            (batom_collat_token.clone(), Uint256::from(10000000u64)),
-        ],
-    };
-    let info = mock_info("addr0000", &[]);
+    let info = mock_info("owner", &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
         res.messages,
-        vec![
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "custody_bluna".to_string(),
-                funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::LockCollateral {
-                    borrower: "addr0000".to_string(),
-                    amount: Uint256::from(1000000u64),
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "custody_batom".to_string(),
-                funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::LockCollateral {
-                    borrower: "addr0000".to_string(),
-                    amount: Uint256::from(10000000u64),
-                })
-                .unwrap(),
-            }))
-        ]
-    );
-
-    assert_eq!(
-        res.attributes,
-        vec![
-            attr("action", "lock_collateral"),
-            attr("borrower", "addr0000"),
-            attr(
-                "collaterals",
-                format!(
-                    "1000000{},10000000{}",
-                    bluna_collat_token, batom_collat_token
-                )
-            ),
-        ]
-    );
-
-    let res = query(
-        deps.as_ref(),
-        mock_env(),
-        QueryMsg::Collaterals {
-            borrower: "addr0000".to_string(),
-        },
-    )
-    .unwrap();
-    let collaterals_res: CollateralsResponse = from_binary(&res).unwrap();
-    assert_eq!(
-        collaterals_res,
-        CollateralsResponse {
-            borrower: "addr0000".to_string(),
-            collaterals: vec![
-                (batom_collat_token.clone(), Uint256::from(10000000u64)),
-                (bluna_collat_token.clone(), Uint256::from(1000000u64)),
-            ]
-        }
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "custody".to_string(),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::UpdateDepositCap { deposit_cap: None }).unwrap(),
+        }))]
     );
 
     let res = query(
         deps.as_ref(),
         mock_env(),
-        QueryMsg::AllCollaterals {
+        QueryMsg::Whitelist {
+            collateral_token: None,
             start_after: None,
             limit: None,
         },
     )
     .unwrap();
-    let all_collaterals_res: AllCollateralsResponse = from_binary(&res).unwrap();
-    assert_eq!(
-        all_collaterals_res,
-        AllCollateralsResponse {
-            all_collaterals: vec![CollateralsResponse {
-                borrower: "addr0000".to_string(),
-                collaterals: vec![
-                    (batom_collat_token, Uint256::from(10000000u64)),
-                    (bluna_collat_token, Uint256::from(1000000u64)),
-                ]
-            }]
-        }
-    );
+    let whitelist_res: WhitelistResponse = from_binary(&res).unwrap();
+    assert_eq!(whitelist_res.elems[0].deposit_cap, None);
 }
 
 #[test]
-fn unlock_collateral() {
+fn migrate_legacy_whitelist() {
+    #[derive(Serialize, Deserialize)]
+    struct LegacyWhitelistElem {
+        name: String,
+        symbol: String,
+        max_ltv: Decimal256,
+        custody_contract: CanonicalAddr,
+    }
+
     let mut deps = mock_dependencies(&[]);
 
-    let info = mock_info("owner", &[]);
-    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
     let msg = InstantiateMsg {
         owner_addr: "owner".to_string(),
         oracle_contract: "oracle".to_string(),
@@ -866,203 +657,152 @@ fn unlock_collateral() {
         buffer_distribution_factor: Decimal256::percent(20),
         anc_purchase_factor: Decimal256::percent(20),
         price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
     };
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    // collateral whitelisted by the previous version
+    let bluna_raw = deps.api.addr_canonicalize("bluna").unwrap();
+    let custody_raw = deps.api.addr_canonicalize("custody").unwrap();
+    Bucket::new(&mut deps.storage, b"whitelist")
+        .save(
+            bluna_raw.as_slice(),
+            &LegacyWhitelistElem {
+                name: "bluna".to_string(),
+                symbol: "bluna".to_string(),
+                max_ltv: Decimal256::percent(60),
+                custody_contract: custody_raw,
+            },
+        )
+        .unwrap();
 
-    // store whitelist elems
+    // collateral whitelisted by the current version
     let msg = ExecuteMsg::Whitelist {
-        name: "bluna".to_string(),
-        symbol: "bluna".to_string(),
-        collateral_token: "bluna".to_string(),
-        custody_contract: "custody_bluna".to_string(),
-        max_ltv: Decimal256::percent(60),
+        name: "beth".to_string(),
+        symbol: "beth".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "beth".to_string(),
+        },
+        custody_contract: "custody_beth".to_string(),
+        max_ltv: Decimal256::percent(50),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
     };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
 
-    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "migrate"), attr("migrated_whitelist", "1")]
+    );
 
-    let msg = ExecuteMsg::Whitelist {
-        name: "batom".to_string(),
-        symbol: "batom".to_string(),
-        collateral_token: "batom".to_string(),
-        custody_contract: "custody_batom".to_string(),
-        max_ltv: Decimal256::percent(60),
-    };
-
-    let _res = execute(deps.as_mut(), env.clone(), info, msg);
-
-    let msg = ExecuteMsg::LockCollateral {
-        collaterals: vec![
-            ("bluna".to_string(), Uint256::from(1000000u64)),
-            ("batom".to_string(), Uint256::from(10000000u64)),
-        ],
-    };
-    let info = mock_info("addr0000", &[]);
-    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-
-    // Failed to unlock more than locked amount
-    let msg = ExecuteMsg::UnlockCollateral {
-        collaterals: vec![
-            ("bluna".to_string(), Uint256::from(1000001u64)),
-            ("batom".to_string(), Uint256::from(10000001u64)),
-        ],
-    };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-    match res {
-        Err(ContractError::UnlockExceedsLocked {}) => (),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
-    deps.querier.with_oracle_price(&[
-        (
-            &("bluna".to_string(), "uusd".to_string()),
-            &(
-                Decimal256::from_ratio(1000u64, 1u64),
-                env.block.time.seconds(),
-                env.block.time.seconds(),
-            ),
-        ),
-        (
-            &("batom".to_string(), "uusd".to_string()),
-            &(
-                Decimal256::from_ratio(2000u64, 1u64),
-                env.block.time.seconds(),
-                env.block.time.seconds(),
-            ),
-        ),
-    ]);
-
-    // borrow_limit = 1000 * 1000000 * 0.6 + 2000 * 10000000 * 0.6
-    // = 12,600,000,000 uusd
-    deps.querier
-        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12600000000u64))]);
-
-    // cannot unlock any tokens
-    // Failed to unlock more than locked amount
-    let msg = ExecuteMsg::UnlockCollateral {
-        collaterals: vec![("bluna".to_string(), Uint256::one())],
-    };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-    match res {
-        Err(ContractError::UnlockTooLarge(12599999400)) => (),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
-    let msg = ExecuteMsg::UnlockCollateral {
-        collaterals: vec![("batom".to_string(), Uint256::one())],
-    };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-    match res {
-        Err(ContractError::UnlockTooLarge(12599998800)) => (),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
-    // borrow_limit = 1000 * 1000000 * 0.6 + 2000 * 10000000 * 0.6
-    // = 12,600,000,000 uusd
-    deps.querier
-        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12599999400u64))]);
     let res = query(
         deps.as_ref(),
-        env.clone(),
-        QueryMsg::BorrowLimit {
-            borrower: "addr0000".to_string(),
-            block_time: None,
+        mock_env(),
+        QueryMsg::Whitelist {
+            collateral_token: None,
+            start_after: None,
+            limit: None,
         },
     )
     .unwrap();
-    let borrow_limit_res: BorrowLimitResponse = from_binary(&res).unwrap();
-    assert_eq!(borrow_limit_res.borrow_limit, Uint256::from(12600000000u64),);
-
-    // Cannot unlock 2bluna
-    let msg = ExecuteMsg::UnlockCollateral {
-        collaterals: vec![("bluna".to_string(), Uint256::from(2u64))],
-    };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-    match res {
-        Err(ContractError::UnlockTooLarge(12599998800)) => (),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
-    // Can unlock 1bluna
-    let msg = ExecuteMsg::UnlockCollateral {
-        collaterals: vec![("bluna".to_string(), Uint256::one())],
-    };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let whitelist_res: WhitelistResponse = from_binary(&res).unwrap();
     assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "custody_bluna".to_string(),
-            funds: vec![],
-            msg: to_binary(&CustodyExecuteMsg::UnlockCollateral {
-                borrower: "addr0000".to_string(),
-                amount: Uint256::one(),
-            })
-            .unwrap(),
-        }))]
+        whitelist_res,
+        WhitelistResponse {
+            elems: vec![
+                WhitelistResponseElem {
+                    name: "beth".to_string(),
+                    symbol: "beth".to_string(),
+                    collateral_token: AssetInfo::Token {
+                        contract_addr: "beth".to_string()
+                    },
+                    custody_contract: "custody_beth".to_string(),
+                    max_ltv: Decimal256::percent(50),
+                    liquidation_threshold: Decimal256::percent(60),
+                    paused: 0,
+                    deposit_cap: None,
+                },
+                WhitelistResponseElem {
+                    name: "bluna".to_string(),
+                    symbol: "bluna".to_string(),
+                    collateral_token: AssetInfo::Token {
+                        contract_addr: "bluna".to_string()
+                    },
+                    custody_contract: "custody".to_string(),
+                    max_ltv: Decimal256::percent(60),
+                    liquidation_threshold: Decimal256::percent(60),
+                    paused: 0,
+                    deposit_cap: None,
+                },
+            ]
+        }
     );
 
+    // migrating again leaves the whitelist untouched
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
     assert_eq!(
         res.attributes,
-        vec![
-            attr("action", "unlock_collateral"),
-            attr("borrower", "addr0000"),
-            attr("collaterals", "1bluna"),
-        ]
+        vec![attr("action", "migrate"), attr("migrated_whitelist", "0")]
     );
 
-    //testing for unlocking more collaterals
-    deps.querier
-        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(125999900u128))]);
+    // collaterals stored by the previous version are read as tokens
+    let borrower_raw = deps.api.addr_canonicalize("addr0000").unwrap();
+    let bluna_raw = deps.api.addr_canonicalize("bluna").unwrap();
+    Bucket::new(&mut deps.storage, b"collateral")
+        .save(
+            borrower_raw.as_slice(),
+            &vec![(bluna_raw, Uint256::from(1000000u64))],
+        )
+        .unwrap();
 
-    let msg = ExecuteMsg::UnlockCollateral {
-        collaterals: vec![
-            ("bluna".to_string(), Uint256::from(1u128)),
-            ("batom".to_string(), Uint256::from(1u128)),
-        ],
-    };
-    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Collaterals {
+            borrower: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let collaterals_res: CollateralsResponse = from_binary(&res).unwrap();
     assert_eq!(
-        res.messages,
-        vec![
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "custody_bluna".to_string(),
-                funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::UnlockCollateral {
-                    borrower: "addr0000".to_string(),
-                    amount: Uint256::from(1u128),
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "custody_batom".to_string(),
-                funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::UnlockCollateral {
-                    borrower: "addr0000".to_string(),
-                    amount: Uint256::from(1u128),
-                })
-                .unwrap(),
-            }))
-        ]
+        collaterals_res.collaterals,
+        vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string()
+            },
+            Uint256::from(1000000u64)
+        )]
     );
-    assert_eq!(
-        res.attributes,
-        vec![
-            attr("action", "unlock_collateral"),
-            attr("borrower", "addr0000"),
-            attr("collaterals", "1bluna,1batom"),
-        ]
+
+    // undecodable collaterals are not mistaken for none
+    let borrower_raw = deps.api.addr_canonicalize("addr0001").unwrap();
+    Bucket::new(&mut deps.storage, b"collateral")
+        .save(
+            borrower_raw.as_slice(),
+            &vec![("not base64!".to_string(), Uint256::from(1000000u64))],
+        )
+        .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Collaterals {
+            borrower: "addr0001".to_string(),
+        },
     );
+    assert!(res.is_err());
 }
 
 #[test]
-fn liquidate_collateral() {
-    let mut deps = mock_dependencies(&[]);
-    deps.querier
-        .with_liquidation_percent(&[(&"liquidation".to_string(), &Decimal256::percent(1))]);
+fn execute_epoch_operations() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(10000000000u128),
+    }]);
 
+    let mut env = mock_env();
     let info = mock_info("owner", &[]);
-    let env = mock_env();
     let msg = InstantiateMsg {
         owner_addr: "owner".to_string(),
         oracle_contract: "oracle".to_string(),
@@ -1071,11 +811,12 @@ fn liquidate_collateral() {
         collector_contract: "collector".to_string(),
         stable_denom: "uusd".to_string(),
         epoch_period: 86400u64,
-        threshold_deposit_rate: Decimal256::permille(3),
+        threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
         target_deposit_rate: Decimal256::permille(5),
         buffer_distribution_factor: Decimal256::percent(20),
         anc_purchase_factor: Decimal256::percent(20),
         price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -1103,9 +844,13 @@ fn liquidate_collateral() {
     let msg = ExecuteMsg::Whitelist {
         name: "bluna".to_string(),
         symbol: "bluna".to_string(),
-        collateral_token: bluna_collat_token.clone(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: bluna_collat_token,
+        },
         custody_contract: "custody_bluna".to_string(),
         max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
     };
 
     let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
@@ -1113,111 +858,3173 @@ fn liquidate_collateral() {
     let msg = ExecuteMsg::Whitelist {
         name: "batom".to_string(),
         symbol: "batom".to_string(),
-        collateral_token: batom_collat_token.clone(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: batom_collat_token,
+        },
         custody_contract: "custody_batom".to_string(),
         max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
     };
 
-    let _res = execute(deps.as_mut(), env.clone(), info, msg);
-
-    let msg = ExecuteMsg::LockCollateral {
-        collaterals: vec![
-            (bluna_collat_token.clone(), Uint256::from(1000000u64)),
-            (batom_collat_token.clone(), Uint256::from(10000000u64)),
-        ],
-    };
-    let info = mock_info("addr0000", &[]);
-    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-
-    deps.querier.with_oracle_price(&[
-        (
-            &(bluna_collat_token.clone(), "uusd".to_string()),
-            &(
-                Decimal256::from_ratio(1000u64, 1u64),
-                env.block.time.seconds(),
-                env.block.time.seconds(),
-            ),
-        ),
-        (
-            &(batom_collat_token.clone(), "uusd".to_string()),
-            &(
-                Decimal256::from_ratio(2000u64, 1u64),
-                env.block.time.seconds(),
-                env.block.time.seconds(),
-            ),
-        ),
-    ]);
-
-    // borrow_limit = 1000 * 1000000 * 0.6 + 2000 * 10000000 * 0.6
-    // = 12,600,000,000 uusd
-    deps.querier
-        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12600000000u64))]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
 
-    let msg = ExecuteMsg::LiquidateCollateral {
-        borrower: "addr0000".to_string(),
-    };
-    let info = mock_info("addr0001", &[]);
+    let msg = ExecuteMsg::ExecuteEpochOperations {};
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     match res {
-        Err(ContractError::CannotLiquidateSafeLoan {}) => (),
+        Err(ContractError::EpochNotPassed(12345)) => (),
         _ => panic!("DO NOT ENTER HERE"),
     }
 
-    deps.querier
-        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12600000001u64))]);
-    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    env.block.height += 86400u64;
+
+    // If deposit_rate is bigger than threshold_deposit_rate
+    deps.querier.with_epoch_state(&[(
+        &"market".to_string(),
+        &(Uint256::from(1000000u64), Decimal256::percent(120)),
+    )]);
+
+    // (120 / 100 - 1) / 86400
+    // deposit rate = 0.000002314814814814
+    // accrued_buffer = 10,000,000,000
+    // anc_purchase_amount = accrued_buffer * 0.2 = 2,000,000,000
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
     assert_eq!(
         res.messages,
         vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![deduct_tax(
+                    deps.as_ref(),
+                    Coin {
+                        denom: "uusd".to_string(),
+                        amount: Uint128::from(2_000_000_000u128),
+                    }
+                )
+                .unwrap()],
+            })),
             SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: "custody_batom".to_string(),
                 funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::LiquidateCollateral {
-                    liquidator: "addr0001".to_string(),
-                    borrower: "addr0000".to_string(),
-                    amount: Uint256::from(100000u64),
-                })
-                .unwrap(),
+                msg: to_binary(&CustodyExecuteMsg::DistributeRewards {}).unwrap(),
             })),
             SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: "custody_bluna".to_string(),
                 funds: vec![],
-                msg: to_binary(&CustodyExecuteMsg::LiquidateCollateral {
-                    liquidator: "addr0001".to_string(),
-                    borrower: "addr0000".to_string(),
+                msg: to_binary(&CustodyExecuteMsg::DistributeRewards {}).unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::UpdateEpochState {
+                    interest_buffer: Uint256::from(8_000_000_000u128),
+                    distributed_interest: Uint256::zero(),
+                    accrued_buffer: Uint256::from(10_000_000_000u128),
+                    anc_purchase_amount: Uint256::from(2_000_000_000u128),
+                })
+                .unwrap(),
+            }))
+        ]
+    );
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "epoch_operations"),
+            attr("deposit_rate", "0.000002314814814814"),
+            attr("exchange_rate", "1.2"),
+            attr("aterra_supply", "1000000"),
+            attr("distributed_interest", "0"),
+            attr("anc_purchase_amount", "2000000000"),
+        ]
+    );
+
+    // store epoch state for test purpose
+    store_epoch_state(
+        deps.as_mut().storage,
+        &EpochState {
+            last_executed_height: env.block.height,
+            prev_exchange_rate: Decimal256::from_str("1.2").unwrap(),
+            prev_aterra_supply: Uint256::from_str("1000000").unwrap(),
+            prev_interest_buffer: Uint256::from_str("9999000000").unwrap(),
+            deposit_rate: Decimal256::from_str("0.000002314814814814").unwrap(),
+        },
+    )
+    .unwrap();
+
+    // If deposit rate is bigger than threshold
+    deps.querier.with_epoch_state(&[(
+        &"market".to_string(),
+        &(Uint256::from(1000000u64), Decimal256::percent(125)),
+    )]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    env.block.height += 86400u64;
+
+    // accrued_buffer = 1,000,000
+    // interest_buffer = 9,999,000,000
+    // (125 / 120 - 1) / 86400
+    // deposit rate = 0.000000482253086419
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![deduct_tax(
+                    deps.as_ref(),
+                    Coin {
+                        denom: "uusd".to_string(),
+                        amount: Uint128::from(200_000u128),
+                    }
+                )
+                .unwrap()]
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "market".to_string(),
+                amount: vec![deduct_tax(
+                    deps.as_ref(),
+                    Coin {
+                        denom: "uusd".to_string(),
+                        amount: Uint128::from(53680u128),
+                    }
+                )
+                .unwrap()]
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_batom".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::DistributeRewards {}).unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_bluna".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::DistributeRewards {}).unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::UpdateEpochState {
+                    interest_buffer: Uint256::from(9999746320u128),
+                    distributed_interest: Uint256::from(53148u128),
+                    accrued_buffer: Uint256::from(1_000_000u128),
+                    anc_purchase_amount: Uint256::from(200_000u128),
+                })
+                .unwrap(),
+            }))
+        ]
+    );
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "epoch_operations"),
+            attr("deposit_rate", "0.000000482253086419"),
+            attr("exchange_rate", "1.25"),
+            attr("aterra_supply", "1000000"),
+            attr("distributed_interest", "53148"),
+            attr("anc_purchase_amount", "200000")
+        ]
+    );
+}
+
+#[test]
+fn update_epoch_state() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(10000000000u128),
+    }]);
+
+    let info = mock_info("owner", &[]);
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // store whitelist elems
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "batom".to_string(),
+        symbol: "batom".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "batom".to_string(),
+        },
+        custody_contract: "custody_batom".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+
+    // only contract itself can execute update_epoch_state
+    let msg = ExecuteMsg::UpdateEpochState {
+        interest_buffer: Uint256::from(10000000000u128),
+        distributed_interest: Uint256::from(1000000u128),
+        accrued_buffer: Uint256::from(3000000u128),
+        anc_purchase_amount: Uint256::from(600000u128),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Assume execute epoch operation is executed
+    let mut env = mock_env();
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    env.block.height += 86400u64;
+
+    deps.querier.with_epoch_state(&[(
+        &"market".to_string(),
+        &(Uint256::from(1000000u64), Decimal256::percent(120)),
+    )]);
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "market".to_string(),
+            funds: vec![],
+            msg: to_binary(&MarketExecuteMsg::ExecuteEpochOperations {
+                deposit_rate: Decimal256::from_str("0.000002314814814814").unwrap(),
+                target_deposit_rate: Decimal256::permille(5),
+                threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
+                distributed_interest: Uint256::from(1000000u128),
+            })
+            .unwrap(),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_epoch_state"),
+            attr("deposit_rate", "0.000002314814814814"),
+            attr("aterra_supply", "1000000"),
+            attr("exchange_rate", "1.2"),
+            attr("interest_buffer", "10000000000"),
+        ]
+    );
+
+    // Deposit rate increased
+    deps.querier.with_epoch_state(&[(
+        &"market".to_string(),
+        &(Uint256::from(1000000u64), Decimal256::percent(125)),
+    )]);
+
+    env.block.height += 86400u64;
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "market".to_string(),
+            funds: vec![],
+            msg: to_binary(&MarketExecuteMsg::ExecuteEpochOperations {
+                deposit_rate: Decimal256::from_str("0.000000482253086419").unwrap(),
+                target_deposit_rate: Decimal256::permille(5),
+                threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
+                distributed_interest: Uint256::from(1000000u128),
+            })
+            .unwrap(),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_epoch_state"),
+            attr("deposit_rate", "0.000000482253086419"),
+            attr("aterra_supply", "1000000"),
+            attr("exchange_rate", "1.25"),
+            attr("interest_buffer", "10000000000"),
+        ]
+    );
+
+    let epoch_state_response = query_epoch_state(
+        deps.as_ref(),
+        Addr::unchecked("market"),
+        env.block.height,
+        None,
+    )
+    .unwrap();
+    let epoch_state = read_epoch_state(deps.as_ref().storage).unwrap();
+
+    // deposit rate = 0.000000482253078703
+    assert_eq!(
+        epoch_state,
+        EpochState {
+            deposit_rate: Decimal256::from_ratio(482253086419u64, 1000000000000000000u64),
+            prev_aterra_supply: epoch_state_response.aterra_supply,
+            prev_exchange_rate: epoch_state_response.exchange_rate,
+            prev_interest_buffer: Uint256::from(10000000000u128),
+            last_executed_height: env.block.height,
+        }
+    );
+
+    // both epochs are kept in the history
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::EpochHistory {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let epoch_history: EpochHistoryResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        epoch_history.epochs,
+        vec![
+            EpochHistoryElem {
+                height: env.block.height - 86400u64,
+                time: env.block.time.seconds(),
+                deposit_rate: Decimal256::from_str("0.000002314814814814").unwrap(),
+                exchange_rate: Decimal256::percent(120),
+                aterra_supply: Uint256::from(1000000u64),
+                interest_buffer: Uint256::from(10000000000u128),
+                accrued_buffer: Uint256::from(3000000u128),
+                anc_purchase_amount: Uint256::from(600000u128),
+                distributed_interest: Uint256::from(1000000u128),
+            },
+            EpochHistoryElem {
+                height: env.block.height,
+                time: env.block.time.seconds(),
+                deposit_rate: Decimal256::from_str("0.000000482253086419").unwrap(),
+                exchange_rate: Decimal256::percent(125),
+                aterra_supply: Uint256::from(1000000u64),
+                interest_buffer: Uint256::from(10000000000u128),
+                accrued_buffer: Uint256::from(3000000u128),
+                anc_purchase_amount: Uint256::from(600000u128),
+                distributed_interest: Uint256::from(1000000u128),
+            },
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::EpochHistory {
+            start_after: Some(env.block.height - 86400u64),
+            limit: Some(10),
+        },
+    )
+    .unwrap();
+    let epoch_history: EpochHistoryResponse = from_binary(&res).unwrap();
+    assert_eq!(epoch_history.epochs.len(), 1);
+    assert_eq!(epoch_history.epochs[0].height, env.block.height);
+}
+
+#[test]
+fn buffer_runway() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(10000000000u128),
+    }]);
+
+    let info = mock_info("owner", &[]);
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // no interest accrued yet and no buffer inflow recorded
+    deps.querier.with_epoch_state(&[(
+        &"market".to_string(),
+        &(Uint256::from(1000000000u64), Decimal256::one()),
+    )]);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::BufferRunway {}).unwrap();
+    let runway: BufferRunwayResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        runway,
+        BufferRunwayResponse {
+            interest_buffer: Uint256::from(10000000000u128),
+            deposit_rate: Decimal256::zero(),
+            missing_deposits: Uint256::from(86400000u64),
+            buffer_inflow: Uint256::zero(),
+            runway_epochs: Some(Uint256::from(111u64)),
+        }
+    );
+
+    // record an epoch with buffer inflow
+    let mut env = mock_env();
+    env.block.height += 86400u64;
+    let msg = ExecuteMsg::UpdateEpochState {
+        interest_buffer: Uint256::from(10000000000u128),
+        distributed_interest: Uint256::zero(),
+        accrued_buffer: Uint256::from(3000000u128),
+        anc_purchase_amount: Uint256::from(600000u128),
+    };
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        msg,
+    )
+    .unwrap();
+
+    // borrower interest pays half of the threshold rate
+    env.block.height += 86400u64;
+    deps.querier.with_epoch_state(&[(
+        &"market".to_string(),
+        &(
+            Uint256::from(1000000000u64),
+            Decimal256::from_str("1.0432").unwrap(),
+        ),
+    )]);
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::BufferRunway {}).unwrap();
+    let runway: BufferRunwayResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        runway,
+        BufferRunwayResponse {
+            interest_buffer: Uint256::from(10000000000u128),
+            deposit_rate: Decimal256::from_str("0.0000005").unwrap(),
+            missing_deposits: Uint256::from(45066240u64),
+            buffer_inflow: Uint256::from(2400000u64),
+            runway_epochs: Some(Uint256::from(230u64)),
+        }
+    );
+
+    // borrower interest covers the threshold rate
+    deps.querier.with_epoch_state(&[(
+        &"market".to_string(),
+        &(Uint256::from(1000000000u64), Decimal256::percent(120)),
+    )]);
+    let res = query(deps.as_ref(), env, QueryMsg::BufferRunway {}).unwrap();
+    let runway: BufferRunwayResponse = from_binary(&res).unwrap();
+    assert_eq!(runway.missing_deposits, Uint256::zero());
+    assert_eq!(runway.runway_epochs, None);
+}
+
+#[test]
+fn lock_collateral() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner", &[]);
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let batom_collat_token = deps
+        .api
+        .addr_humanize(&CanonicalAddr::from(vec![
+            1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]))
+        .unwrap()
+        .to_string();
+
+    let bluna_collat_token = deps
+        .api
+        .addr_humanize(&CanonicalAddr::from(vec![
+            1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]))
+        .unwrap()
+        .to_string();
+
+    // store whitelist elems
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: bluna_collat_token.clone(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "batom".to_string(),
+        symbol: "batom".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: batom_collat_token.clone(),
+        },
+        custody_contract: "custody_batom".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+
+    let _res = execute(deps.as_mut(), mock_env(), info, msg);
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: bluna_collat_token.clone(),
+                },
+                Uint256::from(1000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: batom_collat_token.clone(),
+                },
+                Uint256::from(10000000u64),
+            ),
+        ],
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_bluna".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::LockCollateral {
+                    borrower: "addr0000".to_string(),
+                    amount: Uint256::from(1000000u64),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_batom".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::LockCollateral {
+                    borrower: "addr0000".to_string(),
+                    amount: Uint256::from(10000000u64),
+                })
+                .unwrap(),
+            }))
+        ]
+    );
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "lock_collateral"),
+            attr("borrower", "addr0000"),
+            attr(
+                "collaterals",
+                format!(
+                    "1000000{},10000000{}",
+                    bluna_collat_token, batom_collat_token
+                )
+            ),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Collaterals {
+            borrower: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let collaterals_res: CollateralsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        collaterals_res,
+        CollateralsResponse {
+            borrower: "addr0000".to_string(),
+            collaterals: vec![
+                (
+                    AssetInfo::Token {
+                        contract_addr: batom_collat_token.clone()
+                    },
+                    Uint256::from(10000000u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: bluna_collat_token.clone()
+                    },
+                    Uint256::from(1000000u64)
+                ),
+            ]
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllCollaterals {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let all_collaterals_res: AllCollateralsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        all_collaterals_res,
+        AllCollateralsResponse {
+            all_collaterals: vec![CollateralsResponse {
+                borrower: "addr0000".to_string(),
+                collaterals: vec![
+                    (
+                        AssetInfo::Token {
+                            contract_addr: batom_collat_token
+                        },
+                        Uint256::from(10000000u64)
+                    ),
+                    (
+                        AssetInfo::Token {
+                            contract_addr: bluna_collat_token
+                        },
+                        Uint256::from(1000000u64)
+                    ),
+                ]
+            }]
+        }
+    );
+}
+
+#[test]
+fn unlock_collateral() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // store whitelist elems
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "batom".to_string(),
+        symbol: "batom".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "batom".to_string(),
+        },
+        custody_contract: "custody_batom".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+
+    let _res = execute(deps.as_mut(), env.clone(), info, msg);
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: "bluna".to_string(),
+                },
+                Uint256::from(1000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "batom".to_string(),
+                },
+                Uint256::from(10000000u64),
+            ),
+        ],
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // Failed to unlock more than locked amount
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: "bluna".to_string(),
+                },
+                Uint256::from(1000001u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "batom".to_string(),
+                },
+                Uint256::from(10000001u64),
+            ),
+        ],
+        borrower: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    match res {
+        Err(ContractError::UnlockExceedsLocked {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    deps.querier.with_oracle_price(&[
+        (
+            &("bluna".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(1000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+        (
+            &("batom".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(2000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+    ]);
+
+    // borrow_limit = 1000 * 1000000 * 0.6 + 2000 * 10000000 * 0.6
+    // = 12,600,000,000 uusd
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12600000000u64))]);
+
+    // cannot unlock any tokens
+    // Failed to unlock more than locked amount
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::one(),
+        )],
+        borrower: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    match res {
+        Err(ContractError::UnlockTooLarge(12599999400)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "batom".to_string(),
+            },
+            Uint256::one(),
+        )],
+        borrower: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    match res {
+        Err(ContractError::UnlockTooLarge(12599998800)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // borrow_limit = 1000 * 1000000 * 0.6 + 2000 * 10000000 * 0.6
+    // = 12,600,000,000 uusd
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12599999400u64))]);
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BorrowLimit {
+            borrower: "addr0000".to_string(),
+            block_time: None,
+            stable_denom: None,
+        },
+    )
+    .unwrap();
+    let borrow_limit_res: BorrowLimitResponse = from_binary(&res).unwrap();
+    assert_eq!(borrow_limit_res.borrow_limit, Uint256::from(12600000000u64),);
+
+    // Cannot unlock 2bluna
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(2u64),
+        )],
+        borrower: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    match res {
+        Err(ContractError::UnlockTooLarge(12599998800)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // Can unlock 1bluna
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::one(),
+        )],
+        borrower: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "custody_bluna".to_string(),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::UnlockCollateral {
+                borrower: "addr0000".to_string(),
+                amount: Uint256::one(),
+            })
+            .unwrap(),
+        }))]
+    );
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unlock_collateral"),
+            attr("borrower", "addr0000"),
+            attr("collaterals", "1bluna"),
+        ]
+    );
+
+    //testing for unlocking more collaterals
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(125999900u128))]);
+
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: "bluna".to_string(),
+                },
+                Uint256::from(1u128),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "batom".to_string(),
+                },
+                Uint256::from(1u128),
+            ),
+        ],
+        borrower: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_bluna".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::UnlockCollateral {
+                    borrower: "addr0000".to_string(),
+                    amount: Uint256::from(1u128),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_batom".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::UnlockCollateral {
+                    borrower: "addr0000".to_string(),
+                    amount: Uint256::from(1u128),
+                })
+                .unwrap(),
+            }))
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unlock_collateral"),
+            attr("borrower", "addr0000"),
+            attr("collaterals", "1bluna,1batom"),
+        ]
+    );
+
+    // a spender can unlock on behalf of the borrower with a borrow allowance
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(1u128),
+        )],
+        borrower: Some("addr0000".to_string()),
+        stable_denom: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vault", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::NoBorrowAllowance {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // the allowance is spent by the value of the unlocked collaterals
+    deps.querier
+        .with_borrow_allowance("market", "addr0000", "vault", Uint256::from(1000u128));
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::zero())]);
+    let res = execute(deps.as_mut(), env, mock_info("vault", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "market".to_string(),
+                funds: vec![],
+                msg: to_binary(&MarketExecuteMsg::SpendBorrowAllowance {
+                    borrower: "addr0000".to_string(),
+                    spender: "vault".to_string(),
+                    amount: Uint256::from(1000u128),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_bluna".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::UnlockCollateral {
+                    borrower: "addr0000".to_string(),
+                    amount: Uint256::from(1u128),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+}
+
+#[test]
+fn unlock_collateral_above_allowance() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    deps.querier.with_epoch_state(&[(
+        &"market_krw".to_string(),
+        &(Uint256::zero(), Decimal256::one()),
+    )]);
+    let msg = ExecuteMsg::RegisterMarket {
+        stable_denom: "ukrw".to_string(),
+        market_contract: "market_krw".to_string(),
+        liquidation_contract: "liquidation_krw".to_string(),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(1000000u64),
+        )],
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps.querier.with_oracle_price(&[
+        (
+            &("bluna".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(1000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+        (
+            &("ukrw".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::permille(1),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+    ]);
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::zero())]);
+    deps.querier
+        .with_borrow_allowance("market", "addr0000", "vault", Uint256::from(999u64));
+    deps.querier.with_borrow_allowance(
+        "market_krw",
+        "addr0000",
+        "vault",
+        Uint256::from(1000000u64),
+    );
+
+    // 1 bluna is worth 1,000 uusd
+    let unlock_msg = |stable_denom: Option<&str>| ExecuteMsg::UnlockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::one(),
+        )],
+        borrower: Some("addr0000".to_string()),
+        stable_denom: stable_denom.map(|denom| denom.to_string()),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vault", &[]),
+        unlock_msg(None),
+    );
+    match res {
+        Err(ContractError::UnlockExceedsAllowance(999)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("vault", &[]),
+        unlock_msg(Some("ueur")),
+    );
+    assert!(res.is_err());
+
+    // the allowance granted at the ukrw market covers 1,000,000 ukrw
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("vault", &[]),
+        unlock_msg(Some("ukrw")),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "market_krw".to_string(),
+            funds: vec![],
+            msg: to_binary(&MarketExecuteMsg::SpendBorrowAllowance {
+                borrower: "addr0000".to_string(),
+                spender: "vault".to_string(),
+                amount: Uint256::from(1000000u64),
+            })
+            .unwrap(),
+        }))
+    );
+}
+
+#[test]
+fn liquidate_collateral() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_liquidation_percent(&[(&"liquidation".to_string(), &Decimal256::percent(1))]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let batom_collat_token = deps
+        .api
+        .addr_humanize(&CanonicalAddr::from(vec![
+            1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]))
+        .unwrap()
+        .to_string();
+
+    let bluna_collat_token = deps
+        .api
+        .addr_humanize(&CanonicalAddr::from(vec![
+            1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]))
+        .unwrap()
+        .to_string();
+
+    // store whitelist elems
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: bluna_collat_token.clone(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "batom".to_string(),
+        symbol: "batom".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: batom_collat_token.clone(),
+        },
+        custody_contract: "custody_batom".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+
+    let _res = execute(deps.as_mut(), env.clone(), info, msg);
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: bluna_collat_token.clone(),
+                },
+                Uint256::from(1000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: batom_collat_token.clone(),
+                },
+                Uint256::from(10000000u64),
+            ),
+        ],
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    deps.querier.with_oracle_price(&[
+        (
+            &(bluna_collat_token.clone(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(1000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+        (
+            &(batom_collat_token.clone(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(2000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+    ]);
+
+    // borrow_limit = 1000 * 1000000 * 0.6 + 2000 * 10000000 * 0.6
+    // = 12,600,000,000 uusd
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12600000000u64))]);
+
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: None,
+        stable_denom: None,
+    };
+    let info = mock_info("addr0001", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    match res {
+        Err(ContractError::CannotLiquidateSafeLoan {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12600000001u64))]);
+
+    // paused while the oracle flags a price jump
+    deps.querier.with_price_jump(&[&bluna_collat_token]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    match res {
+        Err(ContractError::PriceJumpDetected(asset)) => assert_eq!(asset, bluna_collat_token),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+    deps.querier.with_price_jump(&[]);
+
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_batom".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::LiquidateCollateral {
+                    liquidator: "addr0001".to_string(),
+                    borrower: "addr0000".to_string(),
+                    amount: Uint256::from(100000u64),
+                    liquidation_contract: None,
+                    market_contract: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_bluna".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::LiquidateCollateral {
+                    liquidator: "addr0001".to_string(),
+                    borrower: "addr0000".to_string(),
                     amount: Uint256::from(10000u64),
+                    liquidation_contract: None,
+                    market_contract: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "market".to_string(),
+                funds: vec![],
+                msg: to_binary(&MarketExecuteMsg::RepayStableFromLiquidation {
+                    borrower: "addr0000".to_string(),
+                    prev_balance: Uint256::zero(),
+                })
+                .unwrap(),
+            }))
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::Collaterals {
+            borrower: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let collaterals_res: CollateralsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        collaterals_res,
+        CollateralsResponse {
+            borrower: "addr0000".to_string(),
+            collaterals: vec![
+                (
+                    AssetInfo::Token {
+                        contract_addr: batom_collat_token
+                    },
+                    Uint256::from(9900000u64)
+                ),
+                (
+                    AssetInfo::Token {
+                        contract_addr: bluna_collat_token
+                    },
+                    Uint256::from(990000u64)
+                ),
+            ]
+        }
+    );
+}
+
+#[test]
+fn liquidate_collateral_close_factor() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_liquidation_percent(&[(&"liquidation".to_string(), &Decimal256::percent(1))]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::percent(1),
+    };
+
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    for (token, custody) in [("bluna", "custody_bluna"), ("batom", "custody_batom")] {
+        let msg = ExecuteMsg::Whitelist {
+            name: token.to_string(),
+            symbol: token.to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: token.to_string(),
+            },
+            custody_contract: custody.to_string(),
+            max_ltv: Decimal256::percent(60),
+            liquidation_threshold: Decimal256::percent(60),
+            deposit_cap: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: "bluna".to_string(),
+                },
+                Uint256::from(1000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "batom".to_string(),
+                },
+                Uint256::from(10000000u64),
+            ),
+        ],
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps.querier.with_oracle_price(&[
+        (
+            &("bluna".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(1000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+        (
+            &("batom".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(2000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+    ]);
+
+    // borrow_limit = 1000 * 1000000 * 0.6 + 2000 * 10000000 * 0.6
+    // = 12,600,000,000 uusd
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12600000001u64))]);
+
+    // liquidation amount = 10000 bluna + 100000 batom = 210,000,000 uusd,
+    // capped by the close factor to 1% of the loan = 126,000,000 uusd
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: None,
+        stable_denom: None,
+    };
+    let info = mock_info("addr0001", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Collaterals {
+            borrower: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let mut collaterals_res: CollateralsResponse = from_binary(&res).unwrap();
+    collaterals_res.collaterals.sort();
+    assert_eq!(
+        collaterals_res.collaterals,
+        vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: "batom".to_string()
+                },
+                Uint256::from(9940000u64)
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "bluna".to_string()
+                },
+                Uint256::from(994000u64)
+            ),
+        ]
+    );
+
+    // liquidator seizes a single collateral
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: Some(AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        }),
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "custody_bluna".to_string(),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::LiquidateCollateral {
+                liquidator: "addr0001".to_string(),
+                borrower: "addr0000".to_string(),
+                amount: Uint256::from(9940u64),
+                liquidation_contract: None,
+                market_contract: None,
+            })
+            .unwrap(),
+        }))
+    );
+
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: Some(AssetInfo::Token {
+            contract_addr: "bxyz".to_string(),
+        }),
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg);
+    match res {
+        Err(ContractError::CollateralNotLocked {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn write_off_bad_debt() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1000000u128),
+    }]);
+    deps.querier
+        .with_liquidation_percent(&[(&"liquidation".to_string(), &Decimal256::one())]);
+    deps.querier
+        .with_market_reserves(Decimal256::from_uint256(300000u64));
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(1000u64),
+        )],
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps.querier.with_oracle_price(&[(
+        &("bluna".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::from_ratio(1000u64, 1u64),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    // collateral value = 1,000,000 uusd
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(3000000u64))]);
+
+    let msg = ExecuteMsg::WriteOffBadDebt {
+        borrower: "addr0000".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::CollateralNotExhausted {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // all collaterals are liquidated; 2,000,000 uusd of the loan is left
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::LiquidateCollateral {
+            borrower: "addr0000".to_string(),
+            collateral_token: None,
+            stable_denom: None,
+        },
+    )
+    .unwrap();
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(2000000u64))]);
+
+    let mut epoch_state = read_epoch_state(deps.as_ref().storage).unwrap();
+    epoch_state.prev_interest_buffer = Uint256::from(1200000u64);
+    store_epoch_state(deps.as_mut().storage, &epoch_state).unwrap();
+
+    // reserves cover 300,000 uusd and the whole buffer is spent
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "market".to_string(),
+            funds: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+            msg: to_binary(&MarketExecuteMsg::WriteOffBadDebt {
+                borrower: "addr0000".to_string(),
+            })
+            .unwrap(),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "write_off_bad_debt"),
+            attr("borrower", "addr0000"),
+            attr("stable_denom", "uusd"),
+            attr("bad_debt", "2000000"),
+            attr("buffer_cover", "1000000"),
+        ]
+    );
+    assert_eq!(
+        read_epoch_state(deps.as_ref().storage)
+            .unwrap()
+            .prev_interest_buffer,
+        Uint256::from(200000u64)
+    );
+
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::zero())]);
+    let res = execute(deps.as_mut(), env, mock_info("addr0001", &[]), msg);
+    match res {
+        Err(ContractError::NoBadDebt {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn liquidation_threshold() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_liquidation_percent(&[(&"liquidation".to_string(), &Decimal256::percent(1))]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // liquidation threshold cannot be lower than max ltv
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(50),
+        deposit_cap: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    match res {
+        Err(ContractError::InvalidLiquidationThreshold {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(80),
+        deposit_cap: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(1000000u64),
+        )],
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    deps.querier.with_oracle_price(&[(
+        &("bluna".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::from_ratio(1000u64, 1u64),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    // borrow_limit = 1000 * 1000000 * 0.6 = 600,000,000 uusd
+    // liquidation_limit = 1000 * 1000000 * 0.8 = 800,000,000 uusd
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(700000000u64))]);
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::HealthFactor {
+            borrower: "addr0000".to_string(),
+            block_time: None,
+        },
+    )
+    .unwrap();
+    let health_res: HealthFactorResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        health_res,
+        HealthFactorResponse {
+            borrower: "addr0000".to_string(),
+            loan_amount: Uint256::from(700000000u64),
+            borrow_limit: Uint256::from(600000000u64),
+            liquidation_limit: Uint256::from(800000000u64),
+            health_factor: Some(Decimal256::from_ratio(8u64, 7u64)),
+            liquidatable: false,
+        }
+    );
+
+    // loan above the borrow limit but below the liquidation limit
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: None,
+        stable_denom: None,
+    };
+    let info = mock_info("addr0001", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    match res {
+        Err(ContractError::CannotLiquidateSafeLoan {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(800000001u64))]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "custody_bluna".to_string(),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::LiquidateCollateral {
+                liquidator: "addr0001".to_string(),
+                borrower: "addr0000".to_string(),
+                amount: Uint256::from(10000u64),
+                liquidation_contract: None,
+                market_contract: None,
+            })
+            .unwrap(),
+        }))
+    );
+}
+
+#[test]
+fn at_risk_borrowers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(80),
+        deposit_cap: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    for borrower in ["addr0000", "addr0001", "addr0002"] {
+        let msg = ExecuteMsg::LockCollateral {
+            collaterals: vec![(
+                AssetInfo::Token {
+                    contract_addr: "bluna".to_string(),
+                },
+                Uint256::from(1000000u64),
+            )],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(borrower, &[]), msg).unwrap();
+    }
+
+    deps.querier.with_oracle_price(&[(
+        &("bluna".to_string(), "uusd".to_string()),
+        &(
+            Decimal256::from_ratio(1000u64, 1u64),
+            env.block.time.seconds(),
+            env.block.time.seconds(),
+        ),
+    )]);
+
+    // liquidation_limit = 1000 * 1000000 * 0.8 = 800,000,000 uusd
+    deps.querier.with_loan_amount(&[
+        (&"addr0000".to_string(), &Uint256::from(700000000u64)),
+        (&"addr0001".to_string(), &Uint256::from(900000000u64)),
+        (&"addr0002".to_string(), &Uint256::zero()),
+    ]);
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::AllHealthFactors {
+            start_after: None,
+            limit: None,
+            block_time: None,
+        },
+    )
+    .unwrap();
+    let all_res: AllHealthFactorsResponse = from_binary(&res).unwrap();
+    assert_eq!(all_res.health_factors.len(), 3);
+
+    let health_of = |borrower: &str| {
+        all_res
+            .health_factors
+            .iter()
+            .find(|h| h.borrower == borrower)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(
+        health_of("addr0001"),
+        HealthFactorResponse {
+            borrower: "addr0001".to_string(),
+            loan_amount: Uint256::from(900000000u64),
+            borrow_limit: Uint256::from(600000000u64),
+            liquidation_limit: Uint256::from(800000000u64),
+            health_factor: Some(Decimal256::from_ratio(8u64, 9u64)),
+            liquidatable: true,
+        }
+    );
+    assert_eq!(health_of("addr0002").health_factor, None);
+    assert!(!health_of("addr0002").liquidatable);
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::AtRisk {
+            max_health: Decimal256::one(),
+            start_after: None,
+            limit: None,
+            block_time: None,
+        },
+    )
+    .unwrap();
+    let at_risk_res: AllHealthFactorsResponse = from_binary(&res).unwrap();
+    assert_eq!(at_risk_res.health_factors, vec![health_of("addr0001")]);
+
+    // page through the borrowers one at a time
+    let mut at_risk: Vec<String> = vec![];
+    let mut start_after: Option<String> = None;
+    loop {
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::AtRisk {
+                max_health: Decimal256::percent(120),
+                start_after: start_after.clone(),
+                limit: Some(1),
+                block_time: None,
+            },
+        )
+        .unwrap();
+        let page: AllHealthFactorsResponse = from_binary(&res).unwrap();
+        if page.last_scanned.is_none() {
+            break;
+        }
+
+        at_risk.extend(page.health_factors.into_iter().map(|h| h.borrower));
+        start_after = page.last_scanned;
+    }
+
+    at_risk.sort();
+    assert_eq!(
+        at_risk,
+        vec!["addr0000".to_string(), "addr0001".to_string()]
+    );
+}
+
+#[test]
+fn pause_collateral_operations() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // store whitelist elems
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "batom".to_string(),
+        symbol: "batom".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "batom".to_string(),
+        },
+        custody_contract: "custody_batom".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+
+    let msg = ExecuteMsg::UpdateGuardian {
+        guardian: Some("guardian".to_string()),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // Unauthorized err
+    let msg = ExecuteMsg::UpdateCollateralPause {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        paused: PAUSE_DEPOSIT | PAUSE_BORROW | PAUSE_UNLOCK,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("guardian", &[]), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Whitelist {
+            collateral_token: Some(AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            }),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let whitelist_res: WhitelistResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        whitelist_res.elems[0].paused,
+        PAUSE_DEPOSIT | PAUSE_BORROW | PAUSE_UNLOCK
+    );
+
+    // guardian cannot unpause
+    let msg = ExecuteMsg::UpdateCollateralPause {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        paused: PAUSE_BORROW | PAUSE_UNLOCK,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("guardian", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // deposits of the paused collateral are refused
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: "bluna".to_string(),
+                },
+                Uint256::from(1000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "batom".to_string(),
+                },
+                Uint256::from(10000000u64),
+            ),
+        ],
+    };
+    let borrower = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), borrower.clone(), msg.clone());
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let unpause_msg = ExecuteMsg::UpdateCollateralPause {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        paused: PAUSE_BORROW | PAUSE_UNLOCK,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), unpause_msg).unwrap();
+    let _res = execute(deps.as_mut(), env.clone(), borrower.clone(), msg).unwrap();
+
+    deps.querier.with_oracle_price(&[
+        (
+            &("bluna".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(1000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+        (
+            &("batom".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(2000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+    ]);
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::zero())]);
+
+    // borrow-paused collateral does not count toward the borrow limit
+    // borrow_limit = 2000 * 10000000 * 0.6 = 12,000,000,000 uusd
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BorrowLimit {
+            borrower: "addr0000".to_string(),
+            block_time: None,
+            stable_denom: None,
+        },
+    )
+    .unwrap();
+    let borrow_limit_res: BorrowLimitResponse = from_binary(&res).unwrap();
+    assert_eq!(borrow_limit_res.borrow_limit, Uint256::from(12000000000u64));
+
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::one(),
+        )],
+        borrower: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), borrower.clone(), msg);
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "batom".to_string(),
+            },
+            Uint256::one(),
+        )],
+        borrower: None,
+        stable_denom: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), borrower.clone(), msg).unwrap();
+
+    // protocol wide liquidation pause
+    let msg = ExecuteMsg::UpdatePause {
+        paused: PAUSE_LIQUIDATION,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("guardian", &[]), msg).unwrap();
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::PauseInfo {}).unwrap();
+    let pause_info: PauseInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        pause_info,
+        PauseInfoResponse {
+            guardian: Some("guardian".to_string()),
+            paused: PAUSE_LIQUIDATION,
+        }
+    );
+
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info("addr0001", &[]), msg);
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn liquidate_paused_collateral() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_liquidation_percent(&[(&"liquidation".to_string(), &Decimal256::percent(1))]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    for (token, custody) in [("bluna", "custody_bluna"), ("batom", "custody_batom")] {
+        let msg = ExecuteMsg::Whitelist {
+            name: token.to_string(),
+            symbol: token.to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: token.to_string(),
+            },
+            custody_contract: custody.to_string(),
+            max_ltv: Decimal256::percent(60),
+            liquidation_threshold: Decimal256::percent(60),
+            deposit_cap: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: "bluna".to_string(),
+                },
+                Uint256::from(1000000u64),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "batom".to_string(),
+                },
+                Uint256::from(10000000u64),
+            ),
+        ],
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps.querier.with_oracle_price(&[
+        (
+            &("bluna".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(1000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+        (
+            &("batom".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(2000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+    ]);
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12600000001u64))]);
+
+    // only bluna liquidations are paused
+    let msg = ExecuteMsg::UpdateCollateralPause {
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        paused: PAUSE_LIQUIDATION,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let liquidator = mock_info("addr0001", &[]);
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: Some(AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        }),
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), liquidator.clone(), msg);
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // the unpaused collateral can still be seized
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: Some(AssetInfo::Token {
+            contract_addr: "batom".to_string(),
+        }),
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), liquidator.clone(), msg).unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "custody_batom".to_string(),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::LiquidateCollateral {
+                liquidator: "addr0001".to_string(),
+                borrower: "addr0000".to_string(),
+                amount: Uint256::from(100000u64),
+                liquidation_contract: None,
+                market_contract: None,
+            })
+            .unwrap(),
+        }))
+    );
+
+    // without a target, the paused collateral is left out
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), liquidator, msg).unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "custody_batom".to_string(),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::LiquidateCollateral {
+                liquidator: "addr0001".to_string(),
+                borrower: "addr0000".to_string(),
+                amount: Uint256::from(99000u64),
+                liquidation_contract: None,
+                market_contract: None,
+            })
+            .unwrap(),
+        }))
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::Collaterals {
+            borrower: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let mut collaterals_res: CollateralsResponse = from_binary(&res).unwrap();
+    collaterals_res.collaterals.sort();
+    assert_eq!(
+        collaterals_res.collaterals,
+        vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: "batom".to_string()
+                },
+                Uint256::from(9801000u64)
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: "bluna".to_string()
+                },
+                Uint256::from(1000000u64)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn auctioned_collateral_limits() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_liquidation_percent(&[(&"liquidation".to_string(), &Decimal256::percent(1))]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    for (token, custody) in [("bluna", "custody_bluna"), ("batom", "custody_batom")] {
+        let msg = ExecuteMsg::Whitelist {
+            name: token.to_string(),
+            symbol: token.to_string(),
+            collateral_token: AssetInfo::Token {
+                contract_addr: token.to_string(),
+            },
+            custody_contract: custody.to_string(),
+            max_ltv: Decimal256::percent(60),
+            liquidation_threshold: Decimal256::percent(60),
+            deposit_cap: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(1000000u64),
+        )],
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps.querier.with_oracle_price(&[
+        (
+            &("bluna".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(1000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+        (
+            &("batom".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(2000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+    ]);
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(12000000000u64))]);
+
+    // batom seized earlier is still up for auction
+    deps.querier.with_pending_collaterals(
+        "liquidation",
+        "addr0000",
+        vec![(
+            AssetInfo::Token {
+                contract_addr: "batom".to_string(),
+            },
+            Uint256::from(10000000u64),
+        )],
+    );
+
+    // borrow_limit = 1000 * 1000000 * 0.6 + 2000 * 10000000 * 0.6
+    // = 12,600,000,000 uusd
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BorrowLimit {
+            borrower: "addr0000".to_string(),
+            block_time: None,
+            stable_denom: None,
+        },
+    )
+    .unwrap();
+    let borrow_limit_res: BorrowLimitResponse = from_binary(&res).unwrap();
+    assert_eq!(borrow_limit_res.borrow_limit, Uint256::from(12600000000u64));
+
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info("addr0001", &[]), msg);
+    match res {
+        Err(ContractError::CannotLiquidateSafeLoan {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn restore_collateral() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(1000000u64),
+        )],
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // the collateral of a cancelled auction is locked again
+    let msg = ExecuteMsg::RestoreCollateral {
+        borrower: "addr0000".to_string(),
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(500000u64),
+        )],
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("liquidation", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "custody_bluna".to_string(),
+            funds: vec![],
+            msg: to_binary(&CustodyExecuteMsg::LockCollateral {
+                borrower: "addr0000".to_string(),
+                amount: Uint256::from(500000u64),
+            })
+            .unwrap(),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "restore_collateral"),
+            attr("borrower", "addr0000"),
+            attr("collaterals", "500000bluna"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::Collaterals {
+            borrower: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let collaterals_res: CollateralsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        collaterals_res.collaterals,
+        vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(1500000u64),
+        )]
+    );
+}
+
+#[test]
+fn register_market() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_liquidation_percent(&[(&"liquidation_krw".to_string(), &Decimal256::percent(10))]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Whitelist {
+        name: "bluna".to_string(),
+        symbol: "bluna".to_string(),
+        collateral_token: AssetInfo::Token {
+            contract_addr: "bluna".to_string(),
+        },
+        custody_contract: "custody_bluna".to_string(),
+        max_ltv: Decimal256::percent(60),
+        liquidation_threshold: Decimal256::percent(60),
+        deposit_cap: None,
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    deps.querier.with_epoch_state(&[(
+        &"market_krw".to_string(),
+        &(Uint256::zero(), Decimal256::one()),
+    )]);
+
+    let msg = ExecuteMsg::RegisterMarket {
+        stable_denom: "ukrw".to_string(),
+        market_contract: "market_krw".to_string(),
+        liquidation_contract: "liquidation_krw".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::RegisterMarket {
+            stable_denom: "uusd".to_string(),
+            market_contract: "market_usd".to_string(),
+            liquidation_contract: "liquidation_usd".to_string(),
+        },
+    );
+    match res {
+        Err(ContractError::BaseStableDenom {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "register_market"),
+            attr("stable_denom", "ukrw"),
+            attr("market_contract", "market_krw"),
+            attr("liquidation_contract", "liquidation_krw"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::Markets {}).unwrap();
+    let markets_res: MarketsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        markets_res,
+        MarketsResponse {
+            markets: vec![
+                MarketResponseElem {
+                    stable_denom: "uusd".to_string(),
+                    market_contract: "market".to_string(),
+                    liquidation_contract: "liquidation".to_string(),
+                },
+                MarketResponseElem {
+                    stable_denom: "ukrw".to_string(),
+                    market_contract: "market_krw".to_string(),
+                    liquidation_contract: "liquidation_krw".to_string(),
+                },
+            ],
+        }
+    );
+
+    let msg = ExecuteMsg::LockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(1000000u64),
+        )],
+    };
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps.querier.with_oracle_price(&[
+        (
+            &("bluna".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::from_ratio(1000u64, 1u64),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+        (
+            &("ukrw".to_string(), "uusd".to_string()),
+            &(
+                Decimal256::permille(1),
+                env.block.time.seconds(),
+                env.block.time.seconds(),
+            ),
+        ),
+    ]);
+
+    // borrow_limit = 1000 * 1000000 * 0.6 = 600,000,000 uusd
+    // loans = 100,000,000 uusd + 200,000,000,000 ukrw (200,000,000 uusd)
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::from(100000000u64))]);
+    deps.querier.with_market_loan_amount(&[(
+        &"market_krw".to_string(),
+        &"addr0000".to_string(),
+        &Uint256::from(200000000000u64),
+    )]);
+
+    let borrow_limit = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, stable_denom: Option<&str>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BorrowLimit {
+                borrower: "addr0000".to_string(),
+                block_time: None,
+                stable_denom: stable_denom.map(|denom| denom.to_string()),
+            },
+        );
+        res.map(|res| {
+            from_binary::<BorrowLimitResponse>(&res)
+                .unwrap()
+                .borrow_limit
+        })
+    };
+    assert_eq!(
+        borrow_limit(&deps, None).unwrap(),
+        Uint256::from(600000000u64)
+    );
+    assert_eq!(
+        borrow_limit(&deps, Some("uusd")).unwrap(),
+        Uint256::from(400000000u64)
+    );
+    // (600,000,000 - 100,000,000) / 0.001
+    assert_eq!(
+        borrow_limit(&deps, Some("ukrw")).unwrap(),
+        Uint256::from(500000000000u64)
+    );
+    assert!(borrow_limit(&deps, Some("ueur")).is_err());
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::HealthFactor {
+            borrower: "addr0000".to_string(),
+            block_time: None,
+        },
+    )
+    .unwrap();
+    let health_factor_res: HealthFactorResponse = from_binary(&res).unwrap();
+    assert_eq!(health_factor_res.loan_amount, Uint256::from(300000000u64));
+
+    // 400,000 bluna left would back 240,000,000 uusd
+    let msg = ExecuteMsg::UnlockCollateral {
+        collaterals: vec![(
+            AssetInfo::Token {
+                contract_addr: "bluna".to_string(),
+            },
+            Uint256::from(600000u64),
+        )],
+        borrower: None,
+        stable_denom: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg);
+    match res {
+        Err(ContractError::UnlockTooLarge(limit)) => assert_eq!(limit, 240000000u128),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // loans = 100,000,000 uusd + 600,000,000,000 ukrw (600,000,000 uusd)
+    deps.querier.with_market_loan_amount(&[(
+        &"market_krw".to_string(),
+        &"addr0000".to_string(),
+        &Uint256::from(600000000000u64),
+    )]);
+
+    let msg = ExecuteMsg::LiquidateCollateral {
+        borrower: "addr0000".to_string(),
+        collateral_token: None,
+        stable_denom: Some("ukrw".to_string()),
+    };
+    let res = execute(deps.as_mut(), env, mock_info("addr0001", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "custody_bluna".to_string(),
+                funds: vec![],
+                msg: to_binary(&CustodyExecuteMsg::LiquidateCollateral {
+                    liquidator: "addr0001".to_string(),
+                    borrower: "addr0000".to_string(),
+                    amount: Uint256::from(100000u64),
+                    liquidation_contract: Some("liquidation_krw".to_string()),
+                    market_contract: Some("market_krw".to_string()),
                 })
                 .unwrap(),
             })),
             SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "market".to_string(),
+                contract_addr: "market_krw".to_string(),
                 funds: vec![],
                 msg: to_binary(&MarketExecuteMsg::RepayStableFromLiquidation {
                     borrower: "addr0000".to_string(),
                     prev_balance: Uint256::zero(),
                 })
                 .unwrap(),
-            }))
+            })),
         ]
     );
+}
 
-    let res = query(
-        deps.as_ref(),
-        env,
-        QueryMsg::Collaterals {
-            borrower: "addr0000".to_string(),
+#[test]
+fn update_market() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("owner", &[]);
+    let env = mock_env();
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::permille(3),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    deps.querier.with_epoch_state(&[
+        (
+            &"market_krw".to_string(),
+            &(Uint256::zero(), Decimal256::one()),
+        ),
+        (
+            &"market_krw2".to_string(),
+            &(Uint256::zero(), Decimal256::one()),
+        ),
+    ]);
+
+    let msg = ExecuteMsg::RegisterMarket {
+        stable_denom: "ukrw".to_string(),
+        market_contract: "market_krw".to_string(),
+        liquidation_contract: "liquidation_krw".to_string(),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // a registered market can only be changed through update_market
+    let msg = ExecuteMsg::RegisterMarket {
+        stable_denom: "ukrw".to_string(),
+        market_contract: "market_krw2".to_string(),
+        liquidation_contract: "liquidation_krw2".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    match res {
+        Err(ContractError::MarketAlreadyRegistered {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::UpdateMarket {
+        stable_denom: "ukrw".to_string(),
+        market_contract: Some("market_krw2".to_string()),
+        liquidation_contract: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::UpdateMarket {
+            stable_denom: "ueur".to_string(),
+            market_contract: Some("market_eur".to_string()),
+            liquidation_contract: None,
+        },
+    );
+    assert!(res.is_err());
+
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_market"),
+            attr("stable_denom", "ukrw"),
+            attr("market_contract", "market_krw2"),
+            attr("liquidation_contract", "liquidation_krw"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), env, QueryMsg::Markets {}).unwrap();
+    let markets_res: MarketsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        markets_res.markets[1],
+        MarketResponseElem {
+            stable_denom: "ukrw".to_string(),
+            market_contract: "market_krw2".to_string(),
+            liquidation_contract: "liquidation_krw".to_string(),
+        }
+    );
+}
+
+#[test]
+fn market_epoch_operations() {
+    let mut deps = mock_dependencies(&[
+        Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(10000000000u128),
+        },
+        Coin {
+            denom: "ukrw".to_string(),
+            amount: Uint128::from(5000000000u128),
         },
+    ]);
+
+    let mut env = mock_env();
+    let info = mock_info("owner", &[]);
+    let msg = InstantiateMsg {
+        owner_addr: "owner".to_string(),
+        oracle_contract: "oracle".to_string(),
+        market_contract: "market".to_string(),
+        liquidation_contract: "liquidation".to_string(),
+        collector_contract: "collector".to_string(),
+        stable_denom: "uusd".to_string(),
+        epoch_period: 86400u64,
+        threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
+        target_deposit_rate: Decimal256::permille(5),
+        buffer_distribution_factor: Decimal256::percent(20),
+        anc_purchase_factor: Decimal256::percent(20),
+        price_timeframe: 60u64,
+        close_factor: Decimal256::one(),
+    };
+    let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    deps.querier.with_epoch_state(&[
+        (
+            &"market".to_string(),
+            &(Uint256::from(1000000u64), Decimal256::one()),
+        ),
+        (
+            &"market_krw".to_string(),
+            &(Uint256::from(2000000u64), Decimal256::one()),
+        ),
+    ]);
+
+    let msg = ExecuteMsg::RegisterMarket {
+        stable_denom: "ukrw".to_string(),
+        market_contract: "market_krw".to_string(),
+        liquidation_contract: "liquidation_krw".to_string(),
+    };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // the epochs of the market start from its registration
+    assert_eq!(
+        read_market_epoch_state(deps.as_ref().storage, "ukrw").unwrap(),
+        EpochState {
+            deposit_rate: Decimal256::zero(),
+            prev_aterra_supply: Uint256::from(2000000u64),
+            prev_exchange_rate: Decimal256::one(),
+            prev_interest_buffer: Uint256::from(5000000000u64),
+            last_executed_height: env.block.height,
+        }
+    );
+
+    // store epoch state for test purpose
+    let mut market_epoch_state = read_market_epoch_state(deps.as_ref().storage, "ukrw").unwrap();
+    market_epoch_state.prev_interest_buffer = Uint256::from(4999000000u64);
+    store_market_epoch_state(deps.as_mut().storage, "ukrw", &market_epoch_state).unwrap();
+
+    env.block.height += 86400u64;
+    deps.querier.with_epoch_state(&[
+        (
+            &"market".to_string(),
+            &(Uint256::from(1000000u64), Decimal256::percent(120)),
+        ),
+        (
+            &"market_krw".to_string(),
+            &(Uint256::from(2000000u64), Decimal256::one()),
+        ),
+    ]);
+
+    // ukrw accrued_buffer = 1,000,000
+    // anc_purchase_amount = 200,000
+    // missing_deposits = 2,000,000 * 86400 * 0.000001 = 172,800
+    let msg = ExecuteMsg::ExecuteEpochOperations {};
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(2_000_000_000u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::UpdateEpochState {
+                    interest_buffer: Uint256::from(8_000_000_000u128),
+                    distributed_interest: Uint256::zero(),
+                    accrued_buffer: Uint256::from(10_000_000_000u128),
+                    anc_purchase_amount: Uint256::from(2_000_000_000u128),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![Coin {
+                    denom: "ukrw".to_string(),
+                    amount: Uint128::from(200_000u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "market_krw".to_string(),
+                amount: vec![Coin {
+                    denom: "ukrw".to_string(),
+                    amount: Uint128::from(172_800u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::UpdateMarketEpochState {
+                    stable_denom: "ukrw".to_string(),
+                    interest_buffer: Uint256::from(4_999_627_200u128),
+                    distributed_interest: Uint256::from(172_800u128),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    let msg = ExecuteMsg::UpdateMarketEpochState {
+        stable_denom: "ukrw".to_string(),
+        interest_buffer: Uint256::from(4_999_627_200u128),
+        distributed_interest: Uint256::from(172_800u128),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        msg,
     )
     .unwrap();
-    let collaterals_res: CollateralsResponse = from_binary(&res).unwrap();
     assert_eq!(
-        collaterals_res,
-        CollateralsResponse {
-            borrower: "addr0000".to_string(),
-            collaterals: vec![
-                (batom_collat_token, Uint256::from(9900000u64)),
-                (bluna_collat_token, Uint256::from(990000u64)),
-            ]
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "market_krw".to_string(),
+            funds: vec![],
+            msg: to_binary(&MarketExecuteMsg::ExecuteEpochOperations {
+                deposit_rate: Decimal256::zero(),
+                target_deposit_rate: Decimal256::permille(5),
+                threshold_deposit_rate: Decimal256::from_ratio(1u64, 1000000u64),
+                distributed_interest: Uint256::from(172_800u128),
+            })
+            .unwrap(),
+        }))]
+    );
+    assert_eq!(
+        read_market_epoch_state(deps.as_ref().storage, "ukrw").unwrap(),
+        EpochState {
+            deposit_rate: Decimal256::zero(),
+            prev_aterra_supply: Uint256::from(2000000u64),
+            prev_exchange_rate: Decimal256::one(),
+            prev_interest_buffer: Uint256::from(4_999_627_200u128),
+            last_executed_height: env.block.height,
         }
     );
+
+    // only the loan at the ukrw market is left; reserves cover 100,000 ukrw
+    deps.querier
+        .with_loan_amount(&[(&"addr0000".to_string(), &Uint256::zero())]);
+    deps.querier.with_market_loan_amount(&[(
+        &"market_krw".to_string(),
+        &"addr0000".to_string(),
+        &Uint256::from(500000u64),
+    )]);
+    deps.querier
+        .with_market_reserves(Decimal256::from_uint256(100000u64));
+
+    let msg = ExecuteMsg::WriteOffBadDebt {
+        borrower: "addr0000".to_string(),
+    };
+    let res = execute(deps.as_mut(), env, mock_info("addr0001", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "market_krw".to_string(),
+            funds: vec![Coin {
+                denom: "ukrw".to_string(),
+                amount: Uint128::from(400000u128),
+            }],
+            msg: to_binary(&MarketExecuteMsg::WriteOffBadDebt {
+                borrower: "addr0000".to_string(),
+            })
+            .unwrap(),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "write_off_bad_debt"),
+            attr("borrower", "addr0000"),
+            attr("stable_denom", "ukrw"),
+            attr("bad_debt", "500000"),
+            attr("buffer_cover", "400000"),
+        ]
+    );
+    assert_eq!(
+        read_market_epoch_state(deps.as_ref().storage, "ukrw")
+            .unwrap()
+            .prev_interest_buffer,
+        Uint256::from(4_999_227_200u128)
+    );
 }
@@ -0,0 +1,13 @@
+use cosmwasm_std::{Addr, CosmosMsg, Deps, StdResult, Uint128};
+use terra_cosmwasm::TerraMsgWrapper;
+
+/// Claims the rewards of a custody collateral from its `reward_contract`.
+/// Each custody contract implements it for the reward contract of its asset.
+pub trait RewardAdapter {
+    /// Reward amount `custody` can claim from `reward_contract`
+    fn accrued_rewards(deps: Deps, reward_contract: &Addr, custody: &Addr) -> StdResult<Uint128>;
+
+    /// Message claiming the accrued rewards to the custody,
+    /// paid in native coins
+    fn claim_rewards_msg(reward_contract: &Addr) -> StdResult<CosmosMsg<TerraMsgWrapper>>;
+}
@@ -1,30 +1,51 @@
+use crate::distribution::settle_rewards;
 use crate::error::ContractError;
 use crate::state::{
-    read_borrower_info, read_borrowers, read_config, remove_borrower_info, store_borrower_info,
-    BorrowerInfo, Config,
+    read_borrower_info, read_borrowers, read_config, read_deposit_info, read_pause_info,
+    remove_borrower_info, store_borrower_info, store_deposit_info, BorrowerInfo, Config,
+    DepositInfo,
 };
 
 use cosmwasm_bignumber::Uint256;
 use cosmwasm_std::{
-    attr, to_binary, Addr, CanonicalAddr, CosmosMsg, Deps, DepsMut, MessageInfo, Response,
-    StdResult, WasmMsg,
+    attr, to_binary, Addr, BankMsg, CanonicalAddr, Coin, CosmosMsg, Deps, DepsMut, MessageInfo,
+    Response, StdError, StdResult, Storage, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 use moneymarket::custody::{BorrowerResponse, BorrowersResponse};
 use moneymarket::liquidation::Cw20HookMsg as LiquidationCw20HookMsg;
+use moneymarket::liquidation_queue::ExecuteMsg as LiquidationQueueExecuteMsg;
+use moneymarket::pause::{PAUSE_DEPOSIT, PAUSE_LIQUIDATION, PAUSE_UNLOCK};
+use moneymarket::querier::deduct_tax;
+use moneymarket::tokens::AssetInfoRaw;
 use terra_cosmwasm::TerraMsgWrapper;
 
 /// Deposit new collateral
-/// Executor: bAsset token contract
+/// Executor: collateral token contract, or borrower for native collateral
 pub fn deposit_collateral(
     deps: DepsMut,
     borrower: Addr,
     amount: Uint256,
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    if read_pause_info(deps.storage)?.paused & PAUSE_DEPOSIT != 0 {
+        return Err(ContractError::Paused {});
+    }
+
+    let mut deposit_info: DepositInfo = read_deposit_info(deps.storage)?;
+    deposit_info.total_deposits += amount;
+    if let Some(deposit_cap) = deposit_info.deposit_cap {
+        if deposit_info.total_deposits > deposit_cap {
+            return Err(ContractError::DepositCapExceeded(deposit_cap.into()));
+        }
+    }
+
+    store_deposit_info(deps.storage, &deposit_info)?;
+
     let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
     let mut borrower_info: BorrowerInfo = read_borrower_info(deps.storage, &borrower_raw);
 
     // increase borrower collateral
+    settle_rewards(deps.storage, &mut borrower_info)?;
     borrower_info.balance += amount;
     borrower_info.spendable += amount;
 
@@ -45,6 +66,9 @@ pub fn withdraw_collateral(
     amount: Option<Uint256>,
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
     let config: Config = read_config(deps.storage)?;
+    if read_pause_info(deps.storage)?.paused & PAUSE_UNLOCK != 0 {
+        return Err(ContractError::Paused {});
+    }
 
     let borrower = info.sender;
     let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
@@ -59,27 +83,25 @@ pub fn withdraw_collateral(
     }
 
     // decrease borrower collateral
+    settle_rewards(deps.storage, &mut borrower_info)?;
     borrower_info.balance = borrower_info.balance - amount;
     borrower_info.spendable = borrower_info.spendable - amount;
+    decrease_total_deposits(deps.storage, amount)?;
 
-    if borrower_info.balance == Uint256::zero() {
+    // keep the borrower while rewards are left to claim
+    if borrower_info.balance == Uint256::zero() && borrower_info.pending_rewards.is_zero() {
         remove_borrower_info(deps.storage, &borrower_raw);
     } else {
         store_borrower_info(deps.storage, &borrower_raw, &borrower_info)?;
     }
 
     Ok(Response::new()
-        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: deps
-                .api
-                .addr_humanize(&config.collateral_token)?
-                .to_string(),
-            funds: vec![],
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: borrower.to_string(),
-                amount: amount.into(),
-            })?,
-        }))
+        .add_message(transfer_collateral_msg(
+            deps.as_ref(),
+            &config.collateral_token,
+            &borrower,
+            amount,
+        )?)
         .add_attributes(vec![
             attr("action", "withdraw_collateral"),
             attr("borrower", borrower.as_str()),
@@ -87,6 +109,41 @@ pub fn withdraw_collateral(
         ]))
 }
 
+/// Update the maximum total amount of collateral deposits
+/// Executor: overseer
+pub fn update_deposit_cap(
+    deps: DepsMut,
+    info: MessageInfo,
+    deposit_cap: Option<Uint256>,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.overseer_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut deposit_info: DepositInfo = read_deposit_info(deps.storage)?;
+    deposit_info.deposit_cap = deposit_cap;
+    store_deposit_info(deps.storage, &deposit_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_deposit_cap"),
+        attr(
+            "deposit_cap",
+            deposit_cap.map_or_else(|| "none".to_string(), |cap| cap.to_string()),
+        ),
+    ]))
+}
+
+fn decrease_total_deposits(storage: &mut dyn Storage, amount: Uint256) -> StdResult<()> {
+    let mut deposit_info: DepositInfo = read_deposit_info(storage)?;
+    if amount > deposit_info.total_deposits {
+        return Err(StdError::generic_err("Total deposits underflow"));
+    }
+
+    deposit_info.total_deposits = deposit_info.total_deposits - amount;
+    store_deposit_info(storage, &deposit_info)
+}
+
 /// Decrease spendable collateral to lock
 /// specified amount of collateral token
 /// Executor: overseer
@@ -151,18 +208,27 @@ pub fn unlock_collateral(
     ]))
 }
 
+/// Liquidations of loans in another stable denom name the liquidation
+/// contract and market of that denom
+#[allow(clippy::too_many_arguments)]
 pub fn liquidate_collateral(
     deps: DepsMut,
     info: MessageInfo,
     liquidator: Addr,
     borrower: Addr,
     amount: Uint256,
+    liquidation_contract: Option<Addr>,
+    market_contract: Option<Addr>,
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
     let config: Config = read_config(deps.storage)?;
     if deps.api.addr_canonicalize(info.sender.as_str())? != config.overseer_contract {
         return Err(ContractError::Unauthorized {});
     }
 
+    if read_pause_info(deps.storage)?.paused & PAUSE_LIQUIDATION != 0 {
+        return Err(ContractError::Paused {});
+    }
+
     let borrower_raw: CanonicalAddr = deps.api.addr_canonicalize(borrower.as_str())?;
     let mut borrower_info: BorrowerInfo = read_borrower_info(deps.storage, &borrower_raw);
     let borrowed_amt = borrower_info.balance - borrower_info.spendable;
@@ -172,35 +238,61 @@ pub fn liquidate_collateral(
         ));
     }
 
+    settle_rewards(deps.storage, &mut borrower_info)?;
     borrower_info.balance = borrower_info.balance - amount;
     store_borrower_info(deps.storage, &borrower_raw, &borrower_info)?;
+    decrease_total_deposits(deps.storage, amount)?;
 
-    Ok(Response::new()
-        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: deps
-                .api
-                .addr_humanize(&config.collateral_token)?
-                .to_string(),
+    let liquidation_contract = match liquidation_contract {
+        Some(liquidation_contract) => liquidation_contract,
+        None => deps.api.addr_humanize(&config.liquidation_contract)?,
+    };
+    let market_contract = match market_contract {
+        Some(market_contract) => market_contract,
+        None => deps.api.addr_humanize(&config.market_contract)?,
+    };
+
+    let fee_address = deps
+        .api
+        .addr_humanize(&config.overseer_contract)?
+        .to_string();
+    let liquidation_msg = match config.collateral_token {
+        AssetInfoRaw::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&contract_addr)?.to_string(),
             funds: vec![],
             msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: deps
-                    .api
-                    .addr_humanize(&config.liquidation_contract)?
-                    .to_string(),
+                contract: liquidation_contract.to_string(),
                 amount: amount.into(),
                 msg: to_binary(&LiquidationCw20HookMsg::ExecuteBid {
                     liquidator: liquidator.to_string(),
-                    fee_address: Some(
-                        deps.api
-                            .addr_humanize(&config.overseer_contract)?
-                            .to_string(),
-                    ),
-                    repay_address: Some(
-                        deps.api.addr_humanize(&config.market_contract)?.to_string(),
-                    ),
+                    fee_address: Some(fee_address),
+                    repay_address: Some(market_contract.to_string()),
+                    borrower: Some(borrower.to_string()),
                 })?,
             })?,
-        }))
+        }),
+        // the liquidation contract must accept native collateral,
+        // see `liquidation_queue::ExecuteMsg::ExecuteBid`
+        AssetInfoRaw::NativeToken { denom } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: liquidation_contract.to_string(),
+            funds: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom,
+                    amount: amount.into(),
+                },
+            )?],
+            msg: to_binary(&LiquidationQueueExecuteMsg::ExecuteBid {
+                liquidator: liquidator.to_string(),
+                fee_address: Some(fee_address),
+                repay_address: Some(market_contract.to_string()),
+                borrower: Some(borrower.to_string()),
+            })?,
+        }),
+    };
+
+    Ok(Response::new()
+        .add_message(liquidation_msg)
         .add_attributes(vec![
             attr("action", "liquidate_collateral"),
             attr("liquidator", liquidator),
@@ -209,13 +301,43 @@ pub fn liquidate_collateral(
         ]))
 }
 
+fn transfer_collateral_msg(
+    deps: Deps,
+    collateral_token: &AssetInfoRaw,
+    recipient: &Addr,
+    amount: Uint256,
+) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+    Ok(match collateral_token {
+        AssetInfoRaw::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(contract_addr)?.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: amount.into(),
+            })?,
+        }),
+        AssetInfoRaw::NativeToken { denom } => CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![deduct_tax(
+                deps,
+                Coin {
+                    denom: denom.to_string(),
+                    amount: amount.into(),
+                },
+            )?],
+        }),
+    })
+}
+
 pub fn query_borrower(deps: Deps, borrower: Addr) -> StdResult<BorrowerResponse> {
     let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
-    let borrower_info: BorrowerInfo = read_borrower_info(deps.storage, &borrower_raw);
+    let mut borrower_info: BorrowerInfo = read_borrower_info(deps.storage, &borrower_raw);
+    settle_rewards(deps.storage, &mut borrower_info)?;
     Ok(BorrowerResponse {
         borrower: borrower.to_string(),
         balance: borrower_info.balance,
         spendable: borrower_info.spendable,
+        pending_rewards: borrower_info.pending_rewards,
     })
 }
 
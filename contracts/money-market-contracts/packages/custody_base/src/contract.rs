@@ -0,0 +1,293 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{
+    attr, from_binary, Addr, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+};
+
+use crate::adapter::RewardAdapter;
+use crate::collateral::{
+    deposit_collateral, liquidate_collateral, lock_collateral, unlock_collateral,
+    update_deposit_cap, withdraw_collateral,
+};
+use crate::distribution::{
+    claim_rewards, distribute_hook, distribute_rewards, swap_to_stable_denom, update_reward_routing,
+};
+use crate::error::ContractError;
+use crate::state::{
+    read_config, read_deposit_info, read_pause_info, store_config, store_deposit_info,
+    store_pause_info, Config, PauseInfo,
+};
+
+use cw20::{BalanceResponse, Cw20QueryMsg, Cw20ReceiveMsg};
+use moneymarket::common::optional_addr_validate;
+use moneymarket::custody::{Cw20HookMsg, ExecuteMsg};
+use moneymarket::pause::{is_guardian_pause, is_valid_pause, PauseInfoResponse};
+use moneymarket::querier::query_balance;
+use moneymarket::tokens::AssetInfoRaw;
+use terra_cosmwasm::TerraMsgWrapper;
+
+pub const CLAIM_REWARDS_OPERATION: u64 = 1u64;
+pub const SWAP_TO_STABLE_OPERATION: u64 = 2u64;
+
+/// Handles the `moneymarket::custody::ExecuteMsg` of a custody,
+/// claiming the collateral rewards through `A`
+pub fn execute<A: RewardAdapter>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::DepositCollateral {} => {
+            let borrower = info.sender.clone();
+            receive_native(deps, info, borrower)
+        }
+        ExecuteMsg::DepositCollateralFor { borrower } => {
+            let borrower = deps.api.addr_validate(&borrower)?;
+            receive_native(deps, info, borrower)
+        }
+        ExecuteMsg::UpdateConfig {
+            owner,
+            liquidation_contract,
+        } => {
+            let api = deps.api;
+            update_config(
+                deps,
+                info,
+                optional_addr_validate(api, owner)?,
+                optional_addr_validate(api, liquidation_contract)?,
+            )
+        }
+        ExecuteMsg::UpdateGuardian { guardian } => {
+            let api = deps.api;
+            update_guardian(deps, info, optional_addr_validate(api, guardian)?)
+        }
+        ExecuteMsg::UpdatePause { paused } => update_pause(deps, info, paused),
+        ExecuteMsg::UpdateDepositCap { deposit_cap } => update_deposit_cap(deps, info, deposit_cap),
+        ExecuteMsg::UpdateRewardRouting {
+            buffer_share,
+            borrower_share,
+            collector_share,
+            collector_contract,
+        } => {
+            let api = deps.api;
+            update_reward_routing(
+                deps,
+                info,
+                buffer_share,
+                borrower_share,
+                collector_share,
+                optional_addr_validate(api, collector_contract)?,
+            )
+        }
+        ExecuteMsg::LockCollateral { borrower, amount } => {
+            let borrower_addr = deps.api.addr_validate(&borrower)?;
+            lock_collateral(deps, info, borrower_addr, amount)
+        }
+        ExecuteMsg::UnlockCollateral { borrower, amount } => {
+            let borrower_addr = deps.api.addr_validate(&borrower)?;
+            unlock_collateral(deps, info, borrower_addr, amount)
+        }
+        ExecuteMsg::DistributeRewards {} => distribute_rewards::<A>(deps, env, info),
+        ExecuteMsg::WithdrawCollateral { amount } => withdraw_collateral(deps, info, amount),
+        ExecuteMsg::ClaimRewards {} => claim_rewards(deps, info),
+        ExecuteMsg::LiquidateCollateral {
+            liquidator,
+            borrower,
+            amount,
+            liquidation_contract,
+            market_contract,
+        } => {
+            let api = deps.api;
+            let liquidator_addr = api.addr_validate(&liquidator)?;
+            let borrower_addr = api.addr_validate(&borrower)?;
+            liquidate_collateral(
+                deps,
+                info,
+                liquidator_addr,
+                borrower_addr,
+                amount,
+                optional_addr_validate(api, liquidation_contract)?,
+                optional_addr_validate(api, market_contract)?,
+            )
+        }
+    }
+}
+
+pub fn reply(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    match msg.id {
+        // ClaimRewards callback
+        CLAIM_REWARDS_OPERATION => swap_to_stable_denom(deps, env),
+        // Swap to stable callback
+        SWAP_TO_STABLE_OPERATION => distribute_hook(deps, env),
+        _ => Err(ContractError::InvalidReplyId {}),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let contract_addr = info.sender;
+
+    let borrower = match from_binary(&cw20_msg.msg) {
+        Ok(Cw20HookMsg::DepositCollateral {}) => cw20_msg.sender,
+        Ok(Cw20HookMsg::DepositCollateralFor { borrower }) => borrower,
+        _ => return Err(ContractError::MissingDepositCollateralHook {}),
+    };
+
+    // only asset contract can execute this message
+    let config: Config = read_config(deps.storage)?;
+    match config.collateral_token {
+        AssetInfoRaw::Token {
+            contract_addr: collateral_token,
+        } => {
+            if deps.api.addr_canonicalize(contract_addr.as_str())? != collateral_token {
+                return Err(ContractError::Unauthorized {});
+            }
+        }
+        AssetInfoRaw::NativeToken { .. } => return Err(ContractError::UnsupportedDeposit {}),
+    }
+
+    let borrower = deps.api.addr_validate(&borrower)?;
+    deposit_collateral(deps, borrower, cw20_msg.amount.into())
+}
+
+pub fn receive_native(
+    deps: DepsMut,
+    info: MessageInfo,
+    borrower: Addr,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let denom = match config.collateral_token {
+        AssetInfoRaw::NativeToken { denom } => denom,
+        AssetInfoRaw::Token { .. } => return Err(ContractError::UnsupportedDeposit {}),
+    };
+
+    // other coins sent along are not counted
+    let amount: Uint256 = info
+        .funds
+        .iter()
+        .find(|c| c.denom == denom)
+        .map(|c| Uint256::from(c.amount))
+        .unwrap_or_else(Uint256::zero);
+    if amount.is_zero() {
+        return Err(ContractError::ZeroDeposit(denom));
+    }
+
+    deposit_collateral(deps, borrower, amount)
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<Addr>,
+    liquidation_contract: Option<Addr>,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let mut config: Config = read_config(deps.storage)?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_canonicalize(owner.as_str())?;
+    }
+
+    if let Some(liquidation_contract) = liquidation_contract {
+        config.liquidation_contract = deps.api.addr_canonicalize(liquidation_contract.as_str())?;
+    }
+
+    store_config(deps.storage, &config)?;
+    Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
+}
+
+pub fn update_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<Addr>,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    pause_info.guardian = match guardian {
+        Some(guardian) => Some(deps.api.addr_canonicalize(guardian.as_str())?),
+        None => None,
+    };
+
+    store_pause_info(deps.storage, &pause_info)?;
+    Ok(Response::new().add_attributes(vec![attr("action", "update_guardian")]))
+}
+
+pub fn update_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: u8,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let mut pause_info: PauseInfo = read_pause_info(deps.storage)?;
+
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let is_guardian = pause_info.guardian.as_ref() == Some(&sender_raw);
+    if sender_raw != config.owner && !(is_guardian && is_guardian_pause(pause_info.paused, paused))
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !is_valid_pause(paused) {
+        return Err(ContractError::InvalidPause {});
+    }
+
+    pause_info.paused = paused;
+    store_pause_info(deps.storage, &pause_info)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_pause"),
+        attr("paused", paused.to_string()),
+    ]))
+}
+
+pub fn query_pause_info(deps: Deps) -> StdResult<PauseInfoResponse> {
+    let pause_info: PauseInfo = read_pause_info(deps.storage)?;
+    Ok(PauseInfoResponse {
+        guardian: match pause_info.guardian {
+            Some(guardian) => Some(deps.api.addr_humanize(&guardian)?.to_string()),
+            None => None,
+        },
+        paused: pause_info.paused,
+    })
+}
+
+/// Count the deposits made before they were tracked
+/// from the collateral held by the custody
+pub fn migrate_total_deposits(deps: DepsMut, env: Env) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+    let mut deposit_info = read_deposit_info(deps.storage)?;
+    deposit_info.total_deposits = match config.collateral_token {
+        AssetInfoRaw::Token { contract_addr } => {
+            let balance: BalanceResponse = deps.querier.query_wasm_smart(
+                deps.api.addr_humanize(&contract_addr)?,
+                &Cw20QueryMsg::Balance {
+                    address: env.contract.address.to_string(),
+                },
+            )?;
+            balance.balance.into()
+        }
+        AssetInfoRaw::NativeToken { denom } => {
+            query_balance(deps.as_ref(), env.contract.address, denom)?
+        }
+    };
+    store_deposit_info(deps.storage, &deposit_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("total_deposits", deposit_info.total_deposits.to_string()),
+    ]))
+}
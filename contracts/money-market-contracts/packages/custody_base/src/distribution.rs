@@ -0,0 +1,290 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    attr, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, ReplyOn, Response,
+    StdResult, Storage, SubMsg, Uint128,
+};
+
+use crate::adapter::RewardAdapter;
+use crate::contract::{CLAIM_REWARDS_OPERATION, SWAP_TO_STABLE_OPERATION};
+use crate::error::ContractError;
+use crate::state::{
+    read_borrower_info, read_config, read_deposit_info, read_reward_routing, read_reward_state,
+    store_borrower_info, store_reward_routing, store_reward_state, BorrowerInfo, Config,
+    RewardRouting, RewardState,
+};
+
+use moneymarket::custody::RewardRoutingResponse;
+use moneymarket::querier::{deduct_tax, query_all_balances, query_balance};
+use moneymarket::tokens::AssetInfoRaw;
+use terra_cosmwasm::{create_swap_msg, TerraMsgWrapper};
+
+// REWARD_THRESHOLD
+// This value is used as the minimum reward claim amount
+// thus if a user's reward is less than 1 ust do not send the ClaimRewards msg
+const REWARDS_THRESHOLD: Uint128 = Uint128::new(1000000);
+
+/// Request withdraw reward operation to
+/// reward contract and execute `distribute_hook`;
+/// nothing to do without a reward contract
+/// Executor: overseer
+pub fn distribute_rewards<A: RewardAdapter>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.overseer_contract != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let reward_contract = match config.reward_contract {
+        Some(reward_contract) => deps.api.addr_humanize(&reward_contract)?,
+        None => return Ok(Response::default()),
+    };
+
+    let accrued_rewards: Uint128 =
+        A::accrued_rewards(deps.as_ref(), &reward_contract, &env.contract.address)?;
+    if accrued_rewards < REWARDS_THRESHOLD {
+        return Ok(Response::default());
+    }
+
+    // Do not emit the event logs here
+    Ok(
+        Response::new().add_submessages(vec![SubMsg::reply_on_success(
+            A::claim_rewards_msg(&reward_contract)?,
+            CLAIM_REWARDS_OPERATION,
+        )]),
+    )
+}
+
+/// Split swapped rewards between the overseer, the borrowers
+/// and the collector following the reward routing
+/// Executor: itself
+pub fn distribute_hook(
+    deps: DepsMut,
+    env: Env,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let contract_addr = env.contract.address;
+    let config: Config = read_config(deps.storage)?;
+    let overseer_contract = deps.api.addr_humanize(&config.overseer_contract)?;
+    let reward_routing: RewardRouting = read_reward_routing(deps.storage)?;
+    let mut reward_state: RewardState = read_reward_state(deps.storage)?;
+
+    // reward_amount = (prev_balance + reward_amount) - prev_balance
+    // = (unclaimed_rewards + reward_amount) - unclaimed_rewards
+    let balance: Uint256 = query_balance(
+        deps.as_ref(),
+        contract_addr,
+        config.stable_denom.to_string(),
+    )?;
+    let reward_amount = if balance > reward_state.unclaimed_rewards {
+        balance - reward_state.unclaimed_rewards
+    } else {
+        Uint256::zero()
+    };
+
+    let collector_rewards = match reward_routing.collector_contract {
+        Some(_) => reward_amount * reward_routing.collector_share,
+        None => Uint256::zero(),
+    };
+
+    // without collaterals the borrower share stays in the buffer
+    let total_deposits = read_deposit_info(deps.storage)?.total_deposits;
+    let borrower_rewards = if total_deposits.is_zero() {
+        Uint256::zero()
+    } else {
+        reward_amount * reward_routing.borrower_share
+    };
+    if !borrower_rewards.is_zero() {
+        reward_state.global_index +=
+            Decimal256::from_uint256(borrower_rewards) / Decimal256::from_uint256(total_deposits);
+        reward_state.unclaimed_rewards += borrower_rewards;
+        store_reward_state(deps.storage, &reward_state)?;
+    }
+
+    let buffer_rewards = reward_amount - collector_rewards - borrower_rewards;
+
+    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
+    if !buffer_rewards.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: overseer_contract.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom.clone(),
+                    amount: buffer_rewards.into(),
+                },
+            )?],
+        }));
+    }
+
+    if let (Some(collector_contract), false) = (
+        reward_routing.collector_contract,
+        collector_rewards.is_zero(),
+    ) {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: deps.api.addr_humanize(&collector_contract)?.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom,
+                    amount: collector_rewards.into(),
+                },
+            )?],
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "distribute_rewards"),
+        attr("buffer_rewards", buffer_rewards),
+        attr("borrower_rewards", borrower_rewards),
+        attr("collector_rewards", collector_rewards),
+    ]))
+}
+
+/// Accrue the borrower share of rewards on the current collateral balance;
+/// must run before the balance changes
+pub fn settle_rewards(storage: &dyn Storage, borrower_info: &mut BorrowerInfo) -> StdResult<()> {
+    let reward_state: RewardState = read_reward_state(storage)?;
+    borrower_info.pending_rewards +=
+        borrower_info.balance * (reward_state.global_index - borrower_info.reward_index);
+    borrower_info.reward_index = reward_state.global_index;
+    Ok(())
+}
+
+/// Send the accrued borrower share of rewards
+/// Executor: borrower
+pub fn claim_rewards(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+
+    let borrower = info.sender;
+    let borrower_raw = deps.api.addr_canonicalize(borrower.as_str())?;
+    let mut borrower_info: BorrowerInfo = read_borrower_info(deps.storage, &borrower_raw);
+    settle_rewards(deps.storage, &mut borrower_info)?;
+
+    let claim_amount = borrower_info.pending_rewards;
+    borrower_info.pending_rewards = Uint256::zero();
+    store_borrower_info(deps.storage, &borrower_raw, &borrower_info)?;
+
+    let mut reward_state: RewardState = read_reward_state(deps.storage)?;
+    reward_state.unclaimed_rewards = if reward_state.unclaimed_rewards > claim_amount {
+        reward_state.unclaimed_rewards - claim_amount
+    } else {
+        Uint256::zero()
+    };
+    store_reward_state(deps.storage, &reward_state)?;
+
+    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
+    if !claim_amount.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: borrower.to_string(),
+            amount: vec![deduct_tax(
+                deps.as_ref(),
+                Coin {
+                    denom: config.stable_denom,
+                    amount: claim_amount.into(),
+                },
+            )?],
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "claim_rewards"),
+        attr("borrower", borrower),
+        attr("claim_amount", claim_amount),
+    ]))
+}
+
+pub fn update_reward_routing(
+    deps: DepsMut,
+    info: MessageInfo,
+    buffer_share: Decimal256,
+    borrower_share: Decimal256,
+    collector_share: Decimal256,
+    collector_contract: Option<Addr>,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if buffer_share + borrower_share + collector_share != Decimal256::one()
+        || (!collector_share.is_zero() && collector_contract.is_none())
+    {
+        return Err(ContractError::InvalidRewardRouting {});
+    }
+
+    store_reward_routing(
+        deps.storage,
+        &RewardRouting {
+            buffer_share,
+            borrower_share,
+            collector_share,
+            collector_contract: match collector_contract {
+                Some(collector_contract) => {
+                    Some(deps.api.addr_canonicalize(collector_contract.as_str())?)
+                }
+                None => None,
+            },
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_reward_routing"),
+        attr("buffer_share", buffer_share.to_string()),
+        attr("borrower_share", borrower_share.to_string()),
+        attr("collector_share", collector_share.to_string()),
+    ]))
+}
+
+pub fn query_reward_routing(deps: Deps) -> StdResult<RewardRoutingResponse> {
+    let reward_routing: RewardRouting = read_reward_routing(deps.storage)?;
+    Ok(RewardRoutingResponse {
+        buffer_share: reward_routing.buffer_share,
+        borrower_share: reward_routing.borrower_share,
+        collector_share: reward_routing.collector_share,
+        collector_contract: match reward_routing.collector_contract {
+            Some(collector_contract) => {
+                Some(deps.api.addr_humanize(&collector_contract)?.to_string())
+            }
+            None => None,
+        },
+    })
+}
+
+/// Swap all coins to stable_denom and execute `distribute_hook`,
+/// right away when the rewards are all in stable_denom
+/// Executor: itself
+pub fn swap_to_stable_denom(
+    deps: DepsMut,
+    env: Env,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+
+    // a native collateral is held in the same balances as the rewards
+    let collateral_denom = match &config.collateral_token {
+        AssetInfoRaw::NativeToken { denom } => Some(denom.as_str()),
+        AssetInfoRaw::Token { .. } => None,
+    };
+
+    let contract_addr = env.contract.address.clone();
+    let balances: Vec<Coin> = query_all_balances(deps.as_ref(), contract_addr)?;
+    let mut messages: Vec<SubMsg<TerraMsgWrapper>> = balances
+        .iter()
+        .filter(|x| x.denom != config.stable_denom && Some(x.denom.as_str()) != collateral_denom)
+        .map(|coin: &Coin| SubMsg::new(create_swap_msg(coin.clone(), config.stable_denom.clone())))
+        .collect();
+
+    match messages.last_mut() {
+        Some(last) => {
+            last.id = SWAP_TO_STABLE_OPERATION;
+            last.reply_on = ReplyOn::Success;
+        }
+        None => return distribute_hook(deps, env),
+    }
+
+    Ok(Response::new().add_submessages(messages))
+}
@@ -12,21 +12,39 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Deposit amount exceeds the collateral deposit cap: {0}")]
+    DepositCapExceeded(u128),
+
     #[error("Liquidation amount cannot exceed locked amount: {0}")]
     LiquidationAmountExceedsLocked(u128),
 
     #[error("Lock amount cannot excceed the user's spendable amount: {0}")]
     LockAmountExceedsSpendable(u128),
 
+    #[error("Invalid pause bitmask")]
+    InvalidPause {},
+
+    #[error("Reward routing shares must sum to one and a collector share needs a collector")]
+    InvalidRewardRouting {},
+
     #[error("Invalid reply ID")]
     InvalidReplyId {},
 
     #[error("Invalid request: \"deposit collateral\" message not included in request")]
     MissingDepositCollateralHook {},
 
+    #[error("Operation is paused")]
+    Paused {},
+
     #[error("Unlock amount cannot exceed locked amount: {0}")]
     UnlockAmountExceedsLocked(u128),
 
+    #[error("Deposit does not match the collateral of this custody")]
+    UnsupportedDeposit {},
+
     #[error("Withdraw amount cannot exceed the user's spendable amount: {0}")]
     WithdrawAmountExceedsSpendable(u128),
+
+    #[error("Deposit amount must be greater than 0 {0}")]
+    ZeroDeposit(String),
 }
@@ -0,0 +1,9 @@
+pub mod adapter;
+pub mod collateral;
+pub mod contract;
+pub mod distribution;
+pub mod error;
+pub mod state;
+
+#[cfg(test)]
+mod testing;
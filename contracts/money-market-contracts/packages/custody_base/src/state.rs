@@ -0,0 +1,199 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{CanonicalAddr, Deps, Order, StdResult, Storage};
+use cosmwasm_storage::{Bucket, ReadonlyBucket, ReadonlySingleton, Singleton};
+use moneymarket::custody::{BAssetInfo, BorrowerResponse};
+use moneymarket::tokens::AssetInfoRaw;
+
+const KEY_CONFIG: &[u8] = b"config";
+const KEY_PAUSE_INFO: &[u8] = b"pause_info";
+const KEY_DEPOSIT_INFO: &[u8] = b"deposit_info";
+const KEY_REWARD_ROUTING: &[u8] = b"reward_routing";
+const KEY_REWARD_STATE: &[u8] = b"reward_state";
+const PREFIX_BORROWER: &[u8] = b"borrower";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    /// Stored as a bare address by the CW20 custodies
+    /// released before native collaterals were supported
+    pub collateral_token: AssetInfoRaw,
+    pub overseer_contract: CanonicalAddr,
+    pub market_contract: CanonicalAddr,
+    pub liquidation_contract: CanonicalAddr,
+    pub stable_denom: String,
+    pub basset_info: BAssetInfo,
+    /// Contract the collateral rewards are claimed from through the
+    /// custody's `RewardAdapter`, none when the collateral earns no rewards
+    pub reward_contract: Option<CanonicalAddr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BorrowerInfo {
+    pub balance: Uint256,
+    pub spendable: Uint256,
+    #[serde(default)]
+    pub reward_index: Decimal256,
+    #[serde(default)]
+    pub pending_rewards: Uint256,
+}
+
+pub fn store_config(storage: &mut dyn Storage, data: &Config) -> StdResult<()> {
+    Singleton::new(storage, KEY_CONFIG).save(data)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    ReadonlySingleton::new(storage, KEY_CONFIG).load()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseInfo {
+    pub guardian: Option<CanonicalAddr>,
+    pub paused: u8,
+}
+
+pub fn store_pause_info(storage: &mut dyn Storage, data: &PauseInfo) -> StdResult<()> {
+    Singleton::new(storage, KEY_PAUSE_INFO).save(data)
+}
+
+pub fn read_pause_info(storage: &dyn Storage) -> StdResult<PauseInfo> {
+    Ok(ReadonlySingleton::new(storage, KEY_PAUSE_INFO)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct DepositInfo {
+    pub deposit_cap: Option<Uint256>,
+    pub total_deposits: Uint256,
+}
+
+pub fn store_deposit_info(storage: &mut dyn Storage, data: &DepositInfo) -> StdResult<()> {
+    Singleton::new(storage, KEY_DEPOSIT_INFO).save(data)
+}
+
+pub fn read_deposit_info(storage: &dyn Storage) -> StdResult<DepositInfo> {
+    Ok(ReadonlySingleton::new(storage, KEY_DEPOSIT_INFO)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardRouting {
+    pub buffer_share: Decimal256,
+    pub borrower_share: Decimal256,
+    pub collector_share: Decimal256,
+    pub collector_contract: Option<CanonicalAddr>,
+}
+
+impl Default for RewardRouting {
+    /// All rewards go to the overseer interest buffer
+    fn default() -> Self {
+        RewardRouting {
+            buffer_share: Decimal256::one(),
+            borrower_share: Decimal256::zero(),
+            collector_share: Decimal256::zero(),
+            collector_contract: None,
+        }
+    }
+}
+
+pub fn store_reward_routing(storage: &mut dyn Storage, data: &RewardRouting) -> StdResult<()> {
+    Singleton::new(storage, KEY_REWARD_ROUTING).save(data)
+}
+
+pub fn read_reward_routing(storage: &dyn Storage) -> StdResult<RewardRouting> {
+    Ok(ReadonlySingleton::new(storage, KEY_REWARD_ROUTING)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+/// Borrower rewards accrue per unit of deposited collateral;
+/// `unclaimed_rewards` is held by the custody until claimed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RewardState {
+    pub global_index: Decimal256,
+    pub unclaimed_rewards: Uint256,
+}
+
+pub fn store_reward_state(storage: &mut dyn Storage, data: &RewardState) -> StdResult<()> {
+    Singleton::new(storage, KEY_REWARD_STATE).save(data)
+}
+
+pub fn read_reward_state(storage: &dyn Storage) -> StdResult<RewardState> {
+    Ok(ReadonlySingleton::new(storage, KEY_REWARD_STATE)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+pub fn store_borrower_info(
+    storage: &mut dyn Storage,
+    borrower: &CanonicalAddr,
+    borrower_info: &BorrowerInfo,
+) -> StdResult<()> {
+    let mut borrower_bucket: Bucket<BorrowerInfo> = Bucket::new(storage, PREFIX_BORROWER);
+    borrower_bucket.save(borrower.as_slice(), borrower_info)?;
+
+    Ok(())
+}
+
+pub fn remove_borrower_info(storage: &mut dyn Storage, borrower: &CanonicalAddr) {
+    let mut borrower_bucket: Bucket<BorrowerInfo> = Bucket::new(storage, PREFIX_BORROWER);
+    borrower_bucket.remove(borrower.as_slice());
+}
+
+pub fn read_borrower_info(storage: &dyn Storage, borrower: &CanonicalAddr) -> BorrowerInfo {
+    let borrower_bucket: ReadonlyBucket<BorrowerInfo> =
+        ReadonlyBucket::new(storage, PREFIX_BORROWER);
+    match borrower_bucket.load(borrower.as_slice()) {
+        Ok(v) => v,
+        _ => BorrowerInfo {
+            balance: Uint256::zero(),
+            spendable: Uint256::zero(),
+            reward_index: Decimal256::zero(),
+            pending_rewards: Uint256::zero(),
+        },
+    }
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_borrowers(
+    deps: Deps,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<BorrowerResponse>> {
+    let position_bucket: ReadonlyBucket<BorrowerInfo> =
+        ReadonlyBucket::new(deps.storage, PREFIX_BORROWER);
+
+    let global_index = read_reward_state(deps.storage)?.global_index;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    position_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            let borrower: CanonicalAddr = CanonicalAddr::from(k);
+            Ok(BorrowerResponse {
+                borrower: deps.api.addr_humanize(&borrower)?.to_string(),
+                balance: v.balance,
+                spendable: v.spendable,
+                pending_rewards: v.pending_rewards + v.balance * (global_index - v.reward_index),
+            })
+        })
+        .collect()
+}
+
+// this will set the first key after the provided key, by appending a 1 byte
+fn calc_range_start(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
+    start_after.map(|addr| {
+        let mut v = addr.as_slice().to_vec();
+        v.push(1);
+        v
+    })
+}
@@ -0,0 +1,837 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, to_binary, Addr, Api, Attribute, BankMsg, Coin, ContractResult, CosmosMsg, Decimal,
+    DepsMut, Env, MessageInfo, Reply, Response, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+};
+
+use crate::collateral::{query_borrower, query_borrowers};
+use crate::contract::{
+    self as custody, query_pause_info, reply, CLAIM_REWARDS_OPERATION, SWAP_TO_STABLE_OPERATION,
+};
+use crate::distribution::query_reward_routing;
+use crate::error::ContractError;
+use crate::state::{read_borrower_info, read_config, store_config, Config};
+use crate::testing::mock_querier::{mock_dependencies, MockRewardAdapter};
+
+use cw20::Cw20ReceiveMsg;
+use moneymarket::custody::{
+    BAssetInfo, BorrowerResponse, Cw20HookMsg, ExecuteMsg, RewardRoutingResponse,
+};
+use moneymarket::custody_cw20::{AccruedRewardsResponse, RewardAdapterExecuteMsg};
+use moneymarket::pause::{PauseInfoResponse, PAUSE_ALL, PAUSE_DEPOSIT, PAUSE_UNLOCK};
+use moneymarket::tokens::AssetInfoRaw;
+use terra_cosmwasm::{create_swap_msg, TerraMsgWrapper};
+
+/// Stores the config of a custody holding the `token` CW20 collateral,
+/// as the custody contracts do on instantiation
+fn instantiate(deps: DepsMut, reward_contract: Option<&str>) {
+    let api = deps.api;
+    let config = Config {
+        owner: api.addr_canonicalize("owner").unwrap(),
+        collateral_token: AssetInfoRaw::Token {
+            contract_addr: api.addr_canonicalize("token").unwrap(),
+        },
+        overseer_contract: api.addr_canonicalize("overseer").unwrap(),
+        market_contract: api.addr_canonicalize("market").unwrap(),
+        liquidation_contract: api.addr_canonicalize("liquidation").unwrap(),
+        stable_denom: "uusd".to_string(),
+        basset_info: BAssetInfo {
+            name: "token".to_string(),
+            symbol: "token".to_string(),
+            decimals: 6,
+        },
+        reward_contract: reward_contract.map(|addr| api.addr_canonicalize(addr).unwrap()),
+    };
+
+    store_config(deps.storage, &config).unwrap();
+}
+
+fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response<TerraMsgWrapper>, ContractError> {
+    custody::execute::<MockRewardAdapter>(deps, env, info, msg)
+}
+
+#[test]
+fn update_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate(deps.as_mut(), Some("reward"));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: Some("owner2".to_string()),
+        liquidation_contract: Some("liquidation2".to_string()),
+    };
+    let info = mock_info("owner", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+    let config = read_config(deps.as_ref().storage).unwrap();
+    assert_eq!(deps.api.addr_canonicalize("owner2").unwrap(), config.owner);
+    assert_eq!(
+        deps.api.addr_canonicalize("liquidation2").unwrap(),
+        config.liquidation_contract
+    );
+    assert_eq!(
+        deps.api.addr_canonicalize("overseer").unwrap(),
+        config.overseer_contract
+    );
+    assert_eq!(
+        deps.api.addr_canonicalize("market").unwrap(),
+        config.market_contract
+    );
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn lock_collateral() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate(deps.as_mut(), Some("reward"));
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositCollateral {}).unwrap(),
+    });
+
+    let info = mock_info("token", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "100"),
+        ]
+    );
+
+    let msg = ExecuteMsg::LockCollateral {
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(50u64),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    //locking more than spendable
+    let info2 = mock_info("overseer", &[]);
+    let msg2 = ExecuteMsg::LockCollateral {
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(200u128),
+    };
+    let res2 = execute(deps.as_mut(), mock_env(), info2, msg2).unwrap_err();
+
+    assert_eq!(res2, ContractError::LockAmountExceedsSpendable(100));
+
+    let info = mock_info("overseer", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "lock_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "50"),
+        ]
+    );
+
+    //directly checking if spendable is decreased by amount
+    let spend = read_borrower_info(
+        &deps.storage,
+        &deps.api.addr_canonicalize("addr0000").unwrap(),
+    )
+    .spendable;
+    assert_eq!(spend, Uint256::from(50u128));
+
+    let msg = ExecuteMsg::WithdrawCollateral {
+        amount: Some(Uint256::from(51u64)),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::WithdrawAmountExceedsSpendable(50)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::WithdrawCollateral {
+        amount: Some(Uint256::from(50u64)),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "50"),
+        ]
+    );
+
+    let borrower_res = query_borrower(deps.as_ref(), Addr::unchecked("addr0000")).unwrap();
+    assert_eq!(
+        borrower_res,
+        BorrowerResponse {
+            borrower: "addr0000".to_string(),
+            balance: Uint256::from(50u64),
+            spendable: Uint256::from(0u64),
+            pending_rewards: Uint256::zero(),
+        }
+    );
+
+    // Unlock partial amount of collateral
+    let msg = ExecuteMsg::UnlockCollateral {
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(30u64),
+    };
+
+    //unauthorized sender
+    let info2 = mock_info("addr0000", &[]);
+    let res2 = execute(deps.as_mut(), mock_env(), info2, msg.clone());
+    match res2 {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    //unlocking more than allowed (which is 50 - 0 = 50)
+    let msg3 = ExecuteMsg::UnlockCollateral {
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(230u128),
+    };
+
+    let info3 = mock_info("overseer", &[]);
+    let res3 = execute(deps.as_mut(), mock_env(), info3, msg3);
+    match res3 {
+        Err(ContractError::UnlockAmountExceedsLocked(50)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("overseer", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "unlock_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "30"),
+        ]
+    );
+
+    //checking if amount is added to spendable
+    let spend = read_borrower_info(
+        &deps.storage,
+        &deps.api.addr_canonicalize("addr0000").unwrap(),
+    )
+    .spendable;
+    assert_eq!(spend, Uint256::from(30u128));
+
+    let msg = ExecuteMsg::WithdrawCollateral {
+        amount: Some(Uint256::from(30u64)),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw_collateral"),
+            attr("borrower", "addr0000"),
+            attr("amount", "30"),
+        ]
+    );
+
+    let borrower_res = query_borrower(deps.as_ref(), Addr::unchecked("addr0000")).unwrap();
+    assert_eq!(
+        borrower_res,
+        BorrowerResponse {
+            borrower: "addr0000".to_string(),
+            balance: Uint256::from(20u64),
+            spendable: Uint256::from(0u64),
+            pending_rewards: Uint256::zero(),
+        }
+    );
+}
+
+#[test]
+fn distribute_hook() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1000000u128),
+    }]);
+
+    deps.querier.with_token_balances(&[(
+        &"token".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+    )]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    instantiate(deps.as_mut(), Some("reward"));
+
+    // Claimed rewards is 1000000uusd
+    // mimic last swap_msg callback to execute distribute_hook
+    deps.querier.set_other_balances(Uint128::new(1000000));
+    let reply_msg = Reply {
+        id: 2,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_rewards"),
+            attr("buffer_rewards", "1000000"),
+            attr("borrower_rewards", "0"),
+            attr("collector_rewards", "0"),
+        ]
+    );
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "overseer".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(990099u128)
+            }],
+        })),],
+    )
+}
+
+#[test]
+fn distribution_hook_zero_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate(deps.as_mut(), Some("reward"));
+
+    // Claimed rewards is 1000000uusd
+    // mimic last swap_msg callback to execute distribute_hook
+    let reply_msg = Reply {
+        id: 2,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_rewards"),
+            attr("buffer_rewards", "0"),
+            attr("borrower_rewards", "0"),
+            attr("collector_rewards", "0"),
+        ]
+    );
+
+    assert_eq!(res.messages, vec![],)
+}
+
+#[test]
+fn swap_to_stable_denom() {
+    let mut deps = mock_dependencies(&[
+        Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        },
+        Coin {
+            denom: "ukrw".to_string(),
+            amount: Uint128::from(20000000000u128),
+        },
+        Coin {
+            denom: "usdr".to_string(),
+            amount: Uint128::from(2000000u128),
+        },
+    ]);
+
+    instantiate(deps.as_mut(), Some("reward"));
+
+    // mimic callback from distribute_rewards to execute swap_to_stable_denom
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(create_swap_msg(
+                Coin {
+                    denom: "ukrw".to_string(),
+                    amount: Uint128::from(20000000000u128),
+                },
+                "uusd".to_string(),
+            )),
+            SubMsg::reply_on_success(
+                create_swap_msg(
+                    Coin {
+                        denom: "usdr".to_string(),
+                        amount: Uint128::from(2000000u128),
+                    },
+                    "uusd".to_string(),
+                ),
+                SWAP_TO_STABLE_OPERATION
+            ),
+        ]
+    );
+}
+
+#[test]
+fn proper_distribute_rewards_with_no_rewards() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1000000u128),
+    }]);
+
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), Some("reward"));
+
+    let msg = ExecuteMsg::DistributeRewards {};
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::DistributeRewards {};
+    let info = mock_info("overseer", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // must return
+    assert_eq!(res, Response::default());
+
+    let msg = ExecuteMsg::DistributeRewards {};
+    let info = mock_info("overseer", &[]);
+    deps.querier.set_accrued_rewards(AccruedRewardsResponse {
+        rewards: Uint128::new(0),
+    });
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // must return
+    assert_eq!(res, Response::default());
+
+    let msg = ExecuteMsg::DistributeRewards {};
+    let info = mock_info("overseer", &[]);
+
+    deps.querier.set_accrued_rewards(AccruedRewardsResponse {
+        rewards: Uint128::new(10000000),
+    });
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    // Do not print logs at this step
+    let empty_vector: Vec<Attribute> = Vec::new();
+    assert_eq!(res.attributes, empty_vector);
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward".to_string(),
+                funds: vec![],
+                msg: to_binary(&RewardAdapterExecuteMsg::ClaimRewards { recipient: None }).unwrap(),
+            }),
+            CLAIM_REWARDS_OPERATION
+        ),]
+    );
+}
+
+#[test]
+fn pause_operations() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate(deps.as_mut(), Some("reward"));
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::DepositCollateral {}).unwrap(),
+    });
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("token", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    let msg2 = ExecuteMsg::LockCollateral {
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(50u64),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg2).unwrap();
+
+    // Unauthorized err
+    let msg2 = ExecuteMsg::UpdateGuardian {
+        guardian: Some("guardian".to_string()),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg2.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg2).unwrap();
+
+    let msg2 = ExecuteMsg::UpdatePause { paused: PAUSE_ALL };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg2).unwrap();
+
+    let pause_info = query_pause_info(deps.as_ref()).unwrap();
+    assert_eq!(
+        pause_info,
+        PauseInfoResponse {
+            guardian: Some("guardian".to_string()),
+            paused: PAUSE_ALL,
+        }
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("token", &[]), msg);
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::WithdrawCollateral { amount: None };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::LiquidateCollateral {
+        liquidator: "liquidator".to_string(),
+        borrower: "addr0000".to_string(),
+        amount: Uint256::from(10u64),
+        liquidation_contract: None,
+        market_contract: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("overseer", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Paused {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // guardian cannot unpause
+    let msg2 = ExecuteMsg::UpdatePause {
+        paused: PAUSE_DEPOSIT | PAUSE_UNLOCK,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("guardian", &[]),
+        msg2.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg2).unwrap();
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg).unwrap();
+}
+
+#[test]
+fn deposit_cap() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate(deps.as_mut(), Some("reward"));
+
+    // Unauthorized err
+    let msg = ExecuteMsg::UpdateDepositCap {
+        deposit_cap: Some(Uint256::from(100u64)),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(60u128),
+        msg: to_binary(&Cw20HookMsg::DepositCollateral {}).unwrap(),
+    });
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("token", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("token", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::DepositCapExceeded(100u128)) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // withdrawals free up room under the cap
+    let msg2 = ExecuteMsg::WithdrawCollateral {
+        amount: Some(Uint256::from(20u64)),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg2).unwrap();
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("token", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    let msg2 = ExecuteMsg::UpdateDepositCap { deposit_cap: None };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg2).unwrap();
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("token", &[]), msg).unwrap();
+}
+
+#[test]
+fn reward_routing() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate(deps.as_mut(), Some("reward"));
+
+    // all rewards go to the overseer by default
+    let routing_res = query_reward_routing(deps.as_ref()).unwrap();
+    assert_eq!(
+        routing_res,
+        RewardRoutingResponse {
+            buffer_share: Decimal256::one(),
+            borrower_share: Decimal256::zero(),
+            collector_share: Decimal256::zero(),
+            collector_contract: None,
+        }
+    );
+
+    let msg = ExecuteMsg::UpdateRewardRouting {
+        buffer_share: Decimal256::percent(50),
+        borrower_share: Decimal256::percent(30),
+        collector_share: Decimal256::percent(20),
+        collector_contract: Some("collector".to_string()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // shares must sum to one
+    let msg = ExecuteMsg::UpdateRewardRouting {
+        buffer_share: Decimal256::percent(50),
+        borrower_share: Decimal256::percent(30),
+        collector_share: Decimal256::percent(30),
+        collector_contract: Some("collector".to_string()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+    match res {
+        Err(ContractError::InvalidRewardRouting {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // collector share needs a collector
+    let msg = ExecuteMsg::UpdateRewardRouting {
+        buffer_share: Decimal256::percent(50),
+        borrower_share: Decimal256::percent(30),
+        collector_share: Decimal256::percent(20),
+        collector_contract: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+    match res {
+        Err(ContractError::InvalidRewardRouting {}) => (),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::UpdateRewardRouting {
+        buffer_share: Decimal256::percent(50),
+        borrower_share: Decimal256::percent(30),
+        collector_share: Decimal256::percent(20),
+        collector_contract: Some("collector".to_string()),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    for (borrower, amount) in [("addr0000", 100u128), ("addr0001", 300u128)] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: borrower.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::DepositCollateral {}).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("token", &[]), msg).unwrap();
+    }
+
+    // Claimed rewards is 1000000uusd
+    deps.querier.set_other_balances(Uint128::new(1000000));
+    let reply_msg = Reply {
+        id: 2,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_rewards"),
+            attr("buffer_rewards", "500000"),
+            attr("borrower_rewards", "300000"),
+            attr("collector_rewards", "200000"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "overseer".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(500000u128)
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(200000u128)
+                }],
+            })),
+        ]
+    );
+
+    // borrower rewards accrue pro rata to the collateral balance
+    let borrower_res = query_borrower(deps.as_ref(), Addr::unchecked("addr0000")).unwrap();
+    assert_eq!(borrower_res.pending_rewards, Uint256::from(75000u64));
+
+    let msg = ExecuteMsg::ClaimRewards {};
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(75000u128)
+            }],
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_rewards"),
+            attr("borrower", "addr0000"),
+            attr("claim_amount", "75000"),
+        ]
+    );
+
+    // withdrawn collateral no longer earns, unclaimed rewards are not redistributed
+    let msg = ExecuteMsg::WithdrawCollateral { amount: None };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps.querier.set_other_balances(Uint128::new(1225000));
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_rewards"),
+            attr("buffer_rewards", "500000"),
+            attr("borrower_rewards", "300000"),
+            attr("collector_rewards", "200000"),
+        ]
+    );
+
+    let borrowers_res = query_borrowers(deps.as_ref(), None, None).unwrap();
+    assert_eq!(
+        borrowers_res.borrowers,
+        vec![BorrowerResponse {
+            borrower: "addr0001".to_string(),
+            balance: Uint256::from(300u64),
+            spendable: Uint256::from(300u64),
+            pending_rewards: Uint256::from(525000u64),
+        }]
+    );
+}
+
+#[test]
+fn stable_rewards_distributed_without_swap() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1000000u128),
+    }]);
+
+    instantiate(deps.as_mut(), Some("reward"));
+
+    // mimic callback from distribute_rewards; nothing to swap
+    deps.querier.set_other_balances(Uint128::new(1000000));
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "overseer".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000000u128)
+            }],
+        }))]
+    );
+}
+
+#[test]
+fn no_reward_adapter() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate(deps.as_mut(), None);
+
+    let config = read_config(deps.as_ref().storage).unwrap();
+    assert_eq!(config.reward_contract, None);
+
+    // overseer epoch operations still go through
+    deps.querier.set_accrued_rewards(AccruedRewardsResponse {
+        rewards: Uint128::from(1000000u128),
+    });
+    let msg = ExecuteMsg::DistributeRewards {};
+    let res = execute(deps.as_mut(), mock_env(), mock_info("overseer", &[]), msg).unwrap();
+    assert_eq!(res, Response::default());
+}
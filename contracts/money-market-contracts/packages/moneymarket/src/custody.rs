@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_bignumber::Uint256;
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cw20::Cw20ReceiveMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -25,6 +25,10 @@ pub struct InstantiateMsg {
     pub basset_info: BAssetInfo,
 }
 
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -40,6 +44,22 @@ pub enum ExecuteMsg {
         owner: Option<String>,
         liquidation_contract: Option<String>,
     },
+    /// Set the guardian allowed to pause operations
+    UpdateGuardian { guardian: Option<String> },
+    /// Set the pause bitmask (see `moneymarket::pause`);
+    /// the guardian can only add flags
+    UpdatePause { paused: u8 },
+    /// Update the maximum total amount of collateral deposits
+    UpdateDepositCap { deposit_cap: Option<Uint256> },
+    /// Set how claimed bAsset rewards are split between the overseer
+    /// interest buffer, the borrowers and the collector;
+    /// the shares must sum to one
+    UpdateRewardRouting {
+        buffer_share: Decimal256,
+        borrower_share: Decimal256,
+        collector_share: Decimal256,
+        collector_contract: Option<String>,
+    },
     /// Make specified amount of tokens unspendable
     LockCollateral { borrower: String, amount: Uint256 },
     /// Make specified amount of collateral tokens spendable
@@ -53,6 +73,10 @@ pub enum ExecuteMsg {
         liquidator: String,
         borrower: String,
         amount: Uint256,
+        /// Liquidation contract and market of another stable denom;
+        /// the configured ones are used when not given
+        liquidation_contract: Option<String>,
+        market_contract: Option<String>,
     },
 
     ////////////////////
@@ -63,6 +87,14 @@ pub enum ExecuteMsg {
     /// If the amount is not given,
     /// return all spendable collateral
     WithdrawCollateral { amount: Option<Uint256> },
+    /// Deposit the native collateral sent with the message;
+    /// CW20 collateral is deposited through `Cw20HookMsg`
+    DepositCollateral {},
+    /// Deposit the native collateral sent with the message for
+    /// `borrower`, as the liquidation contract does on cancelling an auction
+    DepositCollateralFor { borrower: String },
+    /// Claim the borrower share of bAsset rewards
+    ClaimRewards {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -70,6 +102,8 @@ pub enum ExecuteMsg {
 pub enum Cw20HookMsg {
     /// Deposit collateral token
     DepositCollateral {},
+    /// Deposit collateral token for `borrower`
+    DepositCollateralFor { borrower: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -83,6 +117,8 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    PauseInfo {},
+    RewardRouting {},
 }
 
 // We define a custom struct for each query response
@@ -104,6 +140,7 @@ pub struct BorrowerResponse {
     pub borrower: String,
     pub balance: Uint256,
     pub spendable: Uint256,
+    pub pending_rewards: Uint256,
 }
 
 // We define a custom struct for each query response
@@ -112,6 +149,15 @@ pub struct BorrowersResponse {
     pub borrowers: Vec<BorrowerResponse>,
 }
 
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardRoutingResponse {
+    pub buffer_share: Decimal256,
+    pub borrower_share: Decimal256,
+    pub collector_share: Decimal256,
+    pub collector_contract: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BAssetInfo {
     pub name: String,
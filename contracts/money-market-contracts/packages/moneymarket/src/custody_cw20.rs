@@ -0,0 +1,63 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::custody::BAssetInfo;
+use cosmwasm_std::Uint128;
+
+/// Execute and query messages are shared with
+/// the bAsset custodies, see `moneymarket::custody`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct InstantiateMsg {
+    /// owner address
+    pub owner: String,
+    /// CW20 collateral token address
+    pub collateral_token: String,
+    /// overseer contract address
+    pub overseer_contract: String,
+    /// market contract address
+    pub market_contract: String,
+    /// liquidation contract address
+    pub liquidation_contract: String,
+    /// Reward denom. Rewards paid in other coins are
+    /// converted to the `stable_denom`.
+    pub stable_denom: String,
+    pub basset_info: BAssetInfo,
+    /// Contract claiming the collateral rewards on behalf of the custody;
+    /// the collateral earns no rewards when not given
+    pub reward_adapter: Option<String>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub collateral_token: String,
+    pub overseer_contract: String,
+    pub market_contract: String,
+    pub liquidation_contract: String,
+    pub stable_denom: String,
+    pub basset_info: BAssetInfo,
+    pub reward_adapter: Option<String>,
+}
+
+/// Interface a reward adapter implements. The bAsset reward
+/// contracts already do, so they can be plugged in as they are.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardAdapterExecuteMsg {
+    /// Send the rewards accrued by the sender, in native coins
+    ClaimRewards { recipient: Option<String> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardAdapterQueryMsg {
+    /// Reward amount claimable by `address`
+    AccruedRewards { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct AccruedRewardsResponse {
+    pub rewards: Uint128,
+}
@@ -0,0 +1,43 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::custody::BAssetInfo;
+
+/// Execute and query messages are shared with
+/// the bAsset custodies, see `moneymarket::custody`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct InstantiateMsg {
+    /// owner address
+    pub owner: String,
+    /// Bank denom of the collateral
+    pub collateral_denom: String,
+    /// overseer contract address
+    pub overseer_contract: String,
+    /// market contract address
+    pub market_contract: String,
+    /// liquidation contract address, which must accept
+    /// native collateral (see `liquidation_queue::ExecuteMsg::ExecuteBid`)
+    pub liquidation_contract: String,
+    /// Reward denom. Rewards paid in other coins are
+    /// converted to the `stable_denom`.
+    pub stable_denom: String,
+    pub basset_info: BAssetInfo,
+    /// Contract claiming the collateral rewards on behalf of the custody,
+    /// see `moneymarket::custody_cw20::RewardAdapterExecuteMsg`;
+    /// the collateral earns no rewards when not given
+    pub reward_adapter: Option<String>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub collateral_denom: String,
+    pub overseer_contract: String,
+    pub market_contract: String,
+    pub liquidation_contract: String,
+    pub stable_denom: String,
+    pub basset_info: BAssetInfo,
+    pub reward_adapter: Option<String>,
+}
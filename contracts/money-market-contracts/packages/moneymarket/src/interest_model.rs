@@ -8,6 +8,9 @@ pub struct InstantiateMsg {
     pub owner: String,
     pub base_rate: Decimal256,
     pub interest_multiplier: Decimal256,
+    /// Additional curve segments above the first one,
+    /// sorted by ascending utilization
+    pub kinks: Vec<InterestKink>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -17,13 +20,59 @@ pub enum ExecuteMsg {
         owner: Option<String>,
         base_rate: Option<Decimal256>,
         interest_multiplier: Option<Decimal256>,
+        kinks: Option<Vec<InterestKink>>,
     },
+    /// Enable, reconfigure or (with `None`) disable the
+    /// adaptive interest multiplier controller
+    UpdateRateController {
+        rate_controller: Option<RateControllerConfig>,
+    },
+
+    ////////////////////
+    /// Market operations
+    ////////////////////
+    /// Adjust the interest multiplier towards the target utilization;
+    /// executed by the market contract on every epoch operation
+    ExecuteEpochOperations {
+        market_balance: Uint256,
+        total_liabilities: Decimal256,
+        total_reserves: Decimal256,
+    },
+}
+
+/// Point of the borrow rate curve where the slope changes.
+/// Above `utilization`, the rate grows by `interest_multiplier`
+/// per unit of utilization until the next kink.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InterestKink {
+    pub utilization: Decimal256,
+    pub interest_multiplier: Decimal256,
+}
+
+/// Settings of the controller which moves `interest_multiplier` every epoch
+/// by `proportional_gain * (utilization - last_utilization)
+///    + integral_gain * (utilization - target_utilization)`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateControllerConfig {
+    /// Market contract allowed to trigger epoch adjustments
+    pub market_contract: String,
+    /// Utilization ratio the controller steers the market towards
+    pub target_utilization: Decimal256,
+    /// Gain applied to the utilization change since the last epoch
+    pub proportional_gain: Decimal256,
+    /// Gain applied to the distance from the target utilization
+    pub integral_gain: Decimal256,
+    /// Lower bound of the adjusted interest multiplier
+    pub min_interest_multiplier: Decimal256,
+    /// Upper bound of the adjusted interest multiplier
+    pub max_interest_multiplier: Decimal256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    RateController {},
     BorrowRate {
         market_balance: Uint256,
         total_liabilities: Decimal256,
@@ -37,6 +86,7 @@ pub struct ConfigResponse {
     pub owner: String,
     pub base_rate: Decimal256,
     pub interest_multiplier: Decimal256,
+    pub kinks: Vec<InterestKink>,
 }
 
 // We define a custom struct for each query response
@@ -44,3 +94,12 @@ pub struct ConfigResponse {
 pub struct BorrowRateResponse {
     pub rate: Decimal256,
 }
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateControllerResponse {
+    pub rate_controller: Option<RateControllerConfig>,
+    pub interest_multiplier: Decimal256,
+    pub last_utilization: Decimal256,
+    pub last_executed_height: u64,
+}
@@ -1,5 +1,7 @@
 pub mod common;
 pub mod custody;
+pub mod custody_cw20;
+pub mod custody_native;
 pub mod distribution_model;
 pub mod interest_model;
 pub mod liquidation;
@@ -7,6 +9,7 @@ pub mod liquidation_queue;
 pub mod market;
 pub mod oracle;
 pub mod overseer;
+pub mod pause;
 pub mod querier;
 pub mod tokens;
 
@@ -59,6 +59,7 @@ pub enum Cw20HookMsg {
         liquidator: String,
         fee_address: Option<String>,
         repay_address: Option<String>,
+        borrower: Option<String>,
     },
 }
 
@@ -86,6 +87,10 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Collaterals of the borrower seized but not yet sold
+    PendingCollaterals {
+        borrower: String,
+    },
 }
 
 // We define a custom struct for each query response
@@ -107,6 +112,12 @@ pub struct LiquidationAmountResponse {
     pub collaterals: TokensHuman,
 }
 
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingCollateralsResponse {
+    pub collaterals: TokensHuman,
+}
+
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BidResponse {
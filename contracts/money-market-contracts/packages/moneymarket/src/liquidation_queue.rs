@@ -5,7 +5,7 @@ use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::Uint128;
 use cw20::Cw20ReceiveMsg;
 
-use crate::tokens::TokensHuman;
+use crate::tokens::{AssetInfo, TokensHuman};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -46,23 +46,41 @@ pub enum ExecuteMsg {
         price_timeframe: Option<u64>,
         waiting_period: Option<u64>,
         overseer: Option<String>,
+        /// Minimum amount of a bid with an expiry or a price floor
+        min_conditional_bid_amount: Option<Uint256>,
     },
     /// Owner operation to whitelist a new collateral
     WhitelistCollateral {
-        collateral_token: String,
+        collateral_token: AssetInfo,
         bid_threshold: Uint256,
         max_slot: u8,
         premium_rate_per_slot: Decimal256,
+        /// Defaults to bid pools
+        liquidation_mode: Option<LiquidationMode>,
     },
     UpdateCollateralInfo {
-        collateral_token: String,
+        collateral_token: AssetInfo,
         bid_threshold: Option<Uint256>,
         max_slot: Option<u8>,
+        /// Pair contract used to sell auto-claimed collateral for stable denom
+        swap_pair: Option<String>,
+        /// Max spread accepted when selling auto-claimed collateral
+        max_swap_spread: Option<Decimal256>,
+        liquidation_mode: Option<LiquidationMode>,
     },
     /// Submit a new bid to a bid pool
     SubmitBid {
-        collateral_token: String,
+        collateral_token: AssetInfo,
         premium_slot: u8,
+        /// Block time after which the bid is refunded instead of consumed
+        expires_at: Option<u64>,
+        /// The bid is only consumed while the collateral price is above this floor
+        min_collateral_price: Option<Decimal256>,
+    },
+    /// Set or clear the auto-claim action of a bid
+    SetAutoClaim {
+        bid_idx: Uint128,
+        auto_claim: Option<AutoClaim>,
     },
     /// Withdraw a bid
     RetractBid {
@@ -71,14 +89,66 @@ pub enum ExecuteMsg {
     },
     /// After waiting_period expires, user can activate the bid
     ActivateBids {
-        collateral_token: String,
+        collateral_token: AssetInfo,
         bids_idx: Option<Vec<Uint128>>,
     },
-    /// Claim the corresponding amount of liquidated collateral
+    /// Buy collateral from a dutch auction with the sent stable denom.
+    /// Stable denom left over after the auction is filled is refunded
+    FillAuction {
+        auction_idx: Uint128,
+    },
+    /// Close a dutch auction and return the collateral left to the
+    /// borrower. The owner can cancel any auction, anyone else only
+    /// those that expired
+    CancelAuction {
+        auction_idx: Uint128,
+    },
+    /// Claim the corresponding amount of liquidated collateral.
+    /// Bids with an auto-claim action can be claimed by anyone
     ClaimLiquidations {
-        collateral_token: String,
+        collateral_token: AssetInfo,
         bids_idx: Option<Vec<Uint128>>,
     },
+    /// Custody interface to liquidate the sent native collateral,
+    /// see `Cw20HookMsg::ExecuteBid`
+    ExecuteBid {
+        liquidator: String, // Legacy parameter, ignored
+        fee_address: Option<String>,
+        repay_address: Option<String>,
+        /// Required for collaterals liquidated through dutch auctions
+        borrower: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidationMode {
+    /// Liquidated collateral is bought by bids in premium slots
+    BidPools {},
+    /// Liquidated collateral is sold in a descending-price auction,
+    /// starting at the oracle price and reaching `max_premium_rate`
+    /// discount after `duration` seconds
+    DutchAuction {
+        max_premium_rate: Decimal256,
+        duration: u64,
+    },
+}
+
+impl Default for LiquidationMode {
+    fn default() -> Self {
+        LiquidationMode::BidPools {}
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoClaim {
+    /// Sell claimed collateral for stable denom and submit
+    /// the proceeds as a new bid in the same premium slot
+    Compound {},
+    /// Sell claimed collateral for stable denom and send
+    /// the proceeds to the recipient
+    Withdraw { recipient: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -89,6 +159,8 @@ pub enum Cw20HookMsg {
         liquidator: String, // Legacy parameter, ignored
         fee_address: Option<String>,
         repay_address: Option<String>,
+        /// Required for collaterals liquidated through dutch auctions
+        borrower: Option<String>,
     },
 }
 
@@ -103,26 +175,54 @@ pub enum QueryMsg {
         collateral_prices: Vec<Decimal256>,
     },
     CollateralInfo {
-        collateral_token: String,
+        collateral_token: AssetInfo,
     },
     Bid {
         bid_idx: Uint128,
     },
     BidsByUser {
-        collateral_token: String,
+        collateral_token: AssetInfo,
         bidder: String,
         start_after: Option<Uint128>,
         limit: Option<u8>,
     },
     BidPool {
-        collateral_token: String,
+        collateral_token: AssetInfo,
         bid_slot: u8,
     },
     BidPoolsByCollateral {
-        collateral_token: String,
+        collateral_token: AssetInfo,
         start_after: Option<u8>,
         limit: Option<u8>,
     },
+    Auction {
+        auction_idx: Uint128,
+    },
+    AuctionsByCollateral {
+        collateral_token: AssetInfo,
+        start_after: Option<Uint128>,
+        limit: Option<u8>,
+    },
+    /// Collaterals of the borrower still up for auction
+    PendingCollaterals {
+        borrower: String,
+    },
+    /// Past liquidations, optionally filtered by collateral
+    LiquidationHistory {
+        collateral_token: Option<AssetInfo>,
+        start_after: Option<Uint128>,
+        limit: Option<u8>,
+    },
+    /// Cumulative liquidation totals of a collateral
+    LiquidationStats {
+        collateral_token: AssetInfo,
+    },
+    /// Bid depth per premium slot at the current oracle price,
+    /// optionally simulating the liquidation of a collateral amount
+    BidDepth {
+        collateral_token: AssetInfo,
+        collateral_amount: Option<Uint256>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -137,6 +237,7 @@ pub struct ConfigResponse {
     pub price_timeframe: u64,
     pub waiting_period: u64,
     pub overseer: String,
+    pub min_conditional_bid_amount: Uint256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -147,7 +248,7 @@ pub struct LiquidationAmountResponse {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BidResponse {
     pub idx: Uint128,
-    pub collateral_token: String,
+    pub collateral_token: AssetInfo,
     pub premium_slot: u8,
     pub bidder: String,
     pub amount: Uint256,
@@ -157,6 +258,9 @@ pub struct BidResponse {
     pub wait_end: Option<u64>,
     pub epoch_snapshot: Uint128,
     pub scale_snapshot: Uint128,
+    pub auto_claim: Option<AutoClaim>,
+    pub expires_at: Option<u64>,
+    pub min_collateral_price: Option<Decimal256>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -176,13 +280,97 @@ pub struct BidPoolResponse {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CollateralInfoResponse {
-    pub collateral_token: String,
+    pub collateral_token: AssetInfo,
     pub bid_threshold: Uint256,
     pub max_slot: u8,
     pub premium_rate_per_slot: Decimal256,
+    pub swap_pair: Option<String>,
+    pub max_swap_spread: Option<Decimal256>,
+    pub liquidation_mode: LiquidationMode,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BidPoolsResponse {
     pub bid_pools: Vec<BidPoolResponse>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionResponse {
+    pub idx: Uint128,
+    pub collateral_token: AssetInfo,
+    pub borrower: String,
+    /// Collateral left to be sold
+    pub amount: Uint256,
+    pub start_time: u64,
+    /// Discount to the oracle price at the current block time
+    pub premium_rate: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionsResponse {
+    pub auctions: Vec<AuctionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingCollateralsResponse {
+    pub collaterals: TokensHuman,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BidDepthResponse {
+    pub collateral_token: AssetInfo,
+    pub collateral_price: Decimal256,
+    pub slots: Vec<BidDepthSlotResponse>,
+    pub simulation: Option<LiquidationSimulationResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BidDepthSlotResponse {
+    pub premium_slot: u8,
+    pub premium_rate: Decimal256,
+    /// Bids that would be consumed at the current price
+    pub bid_amount: Uint256,
+    /// Bids of this and all lower premium slots
+    pub cumulative_bid_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationSimulationResponse {
+    /// Collateral the bids can buy, lower than requested when bids run out
+    pub collateral_amount: Uint256,
+    /// Stable paid by the bids, before fees
+    pub repay_amount: Uint256,
+    /// Average discount to the oracle price
+    pub premium_rate: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationEventResponse {
+    pub idx: Uint128,
+    pub collateral_token: AssetInfo,
+    /// Not known for liquidations executed without a borrower
+    pub borrower: Option<String>,
+    pub collateral_amount: Uint256,
+    /// Stable sent to the repay address, after fees
+    pub repay_amount: Uint256,
+    /// Average discount to the oracle price paid by the bids
+    pub premium_rate: Decimal256,
+    pub bid_fee: Uint256,
+    pub liquidator_fee: Uint256,
+    pub block_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationHistoryResponse {
+    pub liquidations: Vec<LiquidationEventResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationStatsResponse {
+    pub collateral_token: AssetInfo,
+    pub liquidation_count: u64,
+    pub total_collateral_amount: Uint256,
+    pub total_repay_amount: Uint256,
+    pub total_bid_fee: Uint256,
+    pub total_liquidator_fee: Uint256,
+}
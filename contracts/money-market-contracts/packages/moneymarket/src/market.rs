@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::Binary;
 use cw20::Cw20ReceiveMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -46,10 +47,29 @@ pub enum ExecuteMsg {
     UpdateConfig {
         owner_addr: Option<String>,
         max_borrow_factor: Option<Decimal256>,
+        /// Maximum total liabilities; zero removes the cap
+        borrow_cap: Option<Uint256>,
+        /// Fee charged on flash loans, accrued to the reserves
+        flash_loan_fee: Option<Decimal256>,
+        /// Per block premium over the floating rate for fixed-rate loans
+        fixed_rate_premium: Option<Decimal256>,
+        /// Rate deviation allowing fixed-rate loans to be rebalanced;
+        /// zero disables rebalancing
+        fixed_rate_rebalance_threshold: Option<Decimal256>,
         interest_model: Option<String>,
         distribution_model: Option<String>,
     },
 
+    /// Set the guardian allowed to pause operations
+    UpdateGuardian {
+        guardian: Option<String>,
+    },
+    /// Set the pause bitmask (see `moneymarket::pause`);
+    /// the guardian can only add flags
+    UpdatePause {
+        paused: u8,
+    },
+
     ////////////////////
     /// Overseer operations
     ////////////////////
@@ -59,6 +79,21 @@ pub enum ExecuteMsg {
         prev_balance: Uint256,
     },
 
+    /// Write off the loan of a borrower without collateral left.
+    /// The loss is covered by the reserves, then by the sent
+    /// interest buffer funds, and the rest is socialized to depositors
+    WriteOffBadDebt {
+        borrower: String,
+    },
+
+    /// Spend the borrow allowance of `spender` for the value of
+    /// the collaterals it unlocked on behalf of `borrower`
+    SpendBorrowAllowance {
+        borrower: String,
+        spender: String,
+        amount: Uint256,
+    },
+
     /// Execute epoch operations
     /// 1. send reserve to collector contract
     /// 2. update anc_emission_rate state
@@ -75,19 +110,68 @@ pub enum ExecuteMsg {
     /// Deposit stable asset to get interest
     DepositStable {},
 
-    /// Borrow stable asset with collaterals in overseer contract
+    /// Borrow stable asset with collaterals in overseer contract.
+    /// A spender passes `borrower` to borrow against the collaterals
+    /// of a borrower who granted it an allowance
     BorrowStable {
         borrow_amount: Uint256,
         to: Option<String>,
+        borrower: Option<String>,
     },
 
-    /// Repay stable asset to decrease liability
+    /// Borrow stable asset at a fixed rate, priced off the current
+    /// floating rate plus the fixed rate premium. Fails if the
+    /// offered rate is above `max_rate`
+    BorrowStableFixed {
+        borrow_amount: Uint256,
+        max_rate: Option<Decimal256>,
+        to: Option<String>,
+        borrower: Option<String>,
+    },
+
+    /// Allow `spender` to borrow `amount` more against the sender's
+    /// collaterals; `expires_at` (block time) replaces the current expiry
+    IncreaseBorrowAllowance {
+        spender: String,
+        amount: Uint256,
+        expires_at: Option<u64>,
+    },
+
+    /// Remove the borrow allowance of `spender`
+    RevokeBorrowAllowance {
+        spender: String,
+    },
+
+    /// Repay stable asset to decrease liability. The floating-rate
+    /// loan is repaid first
     RepayStable {},
 
+    /// Repay stable asset to decrease liability of `borrower`.
+    /// Excess is returned to the sender
+    RepayStableFor {
+        borrower: String,
+    },
+
     /// Claim distributed ANC rewards
     ClaimRewards {
         to: Option<String>,
     },
+
+    /// Reset the rate of a fixed-rate loan to the currently offered
+    /// fixed rate, once they deviate by more than the rebalance threshold.
+    /// Only the borrower and the owner can raise the rate
+    RebalanceFixedRate {
+        borrower: String,
+    },
+
+    /// Lend `amount` to `callback_contract` while executing `msg` on it.
+    /// The amount plus the flash loan fee must be sent back to the
+    /// market before the callback returns
+    FlashLoan {
+        amount: Uint256,
+        callback_contract: String,
+        msg: Binary,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -117,6 +201,12 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    PauseInfo {},
+    BadDebt {},
+    BorrowAllowance {
+        borrower: String,
+        spender: String,
+    },
 }
 
 // We define a custom struct for each query response
@@ -131,6 +221,9 @@ pub struct ConfigResponse {
     pub distributor_contract: String,
     pub stable_denom: String,
     pub max_borrow_factor: Decimal256,
+    pub flash_loan_fee: Decimal256,
+    pub fixed_rate_premium: Decimal256,
+    pub fixed_rate_rebalance_threshold: Decimal256,
 }
 
 // We define a custom struct for each query response
@@ -145,6 +238,10 @@ pub struct StateResponse {
     pub anc_emission_rate: Decimal256,
     pub prev_aterra_supply: Uint256,
     pub prev_exchange_rate: Decimal256,
+    pub borrow_cap: Option<Uint256>,
+    pub total_fixed_liabilities: Decimal256,
+    pub fixed_interest_per_block: Decimal256,
+    pub fixed_interest_index: Decimal256,
 }
 
 // We define a custom struct for each query response
@@ -160,8 +257,13 @@ pub struct BorrowerInfoResponse {
     pub borrower: String,
     pub interest_index: Decimal256,
     pub reward_index: Decimal256,
+    /// Total loan, floating and fixed
     pub loan_amount: Uint256,
     pub pending_rewards: Decimal256,
+    pub floating_loan_amount: Uint256,
+    pub fixed_loan_amount: Uint256,
+    pub fixed_rate: Decimal256,
+    pub fixed_interest_index: Decimal256,
 }
 
 // We define a custom struct for each query response
@@ -169,3 +271,24 @@ pub struct BorrowerInfoResponse {
 pub struct BorrowerInfosResponse {
     pub borrower_infos: Vec<BorrowerInfoResponse>,
 }
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BorrowAllowanceResponse {
+    pub borrower: String,
+    pub spender: String,
+    /// Amount left to borrow; zero once the allowance has expired
+    pub amount: Uint256,
+    pub expires_at: Option<u64>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BadDebtResponse {
+    pub total_bad_debt: Uint256,
+    pub covered_by_reserves: Uint256,
+    pub covered_by_buffer: Uint256,
+    /// Loss absorbed by the aTerra exchange rate
+    pub socialized: Uint256,
+    pub write_off_count: u64,
+}
@@ -137,6 +137,8 @@ impl WasmMockQuerier {
                                 rate: v.0,
                                 last_updated_base: v.1,
                                 last_updated_quote: v.2,
+                                feeders_base: vec![],
+                                feeders_quote: vec![],
                             })))
                         }
                         None => SystemResult::Err(SystemError::InvalidRequest {
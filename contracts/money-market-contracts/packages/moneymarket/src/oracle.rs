@@ -15,13 +15,44 @@ pub enum ExecuteMsg {
     UpdateConfig {
         owner: Option<String>,
     },
+    /// Add a feeder to the feeder set of the asset
     RegisterFeeder {
         asset: String,
         feeder: String,
     },
+    /// Remove a feeder and its last submission from the asset
+    DeregisterFeeder {
+        asset: String,
+        feeder: String,
+    },
+    /// Set how feeder submissions of the asset are aggregated
+    UpdateFeedConfig {
+        asset: String,
+        /// Minimum number of fresh, non-deviating submissions
+        /// required to update the price
+        min_feeders: u32,
+        /// Maximum relative distance of a submission from the median
+        max_deviation: Option<Decimal256>,
+        /// Maximum age of a submission in seconds
+        max_age: Option<u64>,
+    },
     FeedPrice {
         prices: Vec<(String, Decimal256)>, // (asset, price)
     },
+    /// Set the circuit breaker of the asset; a single price update
+    /// moving more than `max_price_jump` flags a price jump
+    UpdatePriceJumpConfig {
+        asset: String,
+        /// None disables the circuit breaker
+        max_price_jump: Option<Decimal256>,
+        /// Seconds after which a price jump expires;
+        /// None keeps it until cleared by the owner
+        cooldown: Option<u64>,
+    },
+    /// Clear the price jump flag of the asset
+    ClearPriceJump {
+        asset: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -39,6 +70,15 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    PriceJump {
+        asset: String,
+    },
+    /// Time-weighted average price over the last `window` seconds
+    Twap {
+        base: String,
+        quote: String,
+        window: u64,
+    },
 }
 
 // We define a custom struct for each query response
@@ -52,7 +92,10 @@ pub struct ConfigResponse {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct FeederResponse {
     pub asset: String,
-    pub feeder: String,
+    pub feeders: Vec<String>,
+    pub min_feeders: u32,
+    pub max_deviation: Option<Decimal256>,
+    pub max_age: Option<u64>,
 }
 
 // We define a custom struct for each query response
@@ -61,6 +104,8 @@ pub struct PriceResponse {
     pub rate: Decimal256,
     pub last_updated_base: u64,
     pub last_updated_quote: u64,
+    pub feeders_base: Vec<String>,
+    pub feeders_quote: Vec<String>,
 }
 
 // We define a custom struct for each query response
@@ -69,6 +114,7 @@ pub struct PricesResponseElem {
     pub asset: String,
     pub price: Decimal256,
     pub last_updated_time: u64,
+    pub feeders: Vec<String>,
 }
 
 // We define a custom struct for each query response
@@ -76,3 +122,24 @@ pub struct PricesResponseElem {
 pub struct PricesResponse {
     pub prices: Vec<PricesResponseElem>,
 }
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TwapResponse {
+    pub rate: Decimal256,
+    /// Seconds of the window actually covered by observations
+    /// of both assets; shorter than the requested window when
+    /// the price history does not reach back far enough
+    pub observed_window: u64,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceJumpResponse {
+    pub asset: String,
+    pub max_price_jump: Option<Decimal256>,
+    pub cooldown: Option<u64>,
+    /// Whether liquidations of the asset must be paused
+    pub active: bool,
+    pub detected_time: Option<u64>,
+}
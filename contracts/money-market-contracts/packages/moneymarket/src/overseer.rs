@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::tokens::TokensHuman;
+use crate::tokens::{AssetInfo, TokensHuman};
 use cosmwasm_bignumber::{Decimal256, Uint256};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -35,8 +35,14 @@ pub struct InstantiateMsg {
     pub anc_purchase_factor: Decimal256,
     /// Valid oracle price timeframe
     pub price_timeframe: u64,
+    /// Maximum share of a loan repaid by a single liquidation
+    pub close_factor: Decimal256,
 }
 
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -55,21 +61,54 @@ pub enum ExecuteMsg {
         anc_purchase_factor: Option<Decimal256>,
         epoch_period: Option<u64>,
         price_timeframe: Option<u64>,
+        close_factor: Option<Decimal256>,
     },
 
     /// Create new custody contract for the given collateral token
     Whitelist {
-        name: String,             // bAsset name
-        symbol: String,           // bAsset symbol
-        collateral_token: String, // bAsset token contract
-        custody_contract: String, // bAsset custody contract
-        max_ltv: Decimal256,      // Loan To Value ratio
+        name: String,                      // bAsset name
+        symbol: String,                    // bAsset symbol
+        collateral_token: AssetInfo,       // bAsset token contract or native denom
+        custody_contract: String,          // bAsset custody contract
+        max_ltv: Decimal256,               // Loan To Value ratio
+        liquidation_threshold: Decimal256, // Loan To Value ratio that triggers liquidation
+        deposit_cap: Option<Uint256>,      // Maximum total collateral deposits
     },
     /// Update registered whitelist info
     UpdateWhitelist {
-        collateral_token: String,         // bAsset token contract
+        collateral_token: AssetInfo,      // bAsset token contract or native denom
         custody_contract: Option<String>, // bAsset custody contract
         max_ltv: Option<Decimal256>,      // Loan To Value ratio
+        liquidation_threshold: Option<Decimal256>, // Loan To Value ratio that triggers liquidation
+        deposit_cap: Option<Uint256>,     // Maximum total collateral deposits; zero removes the cap
+    },
+
+    /// Register the market of another stable denom, sharing the
+    /// collaterals of this overseer
+    RegisterMarket {
+        stable_denom: String,
+        market_contract: String,
+        liquidation_contract: String,
+    },
+
+    /// Update the contracts of a registered market; a new market
+    /// contract starts its epochs over
+    UpdateMarket {
+        stable_denom: String,
+        market_contract: Option<String>,
+        liquidation_contract: Option<String>,
+    },
+
+    /// Set the guardian allowed to pause operations
+    UpdateGuardian { guardian: Option<String> },
+    /// Set the pause bitmask (see `moneymarket::pause`);
+    /// the guardian can only add flags
+    UpdatePause { paused: u8 },
+    /// Set the pause bitmask of a whitelisted collateral;
+    /// the guardian can only add flags
+    UpdateCollateralPause {
+        collateral_token: AssetInfo,
+        paused: u8,
     },
 
     /// Claims all staking rewards from the bAsset contracts
@@ -81,6 +120,15 @@ pub enum ExecuteMsg {
     UpdateEpochState {
         interest_buffer: Uint256,
         distributed_interest: Uint256,
+        accrued_buffer: Uint256,
+        anc_purchase_amount: Uint256,
+    },
+    /// Update the epoch state of a registered market, whose
+    /// interest buffer is the balance held in its stable denom
+    UpdateMarketEpochState {
+        stable_denom: String,
+        interest_buffer: Uint256,
+        distributed_interest: Uint256,
     },
 
     ////////////////////
@@ -89,14 +137,38 @@ pub enum ExecuteMsg {
     LockCollateral {
         collaterals: TokensHuman, // <(Collateral Token, Amount)>
     },
+    /// A spender holding a borrow allowance of `borrower` at the
+    /// market of `stable_denom` (the base market by default) can unlock
+    /// the borrower's collaterals on their behalf, spending the
+    /// allowance by the value of the unlocked collaterals
     UnlockCollateral {
         collaterals: TokensHuman, // <(Collateral Token, Amount)>
+        borrower: Option<String>,
+        stable_denom: Option<String>,
+    },
+
+    /// Liquidation contract locks the collateral of a cancelled
+    /// auction again, once deposited back to the custody for `borrower`
+    RestoreCollateral {
+        borrower: String,
+        collaterals: TokensHuman, // <(Collateral Token, Amount)>
     },
 
     /////////////////////////////
     /// Permissionless operations
     /////////////////////////////
-    LiquidateCollateral { borrower: String },
+    /// Liquidate the borrower's collaterals, or only
+    /// `collateral_token` when it is given. The proceeds repay the
+    /// loan of the `stable_denom` market, the base market by default
+    LiquidateCollateral {
+        borrower: String,
+        collateral_token: Option<AssetInfo>,
+        stable_denom: Option<String>,
+    },
+    /// Write off the remaining loan of a borrower whose collaterals
+    /// are exhausted; the interest buffer covers what the market
+    /// reserves can not
+    WriteOffBadDebt { borrower: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -105,8 +177,8 @@ pub enum QueryMsg {
     Config {},
     EpochState {},
     Whitelist {
-        collateral_token: Option<String>,
-        start_after: Option<String>,
+        collateral_token: Option<AssetInfo>,
+        start_after: Option<AssetInfo>,
         limit: Option<u32>,
     },
     Collaterals {
@@ -116,10 +188,41 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Borrow limit in the base denom, or in `stable_denom` net of
+    /// the borrower's loans at the other markets when it is given
     BorrowLimit {
         borrower: String,
         block_time: Option<u64>,
+        stable_denom: Option<String>,
+    },
+    HealthFactor {
+        borrower: String,
+        block_time: Option<u64>,
+    },
+    AllHealthFactors {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        block_time: Option<u64>,
+    },
+    /// Borrowers of the scanned page whose health factor is
+    /// at or below `max_health`
+    AtRisk {
+        max_health: Decimal256,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        block_time: Option<u64>,
+    },
+    PauseInfo {},
+    /// Markets sharing the collaterals, starting with the base market
+    Markets {},
+    /// Past epochs in execution order, paginated by block height
+    EpochHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
     },
+    /// Projected number of epochs the interest buffer can keep
+    /// deposits at `threshold_deposit_rate`
+    BufferRunway {},
 }
 
 // We define a custom struct for each query response
@@ -137,6 +240,7 @@ pub struct ConfigResponse {
     pub stable_denom: String,
     pub epoch_period: u64,
     pub price_timeframe: u64,
+    pub close_factor: Decimal256,
 }
 
 // We define a custom struct for each query response
@@ -145,8 +249,13 @@ pub struct WhitelistResponseElem {
     pub name: String,
     pub symbol: String,
     pub max_ltv: Decimal256,
+    pub liquidation_threshold: Decimal256,
     pub custody_contract: String,
-    pub collateral_token: String,
+    pub collateral_token: AssetInfo,
+    /// Pause bitmask of the collateral
+    pub paused: u8,
+    /// Maximum total collateral deposits
+    pub deposit_cap: Option<Uint256>,
 }
 
 // We define a custom struct for each query response
@@ -168,8 +277,75 @@ pub struct AllCollateralsResponse {
     pub all_collaterals: Vec<CollateralsResponse>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketResponseElem {
+    pub stable_denom: String,
+    pub market_contract: String,
+    pub liquidation_contract: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketsResponse {
+    pub markets: Vec<MarketResponseElem>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BorrowLimitResponse {
     pub borrower: String,
     pub borrow_limit: Uint256,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HealthFactorResponse {
+    pub borrower: String,
+    pub loan_amount: Uint256,
+    pub borrow_limit: Uint256,
+    /// Loan amount above which the borrower can be liquidated
+    pub liquidation_limit: Uint256,
+    /// `liquidation_limit / loan_amount`; the loan can be liquidated below one.
+    /// None when the borrower has no loan
+    pub health_factor: Option<Decimal256>,
+    pub liquidatable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllHealthFactorsResponse {
+    pub health_factors: Vec<HealthFactorResponse>,
+    /// Last borrower scanned; pass it as `start_after` to read the next page
+    pub last_scanned: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochHistoryElem {
+    pub height: u64,
+    pub time: u64,
+    /// Deposit rate per block realized over the epoch, including distributed interest
+    pub deposit_rate: Decimal256,
+    pub exchange_rate: Decimal256,
+    pub aterra_supply: Uint256,
+    /// Interest buffer left after the epoch operations
+    pub interest_buffer: Uint256,
+    /// Rewards and reserves added to the interest buffer over the epoch
+    pub accrued_buffer: Uint256,
+    pub anc_purchase_amount: Uint256,
+    pub distributed_interest: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochHistoryResponse {
+    pub epochs: Vec<EpochHistoryElem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BufferRunwayResponse {
+    pub interest_buffer: Uint256,
+    /// Deposit rate paid by borrower interest alone
+    pub deposit_rate: Decimal256,
+    /// Buffer needed each epoch to lift deposits to `threshold_deposit_rate`
+    pub missing_deposits: Uint256,
+    /// Buffer inflow of the last epoch, after ANC purchases
+    pub buffer_inflow: Uint256,
+    /// Epochs the buffer fully covers the missing deposits;
+    /// None when the buffer is not drawn down
+    pub runway_epochs: Option<Uint256>,
+}
@@ -0,0 +1,31 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Stable deposits to the market and collateral deposits to custody
+pub const PAUSE_DEPOSIT: u8 = 1;
+/// Stable borrows from the market
+pub const PAUSE_BORROW: u8 = 1 << 1;
+/// Collateral liquidations
+pub const PAUSE_LIQUIDATION: u8 = 1 << 2;
+/// Collateral unlocks and withdrawals
+pub const PAUSE_UNLOCK: u8 = 1 << 3;
+
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSIT | PAUSE_BORROW | PAUSE_LIQUIDATION | PAUSE_UNLOCK;
+
+/// Returns whether a bitmask only holds known pause flags
+pub fn is_valid_pause(paused: u8) -> bool {
+    paused & !PAUSE_ALL == 0
+}
+
+/// The guardian can only add pause flags; removing them is
+/// reserved to the owner
+pub fn is_guardian_pause(current: u8, paused: u8) -> bool {
+    paused & current == current
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseInfoResponse {
+    pub guardian: Option<String>,
+    pub paused: u8,
+}
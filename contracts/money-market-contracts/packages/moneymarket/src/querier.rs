@@ -9,7 +9,7 @@ use cosmwasm_std::{
 use cw20::{Cw20QueryMsg, TokenInfoResponse};
 use terra_cosmwasm::TerraQuerier;
 
-use crate::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
+use crate::oracle::{PriceJumpResponse, PriceResponse, QueryMsg as OracleQueryMsg};
 
 pub fn query_all_balances(deps: Deps, account_addr: Addr) -> StdResult<Vec<Coin>> {
     // load price form the oracle
@@ -121,3 +121,14 @@ pub fn query_price(
 
     Ok(oracle_price)
 }
+
+pub fn query_price_jump(
+    deps: Deps,
+    oracle_addr: Addr,
+    asset: String,
+) -> StdResult<PriceJumpResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: oracle_addr.to_string(),
+        msg: to_binary(&OracleQueryMsg::PriceJump { asset })?,
+    }))
+}
@@ -1,10 +1,12 @@
 use crate::mock_querier::mock_dependencies;
 use crate::oracle::PriceResponse;
 use crate::querier::{compute_tax, deduct_tax, query_price, query_tax_rate, TimeConstraints};
-use crate::tokens::{Tokens, TokensHuman, TokensMath, TokensToRaw};
+use crate::tokens::{AssetInfo, AssetInfoRaw, Tokens, TokensHuman, TokensMath, TokensToRaw};
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
-use cosmwasm_std::{Addr, Api, CanonicalAddr, Coin, Decimal, StdError, Uint128};
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, Api, CanonicalAddr, Coin, Decimal, StdError, Uint128,
+};
 
 #[test]
 fn tax_rate_querier() {
@@ -91,6 +93,8 @@ fn oracle_price_querier() {
             rate: Decimal256::from_ratio(131, 2),
             last_updated_base: 123,
             last_updated_quote: 321,
+            feeders_base: vec![],
+            feeders_quote: vec![],
         }
     );
 
@@ -125,28 +129,78 @@ fn tokens_math() {
     let deps = mock_dependencies(&[]);
 
     let tokens_1: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token2".to_string(), Uint256::from(1000000u64)),
-        ("token3".to_string(), Uint256::from(1000000u64)),
-        ("token5".to_string(), Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token2".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token3".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token5".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     // not existing item
     let tokens_2: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token4".to_string(), Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token4".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     // not existing item
     let tokens_3: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token6".to_string(), Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token6".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     // sub bigger than source
     let tokens_4: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token2".to_string(), Uint256::from(1200000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token2".to_string(),
+            },
+            Uint256::from(1200000u64),
+        ),
     ];
 
     let mut tokens_1_raw: Tokens = tokens_1.to_raw(deps.as_ref()).unwrap();
@@ -209,15 +263,45 @@ fn tokens_math_normal_add() {
         .to_string();
 
     let tokens_1: TokensHuman = vec![
-        (acct1.clone(), Uint256::from(1000000u64)),
-        (acct2, Uint256::from(1000000u64)),
-        (acct3, Uint256::from(1000000u64)),
-        (acct5, Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: acct1.clone(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: acct2,
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: acct3,
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: acct5,
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     let tokens_2: TokensHuman = vec![
-        (acct1, Uint256::from(1000000u64)),
-        (acct4, Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: acct1,
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: acct4,
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     let mut tokens_1_raw: Tokens = tokens_1.to_raw(deps.as_ref()).unwrap();
@@ -234,13 +318,33 @@ fn token_math_zero_token() {
     let deps = mock_dependencies(&[]);
 
     let tokens_1: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token2".to_string(), Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token2".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     let tokens_2: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token2".to_string(), Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token2".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     let mut tokens_1_raw: Tokens = tokens_1.to_raw(deps.as_ref()).unwrap();
@@ -256,17 +360,52 @@ fn token_math_invalid_token() {
     let deps = mock_dependencies(&[]);
 
     let tokens_1: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token2".to_string(), Uint256::from(1000000u64)),
-        ("token3".to_string(), Uint256::from(1000000u64)),
-        ("token5".to_string(), Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token2".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token3".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token5".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     // duplicated item
     let tokens_2: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token3".to_string(), Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token3".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     let mut tokens_1_raw: Tokens = tokens_1.to_raw(deps.as_ref()).unwrap();
@@ -281,18 +420,58 @@ fn token_math_invalid_token_2() {
     let deps = mock_dependencies(&[]);
 
     let tokens_1: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token2".to_string(), Uint256::from(1000000u64)),
-        ("token2".to_string(), Uint256::from(1000000u64)),
-        ("token3".to_string(), Uint256::from(1000000u64)),
-        ("token5".to_string(), Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token2".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token2".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token3".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token5".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     // duplicated item
     let tokens_2: TokensHuman = vec![
-        ("token1".to_string(), Uint256::from(1000000u64)),
-        ("token2".to_string(), Uint256::from(1000000u64)),
-        ("token3".to_string(), Uint256::from(1000000u64)),
+        (
+            AssetInfo::Token {
+                contract_addr: "token1".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token2".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfo::Token {
+                contract_addr: "token3".to_string(),
+            },
+            Uint256::from(1000000u64),
+        ),
     ];
 
     let mut tokens_1_raw: Tokens = tokens_1.to_raw(deps.as_ref()).unwrap();
@@ -300,3 +479,48 @@ fn token_math_invalid_token_2() {
 
     let _ = tokens_1_raw.sub(tokens_2_raw);
 }
+
+#[test]
+fn stored_tokens_format() {
+    let deps = mock_dependencies(&[]);
+    let token_raw = deps.api.addr_canonicalize("token1").unwrap();
+
+    // collaterals stored before native coins were supported
+    let legacy_tokens: Vec<(CanonicalAddr, Uint256)> =
+        vec![(token_raw.clone(), Uint256::from(1000000u64))];
+    let tokens: Tokens = from_slice(&to_vec(&legacy_tokens).unwrap()).unwrap();
+    assert_eq!(
+        tokens,
+        vec![(
+            AssetInfoRaw::Token {
+                contract_addr: token_raw.clone(),
+            },
+            Uint256::from(1000000u64),
+        )]
+    );
+    assert_eq!(to_vec(&tokens).unwrap(), to_vec(&legacy_tokens).unwrap());
+
+    let tokens: Tokens = vec![
+        (
+            AssetInfoRaw::Token {
+                contract_addr: token_raw,
+            },
+            Uint256::from(1000000u64),
+        ),
+        (
+            AssetInfoRaw::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            Uint256::from(2000000u64),
+        ),
+    ];
+    let stored = to_vec(&tokens).unwrap();
+    assert!(String::from_utf8(stored.clone())
+        .unwrap()
+        .contains(r#"{"native_token":{"denom":"uluna"}}"#));
+    assert_eq!(from_slice::<Tokens>(&stored).unwrap(), tokens);
+
+    // a broken address is an error, not a missing collateral
+    let res = from_slice::<Tokens>(br#"[["not base64!","1000000"]]"#);
+    assert!(res.is_err());
+}
@@ -1,8 +1,128 @@
+use schemars::JsonSchema;
+use serde::de::{self, Deserializer, EnumAccess, VariantAccess, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
 use cosmwasm_bignumber::Uint256;
-use cosmwasm_std::{CanonicalAddr, Deps, StdError, StdResult};
+use cosmwasm_std::{Api, Binary, CanonicalAddr, Deps, StdError, StdResult};
+
+/// Collateral asset, either a CW20 token or a native bank denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetInfo {
+    Token { contract_addr: String },
+    NativeToken { denom: String },
+}
+
+impl fmt::Display for AssetInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssetInfo::Token { contract_addr } => write!(f, "{}", contract_addr),
+            AssetInfo::NativeToken { denom } => write!(f, "{}", denom),
+        }
+    }
+}
+
+impl AssetInfo {
+    pub fn to_raw(&self, api: &dyn Api) -> StdResult<AssetInfoRaw> {
+        match self {
+            AssetInfo::Token { contract_addr } => Ok(AssetInfoRaw::Token {
+                contract_addr: api.addr_canonicalize(contract_addr)?,
+            }),
+            AssetInfo::NativeToken { denom } => Ok(AssetInfoRaw::NativeToken {
+                denom: denom.to_string(),
+            }),
+        }
+    }
+}
+
+/// Stored form of `AssetInfo`. Tokens are stored as their bare canonical
+/// address, like every collateral stored before native coins were
+/// supported, and native coins as `{"native_token":{"denom":..}}`.
+#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub enum AssetInfoRaw {
+    Token { contract_addr: CanonicalAddr },
+    NativeToken { denom: String },
+}
+
+#[derive(Serialize, Deserialize)]
+struct NativeTokenRaw {
+    denom: String,
+}
+
+impl Serialize for AssetInfoRaw {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            AssetInfoRaw::Token { contract_addr } => contract_addr.serialize(serializer),
+            AssetInfoRaw::NativeToken { denom } => serializer.serialize_newtype_variant(
+                "AssetInfoRaw",
+                1,
+                "native_token",
+                &NativeTokenRaw {
+                    denom: denom.to_string(),
+                },
+            ),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AssetInfoRaw {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum("AssetInfoRaw", &["native_token"], AssetInfoRawVisitor)
+    }
+}
+
+struct AssetInfoRawVisitor;
+
+impl<'de> Visitor<'de> for AssetInfoRawVisitor {
+    type Value = AssetInfoRaw;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a base64 encoded address or a native token")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        // a bare string is the address of a token
+        let (variant, access): (String, _) = data.variant()?;
+        if variant == "native_token" {
+            let native_token: NativeTokenRaw = access.newtype_variant()?;
+            return Ok(AssetInfoRaw::NativeToken {
+                denom: native_token.denom,
+            });
+        }
+
+        access.unit_variant()?;
+        let contract_addr = Binary::from_base64(&variant).map_err(de::Error::custom)?;
+        Ok(AssetInfoRaw::Token {
+            contract_addr: CanonicalAddr::from(contract_addr.as_slice()),
+        })
+    }
+}
+
+impl AssetInfoRaw {
+    /// Storage key of the asset
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            AssetInfoRaw::Token { contract_addr } => contract_addr.as_slice(),
+            AssetInfoRaw::NativeToken { denom } => denom.as_bytes(),
+        }
+    }
+
+    pub fn to_normal(&self, api: &dyn Api) -> StdResult<AssetInfo> {
+        match self {
+            AssetInfoRaw::Token { contract_addr } => Ok(AssetInfo::Token {
+                contract_addr: api.addr_humanize(contract_addr)?.to_string(),
+            }),
+            AssetInfoRaw::NativeToken { denom } => Ok(AssetInfo::NativeToken {
+                denom: denom.to_string(),
+            }),
+        }
+    }
+}
 
-pub type Token = (CanonicalAddr, Uint256);
-pub type TokenHuman = (String, Uint256);
+pub type Token = (AssetInfoRaw, Uint256);
+pub type TokenHuman = (AssetInfo, Uint256);
 
 pub type Tokens = Vec<Token>;
 pub type TokensHuman = Vec<TokenHuman>;
@@ -23,11 +143,11 @@ pub trait TokensToRaw {
 
 impl TokensMath for Tokens {
     fn sub(&mut self, tokens: Tokens) -> StdResult<()> {
-        self.sort_by(|a, b| a.0.as_slice().cmp(b.0.as_slice()));
+        self.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
         self.assert_duplicate_token();
 
         let mut tokens = tokens;
-        tokens.sort_by(|a, b| a.0.as_slice().cmp(b.0.as_slice()));
+        tokens.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
         tokens.assert_duplicate_token();
 
         let mut i = 0;
@@ -42,7 +162,7 @@ impl TokensMath for Tokens {
 
                 i += 1;
                 j += 1;
-            } else if self[i].0.as_slice().cmp(tokens[j].0.as_slice()) == std::cmp::Ordering::Less {
+            } else if self[i].0.as_bytes().cmp(tokens[j].0.as_bytes()) == std::cmp::Ordering::Less {
                 i += 1;
             } else {
                 return Err(StdError::generic_err("Subtraction underflow"));
@@ -60,11 +180,11 @@ impl TokensMath for Tokens {
     }
 
     fn add(&mut self, tokens: Tokens) {
-        self.sort_by(|a, b| a.0.as_slice().cmp(b.0.as_slice()));
+        self.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
         self.assert_duplicate_token();
 
         let mut tokens = tokens;
-        tokens.sort_by(|a, b| a.0.as_slice().cmp(b.0.as_slice()));
+        tokens.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
         tokens.assert_duplicate_token();
 
         let mut tmp_tokens: Tokens = vec![];
@@ -77,7 +197,7 @@ impl TokensMath for Tokens {
 
                 i += 1;
                 j += 1;
-            } else if self[i].0.as_slice().cmp(tokens[j].0.as_slice())
+            } else if self[i].0.as_bytes().cmp(tokens[j].0.as_bytes())
                 == std::cmp::Ordering::Greater
             {
                 tmp_tokens.push((tokens[j].0.clone(), tokens[j].1));
@@ -109,11 +229,11 @@ impl TokensMath for Tokens {
 
     fn assert_duplicate_token(&self) {
         if self.len() > 1 {
-            let mut before_token = self[0].0.as_slice();
+            let mut before_token = self[0].0.as_bytes();
 
             let mut i = 1;
             while i < self.len() {
-                let next_token = self[i].0.as_slice();
+                let next_token = self[i].0.as_bytes();
                 if before_token == next_token {
                     panic!("duplicate token address");
                 }
@@ -129,7 +249,7 @@ impl TokensToHuman for Tokens {
     fn to_human(&self, deps: Deps) -> StdResult<TokensHuman> {
         let collaterals: TokensHuman = self
             .iter()
-            .map(|c| Ok((deps.api.addr_humanize(&c.0)?.to_string(), c.1)))
+            .map(|c| Ok((c.0.to_normal(deps.api)?, c.1)))
             .collect::<StdResult<TokensHuman>>()?;
         Ok(collaterals)
     }
@@ -139,7 +259,7 @@ impl TokensToRaw for TokensHuman {
     fn to_raw(&self, deps: Deps) -> StdResult<Tokens> {
         let collaterals: Tokens = self
             .iter()
-            .map(|c| Ok((deps.api.addr_canonicalize(c.0.as_str())?, c.1)))
+            .map(|c| Ok((c.0.to_raw(deps.api)?, c.1)))
             .collect::<StdResult<Tokens>>()?;
         Ok(collaterals)
     }